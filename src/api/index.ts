@@ -0,0 +1,362 @@
+/**
+ * Typed client for terminal-mcp's tools, for agents and tests written in
+ * TypeScript that would otherwise hand-roll the JSON for each call. It wraps
+ * the MCP SDK's Client, so it runs over any MCP transport (stdio, WebSocket,
+ * ...), and imports nothing from the server at runtime:
+ *
+ *   import { TerminalMcpClient } from "@ellery/terminal-mcp/api";
+ *   const terminal = await TerminalMcpClient.connect(transport);
+ *   await terminal.type({ text: "npm test\n" });
+ *   const { text } = await terminal.getContent({ visibleOnly: true });
+ *
+ * Argument types come from the tools' own zod schemas, so they can't drift
+ * from what the server accepts.
+ */
+import { Client } from "@modelcontextprotocol/sdk/client/index.js";
+import type { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
+import type { CallToolResult } from "@modelcontextprotocol/sdk/types.js";
+import type { z } from "zod";
+import type {
+  CursorStyle,
+  FlowStats,
+  SecretPrompt,
+  SessionController,
+  SessionCwd,
+  TerminalModes,
+} from "../terminal/index.js";
+import type { ProcessInfo } from "../utils/procfs.js";
+import type { clearSchema } from "../tools/clear.js";
+import type { createSessionSchema } from "../tools/createSession.js";
+import type { describeScreenSchema } from "../tools/describeScreen.js";
+import type { destroySessionSchema } from "../tools/destroySession.js";
+import type { diffScreenSchema } from "../tools/diffScreen.js";
+import type { getClipboardSchema } from "../tools/getClipboard.js";
+import type { getContentSchema } from "../tools/getContent.js";
+import type { getCursorSchema } from "../tools/getCursor.js";
+import type { getEnvironmentSchema } from "../tools/getEnvironment.js";
+import type { getForegroundProcessSchema } from "../tools/getForegroundProcess.js";
+import type { getLinksSchema } from "../tools/getLinks.js";
+import type { getSelectionSchema } from "../tools/getSelection.js";
+import type { getSnapshotSchema } from "../tools/getSnapshot.js";
+import type { getStatusSchema } from "../tools/getStatus.js";
+import type { getTitleSchema } from "../tools/getTitle.js";
+import type { isIdleSchema } from "../tools/isIdle.js";
+import type { listSessionsSchema } from "../tools/listSessions.js";
+import type { listSnapshotsSchema } from "../tools/listSnapshots.js";
+import type { lookupHelpSchema } from "../tools/lookupHelp.js";
+import type { markSchema } from "../tools/mark.js";
+import type { resetSchema } from "../tools/reset.js";
+import type { restartShellSchema } from "../tools/restartShell.js";
+import type { screenshotSchema } from "../tools/screenshot.js";
+import type { searchSchema } from "../tools/search.js";
+import type { secureTypeSchema } from "../tools/secureType.js";
+import type { sendKeySchema } from "../tools/sendKey.js";
+import type { sendMouseSchema } from "../tools/sendMouse.js";
+import type { startRecordingSchema } from "../tools/startRecording.js";
+import type { stopRecordingSchema } from "../tools/stopRecording.js";
+import type { subscribeOutputSchema } from "../tools/subscribeOutput.js";
+import type { testPromptPatternSchema } from "../tools/testPromptPattern.js";
+import type { typeSchema } from "../tools/type.js";
+import type { unsubscribeOutputSchema } from "../tools/unsubscribeOutput.js";
+import type { waitForExitSchema } from "../tools/waitForExit.js";
+
+/**
+ * Arguments of each tool, as the caller writes them (defaults optional)
+ */
+export interface ToolArgs {
+  type: z.input<typeof typeSchema>;
+  secureType: z.input<typeof secureTypeSchema>;
+  sendKey: z.input<typeof sendKeySchema>;
+  sendMouse: z.input<typeof sendMouseSchema>;
+  getContent: z.input<typeof getContentSchema>;
+  mark: z.input<typeof markSchema>;
+  clear: z.input<typeof clearSchema>;
+  reset: z.input<typeof resetSchema>;
+  takeScreenshot: z.input<typeof screenshotSchema>;
+  search: z.input<typeof searchSchema>;
+  getLinks: z.input<typeof getLinksSchema>;
+  getClipboard: z.input<typeof getClipboardSchema>;
+  subscribeOutput: z.input<typeof subscribeOutputSchema>;
+  unsubscribeOutput: z.input<typeof unsubscribeOutputSchema>;
+  getStatus: z.input<typeof getStatusSchema>;
+  getForegroundProcess: z.input<typeof getForegroundProcessSchema>;
+  isIdle: z.input<typeof isIdleSchema>;
+  waitForExit: z.input<typeof waitForExitSchema>;
+  getCursor: z.input<typeof getCursorSchema>;
+  describeScreen: z.input<typeof describeScreenSchema>;
+  getSelection: z.input<typeof getSelectionSchema>;
+  getTitle: z.input<typeof getTitleSchema>;
+  lookupHelp: z.input<typeof lookupHelpSchema>;
+  diffScreen: z.input<typeof diffScreenSchema>;
+  getEnvironment: z.input<typeof getEnvironmentSchema>;
+  listSnapshots: z.input<typeof listSnapshotsSchema>;
+  getSnapshot: z.input<typeof getSnapshotSchema>;
+  startRecording: z.input<typeof startRecordingSchema>;
+  stopRecording: z.input<typeof stopRecordingSchema>;
+  restartShell: z.input<typeof restartShellSchema>;
+  createSession: z.input<typeof createSessionSchema>;
+  listSessions: z.input<typeof listSessionsSchema>;
+  destroySession: z.input<typeof destroySessionSchema>;
+  testPromptPattern: z.input<typeof testPromptPatternSchema>;
+}
+
+export type ToolName = keyof ToolArgs;
+
+export type ToolContent = CallToolResult["content"][number];
+
+export interface ToolResult<S = Record<string, unknown>> {
+  /** Every text block of the result, joined with newlines */
+  text: string;
+  content: ToolContent[];
+  /** structuredContent, for tools that return JSON */
+  structured?: S;
+}
+
+/**
+ * getCursor's structured result
+ */
+export interface CursorInfo {
+  row: number;
+  col: number;
+  visible: boolean;
+  style: CursorStyle;
+  alternateScreen: boolean;
+}
+
+/**
+ * isIdle's structured result
+ */
+export interface IdleInfo {
+  idle: boolean;
+  /** Why the session counts as busy; empty when idle */
+  reasons: string[];
+  atPrompt: boolean;
+  alternateScreen: boolean;
+  foreground: string | null;
+  commandRunning: boolean | null;
+  msSinceOutput: number | null;
+}
+
+/**
+ * getStatus's structured result
+ */
+export interface SessionStatus {
+  sessionId: string;
+  isDefault: boolean;
+  shell: string;
+  command?: string[];
+  running: boolean;
+  exitCode: number | null;
+  title: string;
+  dimensions: { cols: number; rows: number };
+  cursor: { x: number; y: number; style: CursorStyle; visible: boolean };
+  modes: TerminalModes;
+  atPrompt: boolean;
+  cwd: SessionCwd | null;
+  secretPrompt: SecretPrompt;
+  controller: SessionController;
+  flow: FlowStats;
+  process?: ProcessInfo;
+}
+
+/**
+ * A tool returned isError; the message is the tool's own error text
+ */
+export class ToolCallError extends Error {
+  constructor(
+    readonly tool: ToolName,
+    readonly result: ToolResult
+  ) {
+    super(result.text || `${tool} failed`);
+    this.name = "ToolCallError";
+  }
+}
+
+/**
+ * One terminal-mcp server, reached through an MCP client
+ */
+export class TerminalMcpClient {
+  constructor(readonly client: Client) {}
+
+  /**
+   * Connect over a transport, e.g. the SDK's StdioClientTransport running
+   * `terminal-mcp --headless`, and complete the MCP handshake
+   */
+  static async connect(
+    transport: Transport,
+    clientInfo: { name: string; version: string } = { name: "terminal-mcp-api", version: "1.0.0" }
+  ): Promise<TerminalMcpClient> {
+    const client = new Client(clientInfo, { capabilities: {} });
+    await client.connect(transport);
+    return new TerminalMcpClient(client);
+  }
+
+  async close(): Promise<void> {
+    await this.client.close();
+  }
+
+  /**
+   * Call any tool by name. Throws ToolCallError if the tool reports an
+   * error; protocol errors (busy session, rate limit) are the SDK's McpError.
+   */
+  async call<K extends ToolName, S = Record<string, unknown>>(name: K, args: ToolArgs[K]): Promise<ToolResult<S>> {
+    const raw = (await this.client.callTool({ name, arguments: args as Record<string, unknown> })) as CallToolResult;
+    const content = raw.content ?? [];
+    const result: ToolResult<S> = {
+      text: content
+        .filter((block): block is Extract<ToolContent, { type: "text" }> => block.type === "text")
+        .map((block) => block.text)
+        .join("\n"),
+      content,
+      ...(raw.structuredContent && { structured: raw.structuredContent as S }),
+    };
+    if (raw.isError) throw new ToolCallError(name, result as ToolResult);
+    return result;
+  }
+
+  // Input
+
+  type(args: ToolArgs["type"]): Promise<ToolResult> {
+    return this.call("type", args);
+  }
+
+  secureType(args: ToolArgs["secureType"]): Promise<ToolResult> {
+    return this.call("secureType", args);
+  }
+
+  sendKey(args: ToolArgs["sendKey"]): Promise<ToolResult> {
+    return this.call("sendKey", args);
+  }
+
+  sendMouse(args: ToolArgs["sendMouse"]): Promise<ToolResult> {
+    return this.call("sendMouse", args);
+  }
+
+  // Reading the screen
+
+  getContent(args: ToolArgs["getContent"] = {}): Promise<ToolResult> {
+    return this.call("getContent", args);
+  }
+
+  takeScreenshot(args: ToolArgs["takeScreenshot"] = {}): Promise<ToolResult> {
+    return this.call("takeScreenshot", args);
+  }
+
+  search(args: ToolArgs["search"]): Promise<ToolResult> {
+    return this.call("search", args);
+  }
+
+  getLinks(args: ToolArgs["getLinks"] = {}): Promise<ToolResult> {
+    return this.call("getLinks", args);
+  }
+
+  getClipboard(args: ToolArgs["getClipboard"] = {}): Promise<ToolResult> {
+    return this.call("getClipboard", args);
+  }
+
+  getCursor(args: ToolArgs["getCursor"] = {}): Promise<ToolResult<CursorInfo>> {
+    return this.call("getCursor", args);
+  }
+
+  describeScreen(args: ToolArgs["describeScreen"] = {}): Promise<ToolResult> {
+    return this.call("describeScreen", args);
+  }
+
+  getSelection(args: ToolArgs["getSelection"] = {}): Promise<ToolResult> {
+    return this.call("getSelection", args);
+  }
+
+  getTitle(args: ToolArgs["getTitle"] = {}): Promise<ToolResult> {
+    return this.call("getTitle", args);
+  }
+
+  diffScreen(args: ToolArgs["diffScreen"] = {}): Promise<ToolResult> {
+    return this.call("diffScreen", args);
+  }
+
+  listSnapshots(args: ToolArgs["listSnapshots"] = {}): Promise<ToolResult> {
+    return this.call("listSnapshots", args);
+  }
+
+  getSnapshot(args: ToolArgs["getSnapshot"] = {}): Promise<ToolResult> {
+    return this.call("getSnapshot", args);
+  }
+
+  mark(args: ToolArgs["mark"]): Promise<ToolResult> {
+    return this.call("mark", args);
+  }
+
+  clear(args: ToolArgs["clear"] = {}): Promise<ToolResult> {
+    return this.call("clear", args);
+  }
+
+  reset(args: ToolArgs["reset"] = {}): Promise<ToolResult> {
+    return this.call("reset", args);
+  }
+
+  subscribeOutput(args: ToolArgs["subscribeOutput"] = {}): Promise<ToolResult> {
+    return this.call("subscribeOutput", args);
+  }
+
+  unsubscribeOutput(args: ToolArgs["unsubscribeOutput"] = {}): Promise<ToolResult> {
+    return this.call("unsubscribeOutput", args);
+  }
+
+  // Session state
+
+  getStatus(args: ToolArgs["getStatus"] = {}): Promise<ToolResult<SessionStatus>> {
+    return this.call("getStatus", args);
+  }
+
+  getForegroundProcess(args: ToolArgs["getForegroundProcess"] = {}): Promise<ToolResult> {
+    return this.call("getForegroundProcess", args);
+  }
+
+  isIdle(args: ToolArgs["isIdle"] = {}): Promise<ToolResult<IdleInfo>> {
+    return this.call("isIdle", args);
+  }
+
+  waitForExit(args: ToolArgs["waitForExit"] = {}): Promise<ToolResult> {
+    return this.call("waitForExit", args);
+  }
+
+  getEnvironment(args: ToolArgs["getEnvironment"] = {}): Promise<ToolResult> {
+    return this.call("getEnvironment", args);
+  }
+
+  lookupHelp(args: ToolArgs["lookupHelp"]): Promise<ToolResult> {
+    return this.call("lookupHelp", args);
+  }
+
+  testPromptPattern(args: ToolArgs["testPromptPattern"]): Promise<ToolResult> {
+    return this.call("testPromptPattern", args);
+  }
+
+  // Recording
+
+  startRecording(args: ToolArgs["startRecording"] = {}): Promise<ToolResult> {
+    return this.call("startRecording", args);
+  }
+
+  stopRecording(args: ToolArgs["stopRecording"]): Promise<ToolResult> {
+    return this.call("stopRecording", args);
+  }
+
+  // Sessions
+
+  restartShell(args: ToolArgs["restartShell"] = {}): Promise<ToolResult> {
+    return this.call("restartShell", args);
+  }
+
+  createSession(args: ToolArgs["createSession"] = {}): Promise<ToolResult> {
+    return this.call("createSession", args);
+  }
+
+  listSessions(): Promise<ToolResult> {
+    return this.call("listSessions", {});
+  }
+
+  destroySession(args: ToolArgs["destroySession"]): Promise<ToolResult> {
+    return this.call("destroySession", args);
+  }
+}
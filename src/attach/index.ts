@@ -0,0 +1,377 @@
+/**
+ * Attach mode: `terminal-mcp attach` connects the operator's own terminal
+ * to a session of a running headless server, so a human can watch, type
+ * alongside the agent, or take over a stuck session. Every headless server
+ * listens on a Unix socket in getDefaultAttachDir(), named after its pid.
+ * The client puts its terminal in raw mode and forwards keystrokes and
+ * resizes; the server replays the session's buffer and then streams its
+ * output.
+ */
+import * as fs from "fs";
+import * as net from "net";
+import * as path from "path";
+import type { SessionController, TerminalManager, TerminalSession } from "../terminal/index.js";
+import { onLines } from "../terminal/persist.js";
+import { watchSession } from "../observe/index.js";
+import { getDefaultAttachDir } from "../utils/platform.js";
+
+// Ctrl+] — toggles exclusive human control, as in interactive mode
+const TAKEOVER_KEY = "\x1d";
+// Ctrl+\ — detaches, leaving the session running
+const DETACH_KEY = "\x1c";
+
+const YELLOW = "\x1b[33m";
+const RESET = "\x1b[0m";
+// Leave the operator's terminal as we found it, whatever the session's
+// program switched on: alternate screen, hidden cursor, mouse reporting,
+// bracketed paste, colors
+const RESTORE_TERMINAL = "\x1b[?1049l\x1b[?25h\x1b[?1000l\x1b[?1002l\x1b[?1003l\x1b[?1006l\x1b[?2004l\x1b[0m";
+
+type AttachClientMessage =
+  | { type: "attach"; sessionId?: string; cols: number; rows: number }
+  | { type: "input"; data: string }
+  | { type: "resize"; cols: number; rows: number }
+  | { type: "takeover" };
+
+type AttachServerMessage =
+  | { type: "init"; sessionId: string; data: string }
+  | { type: "data"; data: string }
+  | { type: "control"; controller: SessionController }
+  | { type: "exit"; exitCode: number }
+  | { type: "error"; message: string };
+
+function frame(message: AttachClientMessage | AttachServerMessage): string {
+  return JSON.stringify(message) + "\n";
+}
+
+/**
+ * Socket a server with this pid listens on
+ */
+export function attachSocketPath(pid: number = process.pid, dir: string = getDefaultAttachDir()): string {
+  return path.join(dir, `${pid}.sock`);
+}
+
+/**
+ * Why dir can't be trusted with attach sockets, or null if it can. It must
+ * be ours and closed to everyone else, and no directory above it up to a
+ * root-owned one may be another user's or writable by others: in a shared
+ * /tmp, another user could create terminal-mcp-<uid> first and swap in a
+ * socket of their own that records everything typed into attach.
+ */
+function untrustedDirReason(dir: string): string | null {
+  const uid = process.getuid?.();
+  if (uid === undefined) return null;
+  let stat = fs.lstatSync(dir);
+  if (!stat.isDirectory()) return `${dir} is not a directory`;
+  if (stat.uid !== uid) return `${dir} is owned by another user`;
+  if ((stat.mode & 0o077) !== 0) return `${dir} is accessible to other users (mode ${(stat.mode & 0o777).toString(8)})`;
+  for (let parent = path.dirname(dir); ; parent = path.dirname(parent)) {
+    stat = fs.lstatSync(parent);
+    if (stat.uid === 0) return null;
+    if (stat.uid !== uid) return `${parent} is owned by another user`;
+    if ((stat.mode & 0o022) !== 0) return `${parent} is writable by other users`;
+    if (parent === path.dirname(parent)) return null;
+  }
+}
+
+/**
+ * Listen for attach clients. Attached terminals size the session; it goes
+ * back to its previous size once the last one detaches. Returns null,
+ * without listening, when the socket's directory isn't private.
+ */
+export function createAttachServer(manager: TerminalManager, socketPath: string = attachSocketPath()): net.Server | null {
+  fs.mkdirSync(path.dirname(socketPath), { recursive: true, mode: 0o700 });
+  const untrusted = untrustedDirReason(path.dirname(socketPath));
+  if (untrusted) {
+    console.error(`[terminal-mcp] Not listening for attach clients: ${untrusted}`);
+    return null;
+  }
+  // Left behind by an earlier process that had the same pid
+  fs.rmSync(socketPath, { force: true });
+
+  const attached = new Map<TerminalSession, { cols: number; rows: number; clients: number }>();
+  const controlListeners = new Map<string, Set<(controller: SessionController) => void>>();
+  manager.onControlChange((sessionId, controller) => {
+    for (const listener of controlListeners.get(sessionId) ?? []) listener(controller);
+  });
+
+  const server = net.createServer((socket) => {
+    socket.on("error", () => {});
+    const send = (message: AttachServerMessage) => {
+      if (!socket.destroyed) socket.write(frame(message));
+    };
+    let session: TerminalSession | null = null;
+    let sessionId: string | undefined;
+    let started = false;
+    let tookOver = false;
+    let stop = () => {};
+
+    const detach = () => {
+      stop();
+      if (!session) return;
+      const entry = attached.get(session);
+      if (entry && --entry.clients === 0) {
+        attached.delete(session);
+        if (session.isActive()) session.resize(entry.cols, entry.rows);
+      }
+      // Don't leave the agent locked out by someone who has gone
+      if (tookOver && session.isActive() && manager.getController(sessionId) === "human") {
+        manager.setController("shared", sessionId);
+      }
+      session = null;
+    };
+    socket.on("close", detach);
+
+    const start = async (message: Extract<AttachClientMessage, { type: "attach" }>) => {
+      let target: TerminalSession;
+      try {
+        target = await manager.resolveSession(message.sessionId);
+        sessionId = manager.getSessionMetadata(message.sessionId).sessionId;
+      } catch (error) {
+        send({ type: "error", message: (error as Error).message });
+        socket.end();
+        return;
+      }
+      // Gone while the session was starting
+      if (socket.destroyed) return;
+      session = target;
+      const id = sessionId;
+      const entry = attached.get(target);
+      if (entry) {
+        entry.clients++;
+      } else {
+        attached.set(target, { ...target.getDimensions(), clients: 1 });
+      }
+      target.resize(message.cols, message.rows);
+
+      const onControl = (controller: SessionController) => send({ type: "control", controller });
+      const listeners = controlListeners.get(id) ?? new Set();
+      listeners.add(onControl);
+      controlListeners.set(id, listeners);
+      const unwatch = watchSession(target, {
+        onInit: (data) => send({ type: "init", sessionId: id, data }),
+        onData: (data) => send({ type: "data", data }),
+        onResize: () => {},
+        onExit: (exitCode) => {
+          send({ type: "exit", exitCode });
+          socket.end();
+        },
+      });
+      stop = () => {
+        unwatch();
+        listeners.delete(onControl);
+      };
+      if (manager.getController(sessionId) === "human") onControl("human");
+    };
+
+    onLines(socket, (line) => {
+      let message: AttachClientMessage;
+      try {
+        message = JSON.parse(line) as AttachClientMessage;
+      } catch {
+        return;
+      }
+      if (message.type === "attach") {
+        if (!started) void start(message);
+        started = true;
+        return;
+      }
+      if (!session || !session.isActive()) return;
+      try {
+        if (message.type === "input") {
+          session.write(message.data);
+        } else if (message.type === "resize") {
+          session.resize(message.cols, message.rows);
+        } else if (message.type === "takeover") {
+          const next = manager.getController(sessionId) === "human" ? "shared" : "human";
+          tookOver = next === "human";
+          manager.setController(next, sessionId);
+        }
+      } catch (error) {
+        // e.g. input while the shell is restarting
+        send({ type: "error", message: (error as Error).message });
+      }
+    });
+  });
+
+  server.listen(socketPath, () => {
+    try {
+      fs.chmodSync(socketPath, 0o600);
+    } catch {
+      // The directory is private already
+    }
+  });
+  server.on("error", (error) => {
+    console.error(`[terminal-mcp] Cannot listen for attach clients on ${socketPath}: ${error.message}`);
+  });
+  process.on("exit", () => {
+    try {
+      fs.unlinkSync(socketPath);
+    } catch {
+      // Already gone
+    }
+  });
+  return server;
+}
+
+/**
+ * Pids of headless servers that can be attached to. Sockets of servers
+ * that are gone are removed.
+ */
+export function listAttachableServers(dir: string = getDefaultAttachDir()): number[] {
+  let files: string[];
+  try {
+    files = fs.readdirSync(dir);
+  } catch {
+    return [];
+  }
+  const pids: number[] = [];
+  for (const file of files) {
+    const match = /^(\d+)\.sock$/.exec(file);
+    if (!match) continue;
+    const pid = parseInt(match[1], 10);
+    try {
+      process.kill(pid, 0);
+      pids.push(pid);
+    } catch (error) {
+      if ((error as NodeJS.ErrnoException).code === "ESRCH") {
+        fs.rmSync(path.join(dir, file), { force: true });
+      } else {
+        pids.push(pid);
+      }
+    }
+  }
+  return pids.sort((a, b) => a - b);
+}
+
+export interface AttachOptions {
+  /** Server pid or socket path; the only running server when omitted */
+  target?: string;
+  /** Session to attach to (default: the default session) */
+  sessionId?: string;
+}
+
+/**
+ * Which socket to connect to, or an error message
+ */
+function resolveAttachSocket(target: string | undefined): string | { error: string } {
+  // An explicit socket path is the operator's own choice
+  if (target && !/^\d+$/.test(target)) return target;
+  const dir = getDefaultAttachDir();
+  const untrusted = fs.existsSync(dir) ? untrustedDirReason(dir) : null;
+  if (untrusted) return { error: `Refusing to attach: ${untrusted}` };
+  if (target) return attachSocketPath(parseInt(target, 10), dir);
+  const pids = listAttachableServers(dir);
+  if (pids.length === 0) {
+    return { error: "No headless terminal-mcp server is running (servers started with --no-attach can't be attached to)" };
+  }
+  if (pids.length > 1) {
+    return { error: `Several terminal-mcp servers are running; pick one: terminal-mcp attach <pid> (${pids.join(", ")})` };
+  }
+  return attachSocketPath(pids[0]);
+}
+
+/**
+ * The `terminal-mcp attach` subcommand. Resolves with the exit status once
+ * detached.
+ */
+export async function runAttach(options: AttachOptions): Promise<number> {
+  if (!process.stdin.isTTY || !process.stdout.isTTY) {
+    console.error("terminal-mcp attach needs an interactive terminal");
+    return 1;
+  }
+  const socketPath = resolveAttachSocket(options.target);
+  if (typeof socketPath !== "string") {
+    console.error(`[terminal-mcp] ${socketPath.error}`);
+    return 1;
+  }
+
+  return new Promise((resolve) => {
+    const socket = net.connect(socketPath);
+    const send = (message: AttachClientMessage) => {
+      if (!socket.destroyed) socket.write(frame(message));
+    };
+    const notice = (text: string) => process.stdout.write(`\r\n${YELLOW}[terminal-mcp] ${text}${RESET}\r\n`);
+    let status = 0;
+    let attached = false;
+
+    const onInput = (text: string) => {
+      if (text.includes(DETACH_KEY)) {
+        socket.end();
+        return;
+      }
+      if (text === TAKEOVER_KEY) {
+        send({ type: "takeover" });
+        return;
+      }
+      send({ type: "input", data: text });
+    };
+    const onResize = () => send({ type: "resize", cols: process.stdout.columns, rows: process.stdout.rows });
+
+    socket.on("connect", () => {
+      process.stdout.write(
+        `${YELLOW}[terminal-mcp] Attaching. Ctrl+] takes control from the agent; Ctrl+\\ detaches.${RESET}\r\n`
+      );
+      send({ type: "attach", sessionId: options.sessionId, cols: process.stdout.columns, rows: process.stdout.rows });
+      process.stdin.setRawMode(true);
+      // Decoded as a stream, so a character split between reads stays whole
+      process.stdin.setEncoding("utf-8");
+      process.stdin.resume();
+      process.stdin.on("data", onInput);
+      process.stdout.on("resize", onResize);
+    });
+
+    onLines(socket, (line) => {
+      let message: AttachServerMessage;
+      try {
+        message = JSON.parse(line) as AttachServerMessage;
+      } catch {
+        return;
+      }
+      switch (message.type) {
+        case "init":
+          attached = true;
+          // Start from a blank screen, then redraw the session's
+          process.stdout.write("\x1b[H\x1b[2J" + message.data);
+          break;
+        case "data":
+          process.stdout.write(message.data);
+          break;
+        case "control":
+          notice(
+            message.controller === "human"
+              ? "You have control — AI input is paused. Press Ctrl+] to hand control back."
+              : "Control handed back — AI input resumed."
+          );
+          break;
+        case "exit":
+          notice(`Shell exited with code ${message.exitCode}`);
+          break;
+        case "error":
+          if (!attached) status = 1;
+          notice(message.message);
+          break;
+      }
+    });
+
+    socket.on("error", (error: NodeJS.ErrnoException) => {
+      status = 1;
+      console.error(
+        error.code === "ENOENT" || error.code === "ECONNREFUSED"
+          ? `[terminal-mcp] No terminal-mcp server is listening on ${socketPath}`
+          : `[terminal-mcp] ${error.message}`
+      );
+    });
+    socket.on("close", () => {
+      process.stdin.off("data", onInput);
+      process.stdout.off("resize", onResize);
+      if (process.stdin.isRaw) process.stdin.setRawMode(false);
+      process.stdin.pause();
+      if (attached) {
+        process.stdout.write(RESTORE_TERMINAL);
+        notice("Detached; the session keeps running.");
+      }
+      resolve(status);
+    });
+  });
+}
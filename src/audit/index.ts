@@ -0,0 +1,127 @@
+import { createHash } from "crypto";
+import * as fs from "fs";
+import * as path from "path";
+
+/**
+ * Append-only JSONL audit log (--audit-log): tool calls, every byte written
+ * to a PTY, and the size and hash of what each call returned. Each record
+ * carries the SHA-256 of the line before it, so editing, removing, or
+ * reordering lines breaks the chain (see verifyAuditLog()).
+ */
+
+// `prev` of the first record in a file
+export const AUDIT_GENESIS_HASH = "0".repeat(64);
+
+export type AuditRecordType =
+  | "server_start"
+  | "server_stop"
+  | "session_start"
+  | "session_exit"
+  | "tool_call"
+  | "tool_result"
+  | "input";
+
+export interface AuditEntry {
+  type: AuditRecordType;
+  [key: string]: unknown;
+}
+
+// Tool arguments that hold secrets, by tool; their values never reach the log
+const SECRET_TOOL_ARGS: Record<string, string[]> = {
+  secureType: ["text"],
+};
+
+/**
+ * A tool call's arguments as they are logged, with secret values replaced
+ * by their length
+ */
+export function redactToolArgs(tool: string, args: unknown): unknown {
+  const secret = SECRET_TOOL_ARGS[tool];
+  if (!secret || typeof args !== "object" || args === null) return args;
+  const redacted: Record<string, unknown> = { ...(args as Record<string, unknown>) };
+  for (const key of secret) {
+    const value = redacted[key];
+    if (typeof value === "string") redacted[key] = `[redacted: ${value.length} characters]`;
+    else if (value !== undefined) redacted[key] = "[redacted]";
+  }
+  return redacted;
+}
+
+export function sha256(text: string): string {
+  return createHash("sha256").update(text).digest("hex");
+}
+
+export class AuditLog {
+  private fd: number;
+  private seq = 0;
+  private prev = AUDIT_GENESIS_HASH;
+
+  /**
+   * Open (or create) the log, continuing the hash chain of an existing
+   * file. Throws if the file can't be opened for appending.
+   */
+  constructor(readonly filePath: string) {
+    fs.mkdirSync(path.dirname(filePath), { recursive: true });
+    if (fs.existsSync(filePath)) {
+      const lines = fs.readFileSync(filePath, "utf8").split("\n").filter(Boolean);
+      const last = lines[lines.length - 1];
+      if (last) {
+        this.prev = sha256(last);
+        try {
+          this.seq = (JSON.parse(last) as { seq?: number }).seq ?? lines.length;
+        } catch {
+          this.seq = lines.length;
+        }
+      }
+    }
+    this.fd = fs.openSync(filePath, "a", 0o600);
+  }
+
+  /**
+   * Append one record. Written synchronously so records keep their order
+   * and survive a crash right after.
+   */
+  record(entry: AuditEntry): void {
+    const line = JSON.stringify({ seq: ++this.seq, ts: new Date().toISOString(), ...entry, prev: this.prev });
+    fs.writeSync(this.fd, line + "\n");
+    this.prev = sha256(line);
+  }
+
+  close(): void {
+    try {
+      fs.closeSync(this.fd);
+    } catch {
+      // Already closed
+    }
+  }
+}
+
+export interface AuditVerifyResult {
+  ok: boolean;
+  records: number;
+  /** 1-based line of the first broken link */
+  brokenAt?: number;
+  reason?: string;
+}
+
+/**
+ * Check that every record's `prev` is the hash of the line before it
+ */
+export function verifyAuditLog(filePath: string): AuditVerifyResult {
+  const lines = fs.readFileSync(filePath, "utf8").split("\n");
+  if (lines[lines.length - 1] === "") lines.pop();
+  let prev = AUDIT_GENESIS_HASH;
+  for (let i = 0; i < lines.length; i++) {
+    let record: { prev?: unknown };
+    try {
+      record = JSON.parse(lines[i]);
+    } catch {
+      return { ok: false, records: i, brokenAt: i + 1, reason: "not valid JSON" };
+    }
+    if (record.prev !== prev) {
+      return { ok: false, records: i, brokenAt: i + 1, reason: "hash chain broken (a line before it was changed, removed, or reordered)" };
+    }
+    prev = sha256(lines[i]);
+  }
+  return { ok: true, records: lines.length };
+}
@@ -5,25 +5,43 @@ import { VERSION } from "./utils/version.js";
 import {
   ListToolsRequestSchema,
   CallToolRequestSchema,
+  ListResourcesRequestSchema,
+  ListResourceTemplatesRequestSchema,
+  ReadResourceRequestSchema,
 } from "@modelcontextprotocol/sdk/types.js";
 import { toolDefinitions } from "./tools/definitions.js";
+import { resourceTemplates, type ResourceContents, type ResourceEntry } from "./resources/index.js";
+import { ToolExamplesRequestSchema, getToolExamples, withExamples } from "./tools/examples.js";
+import { withAnnotations } from "./tools/annotations.js";
+import { CompleteRequestSchema, type CompletionResult } from "./tools/completions.js";
+import { createInitGuard } from "./utils/initGuard.js";
+import { LOG_NOTIFICATION, registerLogging, type LogMessageParams } from "./logging/index.js";
 
 interface SocketRequest {
   id: number;
   method: string;
   params?: Record<string, unknown>;
+  progressToken?: string | number;
 }
 
 interface SocketResponse {
   id: number;
   result?: unknown;
-  error?: { message: string };
+  error?: { message: string; code?: number; data?: unknown };
+}
+
+interface SocketNotification {
+  method: string;
+  params?: Record<string, unknown>;
 }
 
 /**
  * MCP Client Mode - connects to existing terminal socket and serves MCP over stdio
  */
-export async function startMcpClientMode(socketPath: string): Promise<void> {
+export async function startMcpClientMode(
+  socketPath: string,
+  options: { strictInit?: boolean } = {}
+): Promise<void> {
   // Connect to the interactive terminal's socket
   const socket = await connectToSocket(socketPath);
 
@@ -36,10 +54,16 @@ export async function startMcpClientMode(socketPath: string): Promise<void> {
     {
       capabilities: {
         tools: {},
+        resources: {},
+        logging: {},
+        completions: {},
       },
     }
   );
 
+  const log = registerLogging(server);
+  const assertInitialized = createInitGuard(server, options.strictInit ?? true);
+
   // Request ID counter
   let requestId = 0;
 
@@ -52,7 +76,7 @@ export async function startMcpClientMode(socketPath: string): Promise<void> {
   // Buffer for incoming data
   let buffer = "";
 
-  // Handle responses from the interactive terminal
+  // Handle responses and pushed notifications from the interactive terminal
   socket.on("data", (data) => {
     buffer += data.toString();
     const lines = buffer.split("\n");
@@ -61,12 +85,24 @@ export async function startMcpClientMode(socketPath: string): Promise<void> {
     for (const line of lines) {
       if (line.trim()) {
         try {
-          const response = JSON.parse(line) as SocketResponse;
+          const message = JSON.parse(line) as SocketResponse | SocketNotification;
+          if ("method" in message) {
+            if (message.method === LOG_NOTIFICATION) {
+              log(message.params as unknown as LogMessageParams);
+            } else {
+              // Output streamed by subscribeOutput or progress of a call; relay to the MCP client
+              server.notification({ method: message.method, params: message.params }).catch(() => {});
+            }
+            continue;
+          }
+          const response = message;
           const pending = pendingRequests.get(response.id);
           if (pending) {
             pendingRequests.delete(response.id);
             if (response.error) {
-              pending.reject(new Error(response.error.message));
+              // A code means a JSON-RPC error (e.g. terminal busy); the SDK answers with it as is
+              const { message, code, data } = response.error;
+              pending.reject(code === undefined ? new Error(message) : Object.assign(new Error(message), { code, data }));
             } else {
               pending.resolve(response.result);
             }
@@ -91,10 +127,11 @@ export async function startMcpClientMode(socketPath: string): Promise<void> {
   // Helper to send request to interactive terminal
   async function sendRequest(
     method: string,
-    params?: Record<string, unknown>
+    params?: Record<string, unknown>,
+    progressToken?: string | number
   ): Promise<unknown> {
     const id = ++requestId;
-    const request: SocketRequest = { id, method, params };
+    const request: SocketRequest = { id, method, params, progressToken };
 
     return new Promise((resolve, reject) => {
       pendingRequests.set(id, { resolve, reject });
@@ -109,20 +146,28 @@ export async function startMcpClientMode(socketPath: string): Promise<void> {
 
   // Register list tools handler
   server.setRequestHandler(ListToolsRequestSchema, async () => ({
-    tools: toolDefinitions,
+    tools: withExamples(withAnnotations(toolDefinitions)),
+  }));
+
+  // Extension method: worked argument/result examples per tool
+  server.setRequestHandler(ToolExamplesRequestSchema, async (request) => ({
+    examples: getToolExamples(request.params?.name),
   }));
 
   // Register call tool handler - proxy to socket
   server.setRequestHandler(CallToolRequestSchema, async (request) => {
+    assertInitialized();
     const { name, arguments: args } = request.params;
 
     try {
-      const result = await sendRequest(name, args as Record<string, unknown>);
+      const result = await sendRequest(name, args as Record<string, unknown>, request.params._meta?.progressToken);
       return result as {
         content: Array<{ type: "text"; text: string }>;
+        structuredContent?: Record<string, unknown>;
         isError?: boolean;
       };
     } catch (error) {
+      if (typeof (error as { code?: unknown }).code === "number") throw error;
       const message = error instanceof Error ? error.message : String(error);
       return {
         content: [
@@ -136,6 +181,24 @@ export async function startMcpClientMode(socketPath: string): Promise<void> {
     }
   });
 
+  // Resources - templates are static, listing and reading proxy to socket
+  server.setRequestHandler(ListResourcesRequestSchema, async () =>
+    (await sendRequest("listResources")) as { resources: ResourceEntry[] }
+  );
+
+  server.setRequestHandler(ListResourceTemplatesRequestSchema, async () => ({
+    resourceTemplates,
+  }));
+
+  server.setRequestHandler(ReadResourceRequestSchema, async (request) =>
+    (await sendRequest("readResource", { uri: request.params.uri })) as ResourceContents
+  );
+
+  // Argument completion needs the live session list, so it proxies too
+  server.setRequestHandler(CompleteRequestSchema, async (request) =>
+    (await sendRequest("complete", request.params)) as CompletionResult
+  );
+
   // Connect MCP server to stdio
   const transport = new StdioServerTransport();
   await server.connect(transport);
@@ -0,0 +1,176 @@
+import * as fs from "fs";
+import * as os from "os";
+import * as path from "path";
+import type { TerminalSession } from "../terminal/session.js";
+import { FALLBACK_SHELL, checkShell, getDefaultRecordDir, getDefaultShell, getDefaultSocketPath, resolveExecutable } from "../utils/platform.js";
+
+export interface DoctorOptions {
+  shell?: string;
+  socket?: string;
+  json: boolean;
+}
+
+interface Check {
+  name: string;
+  status: "ok" | "warn" | "fail";
+  detail: string;
+}
+
+const STATUS_ICON: Record<Check["status"], string> = {
+  ok: "✓",
+  warn: "!",
+  fail: "✗",
+};
+
+// How long the test shell gets to print its first output
+const SPAWN_TIMEOUT_MS = 5000;
+const MIN_NODE_MAJOR = 18;
+
+function checkNode(): Check {
+  const major = parseInt(process.versions.node.split(".")[0], 10);
+  return major >= MIN_NODE_MAJOR
+    ? { name: "Node.js", status: "ok", detail: `v${process.versions.node}` }
+    : { name: "Node.js", status: "fail", detail: `v${process.versions.node}; ${MIN_NODE_MAJOR} or newer is required` };
+}
+
+function checkConfiguredShell(shell: string): Check {
+  const problem = checkShell(shell);
+  if (!problem) {
+    return { name: "Shell", status: "ok", detail: resolveExecutable(shell) ?? shell };
+  }
+  if (process.platform === "win32" || checkShell(FALLBACK_SHELL)) {
+    return { name: "Shell", status: "fail", detail: `'${shell}' ${problem}, and there is no usable fallback` };
+  }
+  return { name: "Shell", status: "warn", detail: `'${shell}' ${problem}; sessions will use ${FALLBACK_SHELL} instead` };
+}
+
+/**
+ * Load node-pty and start the shell the server would, through the same
+ * session code, then wait for it to print something
+ */
+async function checkSpawn(shell: string): Promise<Check> {
+  // Imported here so a broken native module is reported, not a crash
+  let sessionModule: typeof import("../terminal/session.js");
+  try {
+    sessionModule = await import("../terminal/session.js");
+  } catch (error) {
+    return {
+      name: "PTY",
+      status: "fail",
+      detail: `node-pty failed to load: ${(error as Error).message}. Reinstall terminal-mcp so its native module is rebuilt for Node ${process.versions.node}.`,
+    };
+  }
+
+  const startedAt = Date.now();
+  let session: TerminalSession;
+  try {
+    session = await sessionModule.TerminalSession.create({ shell, cols: 80, rows: 24, snapshotHistory: 0 });
+  } catch (error) {
+    return { name: "PTY", status: "fail", detail: (error as Error).message };
+  }
+
+  try {
+    const output = await new Promise<boolean>((resolve) => {
+      const timer = setTimeout(() => resolve(false), SPAWN_TIMEOUT_MS);
+      const check = () => {
+        if (session.getContent().trim()) {
+          clearTimeout(timer);
+          resolve(true);
+          return true;
+        }
+        return false;
+      };
+      if (!check()) {
+        const unsubscribe = session.onData(() => {
+          if (check()) unsubscribe();
+        });
+      }
+    });
+    const elapsed = Date.now() - startedAt;
+    if (!output) {
+      return {
+        name: "PTY",
+        status: "warn",
+        detail: `${session.getShell()} started (pid ${session.getPid()}) but showed nothing within ${SPAWN_TIMEOUT_MS / 1000}s; check its rc files`,
+      };
+    }
+    return {
+      name: "PTY",
+      status: "ok",
+      detail: `${session.getShell()} started (pid ${session.getPid()}) and drew a screen in ${elapsed}ms`,
+    };
+  } finally {
+    await session.shutdown();
+  }
+}
+
+function checkLocale(): Check {
+  const locale = process.env.LC_ALL || process.env.LC_CTYPE || process.env.LANG || "";
+  if (process.platform === "win32" || /utf-?8/i.test(locale)) {
+    return { name: "Locale", status: "ok", detail: locale || "default" };
+  }
+  return {
+    name: "Locale",
+    status: "warn",
+    detail: `${locale || "unset"}; not UTF-8, so programs may draw box characters and the prompt indicator as '?'. Set LANG=en_US.UTF-8 or similar.`,
+  };
+}
+
+/**
+ * A directory can be written if it exists and is writable, or its nearest
+ * existing ancestor is
+ */
+function checkWritableDir(name: string, dir: string): Check {
+  let existing = dir;
+  while (!fs.existsSync(existing)) {
+    const parent = path.dirname(existing);
+    if (parent === existing) break;
+    existing = parent;
+  }
+  try {
+    fs.accessSync(existing, fs.constants.W_OK);
+    return { name, status: "ok", detail: dir };
+  } catch {
+    return { name, status: "warn", detail: `${dir} is not writable (${existing} denies access)` };
+  }
+}
+
+function checkSocket(socketPath: string): Check {
+  if (process.platform === "win32") {
+    return { name: "Socket", status: "ok", detail: socketPath };
+  }
+  if (fs.existsSync(socketPath)) {
+    return {
+      name: "Socket",
+      status: "ok",
+      detail: `${socketPath} exists (an interactive terminal-mcp is running, or a stale socket will be replaced)`,
+    };
+  }
+  return checkWritableDir("Socket", path.dirname(socketPath));
+}
+
+export async function runDoctor(opts: DoctorOptions): Promise<number> {
+  const shell = opts.shell ?? getDefaultShell();
+  const checks: Check[] = [
+    { name: "Platform", status: "ok", detail: `${process.platform} ${os.release()} (${process.arch})` },
+    checkNode(),
+    checkConfiguredShell(shell),
+    await checkSpawn(shell),
+    checkLocale(),
+    checkSocket(opts.socket ?? getDefaultSocketPath()),
+    checkWritableDir("Recordings", getDefaultRecordDir()),
+  ];
+  const failed = checks.some((check) => check.status === "fail");
+
+  if (opts.json) {
+    console.log(JSON.stringify({ ok: !failed, checks }, null, 2));
+  } else {
+    for (const check of checks) {
+      console.log(`${STATUS_ICON[check.status]} ${check.name.padEnd(12)} ${check.detail}`);
+    }
+    console.log();
+    console.log(failed ? "Problems found; terminal-mcp will not work until they are fixed." : "terminal-mcp should work here.");
+  }
+
+  return failed ? 1 : 0;
+}
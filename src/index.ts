@@ -3,12 +3,54 @@
 import * as fs from "fs";
 import { createRequire } from "module";
 import updateNotifier from "update-notifier";
-import { startServer } from "./server.js";
+import { startServer, MCP_TRANSPORT_TYPES, type McpTransportType } from "./server.js";
 import { startMcpClientMode } from "./client.js";
-import { TerminalManager } from "./terminal/index.js";
-import { createToolProxyServer } from "./transport/index.js";
+import { runSupervisor, SUPERVISED_ENV } from "./supervisor/index.js";
+import {
+  TerminalManager,
+  loadQueryResponses,
+  parseColor,
+  DEFAULT_DANGER_RULES,
+  compileDangerRules,
+  type DangerRule,
+  type QueryResponses,
+  type RestartMode,
+  type TerminalSession,
+} from "./terminal/index.js";
+import {
+  createToolProxyServer,
+  parseListenAddress,
+  isLoopbackHost,
+  DEFAULT_LISTEN_HOST,
+  DEFAULT_LISTEN_PORT,
+  type ListenAddress,
+  type TlsOptions,
+} from "./transport/index.js";
+import { AuditLog } from "./audit/index.js";
+import { OutputMirror } from "./terminal/mirror.js";
+import { DEFAULT_OBSERVE_PORT } from "./observe/index.js";
+import { parseToolTimeouts } from "./utils/toolLimits.js";
+import { parseInputQuota, type RateLimits } from "./utils/rateLimit.js";
+import { checkResourceLimits, parseByteSize, type ResourceLimits } from "./utils/resourceLimits.js";
+import {
+  BACKEND_TYPES,
+  FLOOD_POLICIES,
+  type BackendConfig,
+  type BackendType,
+  type FloodPolicy,
+} from "./terminal/backend.js";
+import { parseSshTarget } from "./terminal/ssh.js";
+import { checkTmuxSessionName } from "./terminal/tmux.js";
+import { PERSIST_HOLDER_COMMAND, checkPersistName, type PersistTarget } from "./terminal/persist.js";
 import { getBanner } from "./ui/index.js";
-import { getDefaultSocketPath, getDefaultShell, getDefaultRecordDir } from "./utils/platform.js";
+import {
+  getDefaultSocketPath,
+  getDefaultShell,
+  getDefaultRecordDir,
+  getDefaultPersistDir,
+  resolveExecutable,
+} from "./utils/platform.js";
+import { loadEnvFile, parseEnvAssignment, type EnvPolicy } from "./utils/env.js";
 import {
   SandboxController,
   loadConfigFromFile,
@@ -16,6 +58,13 @@ import {
   DEFAULT_PERMISSIONS,
   type SandboxPermissions,
 } from "./sandbox/index.js";
+import {
+  Notifier,
+  loadNotifyConfig,
+  parseEventList,
+  type NotificationEventType,
+  type NotificationSinkConfig,
+} from "./notifications/index.js";
 
 const require = createRequire(import.meta.url);
 const pkg = require("../package.json");
@@ -24,6 +73,11 @@ const { version } = pkg;
 // Default socket path
 const DEFAULT_SOCKET_PATH = getDefaultSocketPath();
 
+// Ctrl+] — toggles exclusive human control in interactive mode
+const TAKEOVER_KEY = "\x1d";
+const YELLOW = "\x1b[33m";
+const RESET = "\x1b[0m";
+
 // Recording mode type
 type RecordingMode = 'always' | 'on-failure' | 'off';
 
@@ -65,6 +119,207 @@ Examples:
   }
   process.exit(await runSetup(setupOpts));
 }
+if (subcommand === "replay") {
+  const { runReplay } = await import("./recording/replay.js");
+  const replayOpts = {
+    file: "",
+    frames: false,
+    frameGap: 0.5,
+    at: undefined as number | undefined,
+    format: "text" as "text" | "json" | "html" | "svg",
+    cols: undefined as number | undefined,
+    rows: undefined as number | undefined,
+  };
+  for (let i = 1; i < subcommandArgs.length; i++) {
+    const a = subcommandArgs[i];
+    const n = subcommandArgs[i + 1];
+    if (a === "--frames") replayOpts.frames = true;
+    else if (a === "--frame-gap" && n) { replayOpts.frameGap = parseFloat(n); i++; }
+    else if (a === "--at" && n) { replayOpts.at = parseFloat(n); i++; }
+    else if (a === "--format" && n && ["text", "json", "html", "svg"].includes(n)) { replayOpts.format = n as typeof replayOpts.format; i++; }
+    else if (a === "--cols" && n) { replayOpts.cols = parseInt(n, 10); i++; }
+    else if (a === "--rows" && n) { replayOpts.rows = parseInt(n, 10); i++; }
+    else if (a === "--help" || a === "-h") {
+      console.log(`
+terminal-mcp replay — render what a recorded session looked like
+
+Usage: terminal-mcp replay <file> [options]
+
+Feeds an asciicast (.cast, v2 or v3) or raw PTY log through the terminal
+emulator and prints the rendered screen. Input events are ignored.
+
+Options:
+  --frames           Print a frame after each burst of output, not just the final screen
+  --frame-gap <sec>  Output pause that ends a frame (default: 0.5)
+  --at <sec>         Stop at this point in the recording and print that screen
+  --format <f>       text (default), json, html, or svg
+  --cols <n>         Override width (raw logs default to 120)
+  --rows <n>         Override height (raw logs default to 40)
+  --help, -h         Show this help.
+
+Examples:
+  terminal-mcp replay session.cast
+  terminal-mcp replay session.cast --at 42.5
+  terminal-mcp replay session.cast --frames --frame-gap 2
+  terminal-mcp replay pty.log --cols 80 --rows 24
+`);
+      process.exit(0);
+    }
+    else if (!a.startsWith("-") && !replayOpts.file) replayOpts.file = a;
+  }
+  if (!replayOpts.file) {
+    console.error("Usage: terminal-mcp replay <file> [options] (see --help)");
+    process.exit(1);
+  }
+  process.exit(await runReplay(replayOpts));
+}
+if (subcommand === "render") {
+  const { runRender } = await import("./render/index.js");
+  const { THEME_NAMES } = await import("./utils/render.js");
+  const renderOpts = {
+    input: undefined as string | undefined,
+    output: undefined as string | undefined,
+    format: "text" as "text" | "json" | "html" | "svg" | "png",
+    cols: 120,
+    rows: 40,
+    theme: undefined as (typeof THEME_NAMES)[number] | undefined,
+    convertEol: true,
+  };
+  for (let i = 1; i < subcommandArgs.length; i++) {
+    const a = subcommandArgs[i];
+    const n = subcommandArgs[i + 1];
+    if (a === "--format" && n && ["text", "json", "html", "svg", "png"].includes(n)) { renderOpts.format = n as typeof renderOpts.format; i++; }
+    else if (a === "--cols" && n) { renderOpts.cols = parseInt(n, 10); i++; }
+    else if (a === "--rows" && n) { renderOpts.rows = parseInt(n, 10); i++; }
+    else if (a === "--theme" && n && (THEME_NAMES as string[]).includes(n)) { renderOpts.theme = n as typeof renderOpts.theme; i++; }
+    else if ((a === "--output" || a === "-o") && n) { renderOpts.output = n; i++; }
+    else if (a === "--no-convert-eol") renderOpts.convertEol = false;
+    else if (a === "--help" || a === "-h") {
+      console.log(`
+terminal-mcp render — render captured ANSI output as a terminal screen
+
+Usage: terminal-mcp render [file] [options]
+       <command> | terminal-mcp render [options]
+
+Runs the input through the same terminal emulator the MCP server uses and
+writes the resulting screen. Reads stdin when no file is given.
+
+Options:
+  --format <f>        text (default), json, html, svg, or png
+  --cols <n>          Screen width (default: 120)
+  --rows <n>          Screen height (default: 40); earlier output scrolls off
+  --theme <name>      Color theme for html/svg/png: ${THEME_NAMES.join(", ")}
+  --output, -o <file> Write to a file instead of stdout (required for png on a TTY)
+  --no-convert-eol    Don't treat bare LF as CRLF (use for raw PTY captures)
+  --help, -h          Show this help.
+
+Examples:
+  terminal-mcp render --cols 120 --rows 40 --format html < output.ansi > screen.html
+  ls --color=always | terminal-mcp render --format png -o ls.png
+`);
+      process.exit(0);
+    }
+    else if (!a.startsWith("-") && !renderOpts.input) renderOpts.input = a;
+  }
+  process.exit(await runRender(renderOpts));
+}
+if (subcommand === "doctor") {
+  const { runDoctor } = await import("./doctor/index.js");
+  const doctorOpts = { shell: undefined as string | undefined, socket: undefined as string | undefined, json: false };
+  for (let i = 1; i < subcommandArgs.length; i++) {
+    const a = subcommandArgs[i];
+    const n = subcommandArgs[i + 1];
+    if (a === "--shell" && n) { doctorOpts.shell = n; i++; }
+    else if (a === "--socket" && n) { doctorOpts.socket = n; i++; }
+    else if (a === "--json") doctorOpts.json = true;
+    else if (a === "--help" || a === "-h") {
+      console.log(`
+terminal-mcp doctor — check that terminal-mcp can run here
+
+Usage: terminal-mcp doctor [options]
+
+Test-spawns a shell on a PTY the way the server does and reports problems:
+a shell that is missing or not executable, node-pty failing to load, a shell
+that starts but never draws anything, a non-UTF-8 locale, and socket or
+recording directories that can't be written.
+
+Options:
+  --shell <path>     Shell to check (default: $SHELL or bash)
+  --socket <path>    Socket path to check (default: ${getDefaultSocketPath()})
+  --json             Print the results as JSON
+  --help, -h         Show this help.
+
+Exits with status 1 if any check fails.
+`);
+      process.exit(0);
+    }
+  }
+  process.exit(await runDoctor(doctorOpts));
+}
+if (subcommand === "attach") {
+  const { runAttach } = await import("./attach/index.js");
+  const attachOpts = { target: undefined as string | undefined, sessionId: undefined as string | undefined };
+  for (let i = 1; i < subcommandArgs.length; i++) {
+    const a = subcommandArgs[i];
+    const n = subcommandArgs[i + 1];
+    if (a === "--session" && n) { attachOpts.sessionId = n; i++; }
+    else if (a === "--help" || a === "-h") {
+      console.log(`
+terminal-mcp attach — connect this terminal to a running headless server's shell
+
+Usage: terminal-mcp attach [pid | socket] [options]
+
+Shows the session's screen and scrollback, then passes your keystrokes and
+window size through, so you can watch the agent, type alongside it, or
+rescue a stuck session. With several headless servers running, give the
+pid of the one to attach to.
+
+Keys:
+  Ctrl+]             Take control: the agent's input is refused until you press it again
+  Ctrl+\\             Detach; the session keeps running
+
+Options:
+  --session <id>     Session to attach to (default: the default session)
+  --help, -h         Show this help.
+`);
+      process.exit(0);
+    }
+    else if (!a.startsWith("-") && !attachOpts.target) attachOpts.target = a;
+  }
+  process.exit(await runAttach(attachOpts));
+}
+if (subcommand === PERSIST_HOLDER_COMMAND) {
+  // Started by --persist; owns the shell and runs until it exits
+  const { runPersistHolder } = await import("./terminal/persist.js");
+  await runPersistHolder(subcommandArgs[1]);
+}
+if (subcommand === "verify-audit") {
+  const { verifyAuditLog } = await import("./audit/index.js");
+  const file = subcommandArgs[1];
+  if (!file || file === "--help" || file === "-h") {
+    console.log(`
+terminal-mcp verify-audit — check an --audit-log file's hash chain
+
+Usage: terminal-mcp verify-audit <file>
+
+Exits with status 1 at the first line that was changed, removed, or
+reordered. Lines cut off the end can't be detected this way; compare the
+last record's hash with one kept elsewhere.
+`);
+    process.exit(file ? 0 : 1);
+  }
+  try {
+    const result = verifyAuditLog(file);
+    if (result.ok) {
+      console.log(`${file}: OK (${result.records} records)`);
+      process.exit(0);
+    }
+    console.error(`${file}:${result.brokenAt}: ${result.reason}`);
+  } catch (error) {
+    console.error(`[terminal-mcp] Could not read ${file}: ${(error as Error).message}`);
+  }
+  process.exit(1);
+}
 
 // Parse command line arguments
 const args = process.argv.slice(2);
@@ -72,8 +327,46 @@ const options: {
   cols?: number;
   rows?: number;
   shell?: string;
+  login?: boolean;
+  shellArgs?: string[];
+  initCommands?: string[];
+  initFile?: string;
+  env?: string[];
+  envFiles?: string[];
+  inheritEnv?: boolean;
+  envAllow?: string[];
+  envDeny?: string[];
+  envDenySecrets?: boolean;
+  limitCpu?: string;
+  limitMemory?: string;
+  limitProcs?: string;
+  limitFsize?: string;
+  cgroup?: boolean;
+  backend?: string;
+  host?: string;
+  sshIdentity?: string;
+  sshKnownHosts?: string;
+  image?: string;
+  container?: string;
+  tmuxSession?: string;
+  tmuxSocket?: string;
+  persist?: string;
+  confirmDangerous?: boolean;
+  confirmPatterns?: string[];
+  auditLog?: string;
+  mirror?: string;
   socket?: string;
+  transport?: string;
+  listen?: string;
+  tlsCert?: string;
+  tlsKey?: string;
+  authToken?: string;
+  authTokenFile?: string;
+  allowOrigins?: string[];
+  observe?: string;
+  attach?: boolean;
   headless?: boolean;
+  supervise?: boolean;
   sandbox?: boolean;
   sandboxConfig?: string;
   record?: RecordingMode;
@@ -82,8 +375,36 @@ const options: {
   idleTimeLimit?: number;
   maxDuration?: number;
   inactivityTimeout?: number;
+  recordInput?: boolean;
   maxSessions?: number;
   sessionIdleTimeout?: number;
+  idleTimeout?: number;
+  maxLifetime?: number;
+  exitOnLimit?: boolean;
+  promptPattern?: string;
+  procTracking?: boolean;
+  readGuardMs?: number;
+  toolTimeouts?: string[];
+  busyTimeout?: number;
+  rateLimitCalls?: string;
+  rateLimitInput?: string;
+  snapshotHistory?: number;
+  floodPolicy?: string;
+  snapshotInterval?: number;
+  fontDirs?: string[];
+  fallbackFonts?: string[];
+  envRedact?: string[];
+  envReveal?: boolean;
+  notifyWebhooks?: string[];
+  notifySlack?: string[];
+  notifyEvents?: string;
+  notifyConfig?: string;
+  queryResponses?: string;
+  foreground?: string;
+  background?: string;
+  strictInit?: boolean;
+  restartOnExit?: RestartMode;
+  command?: string[];
 } = {};
 
 for (let i = 0; i < args.length; i++) {
@@ -91,6 +412,11 @@ for (let i = 0; i < args.length; i++) {
   const next = args[i + 1];
 
   switch (arg) {
+    case "--":
+      // Everything after -- is the command to run instead of a shell
+      options.command = args.slice(i + 1);
+      i = args.length;
+      break;
     case "--cols":
       if (next) {
         options.cols = parseInt(next, 10);
@@ -109,15 +435,235 @@ for (let i = 0; i < args.length; i++) {
         i++;
       }
       break;
+    case "--login":
+      options.login = true;
+      break;
+    case "--shell-arg":
+      // Values usually start with '-', so take the next argument as is
+      if (next !== undefined) {
+        options.shellArgs = [...(options.shellArgs ?? []), next];
+        i++;
+      }
+      break;
+    case "--init-command":
+      if (next) {
+        options.initCommands = [...(options.initCommands ?? []), next];
+        i++;
+      }
+      break;
+    case "--init-file":
+      if (next) {
+        options.initFile = next;
+        i++;
+      }
+      break;
+    case "--env":
+      if (next) {
+        options.env = [...(options.env ?? []), next];
+        i++;
+      }
+      break;
+    case "--env-file":
+      if (next) {
+        options.envFiles = [...(options.envFiles ?? []), next];
+        i++;
+      }
+      break;
+    case "--no-inherit-env":
+      options.inheritEnv = false;
+      break;
+    case "--env-allow":
+      if (next) {
+        options.envAllow = [
+          ...(options.envAllow ?? []),
+          ...next.split(",").map((p) => p.trim()).filter(Boolean),
+        ];
+        i++;
+      }
+      break;
+    case "--env-deny":
+      if (next) {
+        options.envDeny = [
+          ...(options.envDeny ?? []),
+          ...next.split(",").map((p) => p.trim()).filter(Boolean),
+        ];
+        i++;
+      }
+      break;
+    case "--env-deny-secrets":
+      options.envDenySecrets = true;
+      break;
+    case "--limit-cpu":
+      if (next) {
+        options.limitCpu = next;
+        i++;
+      }
+      break;
+    case "--limit-memory":
+      if (next) {
+        options.limitMemory = next;
+        i++;
+      }
+      break;
+    case "--limit-procs":
+      if (next) {
+        options.limitProcs = next;
+        i++;
+      }
+      break;
+    case "--limit-fsize":
+      if (next) {
+        options.limitFsize = next;
+        i++;
+      }
+      break;
+    case "--cgroup":
+      options.cgroup = true;
+      break;
+    case "--backend":
+      if (next) {
+        options.backend = next;
+        i++;
+      }
+      break;
+    case "--host":
+      if (next) {
+        options.host = next;
+        i++;
+      }
+      break;
+    case "--ssh-identity":
+      if (next) {
+        options.sshIdentity = next;
+        i++;
+      }
+      break;
+    case "--ssh-known-hosts":
+      if (next) {
+        options.sshKnownHosts = next;
+        i++;
+      }
+      break;
+    case "--image":
+      if (next) {
+        options.image = next;
+        i++;
+      }
+      break;
+    case "--container":
+      if (next) {
+        options.container = next;
+        i++;
+      }
+      break;
+    case "--tmux-session":
+      if (next) {
+        options.tmuxSession = next;
+        i++;
+      }
+      break;
+    case "--tmux-socket":
+      if (next) {
+        options.tmuxSocket = next;
+        i++;
+      }
+      break;
+    case "--persist":
+      // Bare --persist uses the name 'default'
+      if (next && !next.startsWith("-")) {
+        options.persist = next;
+        i++;
+      } else {
+        options.persist = "default";
+      }
+      break;
+    case "--confirm-dangerous":
+      options.confirmDangerous = true;
+      break;
+    case "--confirm-pattern":
+      if (next) {
+        options.confirmPatterns = [...(options.confirmPatterns ?? []), next];
+        i++;
+      }
+      break;
+    case "--audit-log":
+      if (next) {
+        options.auditLog = next;
+        i++;
+      }
+      break;
+    case "--mirror":
+      if (next) {
+        options.mirror = next;
+        i++;
+      }
+      break;
     case "--socket":
       if (next) {
         options.socket = next;
         i++;
       }
       break;
+    case "--transport":
+      if (next) {
+        options.transport = next;
+        i++;
+      }
+      break;
+    case "--listen":
+      if (next) {
+        options.listen = next;
+        i++;
+      }
+      break;
+    case "--observe":
+      // Bare --observe serves on loopback at the default port
+      if (next && !next.startsWith("-")) {
+        options.observe = next;
+        i++;
+      } else {
+        options.observe = String(DEFAULT_OBSERVE_PORT);
+      }
+      break;
+    case "--no-attach":
+      options.attach = false;
+      break;
+    case "--tls-cert":
+      if (next) {
+        options.tlsCert = next;
+        i++;
+      }
+      break;
+    case "--tls-key":
+      if (next) {
+        options.tlsKey = next;
+        i++;
+      }
+      break;
+    case "--auth-token":
+      if (next) {
+        options.authToken = next;
+        i++;
+      }
+      break;
+    case "--auth-token-file":
+      if (next) {
+        options.authTokenFile = next;
+        i++;
+      }
+      break;
+    case "--allow-origin":
+      if (next) {
+        options.allowOrigins = [...(options.allowOrigins ?? []), next];
+        i++;
+      }
+      break;
     case "--headless":
       options.headless = true;
       break;
+    case "--supervise":
+      options.supervise = true;
+      break;
     case "--sandbox":
       options.sandbox = true;
       break;
@@ -170,18 +716,171 @@ for (let i = 0; i < args.length; i++) {
         i++;
       }
       break;
+    case "--record-input":
+      options.recordInput = true;
+      break;
     case "--max-sessions":
       if (next) {
         options.maxSessions = parseInt(next, 10);
         i++;
       }
       break;
+    case "--idle-timeout":
+      if (next) {
+        options.idleTimeout = parseFloat(next);
+        i++;
+      }
+      break;
+    case "--max-lifetime":
+      if (next) {
+        options.maxLifetime = parseFloat(next);
+        i++;
+      }
+      break;
+    case "--exit-on-limit":
+      options.exitOnLimit = true;
+      break;
     case "--session-idle-timeout":
       if (next) {
         options.sessionIdleTimeout = parseFloat(next);
         i++;
       }
       break;
+    case "--prompt-pattern":
+      if (next) {
+        options.promptPattern = next;
+        i++;
+      }
+      break;
+    case "--read-guard":
+      if (next) {
+        options.readGuardMs = parseInt(next, 10);
+        i++;
+      }
+      break;
+    case "--tool-timeout":
+      if (next) {
+        options.toolTimeouts = [...(options.toolTimeouts ?? []), next];
+        i++;
+      }
+      break;
+    case "--busy-timeout":
+      if (next) {
+        options.busyTimeout = parseInt(next, 10);
+        i++;
+      }
+      break;
+    case "--rate-limit-calls":
+      if (next) {
+        options.rateLimitCalls = next;
+        i++;
+      }
+      break;
+    case "--rate-limit-input":
+      if (next) {
+        options.rateLimitInput = next;
+        i++;
+      }
+      break;
+    case "--flood-policy":
+      if (next) {
+        options.floodPolicy = next;
+        i++;
+      }
+      break;
+    case "--snapshot-history":
+      if (next) {
+        options.snapshotHistory = parseInt(next, 10);
+        i++;
+      }
+      break;
+    case "--snapshot-interval":
+      if (next) {
+        options.snapshotInterval = parseFloat(next);
+        i++;
+      }
+      break;
+    case "--proc-tracking":
+      options.procTracking = true;
+      break;
+    case "--font-dir":
+      if (next) {
+        options.fontDirs = [...(options.fontDirs ?? []), next];
+        i++;
+      }
+      break;
+    case "--fallback-fonts":
+      if (next) {
+        options.fallbackFonts = next.split(",").map((f) => f.trim()).filter(Boolean);
+        i++;
+      }
+      break;
+    case "--env-redact":
+      if (next) {
+        options.envRedact = [
+          ...(options.envRedact ?? []),
+          ...next.split(",").map((p) => p.trim()).filter(Boolean),
+        ];
+        i++;
+      }
+      break;
+    case "--env-reveal":
+      options.envReveal = true;
+      break;
+    case "--restart-on-exit":
+      // Bare --restart-on-exit keeps scrollback
+      if (next === "keep" || next === "clear") {
+        options.restartOnExit = next;
+        i++;
+      } else {
+        options.restartOnExit = "keep";
+      }
+      break;
+    case "--no-strict-init":
+      options.strictInit = false;
+      break;
+    case "--notify-webhook":
+      if (next) {
+        options.notifyWebhooks = [...(options.notifyWebhooks ?? []), next];
+        i++;
+      }
+      break;
+    case "--notify-slack":
+      if (next) {
+        options.notifySlack = [...(options.notifySlack ?? []), next];
+        i++;
+      }
+      break;
+    case "--notify-events":
+      if (next) {
+        options.notifyEvents = next;
+        i++;
+      }
+      break;
+    case "--notify-config":
+      if (next) {
+        options.notifyConfig = next;
+        i++;
+      }
+      break;
+    case "--query-responses":
+      if (next) {
+        options.queryResponses = next;
+        i++;
+      }
+      break;
+    case "--foreground":
+      if (next) {
+        options.foreground = next;
+        i++;
+      }
+      break;
+    case "--background":
+      if (next) {
+        options.background = next;
+        i++;
+      }
+      break;
     case "--version":
     case "-v":
       console.log(`terminal-mcp v${version}`);
@@ -192,18 +891,106 @@ for (let i = 0; i < args.length; i++) {
 terminal-mcp v${version} - A headless terminal emulator exposed via MCP
 
 Usage: terminal-mcp [options]
+       terminal-mcp [options] -- <command> [args...]   Run a program instead of a shell
        terminal-mcp setup [options]   Install MCP entry into AI tool configs (run 'terminal-mcp setup --help')
+       terminal-mcp replay <file>     Render the screen(s) from a recording (run 'terminal-mcp replay --help')
+       terminal-mcp render [file]     Render captured ANSI output to text/HTML/SVG/PNG (run 'terminal-mcp render --help')
+       terminal-mcp doctor            Check that shells can be spawned here (run 'terminal-mcp doctor --help')
+       terminal-mcp attach [pid]      Connect this terminal to a headless server's shell (run 'terminal-mcp attach --help')
+       terminal-mcp verify-audit <file> Check that an --audit-log file hasn't been edited
 
 Options:
   --cols <number>        Terminal width in columns (default: auto or 120)
   --rows <number>        Terminal height in rows (default: auto or 40)
   --shell <path>         Shell to use (default: $SHELL or bash)
+  --login                Start the shell as a login shell (reads /etc/profile and ~/.profile etc.)
+  --shell-arg <arg>      Extra argument for the shell (repeatable), e.g. --shell-arg --noprofile
+  --init-command <cmd>   Type this into every new shell before it's used (repeatable)
+  --init-file <path>     Init commands from a file, one per line (# comments); run before --init-command
+  --env <KEY=VALUE>      Set a variable in the shell's environment (repeatable)
+  --env-file <path>      Variables from a dotenv file (repeatable); --env wins over files
+  --no-inherit-env       Don't pass this process's environment to shells, except HOME, PATH,
+                         USER, SHELL, locale, and TMPDIR
+  --env-allow <list>     Inherit only these variables plus the essentials (globs, e.g. 'NODE_*,EDITOR')
+  --env-deny <list>      Never inherit these variables (globs, e.g. 'GITHUB_TOKEN,OPENAI_*')
+  --env-deny-secrets     Never inherit credential-like variables (AWS_*, *_TOKEN, *_API_KEY, ...)
+  --limit-cpu <sec>      CPU seconds each process in the shell may use
+  --limit-memory <size>  Address space per process (e.g. 2G; bare numbers are MB), or memory for the
+                         whole shell with --cgroup
+  --limit-procs <n>      Processes your user may have, or processes in the shell with --cgroup
+  --limit-fsize <size>   Largest file a process in the shell may write (e.g. 1G)
+  --cgroup               Run each shell in its own cgroup via systemd-run (Linux), so --limit-memory
+                         and --limit-procs cover the shell's whole process tree
+  --backend <type>       Where shells run: local (default), ssh, docker, or tmux
+  --host <[user@]host[:port]> Remote host for --backend ssh (--shell names the remote shell,
+                         default bash)
+  --ssh-identity <path>  Private key for --backend ssh (default: ssh-agent, then ~/.ssh/id_*)
+  --ssh-known-hosts <path> Host keys to trust (default: ~/.ssh/known_hosts)
+  --image <image>        For --backend docker: start each session in a new container from this image
+                         (pulled if missing, removed with the session)
+  --container <name>     For --backend docker: run shells in this running container instead
+  --tmux-session <name>  For --backend tmux: attach to this tmux session, creating it if needed;
+                         stopping detaches, so the shell survives server restarts
+  --tmux-socket <name>   tmux server socket name (tmux -L) for --backend tmux
+  --persist [name]       With --headless: keep the default session's shell running in a holder
+                         process when the server stops, and reattach to it (restoring its
+                         scrollback) on the next start with the same name (default: default)
+  --confirm-dangerous    Ask the human (MCP elicitation) before tool input runs rm -r, git push --force,
+                         DROP TABLE, etc.; rejected where the client can't ask
+  --confirm-pattern <regex> Also ask before commands matching this (repeatable)
+  --audit-log <path>     Append hash-chained JSONL records of tool calls, PTY input, and result
+                         hashes (check with 'terminal-mcp verify-audit <path>')
+  --mirror <path>        Copy the default session's raw output to a file or named pipe as it arrives
+                         (watch with tail -f or cat)
   --socket <path>        Unix socket path for MCP (default: ${DEFAULT_SOCKET_PATH})
   --headless             Run in headless mode (MCP server with embedded terminal, no TTY needed)
+  --transport <type>     With --headless: how MCP clients connect: stdio (default), websocket, or
+                         tcp (any number of clients sharing the sessions)
+  --listen <[host:]port> Address for --transport websocket/tcp (default: ${DEFAULT_LISTEN_HOST}:${DEFAULT_LISTEN_PORT})
+  --tls-cert <path>      PEM certificate: serve wss:// or TLS (with --tls-key)
+  --tls-key <path>       PEM private key for --tls-cert
+  --auth-token <token>   Token clients must present: required for --transport tcp, and as
+                         'Authorization: Bearer' for websocket and ?token= for --observe
+                         (required off loopback)
+  --auth-token-file <path> Read the token from a file instead (keeps it out of ps)
+  --allow-origin <origin> Browser Origin allowed to open a WebSocket connection (repeatable;
+                         default: none, so web pages can't drive the terminal)
+  --observe [[host:]port] With --headless: serve a read-only live view of the terminal for people
+                         to watch in a browser (default: ${DEFAULT_LISTEN_HOST}:${DEFAULT_OBSERVE_PORT}; needs
+                         --auth-token off loopback)
+  --no-attach            With --headless: don't let 'terminal-mcp attach' connect to this server
+  --supervise            With --headless: restart the server if it crashes (sessions are reset)
   --sandbox              Enable sandbox mode (restricts filesystem/network access)
   --sandbox-config <path> Load sandbox config from JSON file
   --max-sessions <n>     Max concurrent terminal sessions (default: 5)
   --session-idle-timeout <sec> Idle non-default sessions auto-destroy after this (default: 600)
+  --idle-timeout <sec>   Stop every shell after this long without input or tool calls (default: off)
+  --max-lifetime <sec>   Stop every shell this long after startup and refuse new ones (default: off)
+  --exit-on-limit        With --headless: exit the server once either limit has stopped the shells
+  --prompt-pattern <regex> Regex matched against the cursor row to detect a shell prompt
+  --read-guard <ms>      Reads within this long after input wait for its output first (default: 250, 0 = off)
+  --tool-timeout <ms>    Fail tool calls that run longer than this; <tool>=<ms> sets one tool's
+                         limit (repeatable; default: no limit)
+  --busy-timeout <ms>    How long type/sendKey/sendMouse/restartShell wait for another such call on
+                         the same session before failing with a terminal busy error (default: 10000)
+  --rate-limit-calls <n> Tool calls each MCP client may make per minute (default: no limit)
+  --rate-limit-input <bytes> Bytes each client may type per minute with type/secureType/sendKey,
+                         e.g. 64K (default: no limit)
+  --flood-policy <p>     When output arrives faster than it can be parsed: pause (stop reading until
+                         the parser catches up; default) or drop (skip it, noting how much on screen)
+  --snapshot-history <n> Automatic screen snapshots kept per session (default: 20, 0 = off)
+  --snapshot-interval <sec> Also snapshot the screen periodically (default: off)
+  --proc-tracking        Report shell cwd and foreground command from /proc (Linux only)
+  --font-dir <dir>       Font directory for PNG screenshots (repeatable; default: system font dirs)
+  --fallback-fonts <list> Comma-separated font families tried for emoji/CJK/icons in screenshots
+  --env-redact <list>    Extra variable name patterns masked by getEnvironment (e.g. 'MY_*,*_DSN')
+  --env-reveal           Show all values in getEnvironment, including credentials
+  --restart-on-exit [keep|clear] With --headless: start a new shell when a session's shell exits
+                         (keep scrollback by default, or clear it)
+  --no-strict-init       Allow tool calls before the client completes the MCP initialize handshake
+  --query-responses <path> JSON file overriding replies to terminal queries (DA, XTGETTCAP, colors, ...)
+  --foreground <color>   Foreground color reported to OSC 10 queries (e.g. '#cdd6f4')
+  --background <color>   Background color reported to OSC 11 queries (e.g. '#1e1e2e')
   --version, -v          Show version number
   --help, -h             Show this help message
 
@@ -223,9 +1010,21 @@ Recording Options:
                       Recording auto-stops when this limit is reached
   --inactivity-timeout <sec>  Stop after no output (default: 600 = 10 min)
                       Resets on each terminal output event
+  --record-input      Also record input (keystrokes) as "i" events
+                      Off by default: input may contain secrets
+
+Notification Options:
+  --notify-webhook <url>   POST events as JSON to this URL (repeatable)
+  --notify-slack <url>     Post events to a Slack incoming webhook (repeatable)
+  --notify-events <list>   Events sent to the sinks above (default: all)
+                      Events: command_failed, bell, shell_exited, policy_violation,
+                      server_restarted, limit_reached, secret_prompt
+  --notify-config <path>   Load sinks from a JSON file:
+                      {"sinks": [{"type": "slack", "url": "...", "events": ["command_failed"]}]}
 
 Environment Variables:
   TERMINAL_MCP_RECORD_DIR  Default recording output directory
+  TERMINAL_MCP_AUTH_TOKEN  Token for --transport tcp/websocket when --auth-token isn't given
 
 Mode Detection:
   - If --headless: Headless mode (embedded PTY + MCP server over stdio)
@@ -237,6 +1036,8 @@ Interactive Mode (run in your terminal):
   terminal-mcp --sandbox    # With sandbox (interactive permission prompt)
 
   This gives you an interactive shell. AI can observe/interact via MCP.
+  Press Ctrl+] to take exclusive control (AI input is paused); press it
+  again to hand control back.
 
   With recording:
   terminal-mcp --record
@@ -292,6 +1093,471 @@ MCP Client Mode (add to your MCP client config):
   }
 }
 
+/**
+ * Collect notification sinks from --notify-* flags. Exits on invalid config.
+ */
+function resolveNotifications(): NotificationSinkConfig[] {
+  try {
+    const events: NotificationEventType[] | undefined = options.notifyEvents
+      ? parseEventList(options.notifyEvents)
+      : undefined;
+    const sinks: NotificationSinkConfig[] = [
+      ...(options.notifyWebhooks ?? []).map((url) => ({ type: 'webhook' as const, url, events })),
+      ...(options.notifySlack ?? []).map((url) => ({ type: 'slack' as const, url, events })),
+    ];
+    if (options.notifyConfig) {
+      sinks.push(...loadNotifyConfig(options.notifyConfig));
+    }
+    return sinks;
+  } catch (error) {
+    console.error(`[terminal-mcp] Invalid notification config: ${(error as Error).message}`);
+    process.exit(1);
+  }
+}
+
+const notifications = resolveNotifications();
+
+/**
+ * Load --query-responses overrides, with --foreground/--background taking
+ * precedence. Exits on an unreadable or invalid file or color.
+ */
+function resolveQueryResponses(): QueryResponses | undefined {
+  try {
+    const responses = options.queryResponses ? loadQueryResponses(options.queryResponses) : {};
+    for (const key of ["foreground", "background"] as const) {
+      const color = options[key];
+      if (color === undefined) continue;
+      if (!parseColor(color)) {
+        throw new Error(`--${key} must be a color such as '#1e1e2e' or 'rgb:1e/1e/2e'`);
+      }
+      responses[key] = color;
+    }
+    return Object.keys(responses).length > 0 ? responses : undefined;
+  } catch (error) {
+    console.error(`[terminal-mcp] Invalid query responses: ${(error as Error).message}`);
+    process.exit(1);
+  }
+}
+
+const queryResponses = resolveQueryResponses();
+
+/**
+ * Commands from --init-file followed by --init-command ones. Exits on an
+ * unreadable file.
+ */
+function resolveInitCommands(): string[] | undefined {
+  const commands: string[] = [];
+  if (options.initFile) {
+    try {
+      const lines = fs.readFileSync(options.initFile, "utf8").split(/\r?\n/);
+      commands.push(...lines.map((line) => line.trim()).filter((line) => line && !line.startsWith("#")));
+    } catch (error) {
+      console.error(`[terminal-mcp] Cannot read init file: ${(error as Error).message}`);
+      process.exit(1);
+    }
+  }
+  commands.push(...(options.initCommands ?? []));
+  return commands.length > 0 ? commands : undefined;
+}
+
+const initCommands = resolveInitCommands();
+
+/**
+ * Variables from --env-file (in order) overridden by --env. Exits on an
+ * unreadable file or malformed assignment.
+ */
+function resolveEnv(): Record<string, string> | undefined {
+  try {
+    const env: Record<string, string> = {};
+    for (const file of options.envFiles ?? []) {
+      Object.assign(env, loadEnvFile(file));
+    }
+    for (const assignment of options.env ?? []) {
+      const [name, value] = parseEnvAssignment(assignment);
+      env[name] = value;
+    }
+    return Object.keys(env).length > 0 ? env : undefined;
+  } catch (error) {
+    console.error(`[terminal-mcp] Invalid environment: ${(error as Error).message}`);
+    process.exit(1);
+  }
+}
+
+const shellEnv = resolveEnv();
+
+/**
+ * Built-in rules with --confirm-dangerous, plus --confirm-pattern ones.
+ * Exits on an invalid regex.
+ */
+function resolveDangerRules(): DangerRule[] | undefined {
+  try {
+    const rules = [
+      ...(options.confirmDangerous ? DEFAULT_DANGER_RULES : []),
+      ...compileDangerRules(options.confirmPatterns ?? []),
+    ];
+    return rules.length > 0 ? rules : undefined;
+  } catch (error) {
+    console.error(`[terminal-mcp] ${(error as Error).message}`);
+    process.exit(1);
+  }
+}
+
+const dangerRules = resolveDangerRules();
+
+/**
+ * --tool-timeout values by tool name ("*" for every tool). Exits on a
+ * malformed one.
+ */
+function resolveToolTimeouts(): Record<string, number> | undefined {
+  if (!options.toolTimeouts) return undefined;
+  try {
+    return parseToolTimeouts(options.toolTimeouts);
+  } catch (error) {
+    console.error(`[terminal-mcp] ${(error as Error).message}`);
+    process.exit(1);
+  }
+}
+
+const toolTimeouts = resolveToolTimeouts();
+
+/**
+ * --rate-limit-calls and --rate-limit-input, exiting on a bad value
+ */
+function resolveRateLimits(): RateLimits | undefined {
+  if (!options.rateLimitCalls && !options.rateLimitInput) return undefined;
+  const limits: RateLimits = {};
+  if (options.rateLimitCalls) {
+    const calls = Number(options.rateLimitCalls);
+    if (!Number.isInteger(calls) || calls <= 0) {
+      console.error(`[terminal-mcp] Invalid --rate-limit-calls '${options.rateLimitCalls}': expected a positive integer`);
+      process.exit(1);
+    }
+    limits.callsPerMinute = calls;
+  }
+  if (options.rateLimitInput) {
+    try {
+      limits.inputBytesPerMinute = parseInputQuota(options.rateLimitInput);
+    } catch (error) {
+      console.error(`[terminal-mcp] Invalid --rate-limit-input: ${(error as Error).message}`);
+      process.exit(1);
+    }
+  }
+  return limits;
+}
+
+const rateLimits = resolveRateLimits();
+
+/**
+ * Open the --audit-log file, exiting if it can't be written
+ */
+function resolveAuditLog(): AuditLog | undefined {
+  if (!options.auditLog) return undefined;
+  try {
+    const audit = new AuditLog(options.auditLog);
+    audit.record({ type: "server_start", version: pkg.version, pid: process.pid, argv: args });
+    return audit;
+  } catch (error) {
+    console.error(`[terminal-mcp] Cannot open audit log ${options.auditLog}: ${(error as Error).message}`);
+    process.exit(1);
+  }
+}
+
+/**
+ * --flood-policy, exiting on an unknown one
+ */
+function resolveFloodPolicy(): FloodPolicy | undefined {
+  if (!options.floodPolicy) return undefined;
+  if (!FLOOD_POLICIES.includes(options.floodPolicy as FloodPolicy)) {
+    console.error(`[terminal-mcp] Invalid --flood-policy '${options.floodPolicy}': expected ${FLOOD_POLICIES.join(", ")}`);
+    process.exit(1);
+  }
+  return options.floodPolicy as FloodPolicy;
+}
+
+const floodPolicy = resolveFloodPolicy();
+
+/**
+ * Open the --mirror file or FIFO, exiting if it can't be written
+ */
+function resolveMirror(): OutputMirror | undefined {
+  if (!options.mirror) return undefined;
+  try {
+    return new OutputMirror(options.mirror);
+  } catch (error) {
+    console.error(`[terminal-mcp] Cannot open --mirror ${options.mirror}: ${(error as Error).message}`);
+    process.exit(1);
+  }
+}
+
+const envPolicy: EnvPolicy = {
+  inherit: options.inheritEnv,
+  allow: options.envAllow,
+  deny: options.envDeny,
+  denySecrets: options.envDenySecrets,
+};
+
+/**
+ * --limit-* and --cgroup as ResourceLimits. Exits on a malformed value or
+ * limits this platform can't apply.
+ */
+function resolveResourceLimits(): ResourceLimits | undefined {
+  const count = (flag: string, value: string | undefined): number | undefined => {
+    if (value === undefined) return undefined;
+    const n = Number(value);
+    if (!Number.isInteger(n) || n <= 0) {
+      console.error(`[terminal-mcp] Invalid ${flag} '${value}': expected a positive whole number`);
+      process.exit(1);
+    }
+    return n;
+  };
+  const size = (flag: string, value: string | undefined): number | undefined => {
+    if (value === undefined) return undefined;
+    try {
+      return parseByteSize(value);
+    } catch (error) {
+      console.error(`[terminal-mcp] Invalid ${flag}: ${(error as Error).message}`);
+      process.exit(1);
+    }
+  };
+  const limits: ResourceLimits = {
+    cpuSeconds: count("--limit-cpu", options.limitCpu),
+    memoryBytes: size("--limit-memory", options.limitMemory),
+    maxProcesses: count("--limit-procs", options.limitProcs),
+    fileSizeBytes: size("--limit-fsize", options.limitFsize),
+    cgroup: options.cgroup,
+  };
+  if (Object.values(limits).every((value) => value === undefined)) return undefined;
+  const problem = checkResourceLimits(limits);
+  if (problem) {
+    console.error(`[terminal-mcp] Cannot apply resource limits: ${problem}`);
+    process.exit(1);
+  }
+  return limits;
+}
+
+const resourceLimits = resolveResourceLimits();
+
+/**
+ * --backend and the options of the ssh and docker backends as a BackendConfig. Exits on a
+ * bad value or options that don't work with the backend.
+ */
+function resolveBackend(): BackendConfig | undefined {
+  const inferred = options.host
+    ? "ssh"
+    : options.image || options.container
+      ? "docker"
+      : options.tmuxSession
+        ? "tmux"
+        : "local";
+  const type = (options.backend ?? inferred) as BackendType;
+  if (!BACKEND_TYPES.includes(type)) {
+    console.error(`[terminal-mcp] Invalid --backend '${options.backend}': expected ${BACKEND_TYPES.join(", ")}`);
+    process.exit(1);
+  }
+  if (type !== "ssh" && (options.host || options.sshIdentity || options.sshKnownHosts)) {
+    console.error("[terminal-mcp] --host, --ssh-identity, and --ssh-known-hosts require --backend ssh");
+    process.exit(1);
+  }
+  if (type !== "docker" && (options.image || options.container)) {
+    console.error("[terminal-mcp] --image and --container require --backend docker");
+    process.exit(1);
+  }
+  if (type !== "tmux" && (options.tmuxSession || options.tmuxSocket)) {
+    console.error("[terminal-mcp] --tmux-session and --tmux-socket require --backend tmux");
+    process.exit(1);
+  }
+  if (type === "local") return undefined;
+  // Both act on processes we start; a tmux session may already be running outside them
+  if (options.sandbox || resourceLimits) {
+    console.error(`[terminal-mcp] --sandbox and --limit-*/--cgroup can't be used with --backend ${type}`);
+    process.exit(1);
+  }
+  if (type === "docker") {
+    if (!options.image === !options.container) {
+      console.error("[terminal-mcp] --backend docker requires either --image or --container");
+      process.exit(1);
+    }
+    return { type: "docker", image: options.image, container: options.container };
+  }
+  if (type === "tmux") {
+    const session = options.tmuxSession ?? "terminal-mcp";
+    const problem = checkTmuxSessionName(session) ?? (resolveExecutable("tmux") ? null : "tmux was not found on PATH");
+    if (problem) {
+      console.error(`[terminal-mcp] Cannot use --backend tmux: ${problem}`);
+      process.exit(1);
+    }
+    return { type: "tmux", session, socketName: options.tmuxSocket };
+  }
+  if (!options.host) {
+    console.error("[terminal-mcp] --backend ssh requires --host [user@]host[:port]");
+    process.exit(1);
+  }
+  try {
+    return {
+      type: "ssh",
+      ...parseSshTarget(options.host),
+      identityFile: options.sshIdentity,
+      knownHostsFile: options.sshKnownHosts,
+    };
+  } catch (error) {
+    console.error(`[terminal-mcp] Invalid --host: ${(error as Error).message}`);
+    process.exit(1);
+  }
+}
+
+const backend = resolveBackend();
+
+/**
+ * --persist as a PersistTarget. Exits on a bad name or options the holder
+ * process can't carry over.
+ */
+function resolvePersist(): PersistTarget | undefined {
+  if (options.persist === undefined) return undefined;
+  const problem = checkPersistName(options.persist);
+  if (problem) {
+    console.error(`[terminal-mcp] Invalid --persist name: ${problem}`);
+    process.exit(1);
+  }
+  if (backend) {
+    console.error(`[terminal-mcp] --persist only works with local shells; --backend ${backend.type} has its own`);
+    process.exit(1);
+  }
+  // The sandbox is torn down when the server exits, which would leave the shell outside it
+  if (options.sandbox) {
+    console.error("[terminal-mcp] --persist can't be used with --sandbox");
+    process.exit(1);
+  }
+  return { name: options.persist, dir: getDefaultPersistDir() };
+}
+
+const persist = resolvePersist();
+
+/**
+ * The token from --auth-token, --auth-token-file, or TERMINAL_MCP_AUTH_TOKEN
+ */
+function resolveAuthToken(): string | undefined {
+  if (options.authToken && options.authTokenFile) {
+    console.error("[terminal-mcp] Use either --auth-token or --auth-token-file, not both");
+    process.exit(1);
+  }
+  let token = options.authToken ?? process.env.TERMINAL_MCP_AUTH_TOKEN;
+  if (options.authTokenFile) {
+    try {
+      token = fs.readFileSync(options.authTokenFile, "utf-8").trim();
+    } catch (error) {
+      console.error(`[terminal-mcp] Cannot read --auth-token-file: ${(error as Error).message}`);
+      process.exit(1);
+    }
+  }
+  if (token !== undefined && !token) {
+    console.error("[terminal-mcp] The auth token is empty");
+    process.exit(1);
+  }
+  return token;
+}
+
+/**
+ * --transport, --listen, TLS, and the auth token. Exits on a bad value or
+ * options that don't apply to the transport.
+ */
+function resolveTransport(): {
+  transport: McpTransportType;
+  listen?: ListenAddress;
+  tls?: TlsOptions;
+  authToken?: string;
+  allowedOrigins?: string[];
+} {
+  const transport = (options.transport ?? "stdio") as McpTransportType;
+  if (!MCP_TRANSPORT_TYPES.includes(transport)) {
+    console.error(`[terminal-mcp] Invalid --transport '${options.transport}': expected ${MCP_TRANSPORT_TYPES.join(", ")}`);
+    process.exit(1);
+  }
+  const authToken = resolveAuthToken();
+  if (transport === "stdio") {
+    if (options.listen || options.tlsCert || options.tlsKey) {
+      console.error("[terminal-mcp] --listen and --tls-* require --transport websocket or tcp");
+      process.exit(1);
+    }
+    // The observer checks the token too
+    if ((options.authToken || options.authTokenFile) && options.observe === undefined) {
+      console.error("[terminal-mcp] --auth-token* requires --transport websocket or tcp, or --observe");
+      process.exit(1);
+    }
+    if (options.allowOrigins) {
+      console.error("[terminal-mcp] --allow-origin requires --transport websocket");
+      process.exit(1);
+    }
+    return { transport, authToken };
+  }
+  if (transport === "tcp" && options.allowOrigins) {
+    console.error("[terminal-mcp] --allow-origin requires --transport websocket");
+    process.exit(1);
+  }
+  if (transport === "tcp" && !authToken) {
+    console.error("[terminal-mcp] --transport tcp requires --auth-token, --auth-token-file, or TERMINAL_MCP_AUTH_TOKEN");
+    process.exit(1);
+  }
+  let listen: ListenAddress;
+  try {
+    listen = parseListenAddress(options.listen ?? String(DEFAULT_LISTEN_PORT));
+  } catch (error) {
+    console.error(`[terminal-mcp] Invalid --listen: ${(error as Error).message}`);
+    process.exit(1);
+  }
+  if (!options.tlsCert !== !options.tlsKey) {
+    console.error("[terminal-mcp] --tls-cert and --tls-key must be given together");
+    process.exit(1);
+  }
+  let tls: TlsOptions | undefined;
+  if (options.tlsCert && options.tlsKey) {
+    try {
+      tls = { cert: fs.readFileSync(options.tlsCert), key: fs.readFileSync(options.tlsKey) };
+    } catch (error) {
+      console.error(`[terminal-mcp] Cannot read TLS certificate or key: ${(error as Error).message}`);
+      process.exit(1);
+    }
+  } else if (!isLoopbackHost(listen.host)) {
+    console.error(`[terminal-mcp] Warning: listening on ${listen.host} without TLS; the auth token and all traffic are sent in the clear`);
+  }
+  // Off loopback there's no Host check, so the token is all that keeps others out
+  if (transport === "websocket" && !authToken && !isLoopbackHost(listen.host)) {
+    console.error(`[terminal-mcp] --transport websocket on ${listen.host} requires --auth-token, --auth-token-file, or TERMINAL_MCP_AUTH_TOKEN`);
+    process.exit(1);
+  }
+  return { transport, listen, tls, authToken, allowedOrigins: options.allowOrigins };
+}
+
+const { transport, listen, tls, authToken, allowedOrigins } = resolveTransport();
+
+/**
+ * Where --observe serves its page. Exits on a bad address, or one off
+ * loopback without a token.
+ */
+function resolveObserve(): ListenAddress | undefined {
+  if (options.observe === undefined) return undefined;
+  let address: ListenAddress;
+  try {
+    address = parseListenAddress(options.observe);
+  } catch (error) {
+    console.error(`[terminal-mcp] Invalid --observe: ${(error as Error).message}`);
+    process.exit(1);
+  }
+  if (!isLoopbackHost(address.host)) {
+    if (!authToken) {
+      console.error(`[terminal-mcp] --observe on ${address.host} requires --auth-token, --auth-token-file, or TERMINAL_MCP_AUTH_TOKEN`);
+      process.exit(1);
+    }
+    console.error(`[terminal-mcp] Warning: the observer on ${address.host} is plain HTTP; the token and the terminal are sent in the clear`);
+  }
+  if (listen && address.host === listen.host && address.port === listen.port) {
+    console.error("[terminal-mcp] --observe and --listen must use different ports");
+    process.exit(1);
+  }
+  return address;
+}
+
+const observe = resolveObserve();
+
 async function main() {
   const socketPath = options.socket || DEFAULT_SOCKET_PATH;
   const isInteractive = process.stdin.isTTY;
@@ -306,6 +1572,60 @@ async function main() {
     process.exit(1);
   }
 
+  // In interactive mode the shell exiting ends terminal-mcp, as in any terminal
+  if (options.restartOnExit && !options.headless) {
+    console.error('Error: --restart-on-exit requires --headless');
+    process.exit(1);
+  }
+  // Interactive and client mode have transports of their own
+  if (transport !== "stdio" && !options.headless) {
+    console.error('Error: --transport requires --headless');
+    process.exit(1);
+  }
+  // In interactive mode the user's terminal is the session; there's nothing to reattach
+  if (options.persist !== undefined && !options.headless) {
+    console.error('Error: --persist requires --headless');
+    process.exit(1);
+  }
+  // Interactive mode already shows the terminal to the person running it
+  if (observe && !options.headless) {
+    console.error('Error: --observe requires --headless');
+    process.exit(1);
+  }
+  // In interactive mode stopping the shell already ends terminal-mcp
+  if (options.exitOnLimit && !options.headless) {
+    console.error('Error: --exit-on-limit requires --headless');
+    process.exit(1);
+  }
+
+  if (options.command && options.command.length === 0) {
+    console.error('Error: expected a command after --');
+    process.exit(1);
+  }
+  // Client mode only relays to a terminal-mcp that is already running
+  if (options.command && !options.headless && !isInteractive) {
+    console.error('Error: running a command after -- requires --headless or an interactive terminal');
+    process.exit(1);
+  }
+
+  if (options.supervise && !process.env[SUPERVISED_ENV]) {
+    if (!options.headless) {
+      console.error('Error: --supervise requires --headless');
+      process.exit(1);
+    }
+    // The supervisor relays one client's stdio
+    if (transport !== "stdio") {
+      console.error('Error: --supervise only works with --transport stdio');
+      process.exit(1);
+    }
+    // Watchdog: relay stdio to a child server and restart it on crashes
+    runSupervisor({
+      args: args.filter((arg) => arg !== '--supervise'),
+      notifier: notifications.length > 0 ? new Notifier(notifications) : undefined,
+    });
+    return;
+  }
+
   if (options.headless) {
     // Headless mode: Spawn PTY internally, serve MCP directly over stdio
     // No TTY or socket needed
@@ -313,15 +1633,54 @@ async function main() {
       cols: options.cols,
       rows: options.rows,
       shell: options.shell,
+      login: options.login,
+      shellArgs: options.shellArgs,
+      initCommands,
+      env: shellEnv,
+      envPolicy,
+      resourceLimits,
+      backend,
+      persist,
+      dangerRules,
+      auditLog: resolveAuditLog(),
+      mirror: resolveMirror(),
       maxSessions: options.maxSessions,
       sessionIdleTimeout: options.sessionIdleTimeout,
+      idleTimeout: options.idleTimeout,
+      maxLifetime: options.maxLifetime,
+      exitOnLimit: options.exitOnLimit,
+      promptPattern: options.promptPattern,
+      procTracking: options.procTracking,
+      readGuardMs: options.readGuardMs,
+      toolTimeouts,
+      busyTimeout: options.busyTimeout,
+      rateLimits,
+      snapshotHistory: options.snapshotHistory,
+      floodPolicy,
+      snapshotInterval: options.snapshotInterval,
+      queryResponses,
+      fontDirs: options.fontDirs,
+      fallbackFonts: options.fallbackFonts,
+      envRedact: options.envRedact,
+      envReveal: options.envReveal,
+      notifications,
+      strictInit: options.strictInit,
+      restartOnExit: options.restartOnExit,
+      command: options.command,
+      transport,
+      listen,
+      tls,
+      authToken,
+      allowedOrigins,
+      observe,
+      attach: options.attach,
     });
   } else if (isInteractive) {
     // Interactive mode: Shell on stdin/stdout, tool proxy on Unix socket
     await startInteractiveMode(socketPath);
   } else {
     // MCP client mode: Connect to socket, serve MCP over stdio
-    await startMcpClientMode(socketPath);
+    await startMcpClientMode(socketPath, { strictInit: options.strictInit });
   }
 }
 
@@ -426,6 +1785,16 @@ async function startInteractiveMode(socketPath: string): Promise<void> {
     cols,
     rows,
     shell: options.shell,
+    login: options.login,
+    shellArgs: options.shellArgs,
+    initCommands,
+    env: shellEnv,
+    envPolicy,
+    resourceLimits,
+    backend,
+    dangerRules,
+    auditLog: resolveAuditLog(),
+    mirror: resolveMirror(),
     startupBanner,
     sandboxController,
     record: options.record,
@@ -434,12 +1803,51 @@ async function startInteractiveMode(socketPath: string): Promise<void> {
     idleTimeLimit: options.idleTimeLimit,
     maxDuration: options.maxDuration,
     inactivityTimeout: options.inactivityTimeout,
+    recordInput: options.recordInput,
     maxSessions: options.maxSessions,
     sessionIdleTimeout: options.sessionIdleTimeout,
+    idleTimeout: options.idleTimeout,
+    maxLifetime: options.maxLifetime,
+    promptPattern: options.promptPattern,
+    procTracking: options.procTracking,
+    readGuardMs: options.readGuardMs,
+    toolTimeouts,
+    busyTimeout: options.busyTimeout,
+    rateLimits,
+    snapshotHistory: options.snapshotHistory,
+    floodPolicy,
+    snapshotInterval: options.snapshotInterval,
+    queryResponses,
+    fontDirs: options.fontDirs,
+    fallbackFonts: options.fallbackFonts,
+    envRedact: options.envRedact,
+    envReveal: options.envReveal,
+    notifications,
+    // The banner and first prompt go straight to the user's terminal, so
+    // creating the session mustn't wait for them
+    launchCheck: false,
+    command: options.command,
   });
 
   // Get the session and set up interactive I/O
-  const session = await manager.initSession();
+  let session: TerminalSession;
+  try {
+    session = await manager.initSession();
+  } catch (error) {
+    console.error(`[terminal-mcp] ${(error as Error).message}`);
+    console.error("[terminal-mcp] Run 'terminal-mcp doctor' to check the environment.");
+    process.exit(1);
+  }
+  const shellFallback = session.getShellFallback();
+  if (shellFallback) {
+    console.error(
+      `[terminal-mcp] Warning: shell '${shellFallback.requested}' ${shellFallback.reason}; using ${shellFallback.shell} instead`
+    );
+  }
+
+  // The user's real terminal answers DA/DSR/etc. queries for this session;
+  // replying from the emulator as well would send duplicate responses.
+  session.setRespondToQueries(false);
 
   // Track if we've shown the banner (for Windows, show after shell init)
   let bannerShown = false;
@@ -467,6 +1875,7 @@ async function startInteractiveMode(socketPath: string): Promise<void> {
   session.onExit(async (code) => {
     // Finalize recordings and get results
     const recordings = await manager.finalizeRecordings(code);
+    await manager.flushNotifications();
 
     console.log(`\n[terminal-mcp] Shell exited with code ${code}`);
 
@@ -491,9 +1900,23 @@ async function startInteractiveMode(socketPath: string): Promise<void> {
   }
   process.stdin.resume();
 
-  // Pipe stdin directly to PTY
+  // Pipe stdin directly to PTY. The takeover key toggles exclusive human
+  // control: while held, AI tool input is rejected so the human can work
+  // without the agent typing over them.
   process.stdin.on("data", (data) => {
-    session.write(data.toString());
+    const text = data.toString();
+    if (text === TAKEOVER_KEY) {
+      manager.setController(manager.getController() === "human" ? "shared" : "human");
+      return;
+    }
+    session.write(text);
+  });
+
+  manager.onControlChange((_sessionId, controller) => {
+    const message = controller === "human"
+      ? "You have control — AI input is paused. Press Ctrl+] to hand control back."
+      : "Control handed back — AI input resumed.";
+    process.stdout.write(`\r\n${YELLOW}[terminal-mcp] ${message}${RESET}\r\n`);
   });
 
   // Handle terminal resize
@@ -517,9 +1940,10 @@ async function startInteractiveMode(socketPath: string): Promise<void> {
     }
   }
 
-  // Async cleanup that also cleans up sandbox resources
+  // Async cleanup that stops the shell, finalizes recordings, and cleans
+  // up sandbox resources
   async function cleanupAsync() {
-    await manager.disposeAsync();
+    await manager.shutdown();
     socketServer.close();
     try {
       fs.unlinkSync(socketPath);
@@ -0,0 +1,53 @@
+import { Server } from "@modelcontextprotocol/sdk/server/index.js";
+import { SetLevelRequestSchema } from "@modelcontextprotocol/sdk/types.js";
+import type { LogEntry, LogLevel } from "../terminal/index.js";
+
+/**
+ * MCP logging: server-side events (a shell dying, a policy rejecting
+ * input, environment values being masked) sent to the client as
+ * notifications/message, since many MCP hosts hide the server's stderr.
+ */
+
+export const LOG_NOTIFICATION = "notifications/message";
+export const DEFAULT_LOG_LEVEL: LogLevel = "info";
+
+const LOG_LEVELS: LogLevel[] = ["debug", "info", "notice", "warning", "error", "critical", "alert", "emergency"];
+
+export interface LogMessageParams {
+  level: LogLevel;
+  logger: string;
+  data: Record<string, unknown>;
+}
+
+/**
+ * Convert a manager log entry into notifications/message params
+ */
+export function toLogMessage(entry: LogEntry): LogMessageParams {
+  return {
+    level: entry.level,
+    logger: "terminal-mcp",
+    data: {
+      message: entry.message,
+      ...(entry.sessionId && { sessionId: entry.sessionId }),
+      ...entry.data,
+    },
+  };
+}
+
+/**
+ * Handle logging/setLevel for a server. Returns a function that sends a log
+ * message to the client if it is at or above the level the client chose.
+ */
+export function registerLogging(server: Server): (params: LogMessageParams) => void {
+  let minLevel = LOG_LEVELS.indexOf(DEFAULT_LOG_LEVEL);
+
+  server.setRequestHandler(SetLevelRequestSchema, async (request) => {
+    minLevel = LOG_LEVELS.indexOf(request.params.level as LogLevel);
+    return {};
+  });
+
+  return (params) => {
+    if (LOG_LEVELS.indexOf(params.level) < minLevel) return;
+    server.notification({ method: LOG_NOTIFICATION, params: { ...params } }).catch(() => {});
+  };
+}
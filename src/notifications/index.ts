@@ -0,0 +1,10 @@
+export { Notifier, loadNotifyConfig, parseEventList } from "./notifier.js";
+export { WebhookSink, SlackSink, createSink } from "./sinks.js";
+export { NOTIFICATION_EVENT_TYPES } from "./types.js";
+export type {
+  NotificationEvent,
+  NotificationEventType,
+  NotificationSink,
+  NotificationSinkConfig,
+  NotificationSinkType,
+} from "./types.js";
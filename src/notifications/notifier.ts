@@ -0,0 +1,125 @@
+import * as fs from "fs";
+import { createSink } from "./sinks.js";
+import {
+  NOTIFICATION_EVENT_TYPES,
+  type NotificationEvent,
+  type NotificationEventType,
+  type NotificationSink,
+  type NotificationSinkConfig,
+} from "./types.js";
+
+// Full-screen programs and tab completion can ring the bell repeatedly;
+// forward at most one bell per session in this window
+const BELL_THROTTLE_MS = 30 * 1000;
+
+interface SinkEntry {
+  sink: NotificationSink;
+  events: Set<NotificationEventType>;
+}
+
+/**
+ * Fans events out to the configured sinks. Delivery is fire-and-forget:
+ * failures are logged to stderr and never affect the session.
+ */
+export class Notifier {
+  private sinks: SinkEntry[];
+  private lastBellAt: Map<string, number> = new Map();
+  private pending: Set<Promise<void>> = new Set();
+
+  constructor(configs: NotificationSinkConfig[]) {
+    this.sinks = configs.map((config) => ({
+      sink: createSink(config),
+      events: new Set(config.events ?? NOTIFICATION_EVENT_TYPES),
+    }));
+  }
+
+  /**
+   * Whether any sink wants this event type
+   */
+  wants(type: NotificationEventType): boolean {
+    return this.sinks.some((entry) => entry.events.has(type));
+  }
+
+  notify(
+    type: NotificationEventType,
+    sessionId: string,
+    message: string,
+    details?: Record<string, unknown>
+  ): void {
+    if (!this.wants(type)) return;
+
+    if (type === 'bell') {
+      const now = Date.now();
+      if (now - (this.lastBellAt.get(sessionId) ?? 0) < BELL_THROTTLE_MS) return;
+      this.lastBellAt.set(sessionId, now);
+    }
+
+    const event: NotificationEvent = {
+      type,
+      sessionId,
+      timestamp: new Date().toISOString(),
+      message,
+      details,
+    };
+    for (const { sink, events } of this.sinks) {
+      if (!events.has(type)) continue;
+      const delivery = sink.send(event).catch((err) => {
+        console.error(`[terminal-mcp] Notification to ${sink.name} failed: ${(err as Error).message}`);
+      });
+      this.pending.add(delivery);
+      delivery.finally(() => this.pending.delete(delivery));
+    }
+  }
+
+  /**
+   * Wait for in-flight deliveries, e.g. before the process exits
+   */
+  async flush(): Promise<void> {
+    await Promise.all(this.pending);
+  }
+
+  /**
+   * Forget per-session state (bell throttling) for a destroyed session
+   */
+  forgetSession(sessionId: string): void {
+    this.lastBellAt.delete(sessionId);
+  }
+}
+
+/**
+ * Parse a comma-separated event list, e.g. "command_failed,bell"
+ */
+export function parseEventList(value: string): NotificationEventType[] {
+  const events = value.split(',').map((e) => e.trim()).filter(Boolean);
+  for (const event of events) {
+    if (!NOTIFICATION_EVENT_TYPES.includes(event as NotificationEventType)) {
+      throw new Error(`Unknown notification event '${event}' (expected: ${NOTIFICATION_EVENT_TYPES.join(', ')})`);
+    }
+  }
+  return events as NotificationEventType[];
+}
+
+/**
+ * Load sinks from a JSON file of the form
+ * { "sinks": [{ "type": "slack", "url": "...", "events": ["command_failed"] }] }
+ */
+export function loadNotifyConfig(file: string): NotificationSinkConfig[] {
+  const raw = JSON.parse(fs.readFileSync(file, 'utf8'));
+  if (!raw || !Array.isArray(raw.sinks)) {
+    throw new Error(`${file}: expected an object with a "sinks" array`);
+  }
+  return raw.sinks.map((sink: any, i: number): NotificationSinkConfig => {
+    if (sink?.type !== 'webhook' && sink?.type !== 'slack') {
+      throw new Error(`${file}: sinks[${i}].type must be "webhook" or "slack"`);
+    }
+    if (typeof sink.url !== 'string' || !/^https?:\/\//.test(sink.url)) {
+      throw new Error(`${file}: sinks[${i}].url must be an http(s) URL`);
+    }
+    return {
+      type: sink.type,
+      url: sink.url,
+      events: Array.isArray(sink.events) ? parseEventList(sink.events.join(',')) : undefined,
+      headers: sink.headers && typeof sink.headers === 'object' ? sink.headers : undefined,
+    };
+  });
+}
@@ -0,0 +1,78 @@
+import type { NotificationEvent, NotificationSink, NotificationSinkConfig } from "./types.js";
+
+const REQUEST_TIMEOUT_MS = 5000;
+
+async function postJson(url: string, body: unknown, headers: Record<string, string> = {}): Promise<void> {
+  const response = await fetch(url, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...headers },
+    body: JSON.stringify(body),
+    signal: AbortSignal.timeout(REQUEST_TIMEOUT_MS),
+  });
+  if (!response.ok) {
+    throw new Error(`HTTP ${response.status} ${response.statusText}`);
+  }
+}
+
+/** Redact credentials and paths so sink names are safe to log */
+function describeUrl(url: string): string {
+  try {
+    return new URL(url).host;
+  } catch {
+    return 'invalid-url';
+  }
+}
+
+/**
+ * POSTs each event as-is to a generic webhook endpoint
+ */
+export class WebhookSink implements NotificationSink {
+  readonly name: string;
+
+  constructor(private url: string, private headers: Record<string, string> = {}) {
+    this.name = `webhook(${describeUrl(url)})`;
+  }
+
+  send(event: NotificationEvent): Promise<void> {
+    return postJson(this.url, event, this.headers);
+  }
+}
+
+const SLACK_EMOJI: Record<NotificationEvent['type'], string> = {
+  command_failed: ':x:',
+  bell: ':bell:',
+  shell_exited: ':stop_sign:',
+  policy_violation: ':no_entry:',
+  server_restarted: ':recycle:',
+  limit_reached: ':hourglass:',
+  secret_prompt: ':key:',
+};
+
+/**
+ * Posts a short message to a Slack incoming webhook
+ */
+export class SlackSink implements NotificationSink {
+  readonly name: string;
+
+  constructor(private url: string) {
+    this.name = `slack(${describeUrl(url)})`;
+  }
+
+  send(event: NotificationEvent): Promise<void> {
+    const lines = [`${SLACK_EMOJI[event.type]} *terminal-mcp* [${event.sessionId}] ${event.message}`];
+    const command = event.details?.command;
+    if (typeof command === 'string' && command) {
+      lines.push('```' + command.replace(/```/g, "'''") + '```');
+    }
+    return postJson(this.url, { text: lines.join('\n') });
+  }
+}
+
+export function createSink(config: NotificationSinkConfig): NotificationSink {
+  switch (config.type) {
+    case 'webhook':
+      return new WebhookSink(config.url, config.headers);
+    case 'slack':
+      return new SlackSink(config.url);
+  }
+}
@@ -0,0 +1,59 @@
+/**
+ * Events that can be forwarded to notification sinks
+ * - 'command_failed': A shell command exited non-zero
+ * - 'bell': The terminal rang the bell (BEL), e.g. a program waiting for attention
+ * - 'shell_exited': The session's shell process exited
+ * - 'policy_violation': Tool input was rejected by a session policy
+ * - 'server_restarted': The --supervise watchdog restarted a crashed server
+ * - 'limit_reached': --idle-timeout or --max-lifetime ran out and the shells are being stopped
+ * - 'secret_prompt': A session is waiting for a password or other secret
+ */
+export type NotificationEventType =
+  | 'command_failed'
+  | 'bell'
+  | 'shell_exited'
+  | 'policy_violation'
+  | 'server_restarted'
+  | 'limit_reached'
+  | 'secret_prompt';
+
+export const NOTIFICATION_EVENT_TYPES: NotificationEventType[] = [
+  'command_failed',
+  'bell',
+  'shell_exited',
+  'policy_violation',
+  'server_restarted',
+  'limit_reached',
+  'secret_prompt',
+];
+
+export interface NotificationEvent {
+  type: NotificationEventType;
+  sessionId: string;
+  timestamp: string;
+  /** One-line human-readable summary */
+  message: string;
+  details?: Record<string, unknown>;
+}
+
+/**
+ * Sink type determines the payload shape
+ * - 'webhook': POST the event as JSON
+ * - 'slack': POST a Slack incoming-webhook message ({ text })
+ */
+export type NotificationSinkType = 'webhook' | 'slack';
+
+/**
+ * Sink configuration, as given on the command line or in --notify-config
+ */
+export interface NotificationSinkConfig {
+  type: NotificationSinkType;
+  url: string;
+  events?: NotificationEventType[];   // Default: all events
+  headers?: Record<string, string>;   // Extra request headers (webhook only)
+}
+
+export interface NotificationSink {
+  readonly name: string;
+  send(event: NotificationEvent): Promise<void>;
+}
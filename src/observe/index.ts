@@ -0,0 +1,288 @@
+/**
+ * Observer (--observe [host:]port): a small web page that mirrors a session
+ * read-only, so a human can watch what the agent is doing as it happens.
+ * The page runs xterm.js and is fed over a WebSocket: first the current
+ * buffer, then the session's output as it arrives. Nothing sent by the
+ * browser reaches the shell.
+ */
+import * as fs from "fs";
+import * as http from "http";
+import { createRequire } from "module";
+import * as path from "path";
+import type { Duplex } from "stream";
+import type { TerminalManager, TerminalSession } from "../terminal/index.js";
+import { WebSocketConnection } from "../transport/websocket.js";
+import { checkHttpAccess, loopbackHostNames } from "../transport/auth.js";
+import { formatListenAddress, type ListenAddress } from "../transport/listen.js";
+import { getStyledRows } from "../utils/screenDump.js";
+import { runsToAnsi } from "../utils/reflow.js";
+
+export const DEFAULT_OBSERVE_PORT = 9301;
+
+// xterm.js is served from the installed @xterm/xterm package rather than a
+// CDN: the page holds the token and receives the terminal stream, so a
+// tampered CDN response would get both, and offline the page would break
+const XTERM_ASSETS: Record<string, { file: string; contentType: string }> = {
+  "/xterm.js": { file: "lib/xterm.js", contentType: "text/javascript; charset=utf-8" },
+  "/xterm.css": { file: "css/xterm.css", contentType: "text/css; charset=utf-8" },
+};
+const assetCache = new Map<string, Buffer>();
+
+/**
+ * Contents of a file from the @xterm/xterm package, read once
+ */
+function readXtermAsset(file: string): Buffer {
+  let data = assetCache.get(file);
+  if (!data) {
+    const dir = path.dirname(createRequire(import.meta.url).resolve("@xterm/xterm/package.json"));
+    data = fs.readFileSync(path.join(dir, file));
+    assetCache.set(file, data);
+  }
+  return data;
+}
+
+export interface ObserverOptions {
+  /** Required as ?token=<token> (or a bearer token) when set */
+  authToken?: string;
+}
+
+type ObserverMessage =
+  | { type: "init"; sessionId: string; cols: number; rows: number; data: string }
+  | { type: "data"; data: string }
+  | { type: "resize"; cols: number; rows: number }
+  | { type: "exit"; exitCode: number }
+  | { type: "error"; message: string };
+
+const PAGE = `<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>terminal-mcp</title>
+<link rel="stylesheet" href="/xterm.css">
+<style>
+  html, body { margin: 0; height: 100%; background: #1e1e1e; color: #ccc; font: 13px sans-serif; }
+  #status { padding: 4px 8px; }
+  #terminal { padding: 0 8px; }
+</style>
+</head>
+<body>
+<div id="status">Connecting…</div>
+<div id="terminal"></div>
+<script src="/xterm.js"></script>
+<script>
+  const status = document.getElementById("status");
+  const term = new Terminal({ disableStdin: true, cursorBlink: false, scrollback: 10000 });
+  term.open(document.getElementById("terminal"));
+  const scheme = location.protocol === "https:" ? "wss:" : "ws:";
+  const ws = new WebSocket(scheme + "//" + location.host + "/ws" + location.search);
+  ws.onmessage = (event) => {
+    const message = JSON.parse(event.data);
+    if (message.type === "init") {
+      term.reset();
+      term.resize(message.cols, message.rows);
+      term.write(message.data);
+      status.textContent = "Watching session " + message.sessionId + " (read-only)";
+    } else if (message.type === "data") {
+      term.write(message.data);
+    } else if (message.type === "resize") {
+      term.resize(message.cols, message.rows);
+    } else if (message.type === "exit") {
+      status.textContent = "The shell exited with code " + message.exitCode;
+    } else if (message.type === "error") {
+      status.textContent = message.message;
+    }
+  };
+  ws.onclose = () => {
+    if (status.textContent.startsWith("Watching")) status.textContent = "Disconnected";
+  };
+  ws.onerror = () => {
+    status.textContent = "Cannot connect (wrong or missing ?token=?)";
+  };
+</script>
+</body>
+</html>
+`;
+
+/**
+ * Escape sequences that redraw the session's buffer, scrollback included,
+ * in a fresh terminal of the same size
+ */
+function replayScreen(session: TerminalSession): string {
+  const terminal = session.getTerminal();
+  const buffer = terminal.buffer.active;
+  let out = buffer.type === "alternate" ? "\x1b[?1049h\x1b[H" : "";
+  // The buffer always has at least a screenful of lines, so the last rows
+  // end up on screen and the rest in scrollback
+  out += getStyledRows(terminal, 0, buffer.length)
+    .map((row) => runsToAnsi(row.runs))
+    .join("\r\n");
+  out += `\x1b[${buffer.cursorY + 1};${buffer.cursorX + 1}H`;
+  if (!session.isCursorVisible()) out += "\x1b[?25l";
+  return out;
+}
+
+export interface SessionWatcher {
+  /** The buffer as escape sequences, sent once before any onData */
+  onInit(data: string): void;
+  onData(data: string): void;
+  onResize(cols: number, rows: number): void;
+  onExit(exitCode: number): void;
+}
+
+/**
+ * Follow a session for a viewer: its current buffer, then its output,
+ * with nothing lost or repeated in between. Returns a function that stops.
+ */
+export function watchSession(session: TerminalSession, watcher: SessionWatcher): () => void {
+  // Output that arrives while the buffer is being captured is held back,
+  // then sent after it
+  let held: string[] | null = [];
+  const unsubscribeData = session.onData((data) => {
+    if (held) held.push(data);
+    else watcher.onData(data);
+  });
+  const unsubscribeResize = session.onResize((cols, rows) => watcher.onResize(cols, rows));
+  const unsubscribeExit = session.onExit((exitCode) => {
+    // A restarted shell keeps going in the same session
+    if (session.isRestarting()) return;
+    watcher.onExit(exitCode);
+  });
+  let stopped = false;
+
+  // Parsing is asynchronous; once this empty write is done, everything
+  // received before the subscription is on the emulator's screen
+  session.getTerminal().write("", () => {
+    if (stopped || !held) return;
+    watcher.onInit(replayScreen(session));
+    for (const data of held) watcher.onData(data);
+    held = null;
+  });
+
+  return () => {
+    stopped = true;
+    unsubscribeData();
+    unsubscribeResize();
+    unsubscribeExit();
+  };
+}
+
+/**
+ * Stream one session to a connected page until either side goes away
+ */
+function observe(connection: WebSocketConnection, session: TerminalSession, sessionId: string): void {
+  const send = (message: ObserverMessage) => {
+    connection.send(JSON.stringify(message)).catch(() => {});
+  };
+  const stop = watchSession(session, {
+    onInit: (data) => {
+      const { cols, rows } = session.getDimensions();
+      send({ type: "init", sessionId, cols, rows, data });
+    },
+    onData: (data) => send({ type: "data", data }),
+    onResize: (cols, rows) => send({ type: "resize", cols, rows }),
+    onExit: (exitCode) => send({ type: "exit", exitCode }),
+  });
+  connection.onmessage = () => {
+    // Read-only
+  };
+  connection.onclose = stop;
+}
+
+/**
+ * Serve the observer page on / and its stream on /ws. ?session=<id>
+ * picks a session other than the default one.
+ */
+export function createObserverServer(
+  manager: TerminalManager,
+  listen: ListenAddress,
+  options: ObserverOptions = {}
+): http.Server {
+  const allowedHosts = loopbackHostNames(listen.host, listen.port);
+
+  // The same token check for the page and the stream. Browsers can't set
+  // headers on a WebSocket, so the token comes in the query string and the
+  // page passes its own query on.
+  const refusal = (req: http.IncomingMessage, url: URL): string | null => {
+    const token = url.searchParams.get("token");
+    const headers = token ? { ...req.headers, authorization: `Bearer ${token}` } : req.headers;
+    return checkHttpAccess(headers, {
+      authToken: options.authToken,
+      allowedHosts,
+      // Only the page itself may open the stream
+      allowedOrigins: req.headers.host ? [`http://${req.headers.host}`] : [],
+    });
+  };
+
+  const server = http.createServer((req, res) => {
+    const url = new URL(req.url ?? "/", "http://localhost");
+    const asset = XTERM_ASSETS[url.pathname];
+    if (asset && (req.method === "GET" || req.method === "HEAD")) {
+      // The same public library for everyone, so no token is needed
+      let data: Buffer;
+      try {
+        data = readXtermAsset(asset.file);
+      } catch (error) {
+        res.writeHead(500, { "Content-Type": "text/plain" });
+        res.end(`Cannot read @xterm/xterm: ${(error as Error).message}\n`);
+        return;
+      }
+      res.writeHead(200, { "Content-Type": asset.contentType, "Cache-Control": "max-age=3600" });
+      res.end(req.method === "HEAD" ? undefined : data);
+      return;
+    }
+    if (url.pathname !== "/" || (req.method !== "GET" && req.method !== "HEAD")) {
+      res.writeHead(404, { "Content-Type": "text/plain" });
+      res.end("Not found\n");
+      return;
+    }
+    const refused = refusal(req, url);
+    if (refused) {
+      res.writeHead(403, { "Content-Type": "text/plain" });
+      res.end("Forbidden\n");
+      return;
+    }
+    res.writeHead(200, {
+      "Content-Type": "text/html; charset=utf-8",
+      "Cache-Control": "no-store",
+      // Keep the token in the URL out of any request the page makes
+      "Referrer-Policy": "no-referrer",
+    });
+    res.end(PAGE);
+  });
+
+  server.on("upgrade", (req: http.IncomingMessage, socket: Duplex, head: Buffer) => {
+    socket.on("error", () => {});
+    const url = new URL(req.url ?? "/", "http://localhost");
+    if (url.pathname !== "/ws") {
+      socket.end("HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n");
+      return;
+    }
+    const refused = refusal(req, url);
+    if (refused) {
+      console.error(`[terminal-mcp] Refused observer from ${req.socket.remoteAddress}: ${refused}`);
+      socket.end("HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\nForbidden\n");
+      return;
+    }
+    const connection = WebSocketConnection.accept(req, socket, head);
+    if (!connection) return;
+    const sessionId = url.searchParams.get("session") ?? undefined;
+    manager.resolveSession(sessionId).then(
+      (session) => observe(connection, session, sessionId ?? "default"),
+      (error: Error) => {
+        connection.send(JSON.stringify({ type: "error", message: error.message } satisfies ObserverMessage))
+          .catch(() => {})
+          .finally(() => connection.close());
+      }
+    );
+  });
+
+  server.on("listening", () => {
+    const hint = options.authToken ? "?token=<token>" : "";
+    console.error(`[terminal-mcp] Observer page at http://${formatListenAddress(listen)}/${hint}`);
+  });
+  server.on("error", (error) => {
+    console.error(`[terminal-mcp] Cannot serve the observer on ${formatListenAddress(listen)}: ${error.message}`);
+  });
+  server.listen(listen.port, listen.host);
+  return server;
+}
@@ -3,6 +3,7 @@ import {
   ListPromptsRequestSchema,
   GetPromptRequestSchema,
 } from "@modelcontextprotocol/sdk/types.js";
+import type { TerminalManager } from "../terminal/index.js";
 
 const TOOL_USAGE_PROMPT = `# Terminal MCP Tool Usage Guide
 
@@ -62,33 +63,142 @@ Capture terminal state as structured JSON with:
 Use when you need cursor position (e.g., for interactive apps, editors) or terminal dimensions. For simple command output, prefer getContent().
 `;
 
+const SESSION_ARGUMENT = {
+  name: "sessionId",
+  description: "Session to work in. Omit for the default session.",
+  required: false,
+};
+
 const prompts = [
   {
     name: "tool-usage",
     description: "Instructions for effectively using terminal-mcp tools",
   },
+  {
+    name: "investigate-failure",
+    description: "Find out why a command failed, starting from what is on the screen now",
+    arguments: [
+      {
+        name: "command",
+        description: "The command that failed. Omit to use the last command typed at the prompt.",
+        required: false,
+      },
+      SESSION_ARGUMENT,
+    ],
+  },
+  {
+    name: "drive-tui",
+    description: "Operate the full-screen program on the screen (vim, htop, a menu, an installer) until it reaches a goal",
+    arguments: [
+      {
+        name: "goal",
+        description: "State to reach, e.g. 'the file is saved and vim has exited'",
+        required: true,
+      },
+      SESSION_ARGUMENT,
+    ],
+  },
+  {
+    name: "explain-screen",
+    description: "Explain what the terminal is showing and what can be done next",
+    arguments: [SESSION_ARGUMENT],
+  },
 ];
 
-export function registerPrompts(server: Server): void {
+/**
+ * The session's visible screen as a fenced block, with trailing blank rows
+ * dropped, plus where the cursor is and whether a full-screen program runs
+ */
+async function describeScreen(manager: TerminalManager, sessionId?: string): Promise<string> {
+  await manager.waitForReadable(sessionId);
+  const session = manager.resolveSessionSync(sessionId);
+  const buffer = session.getTerminal().buffer.active;
+  const screen = session.getVisibleContent().replace(/\s+$/, "");
+  const { cols, rows } = session.getDimensions();
+  const title = session.getTitle();
+
+  return [
+    `Terminal ${cols}x${rows}${title ? `, title "${title}"` : ""}, cursor at row ${buffer.cursorY}, col ${buffer.cursorX}` +
+      (buffer.type === "alternate" ? ", full-screen program active (alternate screen)" : "") +
+      ".",
+    "",
+    "```",
+    screen || "(screen is empty)",
+    "```",
+  ].join("\n");
+}
+
+function userMessage(text: string) {
+  return {
+    messages: [
+      {
+        role: "user" as const,
+        content: {
+          type: "text" as const,
+          text,
+        },
+      },
+    ],
+  };
+}
+
+export function registerPrompts(server: Server, manager: TerminalManager): void {
   server.setRequestHandler(ListPromptsRequestSchema, async () => ({
     prompts,
   }));
 
   server.setRequestHandler(GetPromptRequestSchema, async (request) => {
     const { name } = request.params;
+    const args = request.params.arguments ?? {};
+    const sessionId = args.sessionId || undefined;
+    const target = sessionId ? ` (sessionId "${sessionId}")` : "";
 
     if (name === "tool-usage") {
-      return {
-        messages: [
-          {
-            role: "user" as const,
-            content: {
-              type: "text" as const,
-              text: TOOL_USAGE_PROMPT,
-            },
-          },
-        ],
-      };
+      return userMessage(TOOL_USAGE_PROMPT);
+    }
+
+    if (name === "investigate-failure") {
+      const command = args.command || manager.getLastCommand(sessionId);
+      return userMessage(
+        `${command ? `The command \`${command}\`` : "The last command"} failed in the terminal${target}. Find out why and fix it.
+
+Current screen:
+${await describeScreen(manager, sessionId)}
+
+1. Read the error on screen. If it has scrolled away, use getContent (or search for "error") to read the scrollback.
+2. Check the likely causes with short, read-only commands (type, then sendKey Enter, then getContent): files, versions, environment, permissions.
+3. Explain the cause in one or two sentences, then propose a fix. Ask before running anything destructive.
+4. After fixing, re-run the command and confirm it succeeds.`
+      );
+    }
+
+    if (name === "drive-tui") {
+      if (!args.goal) {
+        throw new Error("drive-tui requires a goal argument");
+      }
+      return userMessage(
+        `Operate the program in the terminal${target} until this goal is reached: ${args.goal}
+
+Current screen:
+${await describeScreen(manager, sessionId)}
+
+Work in small steps:
+1. Work out from the screen which program is running and what state it is in (mode, focused item, open dialogs).
+2. Send a few keys with sendKey (type for text entry), then takeScreenshot to check the result and the cursor position.
+3. If the screen didn't change as expected, stop and reassess rather than repeating keys. Escape or Ctrl+C usually backs out.
+4. Stop when the goal is reached and describe the final state. Do not confirm destructive actions (deleting, overwriting, quitting without saving) unless the goal requires them.`
+      );
+    }
+
+    if (name === "explain-screen") {
+      return userMessage(
+        `Explain what the terminal${target} is showing.
+
+Current screen:
+${await describeScreen(manager, sessionId)}
+
+Say which program or prompt is active, summarize any output or errors, and list the likely next actions with the keys or commands for each. Don't send any input.`
+      );
     }
 
     throw new Error(`Unknown prompt: ${name}`);
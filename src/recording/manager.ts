@@ -24,6 +24,7 @@ export class RecordingManager {
       idleTimeLimit: options?.idleTimeLimit ?? 2,
       maxDuration: options?.maxDuration ?? 3600,        // 60 minutes default
       inactivityTimeout: options?.inactivityTimeout ?? 600,  // 10 minutes default
+      captureInput: options?.captureInput ?? false,
     };
   }
 
@@ -45,7 +46,8 @@ export class RecordingManager {
       mergedOptions.format,
       mergedOptions.idleTimeLimit,
       mergedOptions.maxDuration,
-      mergedOptions.inactivityTimeout
+      mergedOptions.inactivityTimeout,
+      mergedOptions.captureInput
     );
 
     this.recordings.set(id, recorder);
@@ -89,6 +91,15 @@ export class RecordingManager {
     }
   }
 
+  /**
+   * Record input to all active recordings that capture input
+   */
+  recordInputToAll(data: string): void {
+    for (const recorder of this.getActiveRecordings()) {
+      recorder.recordInput(data);
+    }
+  }
+
   /**
    * Record resize to all active recordings
    */
@@ -17,7 +17,7 @@ import type {
  * - First line: JSON header with version, dimensions, timestamp
  * - Subsequent lines: JSON arrays [time, type, data]
  *   - time: seconds since start (float)
- *   - type: "o" for output, "r" for resize
+ *   - type: "o" for output, "i" for input, "r" for resize
  *   - data: string content
  */
 export class Recorder {
@@ -32,6 +32,7 @@ export class Recorder {
   private bytesWritten: number = 0;
   private finalized: boolean = false;
   private idleTimeLimit: number;
+  private captureInput: boolean;
   private lastEventTime: number = 0;
   private adjustedElapsed: number = 0;
 
@@ -52,7 +53,8 @@ export class Recorder {
     format: RecordingFormat = 'v2',
     idleTimeLimit: number = 2,
     maxDuration: number = 3600,        // 60 minutes default
-    inactivityTimeout: number = 600    // 10 minutes default
+    inactivityTimeout: number = 600,   // 10 minutes default
+    captureInput: boolean = false
   ) {
     this.id = id;
     this.mode = mode;
@@ -61,6 +63,7 @@ export class Recorder {
     this.idleTimeLimit = idleTimeLimit;
     this.maxDuration = maxDuration;
     this.inactivityTimeout = inactivityTimeout;
+    this.captureInput = captureInput;
 
     // Generate temp and final paths
     const timestamp = Date.now();
@@ -136,6 +139,27 @@ export class Recorder {
     this.writeLine(JSON.stringify(event));
   }
 
+  /**
+   * Record input data (no-op unless input capture is enabled).
+   * Input does not reset the inactivity timer, which tracks output only.
+   */
+  recordInput(data: string): void {
+    if (!this.captureInput || !this.writeStream || this.finalized) {
+      return;
+    }
+
+    const elapsed = this.getElapsedSeconds();
+    const event: AsciicastEvent = [elapsed, 'i', data];
+    this.writeLine(JSON.stringify(event));
+  }
+
+  /**
+   * Whether input events are being recorded
+   */
+  isCapturingInput(): boolean {
+    return this.captureInput;
+  }
+
   /**
    * Record terminal resize event
    */
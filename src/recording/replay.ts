@@ -0,0 +1,160 @@
+import * as fs from "fs";
+import { createEmulator, renderScreen, writeAsync, type ScreenFormat } from "../utils/emulate.js";
+
+export type ReplayFormat = ScreenFormat;
+
+export interface ReplayOptions {
+  file: string;
+  /** Print a frame after each burst of output instead of only the final screen */
+  frames: boolean;
+  /** Seconds of stream time without output that ends a frame (default: 0.5) */
+  frameGap: number;
+  /** Stop replaying at this stream time (seconds) and print that screen */
+  at?: number;
+  format: ReplayFormat;
+  /** Override dimensions (required for raw logs without a header) */
+  cols?: number;
+  rows?: number;
+}
+
+interface ReplayEvent {
+  time: number;
+  type: 'o' | 'r';
+  data: string;
+}
+
+interface ParsedRecording {
+  cols: number;
+  rows: number;
+  events: ReplayEvent[];
+}
+
+const DEFAULT_COLS = 120;
+const DEFAULT_ROWS = 40;
+
+/**
+ * Parse an asciicast v2/v3 file. Anything that doesn't start with an
+ * asciicast header is treated as a raw PTY log and replayed as one output
+ * event. Input ("i") and marker events are skipped.
+ */
+export function parseRecording(content: string): ParsedRecording {
+  const newline = content.indexOf('\n');
+  const firstLine = newline === -1 ? content : content.slice(0, newline);
+
+  let header: any = null;
+  try {
+    header = JSON.parse(firstLine);
+  } catch {
+    // Not JSON — raw log
+  }
+
+  if (!header || typeof header !== 'object' || Array.isArray(header) || (header.version !== 2 && header.version !== 3)) {
+    return {
+      cols: DEFAULT_COLS,
+      rows: DEFAULT_ROWS,
+      events: [{ time: 0, type: 'o', data: content }],
+    };
+  }
+
+  // v3 moved dimensions under "term" and made event times relative
+  const relative = header.version === 3;
+  const cols = relative ? header.term?.cols : header.width;
+  const rows = relative ? header.term?.rows : header.height;
+
+  const events: ReplayEvent[] = [];
+  let time = 0;
+  const lines = newline === -1 ? [] : content.slice(newline + 1).split('\n');
+  for (let i = 0; i < lines.length; i++) {
+    const line = lines[i].trim();
+    if (!line || line.startsWith('#')) continue;
+
+    let event: unknown;
+    try {
+      event = JSON.parse(line);
+    } catch {
+      throw new Error(`Invalid event on line ${i + 2}`);
+    }
+    if (!Array.isArray(event) || event.length < 3) {
+      throw new Error(`Invalid event on line ${i + 2}`);
+    }
+
+    const [t, type, data] = event as [number, string, string];
+    time = relative ? time + t : t;
+    if (type === 'o' || type === 'r') {
+      events.push({ time, type, data });
+    }
+  }
+
+  return {
+    cols: typeof cols === 'number' ? cols : DEFAULT_COLS,
+    rows: typeof rows === 'number' ? rows : DEFAULT_ROWS,
+    events,
+  };
+}
+
+/**
+ * Feed a recording through the terminal emulator and print the rendered
+ * screen(s) to stdout. Returns the process exit code.
+ */
+export async function runReplay(opts: ReplayOptions): Promise<number> {
+  let content: string;
+  try {
+    content = fs.readFileSync(opts.file, 'utf8');
+  } catch (err) {
+    console.error(`Cannot read ${opts.file}: ${(err as Error).message}`);
+    return 1;
+  }
+
+  let recording: ParsedRecording;
+  try {
+    recording = parseRecording(content);
+  } catch (err) {
+    console.error(`${opts.file}: ${(err as Error).message}`);
+    return 1;
+  }
+
+  const terminal = createEmulator(opts.cols ?? recording.cols, opts.rows ?? recording.rows);
+
+  const printFrame = (index: number, time: number) => {
+    // JSON frames are one object per line; other formats get a separator
+    if (opts.format !== 'json') {
+      console.log(`--- frame ${index} @ ${time.toFixed(3)}s ---`);
+    }
+    console.log(renderScreen(terminal, opts.format));
+  };
+
+  let frame = 0;
+  let pendingOutput = false;
+  let lastTime = 0;
+
+  for (const event of recording.events) {
+    if (opts.at !== undefined && event.time > opts.at) break;
+
+    // A gap in output closes the current frame
+    if (opts.frames && pendingOutput && event.time - lastTime >= opts.frameGap) {
+      printFrame(frame++, lastTime);
+      pendingOutput = false;
+    }
+
+    if (event.type === 'o') {
+      await writeAsync(terminal, event.data);
+      pendingOutput = true;
+    } else {
+      // Resize events are "COLSxROWS"; dimension overrides take precedence
+      const match = /^(\d+)x(\d+)$/.exec(event.data);
+      if (match && opts.cols === undefined && opts.rows === undefined) {
+        terminal.resize(parseInt(match[1], 10), parseInt(match[2], 10));
+      }
+    }
+    lastTime = event.time;
+  }
+
+  if (!opts.frames) {
+    console.log(renderScreen(terminal, opts.format));
+  } else if (pendingOutput || frame === 0) {
+    printFrame(frame, lastTime);
+  }
+
+  terminal.dispose();
+  return 0;
+}
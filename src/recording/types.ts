@@ -21,6 +21,7 @@ export interface RecordingOptions {
   idleTimeLimit?: number;      // Max seconds between events (default: 2)
   maxDuration?: number;        // Max recording duration in seconds (default: 3600 = 60 min)
   inactivityTimeout?: number;  // Stop after N seconds of no output (default: 600 = 10 min)
+  captureInput?: boolean;      // Also record input as "i" events (default: false)
 }
 
 /**
@@ -41,16 +42,18 @@ export interface AsciicastHeader {
 /**
  * Asciicast event types:
  * - 'o': output (data written to terminal)
+ * - 'i': input (keystrokes sent to the PTY; only when input capture is on)
  * - 'r': resize (terminal dimensions changed)
  */
 export type AsciicastOutputEvent = [number, 'o', string];
+export type AsciicastInputEvent = [number, 'i', string];
 export type AsciicastResizeEvent = [number, 'r', string];
-export type AsciicastEvent = AsciicastOutputEvent | AsciicastResizeEvent;
+export type AsciicastEvent = AsciicastOutputEvent | AsciicastInputEvent | AsciicastResizeEvent;
 
 /**
  * Reason why recording stopped
  */
-export type StopReason = 'explicit' | 'session_exit' | 'max_duration' | 'inactivity';
+export type StopReason = 'explicit' | 'session_exit' | 'max_duration' | 'inactivity' | 'server_shutdown';
 
 /**
  * Metadata returned after finalizing a recording
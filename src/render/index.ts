@@ -0,0 +1,78 @@
+import * as fs from "fs";
+import { createEmulator, renderScreen, writeAsync, type ScreenFormat } from "../utils/emulate.js";
+import { renderTerminalToPng, type ThemeName } from "../utils/render.js";
+
+export interface RenderCommandOptions {
+  /** Input file; stdin when omitted */
+  input?: string;
+  /** Output file; stdout when omitted */
+  output?: string;
+  format: ScreenFormat | 'png';
+  cols: number;
+  rows: number;
+  theme?: ThemeName;
+  /** Treat bare LF as CRLF (default: true, since captured output rarely has CR) */
+  convertEol: boolean;
+}
+
+async function readInput(input?: string): Promise<Buffer> {
+  if (input) {
+    return fs.readFileSync(input);
+  }
+  const chunks: Buffer[] = [];
+  for await (const chunk of process.stdin) {
+    chunks.push(chunk as Buffer);
+  }
+  return Buffer.concat(chunks);
+}
+
+/**
+ * Run captured ANSI output through the emulator and write the rendered
+ * screen. Returns the process exit code.
+ */
+export async function runRender(opts: RenderCommandOptions): Promise<number> {
+  if (!opts.input && process.stdin.isTTY) {
+    console.error("No input: pipe ANSI output to stdin or pass a file (see --help)");
+    return 1;
+  }
+  if (opts.format === 'png' && !opts.output && process.stdout.isTTY) {
+    console.error("Refusing to write PNG data to a terminal; use --output <file> or redirect stdout");
+    return 1;
+  }
+
+  let data: Buffer;
+  try {
+    data = await readInput(opts.input);
+  } catch (err) {
+    console.error(`Cannot read ${opts.input}: ${(err as Error).message}`);
+    return 1;
+  }
+
+  const terminal = createEmulator(opts.cols, opts.rows, { convertEol: opts.convertEol });
+  await writeAsync(terminal, new Uint8Array(data));
+
+  let rendered: string | Buffer;
+  try {
+    if (opts.format === 'png') {
+      const { png, warnings } = renderTerminalToPng(terminal, { theme: opts.theme });
+      for (const warning of warnings) {
+        console.error(`warning: ${warning}`);
+      }
+      rendered = png;
+    } else {
+      rendered = renderScreen(terminal, opts.format, opts.theme) + '\n';
+    }
+  } catch (err) {
+    console.error((err as Error).message);
+    return 1;
+  } finally {
+    terminal.dispose();
+  }
+
+  if (opts.output) {
+    fs.writeFileSync(opts.output, rendered);
+  } else {
+    process.stdout.write(rendered);
+  }
+  return 0;
+}
@@ -0,0 +1,98 @@
+import { Server } from "@modelcontextprotocol/sdk/server/index.js";
+import {
+  ListResourcesRequestSchema,
+  ListResourceTemplatesRequestSchema,
+  ReadResourceRequestSchema,
+} from "@modelcontextprotocol/sdk/types.js";
+import { TerminalManager } from "../terminal/index.js";
+
+/**
+ * Screen slices addressable as MCP resources. Rows are 0-indexed and the
+ * range is inclusive, matching diffScreen regions.
+ */
+export const resourceTemplates = [
+  {
+    uriTemplate: "terminal://screen/{startRow}-{endRow}",
+    name: "Screen rows",
+    description: "Rows startRow..endRow (0-indexed, inclusive) of the default session's visible screen, as plain text",
+    mimeType: "text/plain",
+  },
+  {
+    uriTemplate: "terminal://sessions/{sessionId}/screen/{startRow}-{endRow}",
+    name: "Session screen rows",
+    description: "Rows startRow..endRow (0-indexed, inclusive) of a session's visible screen, as plain text",
+    mimeType: "text/plain",
+  },
+];
+
+const SCREEN_URI = /^terminal:\/\/(?:sessions\/([^/]+)\/)?screen(?:\/(\d+)-(\d+))?$/;
+
+export interface ResourceEntry {
+  uri: string;
+  name: string;
+  description?: string;
+  mimeType: string;
+}
+
+export interface ResourceContents {
+  contents: Array<{ uri: string; mimeType: string; text: string }>;
+}
+
+/**
+ * Concrete resources: the full visible screen of each active session
+ */
+export function listResources(manager: TerminalManager): { resources: ResourceEntry[] } {
+  const resources: ResourceEntry[] = [];
+  for (const session of manager.listSessions().sessions) {
+    resources.push({
+      uri: session.isDefault ? "terminal://screen" : `terminal://sessions/${session.sessionId}/screen`,
+      name: session.isDefault ? "Screen" : `Screen (session ${session.sessionId})`,
+      description: `Visible screen of the ${session.isDefault ? "default session" : `session ${session.sessionId}`} (${session.cols}x${session.rows})`,
+      mimeType: "text/plain",
+    });
+  }
+  return { resources };
+}
+
+export async function readResource(manager: TerminalManager, uri: string): Promise<ResourceContents> {
+  const match = SCREEN_URI.exec(uri);
+  if (!match) {
+    throw new Error(`Unknown resource: ${uri}`);
+  }
+  const sessionId = match[1] ? decodeURIComponent(match[1]) : undefined;
+  await manager.waitForReadable(sessionId);
+
+  const lines = manager.getVisibleContent(sessionId).split("\n");
+  let text = lines.join("\n");
+  if (match[2] !== undefined) {
+    const startRow = parseInt(match[2], 10);
+    const endRow = parseInt(match[3], 10);
+    const rows = manager.getDimensions(sessionId).rows;
+    if (startRow > endRow || startRow >= rows) {
+      throw new Error(`Invalid row range ${startRow}-${endRow}: the screen has rows 0-${rows - 1}`);
+    }
+    text = lines.slice(startRow, Math.min(endRow, rows - 1) + 1).join("\n");
+  }
+
+  return {
+    contents: [
+      {
+        uri,
+        mimeType: "text/plain",
+        text,
+      },
+    ],
+  };
+}
+
+export function registerResources(server: Server, manager: TerminalManager): void {
+  server.setRequestHandler(ListResourcesRequestSchema, async () => listResources(manager));
+
+  server.setRequestHandler(ListResourceTemplatesRequestSchema, async () => ({
+    resourceTemplates,
+  }));
+
+  server.setRequestHandler(ReadResourceRequestSchema, async (request) =>
+    readResource(manager, request.params.uri)
+  );
+}
@@ -1,19 +1,105 @@
 import { Server } from "@modelcontextprotocol/sdk/server/index.js";
 import { StdioServerTransport } from "@modelcontextprotocol/sdk/server/stdio.js";
 import { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
-import { TerminalManager } from "./terminal/index.js";
+import type { Server as NetServer } from "net";
+import {
+  TerminalManager,
+  type DangerRule,
+  type QueryResponses,
+  type RestartMode,
+  type TerminalSession,
+} from "./terminal/index.js";
 import { VERSION } from "./utils/version.js";
+import { createObserverServer } from "./observe/index.js";
+import { createAttachServer } from "./attach/index.js";
 import { registerTools } from "./tools/index.js";
 import { registerPrompts } from "./prompts/index.js";
+import { registerResources } from "./resources/index.js";
+import { registerLogging, toLogMessage } from "./logging/index.js";
+import type { NotificationSinkConfig } from "./notifications/index.js";
+import type { EnvPolicy } from "./utils/env.js";
+import type { AuditLog } from "./audit/index.js";
+import type { OutputMirror } from "./terminal/mirror.js";
+import type { ResourceLimits } from "./utils/resourceLimits.js";
+import type { RateLimits } from "./utils/rateLimit.js";
+import type { BackendConfig, FloodPolicy } from "./terminal/backend.js";
+import type { PersistTarget } from "./terminal/persist.js";
+import {
+  createTcpServer,
+  createWebSocketServer,
+  formatListenAddress,
+  loopbackHostNames,
+  type ListenAddress,
+  type TlsOptions,
+} from "./transport/index.js";
 
 export interface ServerOptions {
   cols?: number;
   rows?: number;
   shell?: string;
+  login?: boolean;
+  shellArgs?: string[];
+  initCommands?: string[];
+  env?: Record<string, string>;
+  envPolicy?: EnvPolicy;
+  resourceLimits?: ResourceLimits;
+  backend?: BackendConfig;
+  persist?: PersistTarget;
+  dangerRules?: DangerRule[];
+  auditLog?: AuditLog;
+  mirror?: OutputMirror;
   maxSessions?: number;
   sessionIdleTimeout?: number;
+  idleTimeout?: number;
+  maxLifetime?: number;
+  // Exit once --idle-timeout or --max-lifetime has stopped the shells
+  exitOnLimit?: boolean;
+  promptPattern?: string;
+  procTracking?: boolean;
+  readGuardMs?: number;
+  toolTimeouts?: Record<string, number>;
+  busyTimeout?: number;
+  rateLimits?: RateLimits;
+  snapshotHistory?: number;
+  floodPolicy?: FloodPolicy;
+  snapshotInterval?: number;
+  fontDirs?: string[];
+  fallbackFonts?: string[];
+  envRedact?: string[];
+  envReveal?: boolean;
+  notifications?: NotificationSinkConfig[];
+  queryResponses?: QueryResponses;
+  strictInit?: boolean;
+  restartOnExit?: RestartMode;
+  command?: string[];
+  /**
+   * How MCP clients connect (default: stdio). 'websocket' and 'tcp' accept
+   * any number of clients on listen, all sharing the same sessions.
+   */
+  transport?: McpTransportType;
+  listen?: ListenAddress;
+  /** Serve wss:// or TLS instead of plain ws:// or TCP */
+  tls?: TlsOptions;
+  /**
+   * Token clients must present: first thing over tcp, as a bearer token in
+   * the WebSocket handshake
+   */
+  authToken?: string;
+  /** Browser origins allowed to open a WebSocket connection */
+  allowedOrigins?: string[];
+  /** Serve the read-only observer page here (--observe) */
+  observe?: ListenAddress;
+  /** Listen for `terminal-mcp attach` (default: true; --no-attach) */
+  attach?: boolean;
 }
 
+export type McpTransportType = "stdio" | "websocket" | "tcp";
+
+export const MCP_TRANSPORT_TYPES: McpTransportType[] = ["stdio", "websocket", "tcp"];
+
+// Exit anyway if shutting down the sessions takes longer than this
+const SHUTDOWN_TIMEOUT_MS = 10 * 1000;
+
 /**
  * Create and configure the MCP server with an existing terminal manager
  */
@@ -34,7 +120,10 @@ Multi-session: omit sessionId to drive the default session, or call createSessio
 get a new isolated PTY for parallel work (e.g. a build in one session, diagnostics in
 another). The default session cannot be destroyed.`;
 
-export function createServerWithManager(manager: TerminalManager): Server {
+export function createServerWithManager(
+  manager: TerminalManager,
+  options: { strictInit?: boolean } = {}
+): Server {
   const server = new Server(
     {
       name: "terminal-mcp",
@@ -44,33 +133,81 @@ export function createServerWithManager(manager: TerminalManager): Server {
       capabilities: {
         tools: {},
         prompts: {},
+        resources: {},
+        logging: {},
+        completions: {},
       },
       instructions: SERVER_INSTRUCTIONS,
     }
   );
 
-  registerTools(server, manager);
-  registerPrompts(server);
+  registerTools(server, manager, { strictInit: options.strictInit });
+  registerPrompts(server, manager);
+  registerResources(server, manager);
+
+  const log = registerLogging(server);
+  const unsubscribeLog = manager.onLog((entry) => log(toLogMessage(entry)));
+  const onclose = server.onclose;
+  server.onclose = () => {
+    unsubscribeLog();
+    onclose?.();
+  };
 
   return server;
 }
 
 /**
- * Create and configure the MCP server with a new terminal manager
+ * Create a terminal manager from server options
  */
-export function createServer(options: ServerOptions = {}): {
-  server: Server;
-  manager: TerminalManager;
-} {
-  const manager = new TerminalManager({
+function createManager(options: ServerOptions): TerminalManager {
+  return new TerminalManager({
     cols: options.cols,
     rows: options.rows,
     shell: options.shell,
+    login: options.login,
+    shellArgs: options.shellArgs,
+    initCommands: options.initCommands,
+    env: options.env,
+    envPolicy: options.envPolicy,
+    resourceLimits: options.resourceLimits,
+    backend: options.backend,
+    persist: options.persist,
+    dangerRules: options.dangerRules,
+    auditLog: options.auditLog,
+    mirror: options.mirror,
     maxSessions: options.maxSessions,
     sessionIdleTimeout: options.sessionIdleTimeout,
+    idleTimeout: options.idleTimeout,
+    maxLifetime: options.maxLifetime,
+    promptPattern: options.promptPattern,
+    procTracking: options.procTracking,
+    readGuardMs: options.readGuardMs,
+    toolTimeouts: options.toolTimeouts,
+    busyTimeout: options.busyTimeout,
+    rateLimits: options.rateLimits,
+    snapshotHistory: options.snapshotHistory,
+    floodPolicy: options.floodPolicy,
+    snapshotInterval: options.snapshotInterval,
+    queryResponses: options.queryResponses,
+    fontDirs: options.fontDirs,
+    fallbackFonts: options.fallbackFonts,
+    envRedact: options.envRedact,
+    envReveal: options.envReveal,
+    notifications: options.notifications,
+    restartOnExit: options.restartOnExit,
+    command: options.command,
   });
+}
 
-  const server = createServerWithManager(manager);
+/**
+ * Create and configure the MCP server with a new terminal manager
+ */
+export function createServer(options: ServerOptions = {}): {
+  server: Server;
+  manager: TerminalManager;
+} {
+  const manager = createManager(options);
+  const server = createServerWithManager(manager, { strictInit: options.strictInit });
 
   return { server, manager };
 }
@@ -83,26 +220,101 @@ export async function connectServer(server: Server, transport: Transport): Promi
 }
 
 /**
- * Start the MCP server with stdio transport (legacy mode)
+ * Start the MCP server over stdio, or listening for WebSocket or TCP
+ * clients with --transport websocket / tcp
  */
 export async function startServer(options: ServerOptions = {}): Promise<void> {
-  const { server, manager } = createServer(options);
+  const manager = createManager(options);
 
-  // Eagerly initialize the terminal session so tools can use it immediately
-  await manager.initSession();
+  // Eagerly initialize the terminal session so tools can use it immediately.
+  // A shell that can't start is reported here, before the client sees a
+  // server that answers but whose terminal never shows anything.
+  let session: TerminalSession;
+  try {
+    session = await manager.initSession();
+  } catch (error) {
+    console.error(`[terminal-mcp] ${(error as Error).message}`);
+    console.error("[terminal-mcp] Run 'terminal-mcp doctor' to check the environment.");
+    process.exit(1);
+  }
+  const fallback = session.getShellFallback();
+  if (fallback) {
+    console.error(
+      `[terminal-mcp] Warning: shell '${fallback.requested}' ${fallback.reason}; using ${fallback.shell} instead`
+    );
+  }
 
-  const transport = new StdioServerTransport();
+  if (session.isReattached()) {
+    console.error(`[terminal-mcp] Reattached to persisted shell '${options.persist?.name}'`);
+  }
 
-  // Handle graceful shutdown
-  process.on("SIGINT", () => {
-    manager.dispose();
-    process.exit(0);
-  });
+  // Handle graceful shutdown: stop the shells instead of orphaning them
+  // (a --persist shell is detached from instead, on purpose)
+  let shuttingDown = false;
+  const shutdown = () => {
+    if (shuttingDown) return;
+    shuttingDown = true;
+    // Don't hang on a stuck recording or notification sink
+    setTimeout(() => process.exit(1), SHUTDOWN_TIMEOUT_MS).unref();
+    manager.shutdown().finally(() => process.exit(0));
+  };
 
-  process.on("SIGTERM", () => {
-    manager.dispose();
-    process.exit(0);
-  });
+  process.on("SIGINT", shutdown);
+  process.on("SIGTERM", shutdown);
+  process.on("SIGHUP", shutdown);
+  if (options.exitOnLimit) {
+    manager.onLimitReached(shutdown);
+  }
 
-  await server.connect(transport);
+  if (options.observe) {
+    createObserverServer(manager, options.observe, { authToken: options.authToken });
+  }
+  if (options.attach !== false && process.platform !== "win32") {
+    createAttachServer(manager);
+  }
+
+  if ((options.transport === "websocket" || options.transport === "tcp") && options.listen) {
+    const address = formatListenAddress(options.listen);
+    // A server per client; they share the manager and so the sessions
+    const connect = (transport: Transport) => {
+      const server = createServerWithManager(manager, { strictInit: options.strictInit });
+      server.connect(transport).catch((error: Error) => {
+        console.error(`[terminal-mcp] MCP client failed to connect: ${error.message}`);
+      });
+    };
+    let url: string;
+    let listener: NetServer;
+    if (options.transport === "tcp") {
+      if (!options.authToken) {
+        throw new Error("--transport tcp requires an auth token");
+      }
+      url = `${options.tls ? "tls" : "tcp"}://${address}`;
+      listener = createTcpServer(options.listen, { authToken: options.authToken, tls: options.tls }, connect);
+    } else {
+      url = `${options.tls ? "wss" : "ws"}://${address}`;
+      listener = createWebSocketServer(
+        options.listen,
+        {
+          tls: options.tls,
+          authToken: options.authToken,
+          allowedOrigins: options.allowedOrigins,
+          allowedHosts: loopbackHostNames(options.listen.host, options.listen.port),
+        },
+        connect
+      );
+    }
+    listener.on("listening", () => {
+      console.error(`[terminal-mcp] Listening for MCP clients on ${url}`);
+    });
+    listener.on("error", (error) => {
+      console.error(`[terminal-mcp] Cannot listen on ${address}: ${error.message}`);
+      process.exit(1);
+    });
+    return;
+  }
+
+  // stdin EOF: the client has gone away
+  process.stdin.on("end", shutdown);
+  const server = createServerWithManager(manager, { strictInit: options.strictInit });
+  await server.connect(new StdioServerTransport());
 }
@@ -0,0 +1,265 @@
+import { spawn, type ChildProcess } from "child_process";
+import type { Notifier } from "../notifications/index.js";
+
+/**
+ * Watchdog for headless mode (--supervise). The supervisor owns the MCP
+ * client's stdio and runs the real server as a child process, relaying
+ * newline-delimited JSON-RPC in both directions. When the child crashes it
+ * is restarted and the client's initialize handshake is replayed, so the
+ * client keeps its connection without re-initializing.
+ *
+ * Terminal sessions live in the child and are not persisted yet: after a
+ * restart the default session is a fresh shell and other sessions are gone.
+ */
+
+// Set in the child's environment so it runs as a plain server
+export const SUPERVISED_ENV = "TERMINAL_MCP_SUPERVISED";
+
+// Give up if the server crashes this often within the window
+const MAX_RESTARTS = 5;
+const RESTART_WINDOW_MS = 60 * 1000;
+const MAX_BACKOFF_MS = 10 * 1000;
+// After the client disconnects, wait this long for the child to exit. The
+// child itself gives its shells up to 2s to exit before killing them.
+const SHUTDOWN_GRACE_MS = 5000;
+
+// JSON-RPC "internal error"
+const INTERNAL_ERROR = -32603;
+
+export interface SupervisorOptions {
+  /** CLI arguments for the child server, without --supervise */
+  args: string[];
+  /** Sinks for 'server_restarted' events */
+  notifier?: Notifier;
+}
+
+interface JsonRpcMessage {
+  jsonrpc?: string;
+  id?: string | number;
+  method?: string;
+  params?: unknown;
+  result?: unknown;
+  error?: unknown;
+}
+
+function parseMessage(line: string): JsonRpcMessage | null {
+  try {
+    const message = JSON.parse(line);
+    return message && typeof message === "object" ? message : null;
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Split a byte stream into lines, buffering partial lines between chunks
+ */
+function onLines(stream: NodeJS.ReadableStream, handler: (line: string) => void): void {
+  let buffered = "";
+  stream.setEncoding("utf8");
+  stream.on("data", (chunk: string) => {
+    buffered += chunk;
+    let newline: number;
+    while ((newline = buffered.indexOf("\n")) !== -1) {
+      const line = buffered.slice(0, newline).replace(/\r$/, "");
+      buffered = buffered.slice(newline + 1);
+      if (line) handler(line);
+    }
+  });
+}
+
+export class Supervisor {
+  private child: ChildProcess | null = null;
+  private stopping = false;
+  private restarts = 0;
+  private crashTimes: number[] = [];
+
+  // Handshake captured from the client, replayed to each restarted child
+  private initializeRequest: JsonRpcMessage | null = null;
+  private initializedNotification: string | null = null;
+  private replayId: string | null = null;
+
+  // Client messages held while the child is (re)starting
+  private queue: string[] = [];
+  private ready = false;
+  // Client requests the current child has not answered yet
+  private pending: Map<string, string | number> = new Map();
+
+  constructor(private options: SupervisorOptions) {}
+
+  /**
+   * Start the child and relay stdio until the client disconnects
+   */
+  run(): void {
+    onLines(process.stdin, (line) => this.fromClient(line));
+    process.stdin.on("end", () => this.stop("SIGTERM"));
+    process.on("SIGINT", () => this.stop("SIGINT"));
+    process.on("SIGTERM", () => this.stop("SIGTERM"));
+    this.startChild();
+  }
+
+  private startChild(): void {
+    const child = spawn(
+      process.execPath,
+      [...process.execArgv, process.argv[1], ...this.options.args],
+      {
+        stdio: ["pipe", "pipe", "inherit"],
+        env: { ...process.env, [SUPERVISED_ENV]: "1" },
+      }
+    );
+    this.child = child;
+    this.ready = false;
+    this.pending.clear();
+
+    onLines(child.stdout!, (line) => this.fromChild(line));
+    child.stdin!.on("error", () => {
+      // The child died mid-write; its exit handler restarts it
+    });
+    child.on("exit", (code, signal) => this.onChildExit(child, code, signal));
+
+    if (this.initializeRequest) {
+      this.replayId = `supervisor-replay-${this.restarts}`;
+      this.writeChild(JSON.stringify({ ...this.initializeRequest, id: this.replayId }));
+    } else {
+      this.markReady();
+    }
+  }
+
+  private markReady(): void {
+    this.ready = true;
+    if (this.initializedNotification) {
+      this.writeChild(this.initializedNotification);
+    }
+    const queued = this.queue;
+    this.queue = [];
+    for (const line of queued) {
+      this.forward(line);
+    }
+  }
+
+  private fromClient(line: string): void {
+    const message = parseMessage(line);
+    if (message?.method === "initialize" && message.id !== undefined) {
+      this.initializeRequest = message;
+    } else if (message?.method === "notifications/initialized") {
+      // Forwarded now; replayed by markReady() after a restart
+      this.initializedNotification = line;
+      if (this.ready) this.writeChild(line);
+      return;
+    }
+
+    if (this.ready) {
+      this.forward(line);
+    } else {
+      this.queue.push(line);
+    }
+  }
+
+  private forward(line: string): void {
+    const message = parseMessage(line);
+    if (message?.method && message.id !== undefined) {
+      this.pending.set(JSON.stringify(message.id), message.id);
+    }
+    this.writeChild(line);
+  }
+
+  private fromChild(line: string): void {
+    const message = parseMessage(line);
+    if (message && message.id !== undefined && !message.method) {
+      if (message.id === this.replayId) {
+        // Response to the replayed handshake; the client already has one
+        this.replayId = null;
+        this.markReady();
+        return;
+      }
+      this.pending.delete(JSON.stringify(message.id));
+    }
+    process.stdout.write(line + "\n");
+  }
+
+  private writeChild(line: string): void {
+    this.child?.stdin?.write(line + "\n");
+  }
+
+  private writeClient(message: JsonRpcMessage): void {
+    process.stdout.write(JSON.stringify({ jsonrpc: "2.0", ...message }) + "\n");
+  }
+
+  private onChildExit(child: ChildProcess, code: number | null, signal: NodeJS.Signals | null): void {
+    if (child !== this.child) return;
+    this.child = null;
+
+    // A clean exit (client gone, shell exited) ends the supervisor too
+    if (this.stopping || (code === 0 && !signal)) {
+      this.exit(code ?? 0);
+      return;
+    }
+
+    const reason = signal ? `signal ${signal}` : `exit code ${code}`;
+
+    // The crashed child will never answer these
+    for (const id of this.pending.values()) {
+      this.writeClient({
+        id,
+        error: { code: INTERNAL_ERROR, message: `terminal-mcp server crashed (${reason}) and is restarting; retry the request` },
+      });
+    }
+    this.pending.clear();
+
+    const now = Date.now();
+    this.crashTimes = this.crashTimes.filter((t) => now - t < RESTART_WINDOW_MS);
+    this.crashTimes.push(now);
+    if (this.crashTimes.length > MAX_RESTARTS) {
+      console.error(
+        `[terminal-mcp] Server crashed ${this.crashTimes.length} times within ${RESTART_WINDOW_MS / 1000}s (last: ${reason}); giving up`
+      );
+      this.exit(1);
+      return;
+    }
+
+    this.restarts++;
+    const delay = Math.min(250 * 2 ** (this.crashTimes.length - 1), MAX_BACKOFF_MS);
+    console.error(`[terminal-mcp] Server crashed (${reason}); restarting in ${delay}ms`);
+
+    setTimeout(() => {
+      if (this.stopping) return;
+      this.startChild();
+      const params = {
+        restarts: this.restarts,
+        exitCode: code,
+        signal,
+        sessionsRestored: false,
+      };
+      // Custom MCP notification; clients that don't know it ignore it
+      this.writeClient({ method: "notifications/server_restarted", params });
+      this.options.notifier?.notify(
+        "server_restarted",
+        "server",
+        `Server crashed (${reason}) and was restarted; terminal sessions were reset`,
+        params
+      );
+    }, delay);
+  }
+
+  private stop(signal: NodeJS.Signals): void {
+    if (this.stopping) return;
+    this.stopping = true;
+    const child = this.child;
+    if (!child) {
+      this.exit(0);
+      return;
+    }
+    child.stdin?.end();
+    child.kill(signal);
+    setTimeout(() => child.kill("SIGKILL"), SHUTDOWN_GRACE_MS).unref();
+  }
+
+  private exit(code: number): void {
+    const flushed = this.options.notifier?.flush() ?? Promise.resolve();
+    flushed.finally(() => process.exit(code));
+  }
+}
+
+export function runSupervisor(options: SupervisorOptions): void {
+  new Supervisor(options).run();
+}
@@ -0,0 +1,156 @@
+/**
+ * What a session drives: a local PTY (node-pty) or a remote one (SSH).
+ * node-pty's IPty already has this shape, so the local backend is the
+ * IPty itself.
+ */
+
+export interface BackendDisposable {
+  dispose(): void;
+}
+
+export interface TerminalBackend {
+  /** Process id of the local shell; remote shells have none */
+  readonly pid: number | null;
+  write(data: string): void;
+  resize(cols: number, rows: number): void;
+  kill(signal?: string): void;
+  onData(listener: (data: string) => void): BackendDisposable;
+  onExit(listener: (event: { exitCode: number; signal?: number }) => void): BackendDisposable;
+  /**
+   * Stop and restart reading output, so a program printing faster than the
+   * emulator parses blocks on the PTY instead (--flood-policy pause)
+   */
+  pause?(): void;
+  resume?(): void;
+  /**
+   * Forget output kept to replay later (--persist), once the scrollback it
+   * would restore has been cleared
+   */
+  clearHistory?(): void;
+}
+
+/**
+ * What a session does when output arrives faster than it can be parsed
+ * (--flood-policy)
+ * - 'pause': stop reading from the PTY until the emulator catches up, so
+ *   the program blocks on its writes (default)
+ * - 'drop': keep reading but skip the excess, leaving a note on the screen
+ *   saying how much was skipped
+ */
+export type FloodPolicy = "pause" | "drop";
+
+export const FLOOD_POLICIES: FloodPolicy[] = ["pause", "drop"];
+
+/**
+ * Where sessions run (--backend)
+ * - 'local': a PTY on this machine (default)
+ * - 'ssh': an interactive session on --host over SSH
+ * - 'docker': a shell in a container, new from --image or an existing --container
+ * - 'tmux': a local tmux client attached to a named tmux session
+ */
+export type BackendType = "local" | "ssh" | "docker" | "tmux";
+
+export const BACKEND_TYPES: BackendType[] = ["local", "ssh", "docker", "tmux"];
+
+export interface SshTarget {
+  host: string;
+  port: number;
+  username: string;
+  /** Private key file (default: ssh-agent, then ~/.ssh/id_ed25519, id_ecdsa, id_rsa) */
+  identityFile?: string;
+  /** known_hosts file the host key is checked against (default: ~/.ssh/known_hosts) */
+  knownHostsFile?: string;
+}
+
+/**
+ * Exactly one of image and container is set
+ */
+export interface DockerTarget {
+  /** Create a container from this image for each session, removed with it */
+  image?: string;
+  /** Run shells in this running container instead */
+  container?: string;
+}
+
+export interface TmuxTarget {
+  /** tmux session to attach to, created if it doesn't exist */
+  session: string;
+  /** tmux server socket name (tmux -L); default: the user's default server */
+  socketName?: string;
+  /** Kill the tmux session when this session is destroyed instead of detaching */
+  ephemeral?: boolean;
+}
+
+export type BackendConfig =
+  | { type: "local" }
+  | ({ type: "ssh" } & SshTarget)
+  | ({ type: "docker" } & DockerTarget)
+  | ({ type: "tmux" } & TmuxTarget);
+
+/**
+ * Stands for the remote temp directory holding uploaded rc files in
+ * RemoteLaunch args and env values
+ */
+export const REMOTE_DIR = "@TERMINAL_MCP_DIR@";
+
+/**
+ * What to start on a remote host or in a container. files are written to a
+ * private temp directory first and removed when the program exits.
+ */
+export interface RemoteLaunch {
+  program: string;
+  args: string[];
+  env: Record<string, string>;
+  cwd?: string;
+  files?: Record<string, string>;
+}
+
+export function shellQuote(value: string): string {
+  return `'${value.replace(/'/g, "'\\''")}'`;
+}
+
+/**
+ * A POSIX sh script that writes the launch's files, runs it, and cleans up
+ */
+export function buildLaunchScript(launch: RemoteLaunch): string {
+  const arg = (value: string) => shellQuote(value).split(REMOTE_DIR).join(`'"$d"'`);
+  const files = Object.entries(launch.files ?? {});
+  const lines: string[] = [];
+  if (files.length > 0) {
+    lines.push(`d=$(mktemp -d "\${TMPDIR:-/tmp}/terminal-mcp.XXXXXX") || exit 1`);
+    for (const [name, content] of files) {
+      lines.push(`printf '%s' ${shellQuote(content)} > "$d/${name}"`);
+    }
+  }
+  if (launch.cwd) lines.push(`cd ${shellQuote(launch.cwd)} || exit 1`);
+  const env = Object.entries(launch.env).map(([key, value]) => `${key}=${arg(value)}`);
+  const run = ["env", ...env, arg(launch.program), ...launch.args.map(arg)].join(" ");
+  if (files.length > 0) {
+    lines.push(run, "s=$?", 'rm -rf "$d"', "exit $s");
+  } else {
+    lines.push(`exec ${run}`);
+  }
+  return lines.join("\n");
+}
+
+/**
+ * Small listener list returning node-pty style disposables
+ */
+export class BackendEmitter<T> {
+  private listeners: Array<(event: T) => void> = [];
+
+  on(listener: (event: T) => void): BackendDisposable {
+    this.listeners.push(listener);
+    return {
+      dispose: () => {
+        this.listeners = this.listeners.filter((l) => l !== listener);
+      },
+    };
+  }
+
+  emit(event: T): void {
+    for (const listener of [...this.listeners]) {
+      listener(event);
+    }
+  }
+}
@@ -0,0 +1,55 @@
+/**
+ * Commands that need a human's approval before tool input may submit them
+ * (--confirm-dangerous, --confirm-pattern). Matching is on the command line
+ * as submitted at the prompt, so it's a guard rail against accidents, not a
+ * sandbox: aliases, scripts, and programs other than the shell get through.
+ */
+
+export interface DangerRule {
+  pattern: RegExp;
+  /** What the command would do, shown to the human */
+  reason: string;
+}
+
+export const DEFAULT_DANGER_RULES: DangerRule[] = [
+  { pattern: /\brm\s+(-\S+\s+)*(-[a-zA-Z]*[rR]|--recursive)\b/, reason: "recursive delete" },
+  { pattern: /\b(mkfs(\.\w+)?|fdisk|parted|wipefs)\b/, reason: "formatting or partitioning a disk" },
+  { pattern: /\bdd\b.*\bof=\/dev\/|>\s*\/dev\/(sd|hd|nvme|disk|mmcblk)/, reason: "writing directly to a device" },
+  { pattern: /\b(shutdown|reboot|halt|poweroff)\b/, reason: "shutting down the machine" },
+  { pattern: /\bgit\s+push\b.*\s(--force(-with-lease)?|-f)\b/, reason: "force push" },
+  { pattern: /\bgit\s+(reset\s+--hard|clean\s+-[a-zA-Z]*f)/, reason: "discarding uncommitted work" },
+  { pattern: /\b(drop\s+(table|database|schema)|truncate\s+table)\b/i, reason: "dropping database objects" },
+  { pattern: /\bchmod\s+(-\S+\s+)*0?777\b|\bch(own|mod)\s+-R\b/, reason: "recursive or world-writable permission change" },
+  { pattern: /\b(curl|wget)\b[^|]*\|\s*(sudo\s+)?(ba|z|da)?sh\b/, reason: "running a downloaded script" },
+  { pattern: /\bkubectl\s+delete\b|\bterraform\s+destroy\b/, reason: "deleting infrastructure" },
+  { pattern: /:\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:/, reason: "fork bomb" },
+];
+
+/**
+ * Rules for --confirm-pattern regexes. Throws on an invalid one.
+ */
+export function compileDangerRules(patterns: string[]): DangerRule[] {
+  return patterns.map((source) => {
+    try {
+      return { pattern: new RegExp(source), reason: `matches --confirm-pattern '${source}'` };
+    } catch (error) {
+      throw new Error(`Invalid --confirm-pattern '${source}': ${(error as Error).message}`);
+    }
+  });
+}
+
+export interface DangerousCommand {
+  command: string;
+  reason: string;
+}
+
+/**
+ * The first command matching a rule, or null
+ */
+export function findDangerousCommand(commands: string[], rules: DangerRule[]): DangerousCommand | null {
+  for (const command of commands) {
+    const rule = rules.find((r) => r.pattern.test(command));
+    if (rule) return { command, reason: rule.reason };
+  }
+  return null;
+}
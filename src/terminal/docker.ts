@@ -0,0 +1,358 @@
+/**
+ * Docker backend (--backend docker): shells run in a container, each on a
+ * TTY the daemon allocates for an exec. Talks to the Docker Engine API over
+ * its socket directly, so no docker CLI or client library is needed.
+ */
+import * as http from "http";
+import { randomBytes } from "crypto";
+import type { Duplex } from "stream";
+import {
+  BackendEmitter,
+  buildLaunchScript,
+  shellQuote,
+  type BackendDisposable,
+  type DockerTarget,
+  type RemoteLaunch,
+  type TerminalBackend,
+} from "./backend.js";
+
+const API_VERSION = "v1.41";
+const DEFAULT_DOCKER_SOCKET = "/var/run/docker.sock";
+const API_TIMEOUT_MS = 30000;
+// Pulling an image can take minutes
+const PULL_TIMEOUT_MS = 10 * 60 * 1000;
+// How long to wait for an exec's exit code once its stream has closed
+const EXIT_CODE_WAIT_MS = 1000;
+const EXIT_CODE_POLL_MS = 50;
+
+// Keeps a container we created running between shells (restartShell)
+const KEEPALIVE_SCRIPT = "trap 'exit 0' TERM; while :; do sleep 3600 & wait $!; done";
+
+type Endpoint = { socketPath: string } | { host: string; port: number };
+
+/**
+ * Where the daemon listens: DOCKER_HOST (unix:// or plain tcp://), else
+ * the default socket
+ */
+function dockerEndpoint(): Endpoint {
+  const value = process.env.DOCKER_HOST;
+  if (!value) return { socketPath: DEFAULT_DOCKER_SOCKET };
+  if (value.startsWith("unix://")) return { socketPath: value.slice("unix://".length) };
+  if (value.startsWith("tcp://")) {
+    if (process.env.DOCKER_TLS_VERIFY) {
+      throw new Error("DOCKER_TLS_VERIFY is set, but --backend docker only speaks plain HTTP to the daemon");
+    }
+    const url = new URL(value.replace(/^tcp:/, "http:"));
+    return { host: url.hostname, port: parseInt(url.port || "2375", 10) };
+  }
+  throw new Error(`Unsupported DOCKER_HOST '${value}': expected unix:// or tcp://`);
+}
+
+function describeEndpoint(endpoint: Endpoint): string {
+  return "socketPath" in endpoint ? endpoint.socketPath : `${endpoint.host}:${endpoint.port}`;
+}
+
+/**
+ * Minimal Docker Engine API client
+ */
+export class DockerApi {
+  private endpoint = dockerEndpoint();
+
+  request(
+    method: string,
+    apiPath: string,
+    body?: unknown,
+    timeoutMs = API_TIMEOUT_MS
+  ): Promise<{ status: number; text: string }> {
+    return new Promise((resolve, reject) => {
+      const payload = body === undefined ? undefined : JSON.stringify(body);
+      const req = http.request(
+        {
+          ...this.endpoint,
+          method,
+          path: `/${API_VERSION}${apiPath}`,
+          headers: payload === undefined ? {} : { "Content-Type": "application/json", "Content-Length": Buffer.byteLength(payload) },
+          timeout: timeoutMs,
+        },
+        (res) => {
+          let text = "";
+          res.setEncoding("utf-8");
+          res.on("data", (chunk: string) => (text += chunk));
+          res.on("end", () => resolve({ status: res.statusCode ?? 0, text }));
+          res.on("error", reject);
+        }
+      );
+      req.on("timeout", () => req.destroy(new Error(`Docker API request timed out: ${method} ${apiPath}`)));
+      req.on("error", (error) =>
+        reject(new Error(`Cannot reach the Docker daemon at ${describeEndpoint(this.endpoint)}: ${error.message}`))
+      );
+      req.end(payload);
+    });
+  }
+
+  /**
+   * Make a request and parse its JSON reply, throwing the daemon's message
+   * on an error status
+   */
+  async call<T = Record<string, unknown>>(method: string, apiPath: string, body?: unknown, timeoutMs?: number): Promise<T> {
+    const { status, text } = await this.request(method, apiPath, body, timeoutMs);
+    if (status >= 400) {
+      let message = text.trim();
+      try {
+        message = (JSON.parse(text) as { message?: string }).message ?? message;
+      } catch {
+        // Not JSON; keep the raw text
+      }
+      throw Object.assign(new Error(message || `HTTP ${status}`), { status });
+    }
+    if (!text.trim()) return {} as T;
+    try {
+      return JSON.parse(text) as T;
+    } catch {
+      return {} as T;
+    }
+  }
+
+  /**
+   * Start a request the daemon upgrades to a raw stream (attaching to an
+   * exec's TTY)
+   */
+  hijack(apiPath: string, body: unknown): Promise<Duplex> {
+    return new Promise((resolve, reject) => {
+      const payload = JSON.stringify(body);
+      const req = http.request({
+        ...this.endpoint,
+        method: "POST",
+        path: `/${API_VERSION}${apiPath}`,
+        headers: {
+          "Content-Type": "application/json",
+          "Content-Length": Buffer.byteLength(payload),
+          Connection: "Upgrade",
+          Upgrade: "tcp",
+        },
+      });
+      req.on("upgrade", (_res, socket, head) => {
+        if (head.length > 0) socket.unshift(head);
+        resolve(socket);
+      });
+      req.on("response", (res) => {
+        let text = "";
+        res.setEncoding("utf-8");
+        res.on("data", (chunk: string) => (text += chunk));
+        res.on("end", () => reject(new Error(`Docker refused to attach: ${text.trim() || `HTTP ${res.statusCode}`}`)));
+      });
+      req.on("error", (error) =>
+        reject(new Error(`Cannot reach the Docker daemon at ${describeEndpoint(this.endpoint)}: ${error.message}`))
+      );
+      req.end(payload);
+    });
+  }
+}
+
+/**
+ * Split an image reference into the fromImage/tag pair the pull endpoint
+ * takes; without a tag or digest the daemon would pull every tag
+ */
+function pullQuery(image: string): string {
+  const lastSlash = image.lastIndexOf("/");
+  const hasTag = image.includes("@") || image.lastIndexOf(":") > lastSlash;
+  const params = new URLSearchParams({ fromImage: image });
+  if (!hasTag) params.set("tag", "latest");
+  return params.toString();
+}
+
+/**
+ * The container a session's shells run in: one created from --image (and
+ * removed with the session), or an existing one named by --container
+ */
+export class DockerContainer {
+  private constructor(
+    private api: DockerApi,
+    readonly id: string,
+    readonly name: string,
+    private owned: boolean
+  ) {}
+
+  static async open(target: DockerTarget): Promise<DockerContainer> {
+    const api = new DockerApi();
+    if (target.container) {
+      const info = await api
+        .call<{ Id: string; Name: string; State?: { Running?: boolean } }>(
+          "GET",
+          `/containers/${encodeURIComponent(target.container)}/json`
+        )
+        .catch((error: Error & { status?: number }) => {
+          throw error.status === 404 ? new Error(`No container named '${target.container}'`) : error;
+        });
+      if (!info.State?.Running) {
+        throw new Error(`Container '${target.container}' isn't running`);
+      }
+      return new DockerContainer(api, info.Id, info.Name.replace(/^\//, ""), false);
+    }
+
+    const image = target.image!;
+    const name = `terminal-mcp-${randomBytes(4).toString("hex")}`;
+    const create = () =>
+      api.call<{ Id: string }>("POST", `/containers/create?name=${name}`, {
+        Image: image,
+        Entrypoint: ["sh", "-c"],
+        Cmd: [KEEPALIVE_SCRIPT],
+        Labels: { "dev.terminal-mcp": "1" },
+        HostConfig: { AutoRemove: false },
+      });
+    let created: { Id: string };
+    try {
+      created = await create();
+    } catch (error) {
+      if ((error as { status?: number }).status !== 404) throw error;
+      await DockerContainer.pull(api, image);
+      created = await create();
+    }
+    const container = new DockerContainer(api, created.Id, name, true);
+    try {
+      await api.call("POST", `/containers/${created.Id}/start`);
+    } catch (error) {
+      await container.remove();
+      throw error;
+    }
+    return container;
+  }
+
+  private static async pull(api: DockerApi, image: string): Promise<void> {
+    const { status, text } = await api.request("POST", `/images/create?${pullQuery(image)}`, undefined, PULL_TIMEOUT_MS);
+    // Progress comes as a stream of JSON objects; a failure is one with "error"
+    const failure = text
+      .split("\n")
+      .map((line) => {
+        try {
+          return (JSON.parse(line) as { error?: string; message?: string }).error;
+        } catch {
+          return undefined;
+        }
+      })
+      .find(Boolean);
+    if (status >= 400 || failure) {
+      throw new Error(`Cannot pull ${image}: ${failure ?? (text.trim() || `HTTP ${status}`)}`);
+    }
+  }
+
+  /**
+   * Start a launch on a new TTY in the container
+   */
+  exec(launch: RemoteLaunch, cols: number, rows: number): Promise<DockerBackend> {
+    return DockerBackend.start(this.api, this.id, launch, cols, rows);
+  }
+
+  /**
+   * Remove the container if this session created it. Never throws.
+   */
+  async remove(): Promise<void> {
+    if (!this.owned) return;
+    this.owned = false;
+    await this.api.call("DELETE", `/containers/${this.id}?force=true`).catch(() => {});
+  }
+}
+
+/**
+ * One exec in a container, attached through the daemon
+ */
+export class DockerBackend implements TerminalBackend {
+  readonly pid = null;
+  private dataEmitter = new BackendEmitter<string>();
+  private exitEmitter = new BackendEmitter<{ exitCode: number; signal?: number }>();
+  private exited = false;
+
+  private constructor(
+    private api: DockerApi,
+    private containerId: string,
+    private execId: string,
+    private stream: Duplex,
+    // Where the exec's sh writes its pid inside the container, for kill()
+    private pidFile: string
+  ) {
+    stream.setEncoding("utf-8");
+    stream.on("data", (data: string) => this.dataEmitter.emit(data));
+    stream.on("close", () => void this.finish());
+    stream.on("error", () => void this.finish());
+  }
+
+  static async start(
+    api: DockerApi,
+    containerId: string,
+    launch: RemoteLaunch,
+    cols: number,
+    rows: number
+  ): Promise<DockerBackend> {
+    const pidFile = `/tmp/terminal-mcp-${randomBytes(4).toString("hex")}.pid`;
+    const exec = await api.call<{ Id: string }>("POST", `/containers/${containerId}/exec`, {
+      AttachStdin: true,
+      AttachStdout: true,
+      AttachStderr: true,
+      Tty: true,
+      Env: ["TERM=xterm-256color"],
+      Cmd: ["sh", "-c", `echo $$ > ${pidFile}\n${buildLaunchScript(launch)}`],
+    });
+    const stream = await api.hijack(`/exec/${exec.Id}/start`, { Detach: false, Tty: true });
+    const backend = new DockerBackend(api, containerId, exec.Id, stream, pidFile);
+    backend.resize(cols, rows);
+    return backend;
+  }
+
+  private async finish(): Promise<void> {
+    if (this.exited) return;
+    this.exited = true;
+    // The stream closes slightly before the daemon records the exit code
+    let exitCode: number | null = null;
+    const deadline = Date.now() + EXIT_CODE_WAIT_MS;
+    while (exitCode === null && Date.now() < deadline) {
+      const info = await this.api
+        .call<{ Running?: boolean; ExitCode?: number | null }>("GET", `/exec/${this.execId}/json`)
+        .catch(() => null);
+      if (!info) break;
+      if (!info.Running && typeof info.ExitCode === "number") exitCode = info.ExitCode;
+      else await new Promise((resolve) => setTimeout(resolve, EXIT_CODE_POLL_MS));
+    }
+    this.exitEmitter.emit({ exitCode: exitCode ?? 255 });
+  }
+
+  write(data: string): void {
+    if (!this.exited) this.stream.write(data);
+  }
+
+  pause(): void {
+    this.stream.pause();
+  }
+
+  resume(): void {
+    this.stream.resume();
+  }
+
+  resize(cols: number, rows: number): void {
+    if (this.exited) return;
+    this.api.call("POST", `/exec/${this.execId}/resize?h=${rows}&w=${cols}`).catch(() => {});
+  }
+
+  /**
+   * Signal the exec's process inside the container (there is no API for
+   * it). It leads the TTY's session, so when it dies the kernel hangs up
+   * the shell running under it too. SIGKILL also drops the stream.
+   */
+  kill(signal = "SIGHUP"): void {
+    if (this.exited) return;
+    const name = signal.replace(/^SIG/, "");
+    const script = `kill -s ${name} "$(cat ${shellQuote(this.pidFile)})"; rm -f ${shellQuote(this.pidFile)}`;
+    this.api
+      .call<{ Id: string }>("POST", `/containers/${this.containerId}/exec`, { Cmd: ["sh", "-c", script] })
+      .then((exec) => this.api.call("POST", `/exec/${exec.Id}/start`, { Detach: true }))
+      .catch(() => {});
+    if (signal === "SIGKILL") this.stream.destroy();
+  }
+
+  onData(listener: (data: string) => void): BackendDisposable {
+    return this.dataEmitter.on(listener);
+  }
+
+  onExit(listener: (event: { exitCode: number; signal?: number }) => void): BackendDisposable {
+    return this.exitEmitter.on(listener);
+  }
+}
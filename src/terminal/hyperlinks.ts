@@ -0,0 +1,161 @@
+import type { IMarker, Terminal } from "@xterm/headless";
+
+// Oldest links are forgotten beyond this many per session
+const MAX_LINKS = 500;
+
+/**
+ * A hyperlink emitted with OSC 8. Positions are buffer coordinates
+ * (line 0 = oldest scrollback line); `end` is exclusive.
+ */
+export interface Hyperlink {
+  uri: string;
+  /** The id= parameter, which groups the pieces of one link */
+  id?: string;
+  /** Anchor text as printed between the opening and closing sequences */
+  text: string;
+  start: { line: number; col: number };
+  end: { line: number; col: number };
+  /** Screen row of the start (0-indexed), or null if scrolled off */
+  screenRow: number | null;
+}
+
+interface TrackedLink {
+  uri: string;
+  id?: string;
+  text: string;
+  start: IMarker;
+  startCol: number;
+  end: IMarker;
+  endCol: number;
+  alternate: boolean;
+}
+
+interface OpenLink {
+  uri: string;
+  id?: string;
+  start: IMarker;
+  startCol: number;
+  alternate: boolean;
+}
+
+/**
+ * Records OSC 8 hyperlinks (`ESC ] 8 ; params ; URI ST text ESC ] 8 ; ; ST`)
+ * as they are printed. xterm.js stores the link on each cell internally
+ * but doesn't expose it, so the span is tracked with buffer markers, which
+ * follow lines as output scrolls and are disposed when trimmed from the
+ * scrollback.
+ */
+export class HyperlinkTracker {
+  private links: TrackedLink[] = [];
+  private open: OpenLink | null = null;
+
+  constructor(private terminal: Terminal) {
+    terminal.parser.registerOscHandler(8, (data) => {
+      this.handle(data);
+      // Let xterm.js apply its own link attributes as well
+      return false;
+    });
+  }
+
+  private handle(data: string): void {
+    const separator = data.indexOf(";");
+    if (separator === -1) return;
+    const params = data.slice(0, separator);
+    const uri = data.slice(separator + 1);
+
+    // Any OSC 8 ends the current link; a non-empty URI starts the next one
+    if (this.open) this.close(this.open);
+    this.open = null;
+    if (!uri) return;
+
+    const start = this.terminal.registerMarker(0);
+    if (!start) return;
+    const id = /(?:^|:)id=([^:]*)/.exec(params)?.[1];
+    this.open = {
+      uri,
+      id: id || undefined,
+      start,
+      startCol: this.terminal.buffer.active.cursorX,
+      alternate: this.terminal.buffer.active.type === "alternate",
+    };
+  }
+
+  private close(open: OpenLink): void {
+    const end = this.terminal.registerMarker(0);
+    const endCol = Math.min(this.terminal.buffer.active.cursorX, this.terminal.cols);
+    if (!end || open.start.isDisposed) {
+      open.start.dispose();
+      end?.dispose();
+      return;
+    }
+    const text = this.readText(open.start.line, open.startCol, end.line, endCol);
+    if (!text) {
+      open.start.dispose();
+      end.dispose();
+      return;
+    }
+
+    this.links.push({ ...open, text, end, endCol });
+    while (this.links.length > MAX_LINKS) {
+      const dropped = this.links.shift()!;
+      dropped.start.dispose();
+      dropped.end.dispose();
+    }
+  }
+
+  /**
+   * Text between two buffer positions, joining soft-wrapped rows and
+   * separating hard-wrapped ones with a newline
+   */
+  private readText(startLine: number, startCol: number, endLine: number, endCol: number): string {
+    const buffer = this.terminal.buffer.active;
+    let text = "";
+    for (let y = startLine; y <= endLine; y++) {
+      const line = buffer.getLine(y);
+      if (!line) break;
+      if (y > startLine && !line.isWrapped) text += "\n";
+      text += line.translateToString(
+        y < endLine && buffer.getLine(y + 1)?.isWrapped === true ? false : true,
+        y === startLine ? startCol : 0,
+        y === endLine ? endCol : undefined
+      );
+    }
+    return text;
+  }
+
+  /**
+   * Links still on the active buffer whose anchor text hasn't been
+   * overwritten since, oldest first
+   */
+  getLinks(visibleOnly = false): Hyperlink[] {
+    const buffer = this.terminal.buffer.active;
+    const alternate = buffer.type === "alternate";
+    const result: Hyperlink[] = [];
+    for (const link of this.links) {
+      if (link.start.isDisposed || link.end.isDisposed || link.alternate !== alternate) continue;
+      const startLine = link.start.line;
+      const endLine = link.end.line;
+      if (visibleOnly && endLine < buffer.baseY) continue;
+      if (this.readText(startLine, link.startCol, endLine, link.endCol) !== link.text) continue;
+      result.push({
+        uri: link.uri,
+        ...(link.id ? { id: link.id } : {}),
+        text: link.text,
+        start: { line: startLine, col: link.startCol },
+        end: { line: endLine, col: link.endCol },
+        screenRow: startLine >= buffer.baseY ? startLine - buffer.baseY : null,
+      });
+    }
+    return result;
+  }
+
+  dispose(): void {
+    for (const link of this.links) {
+      link.start.dispose();
+      link.end.dispose();
+    }
+    this.links = [];
+    this.open?.start.dispose();
+    this.open = null;
+  }
+}
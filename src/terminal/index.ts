@@ -1,8 +1,49 @@
-export { TerminalSession } from "./session.js";
-export type { TerminalSessionOptions, ScreenshotResult } from "./session.js";
+export { TerminalSession, ShellLaunchError } from "./session.js";
+export type {
+  TerminalSessionOptions,
+  ScreenshotResult,
+  BufferLine,
+  ClipboardEntry,
+  ContentChanges,
+  CursorShape,
+  CursorStyle,
+  TerminalModes,
+  ShellFallback,
+  InitCommandResult,
+  ReportedCwd,
+  FlowStats,
+  OutputMark,
+} from "./session.js";
+export type { Hyperlink } from "./hyperlinks.js";
+export { DEFAULT_TERMCAP, loadQueryResponses, parseColor } from "./queries.js";
+export type { QueryResponses } from "./queries.js";
+export { OutputStreamer, OUTPUT_NOTIFICATION, DEFAULT_OUTPUT_DEBOUNCE_MS } from "./outputStream.js";
+export type { OutputNotificationParams, OutputSubscriptionOptions } from "./outputStream.js";
+export { SshBackend, parseSshTarget } from "./ssh.js";
+export { DockerContainer, DockerBackend } from "./docker.js";
+export { PersistBackend, checkPersistName } from "./persist.js";
+export type { PersistTarget } from "./persist.js";
+export type {
+  TerminalBackend,
+  BackendConfig,
+  BackendType,
+  SshTarget,
+  DockerTarget,
+  TmuxTarget,
+  RemoteLaunch,
+} from "./backend.js";
 export { TerminalManager } from "./manager.js";
 export type {
   TerminalManagerOptions,
   CreateSessionOptions,
   SessionMetadata,
+  SessionController,
+  LogLevel,
+  LogEntry,
+  RestartMode,
+  SessionCwd,
 } from "./manager.js";
+export { SECRET_PROMPT_PATTERN } from "./secretPrompt.js";
+export type { SecretPrompt } from "./secretPrompt.js";
+export { DEFAULT_DANGER_RULES, compileDangerRules } from "./dangerous.js";
+export type { DangerRule, DangerousCommand } from "./dangerous.js";
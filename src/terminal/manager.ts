@@ -1,9 +1,18 @@
 import { randomBytes } from "crypto";
+import * as os from "os";
 import { TerminalSession, TerminalSessionOptions, ScreenshotResult } from "./session.js";
 import type { SandboxController } from "../sandbox/index.js";
 import { RecordingManager } from "../recording/index.js";
 import type { RecordingMode, RecordingFormat, RecordingMetadata } from "../recording/index.js";
 import { getDefaultRecordDir } from "../utils/platform.js";
+import { getForegroundGroup, getProcessInfo, readCwd, type ForegroundGroup, type ProcessInfo } from "../utils/procfs.js";
+import { Notifier, type NotificationSinkConfig } from "../notifications/index.js";
+import { DEFAULT_REDACT_PATTERNS, redactEnv, type RedactedEnv } from "../utils/envRedact.js";
+import { findDangerousCommand, type DangerRule, type DangerousCommand } from "./dangerous.js";
+import { redactToolArgs, sha256, type AuditLog } from "../audit/index.js";
+import { DEFAULT_BUSY_TIMEOUT_MS, TerminalBusyError } from "../utils/toolLimits.js";
+import { RateLimiter, type RateLimits } from "../utils/rateLimit.js";
+import type { OutputMirror } from "./mirror.js";
 
 export interface TerminalManagerOptions extends TerminalSessionOptions {
   sandboxController?: SandboxController;
@@ -13,9 +22,52 @@ export interface TerminalManagerOptions extends TerminalSessionOptions {
   idleTimeLimit?: number;
   maxDuration?: number;
   inactivityTimeout?: number;
+  recordInput?: boolean;
   // Multi-session options
   maxSessions?: number;
   sessionIdleTimeout?: number; // seconds; non-default sessions are evicted after this
+  // Linux only: report shell cwd and foreground job from /proc
+  procTracking?: boolean;
+  // Screenshot rendering
+  fontDirs?: string[];
+  fallbackFonts?: string[];
+  // Forward selected session events to webhooks / Slack
+  notifications?: NotificationSinkConfig[];
+  // getEnvironment: extra name patterns to mask, or reveal all values
+  envRedact?: string[];
+  envReveal?: boolean;
+  // Respawn a session's shell when it exits, keeping or clearing scrollback
+  restartOnExit?: RestartMode;
+  // Commands tool input may only submit with a human's approval
+  dangerRules?: DangerRule[];
+  // Append tool calls, PTY input, and result hashes here
+  auditLog?: AuditLog;
+  // Copy of the default session's raw output (--mirror)
+  mirror?: OutputMirror;
+  // Longest a tool call may run in ms, by tool name ("*" for every tool)
+  toolTimeouts?: Record<string, number>;
+  // How long a call waits for a session another call is driving, in ms
+  busyTimeout?: number;
+  // Tool calls and typed bytes each client may send per minute
+  rateLimits?: RateLimits;
+  // Stop every shell after this many seconds without input or tool calls
+  idleTimeout?: number;
+  // Stop every shell this many seconds after the manager was created
+  maxLifetime?: number;
+}
+
+export type RestartMode = "keep" | "clear";
+
+/**
+ * A session's working directory and where it came from
+ * - 'osc7': reported by the shell at its last prompt
+ * - 'proc': read from /proc/<pid>/cwd (Linux), for shells that don't report it
+ */
+export interface SessionCwd {
+  path: string;
+  source: "osc7" | "proc";
+  /** Set when the report names another machine, e.g. a shell over ssh */
+  host?: string;
 }
 
 export interface CreateSessionOptions {
@@ -24,9 +76,18 @@ export interface CreateSessionOptions {
   rows?: number;
 }
 
+/**
+ * Who may send input to a session. "shared" is the normal state where both
+ * the AI (via tools) and an attached human can type; "human" means a human
+ * has taken over and tool input is rejected until control is handed back.
+ */
+export type SessionController = "shared" | "human";
+
 export interface SessionMetadata {
   sessionId: string;
   shell: string;
+  /** Set when the session runs a command instead of a shell */
+  command?: string[];
   cols: number;
   rows: number;
   createdAt: string;
@@ -34,15 +95,39 @@ export interface SessionMetadata {
   isDefault: boolean;
 }
 
+/**
+ * Severity of a server log entry, using the syslog levels MCP logging uses
+ */
+export type LogLevel = "debug" | "info" | "notice" | "warning" | "error" | "critical" | "alert" | "emergency";
+
+/**
+ * Something worth telling the MCP client about that no tool call returns,
+ * such as a shell dying in the background
+ */
+export interface LogEntry {
+  level: LogLevel;
+  message: string;
+  sessionId?: string;
+  data?: Record<string, unknown>;
+}
+
 interface SessionEntry {
   session: TerminalSession;
   metadata: SessionMetadata;
   lastActivity: number;
   isDefault: boolean;
+  controller: SessionController;
 }
 
+// --restart-on-exit gives up on a shell that dies this often within the window
+const MAX_AUTO_RESTARTS = 5;
+const AUTO_RESTART_WINDOW_MS = 60 * 1000;
+
 const DEFAULT_MAX_SESSIONS = 5;
 const DEFAULT_SESSION_IDLE_TIMEOUT = 600; // 10 minutes
+const LIMIT_CHECK_INTERVAL_MS = 1000;
+// Output must pause this long before the cursor row is checked for a password prompt
+const SECRET_PROMPT_SETTLE_MS = 200;
 
 /**
  * Manages one or more terminal sessions in a single process.
@@ -64,6 +149,20 @@ export class TerminalManager {
   private autoRecordingId: string | null = null;
   private maxSessions: number;
   private sessionIdleTimeoutMs: number;
+  private controlListeners: Array<(sessionId: string, controller: SessionController) => void> = [];
+  private logListeners: Array<(entry: LogEntry) => void> = [];
+  private notifier: Notifier | null;
+  private auditCallId = 0;
+  // Set while tool input is being written, so audited input can say where it came from
+  private writingToolInput = false;
+  // Sessions an exclusive tool call is driving, with the calls waiting their turn
+  private sessionLocks = new Map<string, { tool: string; waiters: Array<() => void> }>();
+  private limitCheckInterval: NodeJS.Timeout | null = null;
+  private readonly startedAt = Date.now();
+  private lastActivity = Date.now();
+  // Why no more shells may start, once --max-lifetime has run out
+  private expiredReason: string | null = null;
+  private limitListeners: Array<(reason: string) => void> = [];
 
   constructor(options: TerminalManagerOptions = {}) {
     this.options = options;
@@ -75,13 +174,20 @@ export class TerminalManager {
       idleTimeLimit: options.idleTimeLimit ?? 2,
       maxDuration: options.maxDuration ?? 3600,
       inactivityTimeout: options.inactivityTimeout ?? 600,
+      captureInput: options.recordInput ?? false,
     });
     this.maxSessions = options.maxSessions ?? DEFAULT_MAX_SESSIONS;
     this.sessionIdleTimeoutMs = (options.sessionIdleTimeout ?? DEFAULT_SESSION_IDLE_TIMEOUT) * 1000;
+    this.notifier = options.notifications?.length ? new Notifier(options.notifications) : null;
 
     // Periodic idle cleanup for non-default sessions
     this.idleCheckInterval = setInterval(() => this.cleanupIdleSessions(), 60 * 1000);
     this.idleCheckInterval.unref();
+
+    if (options.idleTimeout || options.maxLifetime) {
+      this.limitCheckInterval = setInterval(() => this.checkLimits(), LIMIT_CHECK_INTERVAL_MS);
+      this.limitCheckInterval.unref();
+    }
   }
 
   // ---------------------------------------------------------------------------
@@ -96,6 +202,7 @@ export class TerminalManager {
   }
 
   private touchSession(sessionId: string): void {
+    this.lastActivity = Date.now();
     const entry = this.sessions.get(sessionId);
     if (entry) {
       entry.lastActivity = Date.now();
@@ -111,19 +218,399 @@ export class TerminalManager {
       if (now - entry.lastActivity > this.sessionIdleTimeoutMs) {
         entry.session.dispose();
         this.sessions.delete(sessionId);
+        this.notifier?.forgetSession(sessionId);
+        this.log({
+          level: "info",
+          message: `Session '${sessionId}' destroyed after ${this.sessionIdleTimeoutMs / 1000}s idle`,
+          sessionId,
+        });
       }
     }
   }
 
+  /**
+   * Stop every shell once --idle-timeout or --max-lifetime has run out
+   */
+  private checkLimits(): void {
+    const { idleTimeout, maxLifetime } = this.options;
+    const now = Date.now();
+    if (maxLifetime && now - this.startedAt >= maxLifetime * 1000) {
+      this.expiredReason = `terminal-mcp reached its maximum lifetime of ${maxLifetime}s (--max-lifetime)`;
+      void this.stopForLimit("max_lifetime", this.expiredReason, maxLifetime);
+    } else if (idleTimeout && this.sessions.size > 0 && now - this.lastActivity >= idleTimeout * 1000) {
+      void this.stopForLimit("idle_timeout", `No input or tool calls for ${idleTimeout}s (--idle-timeout)`, idleTimeout);
+    }
+  }
+
+  /**
+   * Notify sinks and listeners that a limit ran out, then stop every
+   * session's shell
+   */
+  private async stopForLimit(limit: "idle_timeout" | "max_lifetime", reason: string, seconds: number): Promise<void> {
+    if (this.limitCheckInterval && limit === "max_lifetime") {
+      clearInterval(this.limitCheckInterval);
+      this.limitCheckInterval = null;
+    }
+    // Unregister first so the exits aren't reported as shells dying
+    const entries = Array.from(this.sessions.entries());
+    this.sessions.clear();
+    this.defaultSessionId = null;
+
+    for (const [sessionId] of entries) {
+      this.log({ level: "warning", message: `${reason}; stopping the shell`, sessionId, data: { limit, seconds } });
+      this.notifier?.notify("limit_reached", sessionId, reason, { limit, seconds });
+    }
+    await this.flushNotifications();
+    await Promise.all(entries.map(([, entry]) => entry.session.shutdown()));
+    for (const listener of this.limitListeners) {
+      listener(reason);
+    }
+  }
+
+  /**
+   * Called after --idle-timeout or --max-lifetime has stopped the shells
+   */
+  onLimitReached(listener: (reason: string) => void): void {
+    this.limitListeners.push(listener);
+  }
+
+  /**
+   * Count a new session and any input to it, human or tool, as activity
+   * for --idle-timeout
+   */
+  private wireActivity(session: TerminalSession): void {
+    this.lastActivity = Date.now();
+    session.onInput(() => {
+      this.lastActivity = Date.now();
+    });
+  }
+
   /**
    * Wire recording hooks onto a session so its output is captured by any
    * active recordings.
    */
   private wireRecording(session: TerminalSession): void {
     session.onData((data) => this.recordingManager.recordOutputToAll(data));
+    session.onInput((data, secret) => {
+      if (!secret) this.recordingManager.recordInputToAll(data);
+    });
     session.onResize((cols, rows) => this.recordingManager.recordResizeToAll(cols, rows));
   }
 
+  /**
+   * Log shells that exit on their own; tools on the session fail afterwards
+   * and the client otherwise has no way to learn why.
+   */
+  private wireLogging(session: TerminalSession, sessionId: string): void {
+    session.onExit((exitCode) => {
+      // Destroyed or evicted sessions are already unregistered
+      if (!this.sessions.has(sessionId)) return;
+      const command = session.getCommand();
+      if (command) {
+        this.log({
+          level: exitCode === 0 ? "info" : "error",
+          message: `Command '${command.join(" ")}' in session '${sessionId}' exited with code ${exitCode}`,
+          sessionId,
+          data: { exitCode, command },
+        });
+        return;
+      }
+      this.log({
+        level: exitCode === 0 ? "warning" : "error",
+        message: `Shell in session '${sessionId}' exited with code ${exitCode}`,
+        sessionId,
+        data: { exitCode },
+      });
+    });
+  }
+
+  /**
+   * With --restart-on-exit, start a new shell whenever the session's shell
+   * exits on its own, unless it keeps dying right away.
+   */
+  private wireRestart(session: TerminalSession, sessionId: string): void {
+    const mode = this.options.restartOnExit;
+    if (!mode) return;
+    const recent: number[] = [];
+    session.onExit((exitCode) => {
+      // Destroyed sessions and explicit restarts need nothing here
+      if (!this.sessions.has(sessionId) || session.isRestarting()) return;
+      const now = Date.now();
+      while (recent.length > 0 && now - recent[0] > AUTO_RESTART_WINDOW_MS) recent.shift();
+      if (recent.length >= MAX_AUTO_RESTARTS) {
+        this.log({
+          level: "error",
+          message: `Shell in session '${sessionId}' exited ${MAX_AUTO_RESTARTS} times within a minute; not restarting it again`,
+          sessionId,
+          data: { exitCode },
+        });
+        return;
+      }
+      recent.push(now);
+      session.restart({ clearScrollback: mode === "clear" }).then(
+        () => {
+          this.log({
+            level: "info",
+            message: `Restarted the shell in session '${sessionId}'`,
+            sessionId,
+            data: { exitCode, scrollback: mode },
+          });
+          this.logShellStartup(session, sessionId);
+        },
+        (error) => this.log({
+          level: "error",
+          message: `Failed to restart the shell in session '${sessionId}': ${(error as Error).message}`,
+          sessionId,
+        })
+      );
+    });
+  }
+
+  /**
+   * Log what a client would otherwise only find by reading the screen: a
+   * fallback shell, and init commands that failed or never finished
+   */
+  private logShellStartup(session: TerminalSession, sessionId: string): void {
+    const fallback = session.getShellFallback();
+    if (fallback) {
+      this.log({
+        level: "warning",
+        message: `Shell '${fallback.requested}' ${fallback.reason}; session '${sessionId}' uses ${fallback.shell} instead`,
+        sessionId,
+        data: { ...fallback },
+      });
+    }
+    for (const result of session.getInitResults()) {
+      if (result.timedOut) {
+        this.log({
+          level: "warning",
+          message: `Init command '${result.command}' in session '${sessionId}' didn't return to a prompt; later init commands were skipped`,
+          sessionId,
+          data: { ...result },
+        });
+        break;
+      }
+      if (result.exitCode !== null && result.exitCode !== 0) {
+        this.log({
+          level: "warning",
+          message: `Init command '${result.command}' in session '${sessionId}' exited with code ${result.exitCode}`,
+          sessionId,
+          data: { ...result },
+        });
+      }
+    }
+  }
+
+  /**
+   * Record a session's shell, everything written to its PTY, and its exit
+   * in the audit log
+   */
+  private wireAudit(session: TerminalSession, sessionId: string): void {
+    const audit = this.options.auditLog;
+    if (!audit) return;
+    audit.record({
+      type: "session_start",
+      sessionId,
+      shell: session.getShell(),
+      command: session.getCommand() ?? undefined,
+      pid: session.getPid(),
+    });
+    session.onInput((data, secret) => {
+      const source = this.writingToolInput ? "tool" : "human";
+      audit.record(secret ? { type: "input", sessionId, source, secret: true } : { type: "input", sessionId, source, data });
+    });
+    session.onExit((exitCode) => audit.record({ type: "session_exit", sessionId, exitCode }));
+  }
+
+  /**
+   * Run a tool call, recording it and a hash of its result in the audit
+   * log (if there is one)
+   */
+  async auditToolCall<T>(tool: string, args: unknown, call: () => Promise<T>): Promise<T> {
+    const audit = this.options.auditLog;
+    if (!audit) return call();
+
+    const id = ++this.auditCallId;
+    audit.record({ type: "tool_call", id, tool, args: redactToolArgs(tool, args ?? {}) });
+    const startedAt = Date.now();
+    try {
+      const result = await call();
+      const output = JSON.stringify(result ?? null);
+      const failed = result as { isError?: boolean; error?: unknown } | undefined;
+      audit.record({
+        type: "tool_result",
+        id,
+        tool,
+        durationMs: Date.now() - startedAt,
+        isError: failed?.isError === true || failed?.error !== undefined,
+        outputBytes: Buffer.byteLength(output),
+        outputSha256: sha256(output),
+      });
+      return result;
+    } catch (error) {
+      audit.record({
+        type: "tool_result",
+        id,
+        tool,
+        durationMs: Date.now() - startedAt,
+        isError: true,
+        error: error instanceof Error ? error.message : String(error),
+      });
+      throw error;
+    }
+  }
+
+  /**
+   * Run a call that drives a session (typing, keys, restarts) once no
+   * other such call on that session is running. Throws TerminalBusyError
+   * if the session isn't free within --busy-timeout.
+   */
+  async runExclusive<T>(tool: string, sessionId: string | undefined, call: () => Promise<T>): Promise<T> {
+    // Lock under the session's own ID, so naming the default session and
+    // omitting sessionId contend for the same lock
+    const id = await this.resolveSessionId(sessionId);
+    const lock = this.sessionLocks.get(id);
+    if (lock) {
+      const waitMs = this.options.busyTimeout ?? DEFAULT_BUSY_TIMEOUT_MS;
+      await new Promise<void>((resolve, reject) => {
+        const waiter = () => {
+          clearTimeout(timer);
+          resolve();
+        };
+        const timer = setTimeout(() => {
+          lock.waiters.splice(lock.waiters.indexOf(waiter), 1);
+          reject(new TerminalBusyError(id, waitMs, lock.tool));
+        }, waitMs);
+        lock.waiters.push(waiter);
+      });
+      lock.tool = tool;
+    } else {
+      this.sessionLocks.set(id, { tool, waiters: [] });
+    }
+
+    try {
+      return await call();
+    } finally {
+      const held = this.sessionLocks.get(id)!;
+      const next = held.waiters.shift();
+      if (next) {
+        next();
+      } else {
+        this.sessionLocks.delete(id);
+      }
+    }
+  }
+
+  /**
+   * The ID of the session a call targets: the default session's ID when
+   * sessionId is omitted, once it has started if it is still starting.
+   * Without a default session the call fails on its own, so any key does.
+   */
+  private async resolveSessionId(sessionId?: string): Promise<string> {
+    if (sessionId) return sessionId;
+    if (!this.defaultSessionId && this.defaultSessionPromise) {
+      await this.defaultSessionPromise.catch(() => undefined);
+    }
+    return this.defaultSessionId ?? "default";
+  }
+
+  /**
+   * Longest a call to this tool may run in ms (--tool-timeout), or
+   * undefined for no limit
+   */
+  getToolTimeout(tool: string): number | undefined {
+    const timeouts = this.options.toolTimeouts ?? {};
+    return timeouts[tool] ?? timeouts["*"];
+  }
+
+  /**
+   * A limiter for one client's calls (--rate-limit-calls,
+   * --rate-limit-input), or null when neither is set
+   */
+  createRateLimiter(): RateLimiter | null {
+    const limits = this.options.rateLimits;
+    if (!limits?.callsPerMinute && !limits?.inputBytesPerMinute) return null;
+    return new RateLimiter(limits);
+  }
+
+  private log(entry: LogEntry): void {
+    for (const listener of this.logListeners) {
+      listener(entry);
+    }
+  }
+
+  /**
+   * Subscribe to server log entries. Returns an unsubscribe function.
+   */
+  onLog(listener: (entry: LogEntry) => void): () => void {
+    this.logListeners.push(listener);
+    return () => {
+      this.logListeners = this.logListeners.filter((l) => l !== listener);
+    };
+  }
+
+  /**
+   * Forward a session's bell, failed commands, and shell exit to the
+   * configured notification sinks.
+   */
+  private wireNotifications(session: TerminalSession, sessionId: string): void {
+    const notifier = this.notifier;
+    if (!notifier) return;
+    session.onBell(() => notifier.notify("bell", sessionId, "Terminal bell"));
+    session.onCommandFinished((command, exitCode) => {
+      if (exitCode === 0) return;
+      notifier.notify(
+        "command_failed",
+        sessionId,
+        command ? `Command exited with code ${exitCode}: ${command}` : `Command exited with code ${exitCode}`,
+        { command, exitCode }
+      );
+    });
+    session.onExit((exitCode) => {
+      // Destroyed or evicted sessions are already unregistered when the
+      // PTY exit arrives; only report shells that exited on their own
+      if (!this.sessions.has(sessionId)) return;
+      notifier.notify("shell_exited", sessionId, `Shell exited with code ${exitCode}`, { exitCode });
+    });
+  }
+
+  /**
+   * Log and notify when a session starts waiting for a password, so the
+   * agent hands it to a human instead of guessing. Checked once output
+   * settles, since a prompt is usually the last thing a program prints.
+   */
+  private wireSecretPrompt(session: TerminalSession, sessionId: string): void {
+    let waiting = false;
+    let timer: NodeJS.Timeout | null = null;
+    const check = () => {
+      timer = null;
+      if (!this.sessions.has(sessionId)) return;
+      const secret = session.getSecretPrompt();
+      if (secret.detected && !waiting) {
+        const message = secret.prompt
+          ? `Session '${sessionId}' is waiting for a secret: ${secret.prompt}`
+          : `Session '${sessionId}' is waiting for input with echo off, likely a password`;
+        this.log({
+          level: "warning",
+          message: `${message}. Ask the user to enter it; don't guess credentials.`,
+          sessionId,
+          data: { ...secret },
+        });
+        this.notifier?.notify("secret_prompt", sessionId, message, { ...secret });
+      }
+      waiting = secret.detected;
+    };
+    session.onData(() => {
+      if (timer) clearTimeout(timer);
+      timer = setTimeout(check, SECRET_PROMPT_SETTLE_MS);
+      timer.unref();
+    });
+    session.onExit(() => {
+      if (timer) clearTimeout(timer);
+      timer = null;
+    });
+  }
+
   // ---------------------------------------------------------------------------
   // Default session (single-session API surface)
   // ---------------------------------------------------------------------------
@@ -144,6 +631,9 @@ export class TerminalManager {
     if (this.defaultSessionPromise) {
       return this.defaultSessionPromise;
     }
+    if (this.expiredReason) {
+      throw new Error(`Cannot start a shell: ${this.expiredReason}`);
+    }
 
     this.defaultSessionPromise = (async () => {
       const session = await TerminalSession.create({
@@ -157,7 +647,8 @@ export class TerminalManager {
         session,
         metadata: {
           sessionId: id,
-          shell: this.options.shell ?? process.env.SHELL ?? "/bin/bash",
+          shell: session.getShell(),
+          command: session.getCommand() ?? undefined,
           cols: dims.cols,
           rows: dims.rows,
           createdAt: now,
@@ -166,10 +657,20 @@ export class TerminalManager {
         },
         lastActivity: Date.now(),
         isDefault: true,
+        controller: "shared",
       };
       this.sessions.set(id, entry);
       this.defaultSessionId = id;
       this.wireRecording(session);
+      this.wireActivity(session);
+      this.wireNotifications(session, id);
+      this.wireSecretPrompt(session, id);
+      this.wireLogging(session, id);
+      this.wireRestart(session, id);
+      this.wireAudit(session, id);
+      const mirror = this.options.mirror;
+      if (mirror) session.onData((data) => mirror.write(data));
+      this.logShellStartup(session, id);
       return session;
     })();
 
@@ -192,6 +693,9 @@ export class TerminalManager {
    */
   getSession(): TerminalSession {
     const session = this.getCurrentSession();
+    if (!session && this.defaultSessionId && this.sessions.has(this.defaultSessionId)) {
+      throw new Error("The default session's shell has exited. Call restartShell to start a new one.");
+    }
     if (!session) {
       throw new Error(
         "Session not initialized. Use getSessionAsync() or call initSession() first."
@@ -217,6 +721,7 @@ export class TerminalManager {
       idleTimeLimit: this.options.idleTimeLimit ?? 2,
       maxDuration: this.options.maxDuration ?? 3600,
       inactivityTimeout: this.options.inactivityTimeout ?? 600,
+      captureInput: this.options.recordInput ?? false,
     });
     const session = this.getCurrentSession();
     const dimensions = session?.getDimensions() ?? { cols: 120, rows: 40 };
@@ -235,6 +740,9 @@ export class TerminalManager {
    * Create a new non-default session.
    */
   async createSession(opts: CreateSessionOptions = {}): Promise<SessionMetadata> {
+    if (this.expiredReason) {
+      throw new Error(`Cannot start a shell: ${this.expiredReason}`);
+    }
     if (this.sessions.size >= this.maxSessions) {
       throw new Error(
         `Maximum session limit reached (${this.maxSessions}). ` +
@@ -245,17 +753,30 @@ export class TerminalManager {
     const session = await TerminalSession.create({
       ...this.options,
       shell: opts.shell ?? this.options.shell,
+      // --shell-arg flags are for the configured shell, not one picked here
+      shellArgs: opts.shell ? undefined : this.options.shellArgs,
       cols: opts.cols ?? this.options.cols,
       rows: opts.rows ?? this.options.rows,
-      // Created sessions don't share the interactive startup banner
+      // Created sessions don't share the interactive startup banner, and
+      // nobody watches their first output
       startupBanner: undefined,
+      launchCheck: true,
+      // Only the default session runs the command given after --
+      command: undefined,
+      // Each created session gets a tmux session of its own, ended with it
+      backend:
+        this.options.backend?.type === "tmux"
+          ? { ...this.options.backend, session: `${this.options.backend.session}-${id}`, ephemeral: true }
+          : this.options.backend,
+      // Only the default session's shell outlives the server
+      persist: undefined,
       sandboxController: this.sandboxController,
     });
     const dims = session.getDimensions();
     const now = new Date().toISOString();
     const metadata: SessionMetadata = {
       sessionId: id,
-      shell: opts.shell ?? this.options.shell ?? process.env.SHELL ?? "/bin/bash",
+      shell: session.getShell(),
       cols: dims.cols,
       rows: dims.rows,
       createdAt: now,
@@ -267,8 +788,17 @@ export class TerminalManager {
       metadata,
       lastActivity: Date.now(),
       isDefault: false,
+      controller: "shared",
     });
     this.wireRecording(session);
+    this.wireActivity(session);
+    this.wireNotifications(session, id);
+    this.wireSecretPrompt(session, id);
+    this.wireLogging(session, id);
+    this.wireRestart(session, id);
+    this.wireAudit(session, id);
+    this.logShellStartup(session, id);
+    this.log({ level: "debug", message: `Session '${id}' created (${metadata.shell})`, sessionId: id });
     return metadata;
   }
 
@@ -285,9 +815,27 @@ export class TerminalManager {
     }
     entry.session.dispose();
     this.sessions.delete(sessionId);
+    this.notifier?.forgetSession(sessionId);
     return { success: true, message: `Session '${sessionId}' destroyed` };
   }
 
+  /**
+   * Start a new shell in a session, whether its shell has exited or is
+   * still running (it is stopped first). The session keeps its ID.
+   */
+  async restartShell(sessionId?: string, clearScrollback = false): Promise<SessionMetadata> {
+    const id = sessionId ?? this.defaultSessionId;
+    const entry = id ? this.sessions.get(id) : undefined;
+    if (!id || !entry) {
+      throw new Error(sessionId ? `Session '${sessionId}' not found` : "Session not initialized");
+    }
+    await entry.session.restart({ clearScrollback });
+    this.logShellStartup(entry.session, id);
+    this.touchSession(id);
+    this.log({ level: "info", message: `Restarted the shell in session '${id}' on request`, sessionId: id });
+    return { ...entry.metadata };
+  }
+
   listSessions(): {
     sessions: SessionMetadata[];
     maxSessions: number;
@@ -328,6 +876,15 @@ export class TerminalManager {
     return entry.session;
   }
 
+  /**
+   * Metadata for a session (default session if sessionId is omitted)
+   */
+  getSessionMetadata(sessionId?: string): SessionMetadata {
+    this.resolveSessionSync(sessionId);
+    const entry = this.sessions.get(sessionId ?? this.defaultSessionId!)!;
+    return { ...entry.metadata };
+  }
+
   /**
    * Synchronous variant of resolveSession() — requires the session to
    * already exist. Throws if not created yet (used by sync tool handlers).
@@ -356,8 +913,23 @@ export class TerminalManager {
     return this.getCurrentSession() !== null;
   }
 
-  write(data: string, sessionId?: string): void {
-    this.resolveSessionSync(sessionId).write(data);
+  /**
+   * Write tool input to a session. Rejected while a human has taken control.
+   * Secret input is kept out of the audit log and recordings.
+   */
+  write(data: string, sessionId?: string, options: { secret?: boolean } = {}): void {
+    const session = this.resolveSessionSync(sessionId);
+    if (this.getController(sessionId) === "human") {
+      const message = "A human has taken control of this session; AI input is paused until control is handed back";
+      this.reportPolicyViolation(message, sessionId);
+      throw new Error(message);
+    }
+    this.writingToolInput = true;
+    try {
+      session.write(data, options);
+    } finally {
+      this.writingToolInput = false;
+    }
   }
 
   getContent(sessionId?: string): string {
@@ -368,6 +940,17 @@ export class TerminalManager {
     return this.resolveSessionSync(sessionId).getVisibleContent();
   }
 
+  /**
+   * Wait until a read won't race recently sent input (see --read-guard)
+   */
+  async waitForReadable(sessionId?: string): Promise<void> {
+    await this.resolveSessionSync(sessionId).waitForReadable();
+  }
+
+  getLastCommand(sessionId?: string): string | null {
+    return this.resolveSessionSync(sessionId).getLastCommand();
+  }
+
   getAnsiContent(visibleOnly = false, sessionId?: string): string {
     return this.resolveSessionSync(sessionId).getAnsiContent(visibleOnly);
   }
@@ -380,8 +963,8 @@ export class TerminalManager {
     return this.resolveSessionSync(sessionId).takeScreenshot();
   }
 
-  clear(sessionId?: string): void {
-    this.resolveSessionSync(sessionId).clear();
+  async clear(sessionId?: string, options: { scrollback?: boolean; hard?: boolean } = {}): Promise<void> {
+    await this.resolveSessionSync(sessionId).clear(options);
   }
 
   resize(cols: number, rows: number, sessionId?: string): void {
@@ -392,6 +975,38 @@ export class TerminalManager {
     return this.resolveSessionSync(sessionId).getDimensions();
   }
 
+  // ---------------------------------------------------------------------------
+  // Input control (human takeover / hand-back)
+  // ---------------------------------------------------------------------------
+
+  getController(sessionId?: string): SessionController {
+    const id = sessionId ?? this.defaultSessionId;
+    return (id && this.sessions.get(id)?.controller) || "shared";
+  }
+
+  /**
+   * Change who controls input for a session and notify listeners.
+   */
+  setController(controller: SessionController, sessionId?: string): void {
+    const id = sessionId ?? this.defaultSessionId;
+    const entry = id ? this.sessions.get(id) : undefined;
+    if (!id || !entry) {
+      throw new Error(`Session '${sessionId ?? "default"}' not found`);
+    }
+    if (entry.controller === controller) return;
+    entry.controller = controller;
+    for (const listener of this.controlListeners) {
+      listener(id, controller);
+    }
+  }
+
+  /**
+   * Subscribe to control changes on any session
+   */
+  onControlChange(listener: (sessionId: string, controller: SessionController) => void): void {
+    this.controlListeners.push(listener);
+  }
+
   // ---------------------------------------------------------------------------
   // Recording / lifecycle
   // ---------------------------------------------------------------------------
@@ -409,11 +1024,48 @@ export class TerminalManager {
       clearInterval(this.idleCheckInterval);
       this.idleCheckInterval = null;
     }
+    if (this.limitCheckInterval) {
+      clearInterval(this.limitCheckInterval);
+      this.limitCheckInterval = null;
+    }
     for (const [, entry] of this.sessions) {
       entry.session.dispose();
     }
     this.sessions.clear();
     this.defaultSessionId = null;
+    this.options.mirror?.close();
+  }
+
+  /**
+   * Stop every session's shell and wait for it to exit (see
+   * TerminalSession.shutdown), except a --persist shell, which is only
+   * detached from. Then finalize recordings, deliver pending
+   * notifications, and release sandbox resources, so the server can exit
+   * without leaving orphaned processes or truncated files behind.
+   */
+  async shutdown(): Promise<void> {
+    if (this.idleCheckInterval) {
+      clearInterval(this.idleCheckInterval);
+      this.idleCheckInterval = null;
+    }
+    if (this.limitCheckInterval) {
+      clearInterval(this.limitCheckInterval);
+      this.limitCheckInterval = null;
+    }
+    // Unregister first so the exits aren't reported as shells dying
+    const sessions = Array.from(this.sessions.values(), (entry) => entry.session);
+    this.sessions.clear();
+    this.defaultSessionId = null;
+    await Promise.all(sessions.map((session) => (session.detach() ? undefined : session.shutdown())));
+
+    // A server shutdown isn't a command failure, so on-failure recordings are discarded
+    await this.recordingManager.finalizeAll(null, "server_shutdown");
+    await this.flushNotifications();
+    this.options.mirror?.close();
+    if (this.sandboxController) {
+      await this.sandboxController.cleanup();
+    }
+    this.options.auditLog?.record({ type: "server_stop" });
   }
 
   async disposeAsync(): Promise<void> {
@@ -423,7 +1075,113 @@ export class TerminalManager {
     }
   }
 
+  /**
+   * Add a session's current screen to its snapshot history before a tool
+   * call runs. Does nothing if the session doesn't exist (yet).
+   */
+  recordToolSnapshot(tool: string, sessionId?: string): void {
+    // Reading history shouldn't push the current screen into it
+    if (tool === "listSnapshots" || tool === "getSnapshot") return;
+    const session = sessionId ? this.sessions.get(sessionId)?.session : this.getCurrentSession();
+    session?.recordHistorySnapshot("tool", tool);
+  }
+
+  /**
+   * The first command that writing `data` would submit and that needs a
+   * human's approval (--confirm-dangerous, --confirm-pattern), or null
+   */
+  findDangerousInput(data: string, sessionId?: string): DangerousCommand | null {
+    const rules = this.options.dangerRules ?? [];
+    if (rules.length === 0) return null;
+    const commands = this.resolveSessionSync(sessionId).previewSubmittedCommands(data);
+    return findDangerousCommand(commands, rules);
+  }
+
+  /**
+   * Notify sinks that tool input was rejected by a session policy.
+   */
+  reportPolicyViolation(reason: string, sessionId?: string, details?: Record<string, unknown>): void {
+    const id = sessionId ?? this.defaultSessionId ?? "default";
+    this.log({ level: "warning", message: reason, sessionId: id, data: details });
+    this.notifier?.notify("policy_violation", id, reason, details);
+  }
+
+  /**
+   * Wait for in-flight notifications to be delivered (bounded by the sink
+   * request timeout).
+   */
+  async flushNotifications(): Promise<void> {
+    await this.notifier?.flush();
+  }
+
   getSandboxController(): SandboxController | undefined {
     return this.sandboxController;
   }
+
+  /**
+   * Shell cwd and foreground process read from /proc. Null unless
+   * --proc-tracking is enabled and /proc is available (Linux).
+   */
+  getProcessInfo(sessionId?: string): ProcessInfo | null {
+    const session = this.resolveSessionSync(sessionId);
+    const pid = session.getPid();
+    if (!this.options.procTracking || pid === null) return null;
+    return getProcessInfo(pid);
+  }
+
+  /**
+   * The shell's working directory: the last OSC 7 report, else the shell
+   * process's cwd from /proc. Null when neither is available.
+   */
+  getCwd(sessionId?: string): SessionCwd | null {
+    const session = this.resolveSessionSync(sessionId);
+    const reported = session.getReportedCwd();
+    if (reported) {
+      const local = !reported.host || reported.host === "localhost" || reported.host === os.hostname();
+      return { path: reported.path, source: "osc7", ...(!local && { host: reported.host }) };
+    }
+    const pid = session.getPid();
+    const procCwd = session.getExitCode() === null && pid !== null ? readCwd(pid) : null;
+    return procCwd ? { path: procCwd, source: "proc" } : null;
+  }
+
+  /**
+   * The terminal's foreground process group from /proc, whether or not
+   * --proc-tracking is on. Null without /proc or once the shell has exited.
+   */
+  getForegroundGroup(sessionId?: string): ForegroundGroup | null {
+    const pid = this.resolveSessionSync(sessionId).getPid();
+    return pid === null ? null : getForegroundGroup(pid);
+  }
+
+  /**
+   * Get a session's spawn environment with credential-like values masked,
+   * unless the operator started the server with --env-reveal.
+   */
+  getEnvironment(sessionId?: string): RedactedEnv {
+    const env = this.resolveSessionSync(sessionId).getSpawnEnv();
+    if (this.options.envReveal) {
+      return { env, redacted: [] };
+    }
+    const result = redactEnv(env, [...DEFAULT_REDACT_PATTERNS, ...(this.options.envRedact ?? [])]);
+    if (result.redacted.length > 0) {
+      this.log({
+        level: "info",
+        message: `Masked ${result.redacted.length} credential-like environment variable(s)`,
+        sessionId: sessionId ?? this.defaultSessionId ?? undefined,
+        data: { redacted: result.redacted },
+      });
+    }
+    return result;
+  }
+
+  /**
+   * Font options for image screenshots (--font-dir, --fallback-fonts)
+   */
+  getFontOptions(): { fontDirs?: string[]; fallbackFonts?: string[] } {
+    return {
+      fontDirs: this.options.fontDirs,
+      fallbackFonts: this.options.fallbackFonts,
+    };
+  }
 }
@@ -0,0 +1,87 @@
+/**
+ * Output mirror (--mirror <path>): the default session's raw PTY output,
+ * escape sequences included, is copied to a file or named pipe as it
+ * arrives, so someone can `tail -f` or `cat` the live terminal from another
+ * window without going through MCP.
+ */
+import * as fs from "fs";
+
+// How often a FIFO without a reader is retried
+const FIFO_RETRY_MS = 1000;
+
+export class OutputMirror {
+  private fd: number | null = null;
+  private readonly fifo: boolean;
+  private retryAt = 0;
+  private closed = false;
+
+  /**
+   * Open the mirror. A regular file is truncated; a FIFO is opened once
+   * something reads from it. Throws if the path can't be written.
+   */
+  constructor(private readonly path: string) {
+    let stat: fs.Stats | null = null;
+    try {
+      stat = fs.statSync(path);
+    } catch {
+      // Created below
+    }
+    this.fifo = stat?.isFIFO() ?? false;
+    if (!this.fifo) {
+      this.fd = fs.openSync(path, "w", 0o600);
+    }
+  }
+
+  /**
+   * Copy output to the mirror. Never blocks the session: output that a
+   * FIFO's reader can't take right away, or that arrives while nobody is
+   * reading, is dropped.
+   */
+  write(data: string): void {
+    if (this.closed) return;
+    if (this.fd === null && !this.openFifo()) return;
+    try {
+      fs.writeSync(this.fd!, data);
+    } catch (error) {
+      const code = (error as NodeJS.ErrnoException).code;
+      if (code === "EAGAIN") return;
+      // The reader went away (EPIPE); wait for the next one. For a file,
+      // stop rather than fail on every chunk.
+      this.closeFd();
+      if (!this.fifo) {
+        console.error(`[terminal-mcp] Stopped mirroring output to ${this.path}: ${(error as Error).message}`);
+        this.closed = true;
+      }
+    }
+  }
+
+  close(): void {
+    this.closed = true;
+    this.closeFd();
+  }
+
+  /**
+   * Open the FIFO without blocking; fails (ENXIO) while nothing reads it
+   */
+  private openFifo(): boolean {
+    const now = Date.now();
+    if (now < this.retryAt) return false;
+    try {
+      this.fd = fs.openSync(this.path, fs.constants.O_WRONLY | fs.constants.O_NONBLOCK);
+      return true;
+    } catch {
+      this.retryAt = now + FIFO_RETRY_MS;
+      return false;
+    }
+  }
+
+  private closeFd(): void {
+    if (this.fd === null) return;
+    try {
+      fs.closeSync(this.fd);
+    } catch {
+      // Already closed
+    }
+    this.fd = null;
+  }
+}
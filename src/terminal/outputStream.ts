@@ -0,0 +1,150 @@
+import { collapseCarriageReturns, stripAnsi } from "../utils/ansi.js";
+import type { TerminalManager } from "./manager.js";
+
+/**
+ * MCP notification carrying new terminal output to subscribed clients
+ */
+export const OUTPUT_NOTIFICATION = "notifications/terminal/output";
+
+export const DEFAULT_OUTPUT_DEBOUNCE_MS = 200;
+// Larger bursts are cut down to their tail
+const MAX_CHUNK_CHARS = 16 * 1024;
+
+export interface OutputNotificationParams {
+  sessionId: string;
+  /** Increases by one per notification for this session's subscription */
+  seq: number;
+  /** Output since the previous notification; escape sequences removed and CRLF made LF unless raw */
+  data: string;
+  /** Characters dropped from the start of data because the burst was too large */
+  dropped?: number;
+}
+
+export interface OutputSubscriptionOptions {
+  /** Coalesce output for this long after the first chunk before sending */
+  debounceMs?: number;
+  /** Keep escape sequences (colors, cursor movement) in data */
+  raw?: boolean;
+  /**
+   * Without raw, send only the final state of lines redrawn with carriage
+   * returns (progress bars) instead of every redraw (default: true)
+   */
+  collapseProgress?: boolean;
+}
+
+interface Subscription {
+  unsubscribe: () => void;
+  pending: string;
+  timer: NodeJS.Timeout | null;
+  seq: number;
+  debounceMs: number;
+  raw: boolean;
+  collapseProgress: boolean;
+}
+
+/**
+ * Push-based output for one client connection. Each subscribed session's
+ * PTY output is buffered and flushed as a single notification once the
+ * debounce window after the first unsent chunk has passed.
+ */
+export class OutputStreamer {
+  private subscriptions: Map<string, Subscription> = new Map();
+
+  constructor(
+    private manager: TerminalManager,
+    private send: (params: OutputNotificationParams) => void
+  ) {}
+
+  /**
+   * Start (or reconfigure) streaming a session. Returns the resolved
+   * session ID.
+   */
+  subscribe(sessionId: string | undefined, options: OutputSubscriptionOptions = {}): string {
+    const session = this.manager.resolveSessionSync(sessionId);
+    const id = this.manager.getSessionMetadata(sessionId).sessionId;
+    this.unsubscribe(id);
+
+    const subscription: Subscription = {
+      unsubscribe: () => {},
+      pending: "",
+      timer: null,
+      seq: 0,
+      debounceMs: options.debounceMs ?? DEFAULT_OUTPUT_DEBOUNCE_MS,
+      raw: options.raw ?? false,
+      collapseProgress: options.collapseProgress ?? true,
+    };
+    subscription.unsubscribe = session.onData((data) => {
+      subscription.pending += data;
+      if (!subscription.timer) {
+        subscription.timer = setTimeout(() => this.flush(id, subscription), subscription.debounceMs);
+      }
+    });
+    session.onExit(() => {
+      // A restarted shell keeps streaming into the same session
+      if (session.isRestarting()) return;
+      if (this.subscriptions.get(id) === subscription) this.unsubscribe(id);
+    });
+    this.subscriptions.set(id, subscription);
+    return id;
+  }
+
+  /**
+   * Stop streaming a session, sending any output still buffered. Returns
+   * false if it wasn't subscribed.
+   */
+  unsubscribe(sessionId: string): boolean {
+    const subscription = this.subscriptions.get(sessionId);
+    if (!subscription) return false;
+    subscription.unsubscribe();
+    this.flush(sessionId, subscription);
+    this.subscriptions.delete(sessionId);
+    return true;
+  }
+
+  /**
+   * IDs of the sessions currently streamed to this client
+   */
+  getSubscriptions(): string[] {
+    return [...this.subscriptions.keys()];
+  }
+
+  private flush(sessionId: string, subscription: Subscription): void {
+    if (subscription.timer) {
+      clearTimeout(subscription.timer);
+      subscription.timer = null;
+    }
+    if (!subscription.pending) return;
+
+    let data = subscription.raw
+      ? subscription.pending
+      : subscription.collapseProgress
+        ? collapseCarriageReturns(subscription.pending)
+        : stripAnsi(subscription.pending).replace(/\r\n/g, "\n");
+    subscription.pending = "";
+    if (!data) return;
+
+    const params: OutputNotificationParams = { sessionId, seq: ++subscription.seq, data };
+    if (data.length > MAX_CHUNK_CHARS) {
+      params.dropped = data.length - MAX_CHUNK_CHARS;
+      data = data.slice(-MAX_CHUNK_CHARS);
+      params.data = data;
+    }
+    try {
+      this.send(params);
+    } catch {
+      // Client gone; the connection's close handler disposes us
+    }
+  }
+
+  /**
+   * Drop all subscriptions without sending buffered output, e.g. when the
+   * client disconnects
+   */
+  dispose(): void {
+    for (const subscription of this.subscriptions.values()) {
+      subscription.unsubscribe();
+      if (subscription.timer) clearTimeout(subscription.timer);
+    }
+    this.subscriptions.clear();
+  }
+}
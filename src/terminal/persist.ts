@@ -0,0 +1,399 @@
+/**
+ * Persistent shells (--persist <name>): the default session's PTY is owned
+ * by a small detached holder process instead of the server. The server
+ * talks to it over a Unix socket and only disconnects when it stops, so the
+ * shell outlives the server. The holder keeps the shell's recent output on
+ * disk, and the next server started with the same name reattaches and
+ * replays it into its emulator, restoring the scrollback and screen.
+ */
+import { spawn } from "child_process";
+import * as fs from "fs";
+import * as net from "net";
+import * as path from "path";
+import * as pty from "node-pty";
+import { BackendEmitter, type BackendDisposable, type TerminalBackend } from "./backend.js";
+
+/**
+ * Hidden subcommand that runs the holder (see runPersistHolder)
+ */
+export const PERSIST_HOLDER_COMMAND = "__persist-holder";
+
+// Output kept on disk for replay; the log is trimmed back to this once it
+// has grown to twice the size
+const PERSIST_LOG_BYTES = 1024 * 1024;
+// How long launch() waits for a new holder's socket
+const HOLDER_START_TIMEOUT_MS = 5000;
+const HOLDER_POLL_MS = 50;
+
+export interface PersistTarget {
+  /** Names the holder's files in dir */
+  name: string;
+  /** Directory for the socket and output log (default: getDefaultPersistDir()) */
+  dir: string;
+}
+
+/**
+ * What a new holder starts, passed through a private file rather than argv
+ * so the environment doesn't show up in ps
+ */
+interface HolderConfig {
+  target: PersistTarget;
+  shell: string;
+  cmd: string;
+  args: string[];
+  cwd: string;
+  env: Record<string, string>;
+  cols: number;
+  rows: number;
+}
+
+type HolderMessage =
+  | { type: "hello"; pid: number; shell: string; replay: string }
+  | { type: "busy" }
+  | { type: "data"; data: string }
+  | { type: "exit"; exitCode: number; signal?: number };
+
+type ClientMessage =
+  | { type: "write"; data: string }
+  | { type: "resize"; cols: number; rows: number }
+  | { type: "kill"; signal?: string }
+  | { type: "clearLog" };
+
+/**
+ * Names that are safe to use as file names in the persist directory
+ */
+export function checkPersistName(name: string): string | null {
+  if (!name) return "the name is empty";
+  if (!/^[A-Za-z0-9._-]+$/.test(name) || name.startsWith(".")) {
+    return `use letters, digits, '.', '_', and '-' ('${name}')`;
+  }
+  return null;
+}
+
+function persistPaths(target: PersistTarget): { socket: string; log: string; config: string; error: string } {
+  const base = path.join(target.dir, target.name);
+  return { socket: `${base}.sock`, log: `${base}.log`, config: `${base}.json`, error: `${base}.error` };
+}
+
+function frame(message: HolderMessage | ClientMessage): string {
+  return JSON.stringify(message) + "\n";
+}
+
+/**
+ * Call handler with each complete line from the socket, starting with
+ * whatever was already buffered
+ */
+export function onLines(socket: net.Socket, handler: (line: string) => void, buffered = ""): void {
+  const feed = (chunk: string) => {
+    buffered += chunk;
+    let newline: number;
+    while ((newline = buffered.indexOf("\n")) !== -1) {
+      const line = buffered.slice(0, newline);
+      buffered = buffered.slice(newline + 1);
+      if (line) handler(line);
+    }
+  };
+  feed("");
+  // Decoded as a stream, so a character split between chunks stays whole
+  socket.setEncoding("utf-8");
+  socket.on("data", feed);
+}
+
+/**
+ * The holder process: start the shell on a PTY, append its output to the
+ * log, and relay it to one connected server at a time. Exits, removing its
+ * files, when the shell does. Never resolves.
+ */
+export async function runPersistHolder(configFile: string): Promise<never> {
+  const config = JSON.parse(fs.readFileSync(configFile, "utf-8")) as HolderConfig;
+  fs.unlinkSync(configFile);
+  const paths = persistPaths(config.target);
+
+  let shell: pty.IPty;
+  try {
+    shell = pty.spawn(config.cmd, config.args, {
+      name: "xterm-256color",
+      cols: config.cols,
+      rows: config.rows,
+      cwd: config.cwd,
+      env: config.env,
+    });
+  } catch (error) {
+    fs.writeFileSync(paths.error, (error as Error).message, { mode: 0o600 });
+    process.exit(1);
+  }
+
+  fs.writeFileSync(paths.log, "", { mode: 0o600 });
+  let logBytes = 0;
+  const appendLog = (data: string) => {
+    try {
+      fs.appendFileSync(paths.log, data);
+      logBytes += Buffer.byteLength(data);
+      if (logBytes > 2 * PERSIST_LOG_BYTES) {
+        const content = fs.readFileSync(paths.log);
+        let tail = content.subarray(content.length - PERSIST_LOG_BYTES);
+        // Start on a line boundary rather than mid-character or mid-sequence
+        const newline = tail.indexOf(0x0a);
+        if (newline !== -1) tail = tail.subarray(newline + 1);
+        fs.writeFileSync(paths.log, tail);
+        logBytes = tail.length;
+      }
+    } catch {
+      // Losing replay history is better than losing the shell
+    }
+  };
+
+  let client: net.Socket | null = null;
+  shell.onData((data) => {
+    appendLog(data);
+    client?.write(frame({ type: "data", data }));
+  });
+  shell.onExit(({ exitCode, signal }) => {
+    for (const file of [paths.socket, paths.log]) {
+      try {
+        fs.unlinkSync(file);
+      } catch {
+        // Already gone
+      }
+    }
+    if (!client) process.exit(0);
+    client.end(frame({ type: "exit", exitCode, signal }), () => process.exit(0));
+    setTimeout(() => process.exit(0), 1000).unref();
+  });
+
+  const server = net.createServer((socket) => {
+    socket.on("error", () => {});
+    // A second server would fight the first over the shell
+    if (client) {
+      socket.end(frame({ type: "busy" }));
+      return;
+    }
+    client = socket;
+    socket.on("close", () => {
+      if (client === socket) client = null;
+    });
+    let replay = "";
+    try {
+      replay = fs.readFileSync(paths.log, "utf-8");
+    } catch {
+      // Nothing to restore
+    }
+    socket.write(frame({ type: "hello", pid: shell.pid, shell: config.shell, replay }));
+    onLines(socket, (line) => {
+      let message: ClientMessage;
+      try {
+        message = JSON.parse(line) as ClientMessage;
+      } catch {
+        return;
+      }
+      if (message.type === "write") {
+        shell.write(message.data);
+      } else if (message.type === "resize") {
+        shell.resize(message.cols, message.rows);
+      } else if (message.type === "kill") {
+        try {
+          shell.kill(message.signal);
+        } catch {
+          // Already gone
+        }
+      } else if (message.type === "clearLog") {
+        try {
+          fs.writeFileSync(paths.log, "");
+          logBytes = 0;
+        } catch {
+          // Replays the old output next time, nothing worse
+        }
+      }
+    });
+  });
+  server.listen(paths.socket, () => {
+    try {
+      fs.chmodSync(paths.socket, 0o600);
+    } catch {
+      // The directory is private already
+    }
+  });
+  // Detached from any terminal, but a stray hangup mustn't end the shell
+  process.on("SIGHUP", () => {});
+  return new Promise<never>(() => {});
+}
+
+/**
+ * A shell owned by a holder process, reached over its socket
+ */
+export class PersistBackend implements TerminalBackend {
+  readonly pid: number;
+  private dataEmitter = new BackendEmitter<string>();
+  private exitEmitter = new BackendEmitter<{ exitCode: number; signal?: number }>();
+  private exited = false;
+  private detached = false;
+
+  private constructor(
+    private socket: net.Socket,
+    pid: number,
+    buffered: string
+  ) {
+    this.pid = pid;
+    socket.on("close", () => {
+      // The holder died with the shell, or the connection was lost
+      if (!this.detached) this.finish({ exitCode: 255 });
+    });
+    // Listeners are attached by the caller of attach() before this runs
+    setImmediate(() => {
+      onLines(socket, (line) => this.receive(line), buffered);
+      socket.resume();
+    });
+  }
+
+  /**
+   * Connect to the holder named by target. Returns null when there is no
+   * running holder (a stale socket is removed); otherwise the backend and
+   * the output the holder kept, to be replayed before anything it sends.
+   */
+  static attach(target: PersistTarget): Promise<{ backend: PersistBackend; replay: string; shell: string } | null> {
+    const paths = persistPaths(target);
+    if (!fs.existsSync(paths.socket)) return Promise.resolve(null);
+
+    return new Promise((resolve, reject) => {
+      const socket = net.connect(paths.socket);
+      socket.setEncoding("utf-8");
+      let buffered = "";
+      const onData = (chunk: string) => {
+        buffered += chunk;
+        const newline = buffered.indexOf("\n");
+        if (newline === -1) return;
+        socket.off("data", onData);
+        socket.off("error", onError);
+        socket.pause();
+        const hello = JSON.parse(buffered.slice(0, newline)) as HolderMessage;
+        if (hello.type !== "hello") {
+          socket.destroy();
+          reject(new Error(`persisted shell '${target.name}' is attached to another terminal-mcp`));
+          return;
+        }
+        const backend = new PersistBackend(socket, hello.pid, buffered.slice(newline + 1));
+        resolve({ backend, replay: hello.replay, shell: hello.shell });
+      };
+      const onError = (error: NodeJS.ErrnoException) => {
+        socket.destroy();
+        if (error.code === "ECONNREFUSED" || error.code === "ENOENT") {
+          // The holder is gone (killed, or the machine rebooted)
+          try {
+            fs.unlinkSync(paths.socket);
+          } catch {
+            // Already gone
+          }
+          resolve(null);
+        } else {
+          reject(error);
+        }
+      };
+      socket.on("data", onData);
+      socket.on("error", onError);
+    });
+  }
+
+  /**
+   * Start a holder running cmd and attach to it
+   */
+  static async launch(
+    target: PersistTarget,
+    shell: string,
+    cmd: string,
+    args: string[],
+    options: { cols: number; rows: number; cwd: string; env: Record<string, string> }
+  ): Promise<PersistBackend> {
+    const paths = persistPaths(target);
+    fs.mkdirSync(target.dir, { recursive: true, mode: 0o700 });
+    fs.rmSync(paths.error, { force: true });
+    const config: HolderConfig = { target, shell, cmd, args, ...options };
+    fs.writeFileSync(paths.config, JSON.stringify(config), { mode: 0o600 });
+
+    const child = spawn(process.execPath, [...process.execArgv, process.argv[1], PERSIST_HOLDER_COMMAND, paths.config], {
+      detached: true,
+      stdio: "ignore",
+    });
+    child.on("error", () => {});
+    child.unref();
+
+    const deadline = Date.now() + HOLDER_START_TIMEOUT_MS;
+    while (Date.now() < deadline) {
+      if (fs.existsSync(paths.error)) {
+        const message = fs.readFileSync(paths.error, "utf-8");
+        fs.rmSync(paths.error, { force: true });
+        throw new Error(message);
+      }
+      const attached = await PersistBackend.attach(target).catch(() => null);
+      if (attached) return attached.backend;
+      await new Promise((resolve) => setTimeout(resolve, HOLDER_POLL_MS));
+    }
+    fs.rmSync(paths.config, { force: true });
+    throw new Error(`the holder process for '${target.name}' didn't start within ${HOLDER_START_TIMEOUT_MS / 1000}s`);
+  }
+
+  private receive(line: string): void {
+    let message: HolderMessage;
+    try {
+      message = JSON.parse(line) as HolderMessage;
+    } catch {
+      return;
+    }
+    if (message.type === "data") {
+      this.dataEmitter.emit(message.data);
+    } else if (message.type === "exit") {
+      this.finish({ exitCode: message.exitCode, signal: message.signal });
+    }
+  }
+
+  private finish(event: { exitCode: number; signal?: number }): void {
+    if (this.exited) return;
+    this.exited = true;
+    this.socket.destroy();
+    this.exitEmitter.emit(event);
+  }
+
+  private send(message: ClientMessage): void {
+    if (!this.exited && !this.detached) this.socket.write(frame(message));
+  }
+
+  write(data: string): void {
+    this.send({ type: "write", data });
+  }
+
+  resize(cols: number, rows: number): void {
+    this.send({ type: "resize", cols, rows });
+  }
+
+  pause(): void {
+    this.socket.pause();
+  }
+
+  resume(): void {
+    this.socket.resume();
+  }
+
+  kill(signal?: string): void {
+    this.send({ type: "kill", signal });
+  }
+
+  clearHistory(): void {
+    this.send({ type: "clearLog" });
+  }
+
+  /**
+   * Disconnect, leaving the shell running for the next server
+   */
+  detach(): void {
+    if (this.exited || this.detached) return;
+    this.detached = true;
+    this.socket.end();
+  }
+
+  onData(listener: (data: string) => void): BackendDisposable {
+    return this.dataEmitter.on(listener);
+  }
+
+  onExit(listener: (event: { exitCode: number; signal?: number }) => void): BackendDisposable {
+    return this.exitEmitter.on(listener);
+  }
+}
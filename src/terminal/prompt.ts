@@ -0,0 +1,65 @@
+/**
+ * Prompt detection helpers.
+ *
+ * A prompt pattern is a regex tested against the text on the cursor row,
+ * up to the cursor column. When it matches, the shell is assumed to be
+ * sitting at a prompt waiting for input.
+ */
+
+// Matches our own "⚡ mcp" marker or the usual trailing prompt characters
+// ($ for sh/bash, # for root, % for zsh, > for fish/cmd, ❯ for starship).
+export const DEFAULT_PROMPT_PATTERN = "(?:⚡ mcp|[$#%>❯›»])\\s*$";
+
+export interface PromptMatch {
+  /** Line index in the buffer (0 = oldest scrollback line) */
+  line: number;
+  /** True if the line is currently inside the visible viewport */
+  inViewport: boolean;
+  /** Full text of the line */
+  text: string;
+  /** The substring matched by the pattern */
+  match: string;
+  /** Column where the match starts */
+  column: number;
+}
+
+/**
+ * Compile a prompt pattern, turning regex syntax errors into a readable
+ * error message.
+ */
+export function compilePromptPattern(pattern: string, flags = ""): RegExp {
+  // The global/sticky flags make RegExp#test stateful, which breaks
+  // repeated matching against many lines.
+  const safeFlags = flags.replace(/[gy]/g, "");
+  try {
+    return new RegExp(pattern, safeFlags);
+  } catch (error) {
+    const message = error instanceof Error ? error.message : String(error);
+    throw new Error(`Invalid prompt pattern: ${message}`);
+  }
+}
+
+/**
+ * Run a compiled pattern over a set of buffer lines and collect matches.
+ */
+export function findPromptMatches(
+  lines: Array<{ line: number; text: string; inViewport: boolean }>,
+  regex: RegExp,
+  limit = Infinity
+): PromptMatch[] {
+  const matches: PromptMatch[] = [];
+  for (const entry of lines) {
+    const trimmed = entry.text.replace(/\s+$/, "");
+    const m = regex.exec(trimmed);
+    if (!m) continue;
+    matches.push({
+      line: entry.line,
+      inViewport: entry.inViewport,
+      text: trimmed,
+      match: m[0],
+      column: m.index,
+    });
+    if (matches.length >= limit) break;
+  }
+  return matches;
+}
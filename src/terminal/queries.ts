@@ -0,0 +1,282 @@
+import * as fs from "fs";
+import type { Terminal } from "@xterm/headless";
+import { VERSION } from "../utils/version.js";
+import { THEMES } from "../utils/render.js";
+
+/**
+ * Answers to terminal device queries that xterm.js leaves unanswered or
+ * that an operator wants to change. xterm.js itself already replies to
+ * cursor position (CSI 6n), status (CSI 5n), DA1/DA2, and DECRQM; this adds
+ * XTVERSION, XTGETTCAP, XTWINOPS size reports, and the OSC 10/11/12 color
+ * queries, and lets the DA replies be overridden.
+ *
+ * Strings are sent verbatim, so escape sequences in a JSON config use
+ * "\u001b" for ESC.
+ */
+export interface QueryResponses {
+  /** Reply to primary DA (CSI c); default is xterm.js's "\x1b[?1;2c" */
+  primaryDA?: string;
+  /** Reply to secondary DA (CSI > c); default is xterm.js's "\x1b[>0;276;0c" */
+  secondaryDA?: string;
+  /** Name and version reported to XTVERSION (CSI > q); null disables the reply */
+  xtversion?: string | null;
+  /**
+   * XTGETTCAP capabilities merged over the defaults: a string value,
+   * true for a boolean capability, or null to report it as unsupported
+   */
+  termcap?: Record<string, string | boolean | null>;
+  /** Default foreground reported to OSC 10 ("#rrggbb" or "rgb:r/g/b"; default: the one-dark theme's) */
+  foreground?: string;
+  /** Default background reported to OSC 11 ("#rrggbb") */
+  background?: string;
+  /** Default cursor color reported to OSC 12 ("#rrggbb"; default: the foreground) */
+  cursorColor?: string;
+  /** Pixel size of one cell reported to XTWINOPS (default: 10x20) */
+  cellWidth?: number;
+  cellHeight?: number;
+}
+
+/**
+ * Capabilities reported to XTGETTCAP (DCS + q), matching what xterm-256color
+ * with truecolor support advertises. true marks a boolean capability.
+ */
+export const DEFAULT_TERMCAP: Record<string, string | boolean> = {
+  TN: "xterm-256color",
+  name: "xterm-256color",
+  Co: "256",
+  colors: "256",
+  RGB: "8/8/8",
+  Tc: true,
+  Ss: "\\E[%p1%d q",
+  Se: "\\E[2 q",
+  Ms: "\\E]52;%p1%s;%p2%s\\007",
+  Smulx: "\\E[4:%p1%dm",
+  setrgbf: "\\E[38;2;%p1%d;%p2%d;%p3%dm",
+  setrgbb: "\\E[48;2;%p1%d;%p2%d;%p3%dm",
+};
+
+const DEFAULT_CELL_WIDTH = 10;
+const DEFAULT_CELL_HEIGHT = 20;
+
+const CSI = "\x1b[";
+const DCS = "\x1bP";
+const OSC = "\x1b]";
+const ST = "\x1b\\";
+
+type Rgb = [number, number, number];
+
+/**
+ * Parse "#rgb", "#rrggbb", or X11 "rgb:r/g/b" (1-4 hex digits per channel)
+ * into 8-bit channels
+ */
+export function parseColor(spec: string): Rgb | null {
+  const hash = /^#([0-9a-f]{3}|[0-9a-f]{6})$/i.exec(spec);
+  if (hash) {
+    const hex = hash[1].length === 3 ? hash[1].replace(/./g, "$&$&") : hash[1];
+    return [0, 2, 4].map((i) => parseInt(hex.slice(i, i + 2), 16)) as Rgb;
+  }
+  const x11 = /^rgb:([0-9a-f]{1,4})\/([0-9a-f]{1,4})\/([0-9a-f]{1,4})$/i.exec(spec);
+  if (x11) {
+    return x11.slice(1, 4).map((c) => Math.round((parseInt(c, 16) / (16 ** c.length - 1)) * 255)) as Rgb;
+  }
+  return null;
+}
+
+/**
+ * X11 color spec as xterm reports it: 16 bits per channel
+ */
+function formatColor([r, g, b]: Rgb): string {
+  const channel = (c: number) => c.toString(16).padStart(2, "0").repeat(2);
+  return `rgb:${channel(r)}/${channel(g)}/${channel(b)}`;
+}
+
+function toHex(text: string): string {
+  return Buffer.from(text, "utf8").toString("hex").toUpperCase();
+}
+
+function fromHex(hex: string): string | null {
+  if (!/^(?:[0-9a-fA-F]{2})+$/.test(hex)) return null;
+  return Buffer.from(hex, "hex").toString("utf8");
+}
+
+/**
+ * Install query handlers on the emulator's parser. `reply` writes back to
+ * the program (the PTY); it decides itself whether replies are wanted.
+ */
+export function registerQueryResponder(
+  terminal: Terminal,
+  reply: (data: string) => void,
+  responses: QueryResponses = {}
+): void {
+  const termcap: Record<string, string | boolean | null> = { ...DEFAULT_TERMCAP, ...responses.termcap };
+  const isDefaultQuery = (params: (number | number[])[]) => params.length === 0 || params[0] === 0;
+
+  if (responses.primaryDA !== undefined) {
+    const answer = responses.primaryDA;
+    terminal.parser.registerCsiHandler({ final: "c" }, (params) => {
+      if (!isDefaultQuery(params)) return false;
+      reply(answer);
+      return true;
+    });
+  }
+
+  if (responses.secondaryDA !== undefined) {
+    const answer = responses.secondaryDA;
+    terminal.parser.registerCsiHandler({ prefix: ">", final: "c" }, (params) => {
+      if (!isDefaultQuery(params)) return false;
+      reply(answer);
+      return true;
+    });
+  }
+
+  // XTVERSION: CSI > 0 q -> DCS > | name(version) ST
+  const xtversion = responses.xtversion === undefined ? `terminal-mcp(${VERSION})` : responses.xtversion;
+  terminal.parser.registerCsiHandler({ prefix: ">", final: "q" }, (params) => {
+    if (!isDefaultQuery(params)) return false;
+    if (xtversion !== null) reply(`${DCS}>|${xtversion}${ST}`);
+    return true;
+  });
+
+  // XTGETTCAP: DCS + q <hex name>;<hex name>... ST. Each name is answered
+  // with DCS 1 + r <hex name>[=<hex value>] ST, or DCS 0 + r <hex name> ST
+  // when unknown, as xterm does.
+  terminal.parser.registerDcsHandler({ intermediates: "+", final: "q" }, (data) => {
+    for (const hexName of data.split(";")) {
+      const name = fromHex(hexName);
+      const value = name === null ? null : termcap[name];
+      if (value === null || value === undefined || value === false) {
+        reply(`${DCS}0+r${hexName}${ST}`);
+      } else if (value === true) {
+        reply(`${DCS}1+r${hexName}${ST}`);
+      } else {
+        reply(`${DCS}1+r${hexName}=${toHex(value)}${ST}`);
+      }
+    }
+    return true;
+  });
+
+  // XTWINOPS size reports. There is no window, so pixel sizes are the
+  // grid times a synthetic cell size; TUI frameworks and image protocols
+  // (sixel, kitty graphics) use them to size cells.
+  const cellWidth = responses.cellWidth ?? DEFAULT_CELL_WIDTH;
+  const cellHeight = responses.cellHeight ?? DEFAULT_CELL_HEIGHT;
+  terminal.parser.registerCsiHandler({ final: "t" }, (params) => {
+    const { cols, rows } = terminal;
+    switch (params[0]) {
+      case 14: // text area in pixels
+        reply(`${CSI}4;${rows * cellHeight};${cols * cellWidth}t`);
+        return true;
+      case 15: // screen in pixels; the screen is the text area here
+        reply(`${CSI}5;${rows * cellHeight};${cols * cellWidth}t`);
+        return true;
+      case 16: // cell in pixels
+        reply(`${CSI}6;${cellHeight};${cellWidth}t`);
+        return true;
+      case 18: // text area in characters
+        reply(`${CSI}8;${rows};${cols}t`);
+        return true;
+      case 19: // screen in characters
+        reply(`${CSI}9;${rows};${cols}t`);
+        return true;
+      default:
+        return false;
+    }
+  });
+
+  registerColorQueries(terminal, reply, responses);
+}
+
+/**
+ * OSC 10/11/12: query (?) or set the default foreground, background, and
+ * cursor colors. Programs such as neovim, fzf, and bat query the
+ * background to pick a light or dark theme. As in xterm, one sequence can
+ * address several in turn: OSC 10 ; ? ; ? asks for foreground and
+ * background. OSC 110/111/112 restore the configured defaults.
+ */
+function registerColorQueries(
+  terminal: Terminal,
+  reply: (data: string) => void,
+  responses: QueryResponses
+): void {
+  const theme = THEMES["one-dark"];
+  const defaults: Rgb[] = [
+    parseColor(responses.foreground ?? theme.foreground)!,
+    parseColor(responses.background ?? theme.background)!,
+    parseColor(responses.cursorColor ?? responses.foreground ?? theme.foreground)!,
+  ];
+  const current: Rgb[] = [...defaults];
+
+  for (let first = 0; first < 3; first++) {
+    terminal.parser.registerOscHandler(10 + first, (data) => {
+      data.split(";").forEach((spec, i) => {
+        const index = first + i;
+        if (index > 2) return;
+        if (spec === "?") {
+          reply(`${OSC}${10 + index};${formatColor(current[index])}${ST}`);
+        } else {
+          current[index] = parseColor(spec) ?? current[index];
+        }
+      });
+      return true;
+    });
+    terminal.parser.registerOscHandler(110 + first, () => {
+      current[first] = defaults[first];
+      return true;
+    });
+  }
+
+  // RIS restores them too
+  terminal.parser.registerEscHandler({ final: "c" }, () => {
+    current.splice(0, 3, ...defaults);
+    return false;
+  });
+}
+
+/**
+ * Read QueryResponses from a JSON file. Throws on malformed content.
+ */
+export function loadQueryResponses(file: string): QueryResponses {
+  const config = JSON.parse(fs.readFileSync(file, "utf8"));
+  if (!config || typeof config !== "object" || Array.isArray(config)) {
+    throw new Error(`${file}: expected a JSON object`);
+  }
+  for (const key of ["primaryDA", "secondaryDA"] as const) {
+    if (config[key] !== undefined && typeof config[key] !== "string") {
+      throw new Error(`${file}: "${key}" must be a string`);
+    }
+  }
+  if (config.xtversion !== undefined && config.xtversion !== null && typeof config.xtversion !== "string") {
+    throw new Error(`${file}: "xtversion" must be a string or null`);
+  }
+  for (const key of ["foreground", "background", "cursorColor"] as const) {
+    if (config[key] !== undefined && (typeof config[key] !== "string" || !parseColor(config[key]))) {
+      throw new Error(`${file}: "${key}" must be a color such as "#1e1e2e" or "rgb:1e/1e/2e"`);
+    }
+  }
+  for (const key of ["cellWidth", "cellHeight"] as const) {
+    if (config[key] !== undefined && (!Number.isInteger(config[key]) || config[key] < 1)) {
+      throw new Error(`${file}: "${key}" must be a positive integer`);
+    }
+  }
+  if (config.termcap !== undefined) {
+    if (!config.termcap || typeof config.termcap !== "object" || Array.isArray(config.termcap)) {
+      throw new Error(`${file}: "termcap" must be an object`);
+    }
+    for (const [name, value] of Object.entries(config.termcap)) {
+      if (value !== null && typeof value !== "string" && typeof value !== "boolean") {
+        throw new Error(`${file}: termcap "${name}" must be a string, boolean, or null`);
+      }
+    }
+  }
+  return {
+    primaryDA: config.primaryDA,
+    secondaryDA: config.secondaryDA,
+    xtversion: config.xtversion,
+    termcap: config.termcap,
+    foreground: config.foreground,
+    background: config.background,
+    cursorColor: config.cursorColor,
+    cellWidth: config.cellWidth,
+    cellHeight: config.cellHeight,
+  };
+}
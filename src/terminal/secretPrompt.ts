@@ -0,0 +1,52 @@
+/**
+ * Secret prompt detection.
+ *
+ * A program asking for a password either turns off echo on the terminal
+ * (getpass, sudo, ssh, gpg) or at least says so on the cursor row. Both are
+ * checked: the termios flags can't be fooled by output that merely looks
+ * like a prompt, and the text catches prompts in programs that echo '*'.
+ */
+import { spawnSync } from "child_process";
+
+// "Password:", "[sudo] password for ann:", "Enter passphrase for key '…':",
+// "Verification code:", "PIN?"
+export const SECRET_PROMPT_PATTERN =
+  /\b(pass(word|phrase|code)|PIN|token|secret|OTP|one-time (pass)?code|verification code)\b[^\n]*[:?>]\s*$/i;
+
+export interface SecretPrompt {
+  /** The terminal is most likely waiting for a secret */
+  detected: boolean;
+  /** Echo is off on the PTY; null when the terminal modes can't be read */
+  echoOff: boolean | null;
+  /** The cursor row, when it reads like a password prompt */
+  prompt: string | null;
+}
+
+export const NO_SECRET_PROMPT: SecretPrompt = { detected: false, echoOff: null, prompt: null };
+
+/**
+ * Read whether the terminal at ptsPath has echo off while still reading
+ * whole lines (canonical mode). Full-screen programs turn off both, so
+ * that alone doesn't mean a password is being asked for. Null if stty
+ * can't read the terminal.
+ */
+export function readEchoOff(ptsPath: string): boolean | null {
+  if (process.platform === "win32") return null;
+  const flag = process.platform === "linux" ? "-F" : "-f";
+  const result = spawnSync("stty", [flag, ptsPath, "-a"], { encoding: "utf-8", timeout: 1000 });
+  if (result.status !== 0 || !result.stdout) return null;
+  const tokens = new Set(result.stdout.split(/[\s;]+/));
+  return tokens.has("-echo") && tokens.has("icanon");
+}
+
+/**
+ * Decide whether the cursor row is a secret prompt. echoOff is only looked
+ * up when the row could end a prompt at all, since it costs a process spawn.
+ */
+export function detectSecretPrompt(cursorLine: string, readEcho: () => boolean | null): SecretPrompt {
+  const text = cursorLine.replace(/\s+$/, "");
+  if (!/[:?>]$/.test(text)) return NO_SECRET_PROMPT;
+  const prompt = SECRET_PROMPT_PATTERN.test(text) ? text.trim() : null;
+  const echoOff = readEcho();
+  return { detected: echoOff === true || prompt !== null, echoOff, prompt };
+}
@@ -3,9 +3,34 @@ import * as fs from "fs";
 import * as os from "os";
 import * as path from "path";
 import xtermHeadless from "@xterm/headless";
+import type { IMarker } from "@xterm/headless";
 const { Terminal } = xtermHeadless;
-import { getDefaultShell } from "../utils/platform.js";
+import { FALLBACK_SHELL, checkShell, getDefaultShell } from "../utils/platform.js";
 import type { SandboxController } from "../sandbox/index.js";
+import {
+  REMOTE_DIR,
+  type BackendConfig,
+  type FloodPolicy,
+  type RemoteLaunch,
+  type TerminalBackend,
+} from "./backend.js";
+import { SshBackend } from "./ssh.js";
+import { DockerContainer } from "./docker.js";
+import { killTmuxSession, tmuxAttachCommand } from "./tmux.js";
+import { PersistBackend, type PersistTarget } from "./persist.js";
+import { DEFAULT_PROMPT_PATTERN, compilePromptPattern } from "./prompt.js";
+import { NO_SECRET_PROMPT, detectSecretPrompt, readEchoOff, type SecretPrompt } from "./secretPrompt.js";
+import { HyperlinkTracker, type Hyperlink } from "./hyperlinks.js";
+import { registerQueryResponder, type QueryResponses } from "./queries.js";
+import { getStyledRows } from "../utils/screenDump.js";
+import { runsToAnsi } from "../utils/reflow.js";
+import { stripAnsi } from "../utils/ansi.js";
+import { writeAsync } from "../utils/emulate.js";
+import { sttySane } from "./tty.js";
+import { baseEnv, type EnvPolicy } from "../utils/env.js";
+import { formatByteSize, wrapWithLimits, type ResourceLimits } from "../utils/resourceLimits.js";
+import type { MouseEncoding, MouseTrackingMode } from "../utils/mouse.js";
+import type { ScreenSnapshot } from "../utils/screenDiff.js";
 
 // Custom prompt indicator for terminal-mcp.
 // Includes "mcp" so it's unmistakable — many shell themes (oh-my-zsh,
@@ -14,14 +39,201 @@ import type { SandboxController } from "../sandbox/index.js";
 // sentinel by the precmd hook.
 const PROMPT_INDICATOR = "⚡ mcp";
 
+const DEFAULT_READ_GUARD_MS = 250;
+// Output must pause this long before a guarded read proceeds
+const READ_GUARD_QUIET_MS = 15;
+const READ_GUARD_POLL_MS = 5;
+const MAX_NAMED_SNAPSHOTS = 16;
+const DEFAULT_SNAPSHOT_HISTORY = 20;
+// OSC 52 clipboard writes kept per session, and the largest one accepted
+const MAX_CLIPBOARD_ENTRIES = 10;
+const MAX_CLIPBOARD_BYTES = 1024 * 1024;
+// getContent delta generations remembered per session (one per polling client)
+const MAX_CONTENT_GENERATIONS = 8;
+// Named output marks (see setMark) held per session
+const MAX_MARKS = 32;
+// How long shutdown() waits after SIGHUP before killing the shell
+const SHUTDOWN_GRACE_MS = 2000;
+// A new shell that exits within this long of its first output failed to start
+const LAUNCH_SETTLE_MS = 100;
+// Stop waiting for a slow shell's first output after this long
+const LAUNCH_TIMEOUT_MS = 2000;
+// Longest an init command may run before the rest are abandoned
+const INIT_COMMAND_TIMEOUT_MS = 30000;
+// Output must pause this long, with a prompt showing, before the next init command
+const INIT_QUIET_MS = 150;
+const INIT_POLL_MS = 25;
+// Output queued for the parser past the high mark triggers --flood-policy;
+// reading resumes (or the skipped note is written) below the low mark
+const FLOOD_HIGH_WATERMARK = 2 * 1024 * 1024;
+const FLOOD_LOW_WATERMARK = 256 * 1024;
+
 export interface TerminalSessionOptions {
   cols?: number;
   rows?: number;
   shell?: string;
   cwd?: string;
+  /** Variables set for the shell on top of the inherited environment */
   env?: Record<string, string>;
+  /**
+   * Which of the server's variables the shell inherits (default: all)
+   */
+  envPolicy?: EnvPolicy;
   startupBanner?: string;
   sandboxController?: SandboxController;
+  /** Where the shell runs (default: a local PTY) */
+  backend?: BackendConfig;
+  promptPattern?: string;
+  /**
+   * Reads issued within this many ms of input first wait for the input's
+   * echo/output to be processed (default: 250; 0 disables)
+   */
+  readGuardMs?: number;
+  /** Number of automatic screen snapshots to keep (default: 20; 0 disables) */
+  snapshotHistory?: number;
+  /** What to do with output that arrives faster than it is parsed (default: 'pause') */
+  floodPolicy?: FloodPolicy;
+  /** Also snapshot the screen every this many seconds (default: 0 = off) */
+  snapshotInterval?: number;
+  /** Overrides for replies to DA, XTVERSION, and XTGETTCAP queries */
+  queryResponses?: QueryResponses;
+  /**
+   * Hold create() until the shell has printed something, failing if it
+   * exits first (default: true). Turn off when the caller relays the
+   * shell's first output itself.
+   */
+  launchCheck?: boolean;
+  /**
+   * Run this program and its arguments on the PTY instead of a shell. The
+   * session stays readable after it exits; getExitCode() reports how.
+   */
+  command?: string[];
+  /**
+   * Start the shell as a login shell, so it reads /etc/profile and the
+   * user's profile files (PATH setup from version managers, etc.)
+   */
+  login?: boolean;
+  /** Extra arguments passed to the shell after terminal-mcp's own */
+  shellArgs?: string[];
+  /**
+   * Typed into every new shell (including after a restart) before create()
+   * or restart() resolves, waiting for the prompt between each
+   */
+  initCommands?: string[];
+  /** CPU, memory, process, and file-size limits for the shell's process tree */
+  resourceLimits?: ResourceLimits;
+  /**
+   * Run the shell in a holder process that outlives the server, and
+   * reattach to it if one with this name is already running
+   */
+  persist?: PersistTarget;
+}
+
+/**
+ * How one init command went
+ */
+export interface InitCommandResult {
+  command: string;
+  /** From the shell's exit status hook; null if the shell doesn't report it */
+  exitCode: number | null;
+  /** No prompt came back in time; later commands were not run */
+  timedOut: boolean;
+}
+
+/**
+ * What caused an automatic snapshot
+ * - 'tool': a tool call targeted the session (taken before it runs)
+ * - 'clear': the screen was about to be erased, reset, or swapped for the
+ *   alternate screen
+ * - 'timer': periodic capture (--snapshot-interval)
+ */
+export type SnapshotTrigger = "tool" | "clear" | "timer";
+
+export interface SnapshotHistoryEntry extends ScreenSnapshot {
+  /** Increases monotonically for the lifetime of the session */
+  id: number;
+  trigger: SnapshotTrigger;
+  /** Tool name for 'tool' snapshots */
+  tool?: string;
+}
+
+/**
+ * Text a program copied to the clipboard with OSC 52
+ */
+export interface ClipboardEntry {
+  /** Selection targets from the sequence, e.g. "c" (clipboard) or "p" (primary); "s0" when omitted */
+  selection: string;
+  text: string;
+  /** ISO timestamp */
+  copiedAt: string;
+}
+
+/**
+ * Rows that changed since an earlier getContent generation
+ */
+export interface ContentChanges {
+  /** Pass as `since` on the next call */
+  generation: number;
+  /**
+   * True when `since` was 0, unknown, or expired (or the screen was swapped
+   * or trimmed in a way that can't be tracked): every line is in `changed`
+   */
+  full: boolean;
+  /** Current number of lines; drop any lines at or past this index */
+  lineCount: number;
+  changed: BufferLine[];
+}
+
+interface ContentGeneration {
+  id: number;
+  lines: string[];
+  /** Marks `anchorLine`; follows it as scrollback is trimmed */
+  anchor: IMarker;
+  anchorLine: number;
+  alternate: boolean;
+}
+
+export interface BufferLine {
+  line: number;
+  text: string;
+  inViewport: boolean;
+}
+
+export interface OutputMark {
+  name: string;
+  /** Buffer line the mark is on (0 = oldest scrollback line), or null once it has scrolled out of the scrollback */
+  line: number | null;
+  markedAt: string;
+}
+
+export type CursorShape = "block" | "underline" | "bar";
+
+export interface CursorStyle {
+  shape: CursorShape;
+  blink: boolean;
+}
+
+export interface TerminalModes {
+  /** True while a full-screen app (vim, less, htop) has switched to the alternate screen */
+  alternateScreen: boolean;
+  /** DECCKM — arrow keys should be sent as SS3 (ESC O A) instead of CSI */
+  applicationCursorKeys: boolean;
+  /** DECKPAM — keypad sends application sequences */
+  applicationKeypad: boolean;
+  bracketedPaste: boolean;
+  mouseTracking: MouseTrackingMode;
+}
+
+/**
+ * How a session has coped with output arriving faster than it is parsed
+ */
+export interface FlowStats {
+  /** Reading from the PTY is paused until the parser catches up */
+  paused: boolean;
+  /** Times reading was paused */
+  pauses: number;
+  /** Output not shown on the screen (--flood-policy drop) */
+  skippedBytes: number;
 }
 
 export interface ScreenshotResult {
@@ -29,11 +241,92 @@ export interface ScreenshotResult {
   cursor: {
     x: number;
     y: number;
+    style: CursorStyle;
   };
   dimensions: {
     cols: number;
     rows: number;
   };
+  modes: TerminalModes;
+}
+
+/**
+ * The shell couldn't be started. Carries what was wrong with the configured
+ * shell and, when one was tried, with the fallback.
+ */
+export class ShellLaunchError extends Error {
+  constructor(
+    readonly shell: string,
+    readonly reason: string,
+    readonly fallback?: { shell: string; reason: string }
+  ) {
+    super(
+      `Failed to start shell '${shell}': ${reason}` +
+        (fallback ? `; fallback '${fallback.shell}' also failed: ${fallback.reason}` : "")
+    );
+    this.name = "ShellLaunchError";
+  }
+}
+
+/**
+ * Set when the configured shell couldn't be used and FALLBACK_SHELL was
+ * started instead
+ */
+export interface ShellFallback {
+  requested: string;
+  reason: string;
+  shell: string;
+}
+
+/**
+ * A working directory reported with OSC 7
+ */
+export interface ReportedCwd {
+  path: string;
+  /** Host named in the report; another machine when it came over ssh */
+  host: string;
+}
+
+/**
+ * Parse an OSC 7 payload: file://<host><percent-encoded path>
+ */
+function parseOsc7(data: string): ReportedCwd | null {
+  const match = /^file:\/\/([^/]*)(\/.*)$/.exec(data);
+  if (!match) return null;
+  let cwdPath = match[2];
+  try {
+    cwdPath = decodeURIComponent(cwdPath);
+  } catch {
+    // Not percent-encoded after all; keep it as sent
+  }
+  return { host: match[1], path: cwdPath };
+}
+
+/**
+ * Replay input against the line being typed at the prompt. Returns the
+ * resulting line (null once editing keys make it unknowable) and each line
+ * submitted with Enter (null where it was unknowable).
+ */
+function editLine(line: string | null, data: string): { line: string | null; submitted: Array<string | null> } {
+  const submitted: Array<string | null> = [];
+  const text = data.replace(/\x1b\[20[01]~/g, ""); // bracketed paste markers
+  for (const ch of text) {
+    if (ch === "\r" || ch === "\n") {
+      submitted.push(line);
+      line = "";
+    } else if (ch === "\x03" || ch === "\x15") {
+      // Ctrl+C / Ctrl+U discard the line
+      line = "";
+    } else if (ch === "\x7f" || ch === "\b") {
+      if (line !== null) line = line.slice(0, -1);
+    } else if (ch === "\x1b" || ch === "\t" || ch < " ") {
+      // Arrow keys, history, completion: we can no longer know the line
+      line = null;
+    } else if (line !== null) {
+      line += ch;
+    }
+  }
+  return { line, submitted };
 }
 
 /**
@@ -41,15 +334,81 @@ export interface ScreenshotResult {
  * for full terminal emulation
  */
 export class TerminalSession {
-  private ptyProcess!: pty.IPty;
+  private ptyProcess!: TerminalBackend;
   private terminal!: InstanceType<typeof Terminal>;
   private disposed = false;
+  // The shell has exited; disposed is also set then, but cleanup still runs in dispose()
+  private exited = false;
+  private cleanedUp = false;
+  private restarting = false;
+  private spawnOptions: TerminalSessionOptions = {};
+  private shell = "";
+  private shellFallback: ShellFallback | null = null;
+  private exitCode: number | null = null;
+  private initResults: InitCommandResult[] = [];
   private dataListeners: Array<(data: string) => void> = [];
   private exitListeners: Array<(code: number) => void> = [];
   private resizeListeners: Array<(cols: number, rows: number) => void> = [];
+  private inputListeners: Array<(data: string, secret: boolean) => void> = [];
+  private bellListeners: Array<() => void> = [];
+  private commandFinishedListeners: Array<(command: string | null, exitCode: number) => void> = [];
+  // Best-effort reconstruction of the line being typed at the shell prompt;
+  // null once editing keys make it unreliable (history recall, cursor moves)
+  private pendingCommand: string | null = "";
+  private lastCommand: string | null = null;
+  // Set when a line is submitted at the prompt; cleared when the shell
+  // reports its exit status, so an empty Enter or Ctrl+C isn't reported
+  private commandRunning = false;
+  // Whether the shell has ever reported an exit status; without that,
+  // commandRunning is never cleared and says nothing
+  private reportsExitStatus = false;
+  // Stale-read guard: timestamps of the last input and the last output
+  // chunk, and PTY data not yet processed by the xterm.js parser
+  private readGuardMs = DEFAULT_READ_GUARD_MS;
+  private lastInputAt = 0;
+  private lastOutputAt = 0;
+  private pendingParses = 0;
+  // Flood handling: characters queued for the parser, whether reading is
+  // paused or output is being skipped, and totals for getStatus
+  private unparsed = 0;
+  private flowPaused = false;
+  private skipping = 0;
+  private flow: FlowStats = { paused: false, pauses: 0, skippedBytes: 0 };
+  // Output waiting to be handed to the parser together (see parse())
+  private coalesced: string[] = [];
+  private snapshots: Map<string, ScreenSnapshot> = new Map();
+  // Ring of automatic snapshots, oldest first
+  private history: SnapshotHistoryEntry[] = [];
+  private clipboard: ClipboardEntry[] = [];
+  private generations: ContentGeneration[] = [];
+  private marks: Map<string, { marker: IMarker; markedAt: string }> = new Map();
+  private nextGeneration = 1;
+  private historySize = DEFAULT_SNAPSHOT_HISTORY;
+  private nextHistoryId = 1;
+  private historyTimer: NodeJS.Timeout | null = null;
+  private promptRegex!: RegExp;
+  // Default matches xterm's own (steady block) until the application sends DECSCUSR
+  private cursorStyle: CursorStyle = { shape: "block", blink: false };
+  // DECTCEM (CSI ? 25 h / l); xterm.js doesn't expose it
+  private cursorVisible = true;
+  private hyperlinks!: HyperlinkTracker;
+  private respondToQueries = true;
+  private mouseEncoding: MouseEncoding = "default";
+  private title = "";
+  // Last working directory reported with OSC 7
+  private reportedCwd: ReportedCwd | null = null;
+  // Environment the shell was started with
+  private spawnEnv: Record<string, string> = {};
 
   private rcFile: string | null = null;
   private zdotdir: string | null = null;
+  // --backend docker: the container this session's shells run in
+  private container: DockerContainer | null = null;
+  // --persist: the current shell was already running when we attached
+  private reattached = false;
+  // Emulator replies to output replayed from a persisted shell's log would
+  // reach the shell as input
+  private replaying = false;
 
   /**
    * Private constructor - use TerminalSession.create() instead
@@ -68,13 +427,17 @@ export class TerminalSession {
 
   /**
    * Set up shell-specific prompt customization
-   * Returns args to pass to shell and env modifications
+   * Returns args to pass to shell and env modifications. For a remote
+   * shell, the rc files are returned in files (named relative to
+   * REMOTE_DIR) instead of being written here.
    */
   private setupShellPrompt(
     shellName: string,
     extraEnv?: Record<string, string>,
-    startupBanner?: string
-  ): { args: string[]; env: Record<string, string> } {
+    startupBanner?: string,
+    login = false,
+    remote = false
+  ): { args: string[]; env: Record<string, string>; files?: Record<string, string> } {
     const env: Record<string, string> = {
       TERMINAL_MCP: "1",
       ...extraEnv,
@@ -86,21 +449,38 @@ export class TerminalSession {
       return banner.replace(/'/g, "'\\''");
     };
 
-    if (shellName === "bash" || shellName === "sh") {
+    // A remote sh is often dash, which has no --rcfile
+    if (shellName === "bash" || (shellName === "sh" && !remote)) {
       // Create temp rcfile that sources user's .bashrc then prepends our marker
       // to PS1 every prompt (so themes that rebuild PS1 in PROMPT_COMMAND can't
       // clobber it) and sets the terminal title.
-      const homeDir = os.homedir();
+      // Expanded by the remote shell, whose home isn't ours
+      const homeDir = remote ? "$HOME" : os.homedir();
       const bannerCmd = startupBanner ? `printf '%s\\n' '${escapeBannerForShell(startupBanner)}'` : "";
+      // bash ignores --rcfile in a login shell, so a login shell is emulated
+      // by sourcing the profile files bash -l would read instead of .bashrc
+      const userConfig = login
+        ? `# Source the profile files a login shell reads
+[ -f /etc/profile ] && . /etc/profile
+for _terminal_mcp_profile in "${homeDir}/.bash_profile" "${homeDir}/.bash_login" "${homeDir}/.profile"; do
+  [ -f "$_terminal_mcp_profile" ] && { . "$_terminal_mcp_profile"; break; }
+done
+unset _terminal_mcp_profile`
+        : `# Source user's bashrc if it exists
+[ -f "${homeDir}/.bashrc" ] && source "${homeDir}/.bashrc"`;
       const bashrcContent = `
-# Source user's bashrc if it exists
-[ -f "${homeDir}/.bashrc" ] && source "${homeDir}/.bashrc"
+${userConfig}
 # Set initial terminal title
 printf '\\033]0;[terminal-mcp]\\a'
 # Prepend a marker to PS1 every prompt and refresh the title.
 # Re-runs each PROMPT_COMMAND so themes that rebuild PS1 keep the marker.
+# Also reports the last exit status (OSC 133;D); runs first in
+# PROMPT_COMMAND so $? is still the command's.
 _terminal_mcp_prompt_marker() {
+  local _terminal_mcp_status=$?
+  printf '\\033]133;D;%s\\a' "$_terminal_mcp_status"
   printf '\\033]0;[terminal-mcp] %s\\a' "\${PWD/#$HOME/~}"
+  printf '\\033]7;file://%s%s\\a' "\${HOSTNAME:-}" "\${PWD//\\%/%25}"
   case "$PS1" in
     *"${PROMPT_INDICATOR}"*) ;;
     *) PS1="\\[\\033[30;43m\\] ${PROMPT_INDICATOR} \\[\\033[0m\\] $PS1" ;;
@@ -113,6 +493,9 @@ esac
 # Print startup banner
 ${bannerCmd}
 `;
+      if (remote) {
+        return { args: ["--rcfile", `${REMOTE_DIR}/bashrc`], env, files: { bashrc: bashrcContent } };
+      }
       this.rcFile = path.join(os.tmpdir(), `terminal-mcp-bashrc-${process.pid}`);
       fs.writeFileSync(this.rcFile, bashrcContent);
       return { args: ["--rcfile", this.rcFile], env };
@@ -123,10 +506,8 @@ ${bannerCmd}
       // a precmd hook (running LAST) that prepends our marker. This survives
       // theme regenerators like powerlevel10k / starship that rebuild PROMPT
       // every precmd.
-      const homeDir = os.homedir();
-      this.zdotdir = path.join(os.tmpdir(), `terminal-mcp-zsh-${process.pid}`);
-      fs.mkdirSync(this.zdotdir, { recursive: true });
-
+      // Expanded by the remote shell, whose home isn't ours
+      const homeDir = remote ? "$HOME" : os.homedir();
       const bannerCmd = startupBanner ? `printf '%s\\n' '${escapeBannerForShell(startupBanner)}'` : "";
       const zshrcContent = `
 # Reset ZDOTDIR so nested zsh uses normal config
@@ -141,17 +522,37 @@ print -Pn '\\e]0;[terminal-mcp]\\a'
 autoload -Uz add-zsh-hook
 _terminal_mcp_prompt_marker() {
   print -Pn '\\e]0;[terminal-mcp] %~\\a'
+  printf '\\e]7;file://%s%s\\a' "\${HOST}" "\${PWD//\\%/%25}"
   if [[ "$PROMPT" != *"${PROMPT_INDICATOR}"* ]]; then
     PROMPT="%K{yellow}%F{black} ${PROMPT_INDICATOR} %f%k $PROMPT"
   fi
 }
 add-zsh-hook precmd _terminal_mcp_prompt_marker
+# Report the last exit status (OSC 133;D) from a hook that runs FIRST,
+# before other precmd functions can clobber $?.
+_terminal_mcp_exit_status() {
+  print -n "\\e]133;D;$?\\a"
+}
+precmd_functions=(_terminal_mcp_exit_status \${precmd_functions:#_terminal_mcp_exit_status})
 # Print startup banner
 ${bannerCmd}
 `;
+      // A login zsh reads .zprofile from ZDOTDIR before .zshrc; .zlogin
+      // comes after .zshrc has pointed ZDOTDIR back at the home directory
+      const zprofileContent = `[ -f "${homeDir}/.zprofile" ] && source "${homeDir}/.zprofile"\n`;
+      const args = login ? ["-l"] : [];
+      if (remote) {
+        env.ZDOTDIR = REMOTE_DIR;
+        return { args, env, files: { ".zshrc": zshrcContent, ...(login && { ".zprofile": zprofileContent }) } };
+      }
+      this.zdotdir = path.join(os.tmpdir(), `terminal-mcp-zsh-${process.pid}`);
+      fs.mkdirSync(this.zdotdir, { recursive: true });
       fs.writeFileSync(path.join(this.zdotdir, ".zshrc"), zshrcContent);
+      if (login) {
+        fs.writeFileSync(path.join(this.zdotdir, ".zprofile"), zprofileContent);
+      }
       env.ZDOTDIR = this.zdotdir;
-      return { args: [], env };
+      return { args, env };
     }
 
     // PowerShell (pwsh is PowerShell Core, powershell is Windows PowerShell)
@@ -162,7 +563,9 @@ ${bannerCmd}
       shellName === "pwsh.exe"
     ) {
       env.TERMINAL_MCP_PROMPT = "1";
-      return { args: ["-NoLogo"], env };
+      // pwsh only accepts -Login as its first argument
+      const loginArgs = login && shellName.startsWith("pwsh") ? ["-Login"] : [];
+      return { args: [...loginArgs, "-NoLogo"], env };
     }
 
     // Windows cmd.exe
@@ -173,7 +576,7 @@ ${bannerCmd}
 
     // For other shells, just set env vars and hope for the best
     env.PS1 = `${PROMPT_INDICATOR} $ `;
-    return { args: [], env };
+    return { args: login ? ["-l"] : [], env };
   }
 
   /**
@@ -183,7 +586,9 @@ ${bannerCmd}
   private async initialize(options: TerminalSessionOptions): Promise<void> {
     const cols = options.cols ?? 120;
     const rows = options.rows ?? 40;
-    const shell = options.shell ?? getDefaultShell();
+    this.promptRegex = compilePromptPattern(options.promptPattern ?? DEFAULT_PROMPT_PATTERN);
+    this.readGuardMs = options.readGuardMs ?? DEFAULT_READ_GUARD_MS;
+    this.historySize = Math.max(0, options.snapshotHistory ?? DEFAULT_SNAPSHOT_HISTORY);
 
     // Create headless terminal emulator
     this.terminal = new Terminal({
@@ -193,14 +598,185 @@ ${bannerCmd}
       allowProposedApi: true,
     });
 
-    // Determine shell type and set up custom prompt
-    const shellName = path.basename(shell);
-    const { args, env } = this.setupShellPrompt(shellName, options.env, options.startupBanner);
+    this.registerParserHooks();
+    registerQueryResponder(
+      this.terminal,
+      (data) => {
+        if (this.respondToQueries && !this.replaying && !this.disposed && !this.exited) {
+          this.ptyProcess.write(data);
+        }
+      },
+      options.queryResponses
+    );
+
+    this.spawnOptions = options;
+    await this.spawnShell();
+    // A command is allowed to finish right away; a reattached shell has already started
+    if ((options.launchCheck ?? true) && !options.command?.length && !this.reattached) {
+      await this.waitForLaunch();
+    }
+    await this.runInitCommands();
+  }
+
+  /**
+   * Type the configured init commands one at a time, each once the shell
+   * is back at its prompt. Stops at the first command that doesn't return
+   * to a prompt within INIT_COMMAND_TIMEOUT_MS.
+   */
+  private async runInitCommands(): Promise<void> {
+    this.initResults = [];
+    const commands = this.spawnOptions.initCommands ?? [];
+    // A reattached shell ran them when it started
+    if (commands.length === 0 || this.spawnOptions.command?.length || this.reattached) return;
+
+    if (!(await this.waitForInitPrompt(0))) {
+      this.initResults = commands.map((command) => ({ command, exitCode: null, timedOut: true }));
+      return;
+    }
+    for (const command of commands) {
+      let exitCode: number | null = null;
+      let finished = false;
+      const unsubscribe = this.onCommandFinished((_command, code) => {
+        finished = true;
+        exitCode = code;
+      });
+      const sentAt = Date.now();
+      this.write(`${command}\r`);
+      const ready = await this.waitForInitPrompt(sentAt, () => finished);
+      unsubscribe();
+      this.initResults.push({ command, exitCode, timedOut: !ready });
+      if (!ready) return;
+    }
+  }
+
+  /**
+   * Poll until output has paused with the cursor on a prompt line. Output
+   * must have arrived after `since` (or the exit status hook fired), so the
+   * prompt a command was typed at doesn't count.
+   */
+  private async waitForInitPrompt(since: number, finished: () => boolean = () => false): Promise<boolean> {
+    const deadline = Date.now() + INIT_COMMAND_TIMEOUT_MS;
+    while (Date.now() < deadline && !this.exited) {
+      const quiet = this.pendingParses === 0 && Date.now() - this.lastOutputAt >= INIT_QUIET_MS;
+      if (quiet && (finished() || this.lastOutputAt > since) && this.isAtPrompt()) {
+        return true;
+      }
+      await new Promise((resolve) => setTimeout(resolve, INIT_POLL_MS));
+    }
+    return false;
+  }
+
+  /**
+   * Results of the init commands run in the current shell
+   */
+  getInitResults(): InitCommandResult[] {
+    return this.initResults.map((result) => ({ ...result }));
+  }
+
+  /**
+   * Pick the shell to spawn: the configured one if it exists and is
+   * executable, otherwise FALLBACK_SHELL (except on Windows)
+   */
+  private chooseShell(requested: string): string {
+    this.shellFallback = null;
+    const problem = checkShell(requested);
+    if (!problem) return requested;
+    if (process.platform === "win32" || requested === FALLBACK_SHELL) {
+      throw new ShellLaunchError(requested, problem);
+    }
+    const fallbackProblem = checkShell(FALLBACK_SHELL);
+    if (fallbackProblem) {
+      throw new ShellLaunchError(requested, problem, { shell: FALLBACK_SHELL, reason: fallbackProblem });
+    }
+    this.shellFallback = { requested, reason: problem, shell: FALLBACK_SHELL };
+    return FALLBACK_SHELL;
+  }
+
+  /**
+   * Wait for the new shell's first output. A shell that exits before then,
+   * or right after (bad rc file, missing library, sandbox refusing to
+   * start), would otherwise leave a terminal that never shows anything, so
+   * it is cleaned up and reported as a ShellLaunchError.
+   */
+  private waitForLaunch(): Promise<void> {
+    return new Promise((resolve, reject) => {
+      let output = "";
+      let settleTimer: NodeJS.Timeout | null = null;
+      const finish = (error?: Error) => {
+        clearTimeout(timeout);
+        if (settleTimer) clearTimeout(settleTimer);
+        dataSub.dispose();
+        exitSub.dispose();
+        if (error) {
+          this.dispose();
+          reject(error);
+        } else {
+          resolve();
+        }
+      };
+      const timeout = setTimeout(() => finish(), LAUNCH_TIMEOUT_MS);
+      const dataSub = this.ptyProcess.onData((data) => {
+        output = (output + data).slice(-4096);
+        settleTimer ??= setTimeout(() => finish(), LAUNCH_SETTLE_MS);
+      });
+      const exitSub = this.ptyProcess.onExit(({ exitCode }) => {
+        const lastLine = stripAnsi(output).split(/\r?\n/).map((line) => line.trim()).filter(Boolean).pop();
+        const reason = lastLine
+          ? `exited with code ${exitCode} right after starting: ${lastLine}`
+          : `exited with code ${exitCode} before printing anything`;
+        finish(new ShellLaunchError(this.shell, reason));
+      });
+    });
+  }
+
+  /**
+   * Start the shell on a new PTY attached to the emulator. Called once by
+   * initialize() and again by restart().
+   */
+  private async spawnShell(): Promise<void> {
+    const options = this.spawnOptions;
+    if (options.backend?.type === "ssh" || options.backend?.type === "docker") {
+      await this.spawnRemoteShell(options.backend);
+      return;
+    }
+    this.reattached = false;
+    if (options.persist && (await this.reattachShell(options.persist))) {
+      return;
+    }
+    const { cols, rows } = this.terminal;
+    let shell: string;
+    let args: string[];
+    let env: Record<string, string>;
+    if (options.command?.length) {
+      // No fallback: running some other program instead would be surprising
+      const [program, ...programArgs] = options.command;
+      const problem = checkShell(program);
+      if (problem) {
+        throw new ShellLaunchError(program, problem);
+      }
+      shell = program;
+      args = programArgs;
+      env = { TERMINAL_MCP: "1", ...options.env };
+    } else {
+      shell = this.chooseShell(options.shell ?? getDefaultShell());
+      // Determine shell type and set up custom prompt
+      ({ args, env } = this.setupShellPrompt(path.basename(shell), options.env, options.startupBanner, options.login));
+      // Flags meant for the configured shell may not suit the fallback
+      if (options.shellArgs?.length && !this.shellFallback) {
+        args = [...args, ...options.shellArgs];
+      }
+    }
+    this.shell = shell;
 
     // Determine spawn command - may be wrapped by sandbox
     let spawnCmd = shell;
     let spawnArgs = args;
 
+    // The shell only starts if the tmux session doesn't exist yet
+    if (options.backend?.type === "tmux") {
+      ({ cmd: spawnCmd, args: spawnArgs } = tmuxAttachCommand(options.backend, shell, args, env, options.cwd));
+    }
+
     if (options.sandboxController?.isActive()) {
       const wrapped = await options.sandboxController.wrapShellCommand(shell, args);
       spawnCmd = wrapped.cmd;
@@ -213,28 +789,161 @@ ${bannerCmd}
       }
     }
 
+    // Outside the sandbox wrapper, so the sandbox runs under the limits too
+    if (options.resourceLimits) {
+      ({ cmd: spawnCmd, args: spawnArgs } = wrapWithLimits(spawnCmd, spawnArgs, options.resourceLimits));
+    }
+
     // Spawn PTY process
-    this.ptyProcess = pty.spawn(spawnCmd, spawnArgs, {
-      name: "xterm-256color",
-      cols,
-      rows,
-      cwd: options.cwd ?? process.cwd(),
-      env: { ...process.env, ...env } as Record<string, string>,
+    this.spawnEnv = { ...baseEnv(options.envPolicy), ...env };
+    if (options.backend?.type === "tmux") {
+      // tmux refuses to attach from inside another tmux client
+      delete this.spawnEnv.TMUX;
+    }
+    try {
+      if (options.persist) {
+        this.ptyProcess = await PersistBackend.launch(options.persist, shell, spawnCmd, spawnArgs, {
+          cols,
+          rows,
+          cwd: options.cwd ?? process.cwd(),
+          env: this.spawnEnv,
+        });
+      } else {
+        this.ptyProcess = pty.spawn(spawnCmd, spawnArgs, {
+          name: "xterm-256color",
+          cols,
+          rows,
+          cwd: options.cwd ?? process.cwd(),
+          env: this.spawnEnv,
+        });
+      }
+    } catch (error) {
+      // Nothing to kill; dispose() still removes the rc files
+      this.exited = true;
+      this.dispose();
+      throw new ShellLaunchError(spawnCmd, (error as Error).message);
+    }
+    this.attachBackend();
+  }
+
+  /**
+   * Attach to a running --persist holder, if there is one, and replay the
+   * output it kept so the scrollback and screen look as they did when the
+   * last server detached. The PTY is then resized to ours, which also makes
+   * full-screen programs redraw.
+   */
+  private async reattachShell(target: PersistTarget): Promise<boolean> {
+    let attached: Awaited<ReturnType<typeof PersistBackend.attach>>;
+    try {
+      attached = await PersistBackend.attach(target);
+    } catch (error) {
+      this.exited = true;
+      this.dispose();
+      throw new ShellLaunchError(`persist:${target.name}`, (error as Error).message);
+    }
+    if (!attached) return false;
+    this.shell = attached.shell;
+    this.ptyProcess = attached.backend;
+    this.reattached = true;
+    this.replaying = true;
+    this.terminal.write(attached.replay, () => {
+      this.replaying = false;
     });
+    // Queued after the replay, so live output lands on top of it
+    this.attachBackend();
+    this.ptyProcess.resize(this.terminal.cols, this.terminal.rows);
+    return true;
+  }
+
+  /**
+   * True when the current shell was started by an earlier server (--persist)
+   */
+  isReattached(): boolean {
+    return this.reattached;
+  }
+
+  /**
+   * Start the shell (or command) on a PTY on the --host machine or in the
+   * session's container. The prompt hooks are uploaded with it, so prompt
+   * detection, exit status, and OSC 7 work as they do locally. The sandbox
+   * and resource limits only apply to local shells.
+   */
+  private async spawnRemoteShell(backend: Extract<BackendConfig, { type: "ssh" | "docker" }>): Promise<void> {
+    const options = this.spawnOptions;
+    const { cols, rows } = this.terminal;
+    let launch: RemoteLaunch;
+    if (options.command?.length) {
+      const [program, ...args] = options.command;
+      launch = { program, args, env: { TERMINAL_MCP: "1", ...options.env } };
+    } else {
+      // The remote login shell may be anything; bash is the likeliest to have hooks that work
+      const program = options.shell ?? "bash";
+      const { args, env, files } = this.setupShellPrompt(
+        path.basename(program),
+        options.env,
+        options.startupBanner,
+        options.login,
+        true
+      );
+      launch = { program, args: [...args, ...(options.shellArgs ?? [])], env, files };
+    }
+    launch.cwd = options.cwd;
+    this.shell = launch.program;
+    this.spawnEnv = launch.env;
+    try {
+      if (backend.type === "ssh") {
+        this.ptyProcess = await SshBackend.connect(backend, launch, cols, rows);
+      } else {
+        // Kept across restarts; removed with the session if created for it
+        this.container ??= await DockerContainer.open(backend);
+        this.ptyProcess = await this.container.exec(launch, cols, rows);
+      }
+    } catch (error) {
+      this.exited = true;
+      this.dispose();
+      const where =
+        backend.type === "ssh" ? `${backend.username}@${backend.host}` : `docker:${backend.container ?? backend.image}`;
+      throw new ShellLaunchError(where, (error as Error).message);
+    }
+    this.attachBackend();
+  }
+
+  /**
+   * Feed the new backend's output to the emulator and listeners
+   */
+  private attachBackend(): void {
+    const options = this.spawnOptions;
+
+    // A new shell starts unpaused
+    this.flowPaused = false;
+    this.flow.paused = false;
 
     // Pipe PTY output to terminal emulator and listeners
     this.ptyProcess.onData((data) => {
       if (!this.disposed) {
-        this.terminal.write(data);
-        // Notify all data listeners
+        this.lastOutputAt = Date.now();
+        this.feedEmulator(data);
+        // Listeners (recordings, streams, the mirror) get everything, skipped or not
         for (const listener of this.dataListeners) {
           listener(data);
         }
       }
     });
 
+    const interval = options.snapshotInterval ?? 0;
+    if (interval > 0 && this.historySize > 0) {
+      this.historyTimer = setInterval(() => this.recordHistorySnapshot("timer"), interval * 1000);
+      this.historyTimer.unref();
+    }
+
     this.ptyProcess.onExit(({ exitCode }) => {
-      this.disposed = true;
+      this.exited = true;
+      this.exitCode = exitCode;
+      // A finished command's output stays readable until the session is destroyed
+      if (!options.command?.length) {
+        this.disposed = true;
+      }
+      this.stopHistoryTimer();
       for (const listener of this.exitListeners) {
         listener(exitCode);
       }
@@ -242,34 +951,362 @@ ${bannerCmd}
   }
 
   /**
-   * Subscribe to PTY output data
+   * Queue output for the parser, applying --flood-policy once too much is
+   * waiting: xterm.js would otherwise buffer without limit and, past 50 MB,
+   * throw the data away unannounced
+   */
+  private feedEmulator(data: string): void {
+    if (this.skipping > 0) {
+      const bytes = Buffer.byteLength(data);
+      this.skipping += bytes;
+      this.flow.skippedBytes += bytes;
+      return;
+    }
+    this.parse(data);
+    if (this.unparsed <= FLOOD_HIGH_WATERMARK) return;
+    if (this.spawnOptions.floodPolicy !== "drop" && this.ptyProcess.pause) {
+      if (!this.flowPaused) {
+        this.flowPaused = true;
+        this.flow.paused = true;
+        this.flow.pauses++;
+        this.ptyProcess.pause();
+      }
+    } else {
+      // Counts from 1 so a run of empty chunks still reads as skipping
+      this.skipping = 1;
+    }
+  }
+
+  /**
+   * Queue output for the parser. node-pty hands output over in whatever
+   * chunks it read, with no say over their size: it reads through a Node
+   * stream whose buffers libuv allocates (64 KB each), and exposes no
+   * option for the read size or buffer reuse. So chunks that arrive in the
+   * same turn of the event loop are joined and written as one: heavy output
+   * costs one parser write and callback per turn, not per chunk.
+   */
+  private parse(data: string): void {
+    this.unparsed += data.length;
+    this.coalesced.push(data);
+    if (this.coalesced.length > 1) return;
+    this.pendingParses++;
+    setImmediate(() => {
+      const batch = this.coalesced.length === 1 ? this.coalesced[0] : this.coalesced.join("");
+      this.coalesced = [];
+      if (this.cleanedUp) {
+        this.pendingParses--;
+        return;
+      }
+      this.terminal.write(batch, () => {
+        this.pendingParses--;
+        this.unparsed -= batch.length;
+        if (this.unparsed <= FLOOD_LOW_WATERMARK) this.drained();
+      });
+    });
+  }
+
+  /**
+   * The parser has caught up: read again, or say what was skipped
    */
-  onData(listener: (data: string) => void): void {
+  private drained(): void {
+    if (this.flowPaused) {
+      this.flowPaused = false;
+      this.flow.paused = false;
+      if (!this.exited) this.ptyProcess.resume?.();
+    }
+    if (this.skipping > 0) {
+      const skipped = this.skipping - 1;
+      this.skipping = 0;
+      this.parse(`\x1b[0m\r\n[terminal-mcp: output too fast, ${formatByteSize(skipped)} skipped]\r\n`);
+    }
+  }
+
+  /**
+   * Flood handling so far (see --flood-policy)
+   */
+  getFlowStats(): FlowStats {
+    return { ...this.flow };
+  }
+
+  /**
+   * Hook into the xterm.js parser for sequences we track ourselves.
+   * Handlers return false so xterm's built-in handling still runs.
+   */
+  private registerParserHooks(): void {
+    // DECSCUSR (CSI Ps SP q) — cursor shape and blink
+    this.terminal.parser.registerCsiHandler({ intermediates: " ", final: "q" }, (params) => {
+      const ps = typeof params[0] === "number" ? params[0] : 0;
+      const shapes: CursorShape[] = ["block", "block", "underline", "bar"];
+      this.cursorStyle = {
+        shape: shapes[Math.ceil(Math.max(ps, 1) / 2)] ?? "block",
+        // Ps 0 and odd values blink; even values are steady
+        blink: ps === 0 || ps % 2 === 1,
+      };
+      return false;
+    });
+
+    // DECSET/DECRST (CSI ? Pm h / l) — private modes xterm.js doesn't expose
+    // through its public API
+    const trackPrivateModes = (enabled: boolean) => (params: (number | number[])[]) => {
+      for (const param of params) {
+        if (param === 1006) {
+          this.mouseEncoding = enabled ? "sgr" : "default";
+        } else if (param === 25) {
+          this.cursorVisible = enabled;
+        }
+      }
+      return false;
+    };
+    this.terminal.parser.registerCsiHandler({ prefix: "?", final: "h" }, trackPrivateModes(true));
+    this.terminal.parser.registerCsiHandler({ prefix: "?", final: "l" }, trackPrivateModes(false));
+
+    // RIS (ESC c) and DECSTR (CSI ! p) restore the cursor defaults
+    const resetCursor = () => {
+      this.cursorVisible = true;
+      this.cursorStyle = { shape: "block", blink: false };
+      return false;
+    };
+    this.terminal.parser.registerEscHandler({ final: "c" }, resetCursor);
+    this.terminal.parser.registerCsiHandler({ intermediates: "!", final: "p" }, resetCursor);
+
+    // Snapshot what's on screen just before it's wiped, so output that
+    // flashes briefly before a clear can still be recovered from history:
+    // ED 2/3 (clear screen / scrollback), RIS (ESC c), and switching to the
+    // alternate screen (?47, ?1047, ?1049)
+    this.terminal.parser.registerCsiHandler({ final: "J" }, (params) => {
+      if (params[0] === 2 || params[0] === 3) this.recordHistorySnapshot("clear");
+      return false;
+    });
+    this.terminal.parser.registerEscHandler({ final: "c" }, () => {
+      this.recordHistorySnapshot("clear");
+      return false;
+    });
+    this.terminal.parser.registerCsiHandler({ prefix: "?", final: "h" }, (params) => {
+      if (params.some((p) => p === 47 || p === 1047 || p === 1049)) this.recordHistorySnapshot("clear");
+      return false;
+    });
+
+    // OSC 0 / OSC 2 — window title. Shells often put the cwd or running
+    // command here.
+    this.terminal.onTitleChange((title) => {
+      this.title = title;
+    });
+
+    // OSC 52 ; <selection> ; <base64> — clipboard write (tmux, neovim,
+    // vim-oscyank). Queries ("?") are not answered: the clipboard of the
+    // machine running the server is none of the program's business.
+    this.terminal.parser.registerOscHandler(52, (data) => {
+      const separator = data.indexOf(";");
+      if (separator === -1) return true;
+      const payload = data.slice(separator + 1);
+      if (payload === "?" || payload.length > (MAX_CLIPBOARD_BYTES * 4) / 3 + 4) return true;
+      this.clipboard.push({
+        selection: data.slice(0, separator) || "s0",
+        text: Buffer.from(payload, "base64").toString("utf8"),
+        copiedAt: new Date().toISOString(),
+      });
+      if (this.clipboard.length > MAX_CLIPBOARD_ENTRIES) {
+        this.clipboard.shift();
+      }
+      return true;
+    });
+
+    // OSC 7 ; file://<host>/<path> — working directory, emitted by our
+    // prompt hooks and by most shell integrations (fish, VTE, iTerm2)
+    this.terminal.parser.registerOscHandler(7, (data) => {
+      const cwd = parseOsc7(data);
+      if (cwd) this.reportedCwd = cwd;
+      return true;
+    });
+
+    // OSC 8 — hyperlinks (gh, delta, cargo, ls --hyperlink)
+    this.hyperlinks = new HyperlinkTracker(this.terminal);
+
+    // OSC 133;D;<status> — command finished, emitted by our prompt hooks
+    // (and by other shell integrations such as iTerm2's or VS Code's)
+    this.terminal.parser.registerOscHandler(133, (data) => {
+      const match = /^D(?:;(-?\d+))?/.exec(data);
+      if (match) this.reportsExitStatus = true;
+      if (match && this.commandRunning) {
+        this.commandRunning = false;
+        const exitCode = match[1] !== undefined ? parseInt(match[1], 10) : 0;
+        for (const listener of this.commandFinishedListeners) {
+          listener(this.lastCommand, exitCode);
+        }
+      }
+      return false;
+    });
+
+    this.terminal.onBell(() => {
+      for (const listener of this.bellListeners) {
+        listener();
+      }
+    });
+
+    // xterm.js generates replies to DA, DSR, DECRQM, etc. itself; forward
+    // them to the PTY so the querying program gets an answer.
+    this.terminal.onData((data) => {
+      if (this.respondToQueries && !this.replaying && !this.disposed && !this.exited) {
+        this.ptyProcess.write(data);
+      }
+    });
+  }
+
+  /**
+   * Enable or disable forwarding of emulator-generated replies (DA, DSR,
+   * DECRQM, ...) to the PTY. Disable when a real terminal is attached, since
+   * it already answers those queries itself.
+   */
+  setRespondToQueries(enabled: boolean): void {
+    this.respondToQueries = enabled;
+  }
+
+  /**
+   * Subscribe to PTY output data. Returns a function that unsubscribes.
+   */
+  onData(listener: (data: string) => void): () => void {
     this.dataListeners.push(listener);
+    return () => {
+      this.dataListeners = this.dataListeners.filter((l) => l !== listener);
+    };
   }
 
   /**
-   * Subscribe to PTY exit
+   * Subscribe to PTY exit. Returns a function that unsubscribes.
    */
-  onExit(listener: (code: number) => void): void {
+  onExit(listener: (code: number) => void): () => void {
     this.exitListeners.push(listener);
+    return () => {
+      this.exitListeners = this.exitListeners.filter((l) => l !== listener);
+    };
   }
 
   /**
-   * Subscribe to terminal resize events
+   * Subscribe to terminal resize events. Returns a function that unsubscribes.
    */
-  onResize(listener: (cols: number, rows: number) => void): void {
+  onResize(listener: (cols: number, rows: number) => void): () => void {
     this.resizeListeners.push(listener);
+    return () => {
+      this.resizeListeners = this.resizeListeners.filter((l) => l !== listener);
+    };
   }
 
   /**
-   * Write data to the terminal (simulates typing)
+   * Subscribe to the terminal bell (BEL outside of an escape sequence)
    */
-  write(data: string): void {
+  onBell(listener: () => void): void {
+    this.bellListeners.push(listener);
+  }
+
+  /**
+   * Subscribe to shell commands finishing. Requires the bash/zsh prompt
+   * hooks (or another shell integration emitting OSC 133;D). The command
+   * is null when it couldn't be reconstructed from input.
+   */
+  onCommandFinished(listener: (command: string | null, exitCode: number) => void): () => void {
+    this.commandFinishedListeners.push(listener);
+    return () => {
+      this.commandFinishedListeners = this.commandFinishedListeners.filter((l) => l !== listener);
+    };
+  }
+
+  /**
+   * Subscribe to input written to the PTY (tool input and human keystrokes;
+   * not emulator-generated query replies). For secret input, listeners get
+   * an empty string and secret=true: only that something was typed.
+   */
+  onInput(listener: (data: string, secret: boolean) => void): void {
+    this.inputListeners.push(listener);
+  }
+
+  /**
+   * Write data to the terminal (simulates typing). With secret, the data
+   * is kept from input listeners and from command tracking.
+   */
+  write(data: string, options: { secret?: boolean } = {}): void {
+    if (this.restarting) {
+      throw new Error("The shell is restarting; try again in a moment");
+    }
     if (this.disposed) {
       throw new Error("Terminal session has been disposed");
     }
+    if (this.exited) {
+      throw new Error(
+        `The command has exited with code ${this.exitCode}. Call restartShell to run it again.`
+      );
+    }
     this.ptyProcess.write(data);
+    this.lastInputAt = Date.now();
+    if (options.secret) {
+      for (const listener of this.inputListeners) {
+        listener("", true);
+      }
+      return;
+    }
+    this.trackCommand(data);
+    for (const listener of this.inputListeners) {
+      listener(data, false);
+    }
+  }
+
+  private trackCommand(data: string): void {
+    // Keystrokes inside vim, less, etc. aren't shell commands
+    if (this.terminal.buffer.active.type === "alternate") {
+      this.pendingCommand = "";
+      return;
+    }
+
+    const { line, submitted } = editLine(this.pendingCommand, data);
+    for (const command of submitted) {
+      if (command === null || command.trim()) {
+        this.lastCommand = command?.trim() ?? null;
+        this.commandRunning = true;
+      }
+    }
+    this.pendingCommand = line;
+  }
+
+  /**
+   * The command lines writing `data` would submit at the shell prompt,
+   * without writing it. A line that can't be reconstructed from input
+   * (after history recall, completion, ...) is read from the cursor row
+   * instead, prompt included. Empty while a full-screen program runs.
+   */
+  previewSubmittedCommands(data: string): string[] {
+    if (this.terminal.buffer.active.type === "alternate") return [];
+    const buffer = this.terminal.buffer.active;
+    const cursorRow = buffer.getLine(buffer.baseY + buffer.cursorY)?.translateToString(true) ?? "";
+    return editLine(this.pendingCommand, data)
+      .submitted.map((command) => (command ?? cursorRow).trim())
+      .filter(Boolean);
+  }
+
+  /**
+   * Wait until a read won't race the most recent input. If input was sent
+   * within the guard window, waits for output that arrived after it to be
+   * parsed and then go quiet briefly, giving up when the window elapses
+   * (input that produces no output, e.g. a key in a paused TUI).
+   */
+  async waitForReadable(): Promise<void> {
+    if (this.readGuardMs <= 0 || this.disposed) return;
+    const deadline = this.lastInputAt + this.readGuardMs;
+
+    while (Date.now() < deadline && !this.disposed) {
+      const now = Date.now();
+      const outputSinceInput = this.lastOutputAt >= this.lastInputAt;
+      if (outputSinceInput && this.pendingParses === 0 && now - this.lastOutputAt >= READ_GUARD_QUIET_MS) {
+        return;
+      }
+      await new Promise((resolve) => setTimeout(resolve, READ_GUARD_POLL_MS));
+    }
+  }
+
+  /**
+   * Get the last command line submitted to the shell (reconstructed from
+   * input), or null if unknown
+   */
+  getLastCommand(): string | null {
+    return this.lastCommand;
   }
 
   /**
@@ -301,7 +1338,9 @@ ${bannerCmd}
 
   /**
    * Get terminal content with ANSI color escape sequences preserved.
-   * Reads the xterm.js cell buffer and reconstructs SGR sequences.
+   * Reads the xterm.js cell buffer and reconstructs SGR sequences for every
+   * attribute it tracks (colors, underline style/color, dim, blink,
+   * strikethrough, overline, ...).
    */
   getAnsiContent(visibleOnly = false): string {
     if (this.disposed) {
@@ -309,107 +1348,12 @@ ${bannerCmd}
     }
 
     const buffer = this.terminal.buffer.active;
-    const lines: string[] = [];
-
     const startLine = visibleOnly ? buffer.baseY : 0;
     const endLine = visibleOnly ? buffer.baseY + this.terminal.rows : buffer.length;
-
-    for (let y = startLine; y < endLine; y++) {
-      const line = buffer.getLine(y);
-      if (!line) {
-        lines.push("");
-        continue;
-      }
-
-      let lineStr = "";
-      let lastFg = -999;
-      let lastFgMode = -999;
-      let lastBg = -999;
-      let lastBgMode = -999;
-      let lastBold = false;
-      let lastDim = false;
-      let lastItalic = false;
-      let lastUnderline = false;
-
-      for (let x = 0; x < line.length; x++) {
-        const cell = line.getCell(x);
-        if (!cell) continue;
-        const char = cell.getChars();
-
-        const fg = cell.getFgColor();
-        const fgMode = cell.getFgColorMode();
-        const bg = cell.getBgColor();
-        const bgMode = cell.getBgColorMode();
-        const bold = cell.isBold() === 1;
-        const dim = cell.isDim() === 1;
-        const italic = cell.isItalic() === 1;
-        const underline = cell.isUnderline() === 1;
-
-        const attrChanged =
-          fg !== lastFg || fgMode !== lastFgMode ||
-          bg !== lastBg || bgMode !== lastBgMode ||
-          bold !== lastBold || dim !== lastDim ||
-          italic !== lastItalic || underline !== lastUnderline;
-
-        if (attrChanged) {
-          const sgr: string[] = [];
-
-          // Reset if we had any prior styling
-          if (lastFgMode !== -999) sgr.push("0");
-
-          if (bold) sgr.push("1");
-          if (dim) sgr.push("2");
-          if (italic) sgr.push("3");
-          if (underline) sgr.push("4");
-
-          // Foreground
-          if (fgMode === 0x1000000) {
-            // P16
-            sgr.push(fg < 8 ? `${30 + fg}` : `${90 + fg - 8}`);
-          } else if (fgMode === 0x2000000) {
-            // P256
-            sgr.push(`38;5;${fg}`);
-          } else if (fgMode === 0x3000000) {
-            // RGB
-            sgr.push(`38;2;${(fg >> 16) & 0xFF};${(fg >> 8) & 0xFF};${fg & 0xFF}`);
-          }
-
-          // Background
-          if (bgMode === 0x1000000) {
-            sgr.push(bg < 8 ? `${40 + bg}` : `${100 + bg - 8}`);
-          } else if (bgMode === 0x2000000) {
-            sgr.push(`48;5;${bg}`);
-          } else if (bgMode === 0x3000000) {
-            sgr.push(`48;2;${(bg >> 16) & 0xFF};${(bg >> 8) & 0xFF};${bg & 0xFF}`);
-          }
-
-          if (sgr.length > 0) {
-            lineStr += `\x1b[${sgr.join(";")}m`;
-          }
-
-          lastFg = fg;
-          lastFgMode = fgMode;
-          lastBg = bg;
-          lastBgMode = bgMode;
-          lastBold = bold;
-          lastDim = dim;
-          lastItalic = italic;
-          lastUnderline = underline;
-        }
-
-        lineStr += char || " ";
-      }
-
-      // Reset at end of line if we emitted any SGR
-      if (lastFgMode !== -999) {
-        lineStr += "\x1b[0m";
-      }
-
-      lines.push(lineStr);
-    }
+    const lines = getStyledRows(this.terminal, startLine, endLine).map((row) => runsToAnsi(row.runs));
 
     // Trim trailing empty lines
-    while (lines.length > 0 && lines[lines.length - 1].replace(/\x1b\[[0-9;]*m/g, "").trim() === "") {
+    while (lines.length > 0 && stripAnsi(lines[lines.length - 1]).trim() === "") {
       lines.pop();
     }
 
@@ -438,6 +1382,355 @@ ${bannerCmd}
     return lines.join("\n");
   }
 
+  /**
+   * Get buffer lines with their absolute line index, for callers that need
+   * to know where in the scrollback a line sits.
+   */
+  getBufferLines(visibleOnly = false): BufferLine[] {
+    if (this.disposed) {
+      throw new Error("Terminal session has been disposed");
+    }
+
+    const buffer = this.terminal.buffer.active;
+    const start = visibleOnly ? buffer.baseY : 0;
+    const end = visibleOnly ? buffer.baseY + this.terminal.rows : buffer.length;
+    const lines: BufferLine[] = [];
+
+    for (let i = start; i < end; i++) {
+      const line = buffer.getLine(i);
+      if (line) {
+        lines.push({
+          line: i,
+          text: line.translateToString(true),
+          inViewport: i >= buffer.baseY,
+        });
+      }
+    }
+
+    return lines;
+  }
+
+  /**
+   * Text on the cursor row, up to (not including) the cursor column
+   */
+  getCursorLineText(): string {
+    if (this.disposed) {
+      throw new Error("Terminal session has been disposed");
+    }
+    const buffer = this.terminal.buffer.active;
+    const line = buffer.getLine(buffer.baseY + buffer.cursorY);
+    return line ? line.translateToString(false, 0, buffer.cursorX) : "";
+  }
+
+  /**
+   * Check whether the cursor row looks like a shell prompt, using the
+   * configured prompt pattern (or a caller-supplied one).
+   */
+  isAtPrompt(regex: RegExp = this.promptRegex): boolean {
+    return regex.test(this.getCursorLineText().replace(/\s+$/, ""));
+  }
+
+  /**
+   * Whether a command submitted at the prompt is still running, going by
+   * the exit status the shell reports when it finishes (OSC 133;D). Null
+   * when the shell has never reported one, so it can't be told.
+   */
+  isCommandRunning(): boolean | null {
+    return this.reportsExitStatus ? this.commandRunning : null;
+  }
+
+  /**
+   * Whether the terminal looks like it is waiting for a password or other
+   * secret: echo turned off on the PTY while reading a line, or a cursor row
+   * like "Password:". Echo is read with stty, so only where it can open
+   * the PTY (not Windows).
+   */
+  getSecretPrompt(): SecretPrompt {
+    if (this.exited) return NO_SECRET_PROMPT;
+    // node-pty's Unix terminals expose the slave side's device path
+    const ptsName = (this.ptyProcess as unknown as { ptsName?: string }).ptsName;
+    return detectSecretPrompt(this.getCursorLineText(), () => (ptsName ? readEchoOff(ptsName) : null));
+  }
+
+  /**
+   * Milliseconds since the PTY last produced output, or null if it never has
+   */
+  getMsSinceOutput(): number | null {
+    return this.lastOutputAt === 0 ? null : Date.now() - this.lastOutputAt;
+  }
+
+  /**
+   * Get the prompt pattern used by isAtPrompt()
+   */
+  getPromptPattern(): RegExp {
+    return this.promptRegex;
+  }
+
+  /**
+   * Capture the visible rows and cursor for later comparison
+   */
+  captureSnapshot(): ScreenSnapshot {
+    const buffer = this.terminal.buffer.active;
+    const rows: string[] = [];
+    for (let y = 0; y < this.terminal.rows; y++) {
+      rows.push(buffer.getLine(buffer.baseY + y)?.translateToString(true) ?? "");
+    }
+    return {
+      rows,
+      cursor: { x: buffer.cursorX, y: buffer.cursorY },
+      cols: this.terminal.cols,
+      takenAt: new Date().toISOString(),
+    };
+  }
+
+  /**
+   * Store a snapshot under a name, replacing any previous one. The oldest
+   * name is evicted once MAX_NAMED_SNAPSHOTS are held.
+   */
+  saveSnapshot(name: string, snapshot: ScreenSnapshot = this.captureSnapshot()): ScreenSnapshot {
+    this.snapshots.delete(name);
+    if (this.snapshots.size >= MAX_NAMED_SNAPSHOTS) {
+      const oldest = this.snapshots.keys().next().value;
+      if (oldest !== undefined) this.snapshots.delete(oldest);
+    }
+    this.snapshots.set(name, snapshot);
+    return snapshot;
+  }
+
+  getSnapshot(name: string): ScreenSnapshot | undefined {
+    return this.snapshots.get(name);
+  }
+
+  /**
+   * Add the current screen to the snapshot history. Blank screens and
+   * screens identical to the newest entry are skipped.
+   */
+  recordHistorySnapshot(trigger: SnapshotTrigger, tool?: string): void {
+    if (this.historySize === 0 || this.disposed) return;
+    const snapshot = this.captureSnapshot();
+    if (snapshot.rows.every((row) => row.trim() === "")) return;
+    const newest = this.history[this.history.length - 1];
+    if (newest && newest.rows.length === snapshot.rows.length && newest.rows.every((row, i) => row === snapshot.rows[i])) {
+      return;
+    }
+    this.history.push({ ...snapshot, id: this.nextHistoryId++, trigger, ...(tool && { tool }) });
+    if (this.history.length > this.historySize) {
+      this.history.shift();
+    }
+  }
+
+  /**
+   * Automatic snapshots, oldest first
+   */
+  getSnapshotHistory(): SnapshotHistoryEntry[] {
+    return this.history;
+  }
+
+  /**
+   * Buffer lines that differ from what the caller saw at generation `since`,
+   * and a new generation to pass next time. Lines are compared by buffer
+   * index, adjusted for lines trimmed from the top of the scrollback in
+   * between. `since` = 0 starts tracking and returns everything.
+   */
+  getContentChanges(since: number, visibleOnly = false): ContentChanges {
+    if (this.disposed) {
+      throw new Error("Terminal session has been disposed");
+    }
+
+    const buffer = this.terminal.buffer.active;
+    const alternate = buffer.type === "alternate";
+    const lines = this.getBufferLines(false).map((l) => l.text);
+    const previous = this.generations.find((g) => g.id === since);
+
+    // Lines trimmed off the top since then shift every index down
+    let shift = 0;
+    const full = !previous || previous.alternate !== alternate || previous.anchor.isDisposed;
+    if (previous && !full) {
+      shift = previous.anchorLine - previous.anchor.line;
+    }
+
+    const start = visibleOnly ? buffer.baseY : 0;
+    const changed: BufferLine[] = [];
+    for (let i = start; i < lines.length; i++) {
+      if (full || previous!.lines[i + shift] !== lines[i]) {
+        changed.push({ line: i, text: lines[i], inViewport: i >= buffer.baseY });
+      }
+    }
+
+    const anchor = this.terminal.registerMarker(0);
+    const generation = this.nextGeneration++;
+    if (anchor) {
+      this.generations.push({ id: generation, lines, anchor, anchorLine: anchor.line, alternate });
+      while (this.generations.length > MAX_CONTENT_GENERATIONS) {
+        this.generations.shift()!.anchor.dispose();
+      }
+    }
+
+    return { generation, full, lineCount: lines.length, changed };
+  }
+
+  /**
+   * Mark the cursor's line under a name, replacing any previous mark of
+   * that name, so getContent can later return everything from there on.
+   * The mark follows its line as the scrollback is trimmed. The oldest
+   * name is evicted once MAX_MARKS are held.
+   */
+  setMark(name: string): OutputMark {
+    if (this.disposed) {
+      throw new Error("Terminal session has been disposed");
+    }
+    // Markers live in the active buffer; the alternate one has no scrollback
+    if (this.terminal.buffer.active.type === "alternate") {
+      throw new Error("Cannot mark output while a full-screen program is using the alternate screen");
+    }
+    const marker = this.terminal.registerMarker(0);
+    if (!marker) {
+      throw new Error("Cannot mark output at the cursor's line");
+    }
+    this.marks.get(name)?.marker.dispose();
+    this.marks.delete(name);
+    if (this.marks.size >= MAX_MARKS) {
+      const [oldest, entry] = this.marks.entries().next().value!;
+      entry.marker.dispose();
+      this.marks.delete(oldest);
+    }
+    const markedAt = new Date().toISOString();
+    this.marks.set(name, { marker, markedAt });
+    return { name, line: marker.line, markedAt };
+  }
+
+  /**
+   * Marks set with setMark, oldest first
+   */
+  getMarks(): OutputMark[] {
+    return Array.from(this.marks, ([name, { marker, markedAt }]) => ({
+      name,
+      line: marker.isDisposed ? null : marker.line,
+      markedAt,
+    }));
+  }
+
+  /**
+   * Buffer line of a mark. Throws if there is no such mark, it has
+   * scrolled out of the scrollback, or a full-screen program hides it.
+   */
+  getMarkLine(name: string): number {
+    const mark = this.marks.get(name);
+    if (!mark) {
+      const names = Array.from(this.marks.keys());
+      throw new Error(`No mark named '${name}'${names.length > 0 ? ` (marks: ${names.join(", ")})` : ""}`);
+    }
+    if (mark.marker.isDisposed) {
+      throw new Error(`Mark '${name}' has scrolled out of the scrollback (or the scrollback was cleared)`);
+    }
+    if (this.terminal.buffer.active.type === "alternate") {
+      throw new Error(`Mark '${name}' is in the normal screen; a full-screen program is using the alternate screen`);
+    }
+    return mark.marker.line;
+  }
+
+  /**
+   * Wipe the screen as `clear` would, in the emulator only. The cursor's
+   * line, usually the prompt and anything typed after it, is redrawn on the
+   * top row, so the shell carries on where it was without being told.
+   * scrollback also empties the scrollback and forgets everything that could
+   * bring old output back: snapshot history, getContent delta generations,
+   * marks, and what a persisted shell replays on the next start. hard is a
+   * full reset (RIS) instead of a clear, and implies scrollback.
+   */
+  async clear(options: { scrollback?: boolean; hard?: boolean } = {}): Promise<void> {
+    if (this.disposed) {
+      throw new Error("Terminal session has been disposed");
+    }
+    const buffer = this.terminal.buffer.active;
+    if (buffer.type === "alternate" && !options.hard) {
+      throw new Error(
+        "A full-screen program is using the alternate screen and will redraw over a clear; use hard to reset the terminal"
+      );
+    }
+    const y = buffer.baseY + buffer.cursorY;
+    const line = runsToAnsi(getStyledRows(this.terminal, y, y + 1)[0].runs);
+    const col = buffer.cursorX;
+
+    let sequence = options.hard ? "\x1bc" : "\x1b[H\x1b[2J" + (options.scrollback ? "\x1b[3J" : "");
+    // A full-screen program's cursor line means nothing once it's gone
+    if (buffer.type === "normal") {
+      sequence += line + "\r" + (col > 0 ? `\x1b[${col}C` : "");
+    }
+    await writeAsync(this.terminal, sequence);
+
+    if (options.scrollback || options.hard) {
+      this.wipeHistory();
+    }
+  }
+
+  /**
+   * Forget what referred to the scrollback once it has been wiped: the
+   * snapshot history, delta generations, and marks, along with the
+   * backend's own copy of the output
+   */
+  private wipeHistory(): void {
+    this.history = [];
+    for (const generation of this.generations) generation.anchor.dispose();
+    this.generations = [];
+    for (const { marker } of this.marks.values()) marker.dispose();
+    this.marks.clear();
+    this.ptyProcess.clearHistory?.();
+  }
+
+  /**
+   * Recover a terminal left in a bad state, e.g. by cat-ing a binary file.
+   * The emulator leaves the alternate screen and drops the modes, character
+   * sets, and colors programs switched on, keeping the scrollback unless
+   * clearScrollback (a full reset, RIS). The PTY gets `stty sane` to turn
+   * echo and line editing back on. Returns whether that could be done: it
+   * needs a local PTY.
+   */
+  async resetTerminal(options: { clearScrollback?: boolean } = {}): Promise<{ ttyReset: boolean }> {
+    if (this.disposed) {
+      throw new Error("Terminal session has been disposed");
+    }
+    await writeAsync(
+      this.terminal,
+      options.clearScrollback
+        ? "\x1bc"
+        : // Normal screen, DECSTR soft reset (SGR, cursor, insert and origin
+          // modes), ASCII character sets, then the mouse, paste, cursor key,
+          // and keypad modes DECSTR leaves alone
+          "\x1b[?1049l\x1b[!p\x1b(B\x1b)B\x0f\x1b[?1000l\x1b[?1002l\x1b[?1003l\x1b[?1006l\x1b[?2004l\x1b[?1l\x1b>"
+    );
+    if (options.clearScrollback) {
+      this.wipeHistory();
+    }
+    // node-pty's Unix terminals expose the slave side's device path
+    const ptsName = (this.ptyProcess as unknown as { ptsName?: string }).ptsName;
+    return { ttyReset: ptsName ? sttySane(ptsName) : false };
+  }
+
+  /**
+   * Clipboard writes made via OSC 52, oldest first
+   */
+  getClipboard(): ClipboardEntry[] {
+    return this.clipboard;
+  }
+
+  /**
+   * OSC 8 hyperlinks still present in the buffer, oldest first
+   */
+  getLinks(visibleOnly = false): Hyperlink[] {
+    if (this.disposed) {
+      throw new Error("Terminal session has been disposed");
+    }
+    return this.hyperlinks.getLinks(visibleOnly);
+  }
+
+  private stopHistoryTimer(): void {
+    if (this.historyTimer) {
+      clearInterval(this.historyTimer);
+      this.historyTimer = null;
+    }
+  }
+
   /**
    * Take a screenshot of the terminal state
    */
@@ -453,22 +1746,115 @@ ${bannerCmd}
       cursor: {
         x: buffer.cursorX,
         y: buffer.cursorY,
+        style: this.getCursorStyle(),
       },
       dimensions: {
         cols: this.terminal.cols,
         rows: this.terminal.rows,
       },
+      modes: this.getModes(),
     };
   }
 
   /**
-   * Clear the terminal screen
+   * Get the screen and input modes the application has switched on
    */
-  clear(): void {
+  getModes(): TerminalModes {
     if (this.disposed) {
       throw new Error("Terminal session has been disposed");
     }
-    this.terminal.clear();
+    const modes = this.terminal.modes;
+    return {
+      alternateScreen: this.terminal.buffer.active.type === "alternate",
+      applicationCursorKeys: modes.applicationCursorKeysMode,
+      applicationKeypad: modes.applicationKeypadMode,
+      bracketedPaste: modes.bracketedPasteMode,
+      mouseTracking: modes.mouseTrackingMode,
+    };
+  }
+
+  /**
+   * Get the cursor shape/blink last set by the application via DECSCUSR
+   */
+  getCursorStyle(): CursorStyle {
+    return { ...this.cursorStyle };
+  }
+
+  /**
+   * Whether the application has left the cursor visible (DECTCEM)
+   */
+  isCursorVisible(): boolean {
+    return this.cursorVisible;
+  }
+
+  /**
+   * Get the PID of the shell process attached to the PTY; null for a
+   * remote shell
+   */
+  getPid(): number | null {
+    return this.ptyProcess.pid;
+  }
+
+  /**
+   * Get the shell that was actually started
+   */
+  getShell(): string {
+    return this.shell;
+  }
+
+  /**
+   * The program and arguments run instead of a shell, or null for a shell
+   */
+  getCommand(): string[] | null {
+    return this.spawnOptions.command?.length ? [...this.spawnOptions.command] : null;
+  }
+
+  /**
+   * Exit code of the shell or command, or null while it is running
+   */
+  getExitCode(): number | null {
+    return this.exitCode;
+  }
+
+  /**
+   * Why the configured shell was replaced by FALLBACK_SHELL, or null if it
+   * wasn't
+   */
+  getShellFallback(): ShellFallback | null {
+    return this.shellFallback;
+  }
+
+  /**
+   * Get the environment the shell was started with. Variables exported
+   * later inside the shell are not reflected.
+   */
+  getSpawnEnv(): Record<string, string> {
+    return { ...this.spawnEnv };
+  }
+
+  /**
+   * Get the window title last set via OSC 0/2
+   */
+  getTitle(): string {
+    return this.title;
+  }
+
+  /**
+   * Working directory last reported with OSC 7, by our prompt hooks or any
+   * shell integration that emits it (possibly on a remote host)
+   */
+  getReportedCwd(): ReportedCwd | null {
+    return this.reportedCwd ? { ...this.reportedCwd } : null;
+  }
+
+  /**
+   * Get the mouse reporting mode and encoding requested by the application
+   */
+  getMouseState(): { tracking: MouseTrackingMode; encoding: MouseEncoding } {
+    return {
+      tracking: this.terminal.modes.mouseTrackingMode,
+      encoding: this.mouseEncoding,
+    };
   }
 
   /**
@@ -479,7 +1865,9 @@ ${bannerCmd}
       throw new Error("Terminal session has been disposed");
     }
     this.terminal.resize(cols, rows);
-    this.ptyProcess.resize(cols, rows);
+    if (!this.exited) {
+      this.ptyProcess.resize(cols, rows);
+    }
 
     // Notify all resize listeners
     for (const listener of this.resizeListeners) {
@@ -491,7 +1879,7 @@ ${bannerCmd}
    * Check if the session is still active
    */
   isActive(): boolean {
-    return !this.disposed;
+    return !this.disposed || this.restarting;
   }
 
   /**
@@ -515,13 +1903,126 @@ ${bannerCmd}
     };
   }
 
+  /**
+   * End the shell the way closing a terminal window does: SIGHUP, which
+   * interactive shells pass on to their jobs, then SIGKILL to the shell's
+   * process group if it is still running after timeoutMs. Resolves once the
+   * shell has exited (or could not be killed) and the session is disposed.
+   */
+  async shutdown(timeoutMs = SHUTDOWN_GRACE_MS): Promise<void> {
+    await this.stopShell(timeoutMs);
+    await this.container?.remove();
+    this.dispose();
+  }
+
+  /**
+   * Disconnect from a --persist shell and dispose of the session, leaving
+   * the shell running for the next server. Returns false, doing nothing,
+   * for any other session.
+   */
+  detach(): boolean {
+    if (!(this.ptyProcess instanceof PersistBackend) || this.exited) return false;
+    this.ptyProcess.detach();
+    this.exited = true;
+    this.dispose();
+    return true;
+  }
+
+  private async stopShell(timeoutMs = SHUTDOWN_GRACE_MS): Promise<void> {
+    if (this.exited) return;
+    let unsubscribe = () => {};
+    const exited = new Promise<boolean>((resolve) => {
+      unsubscribe = this.onExit(() => resolve(true));
+    });
+    const timeout = (ms: number) =>
+      new Promise<boolean>((resolve) => setTimeout(() => resolve(false), ms).unref());
+
+    try {
+      this.signalShell("SIGHUP");
+      if (!(await Promise.race([exited, timeout(timeoutMs)]))) {
+        this.signalShell("SIGKILL");
+        await Promise.race([exited, timeout(1000)]);
+      }
+    } finally {
+      unsubscribe();
+    }
+  }
+
+  /**
+   * Start a new shell in this session, stopping the current one first if
+   * it is still running. The emulator, listeners, and session ID carry
+   * over. Scrollback is kept, with modes a crashed program left on (mouse
+   * tracking, alternate screen, hidden cursor) reset and a marker line
+   * written, unless clearScrollback is set.
+   */
+  async restart(options: { clearScrollback?: boolean } = {}): Promise<void> {
+    if (this.cleanedUp) {
+      throw new Error("Terminal session has been disposed");
+    }
+    if (this.restarting) {
+      throw new Error("The shell is already restarting");
+    }
+    this.restarting = true;
+    try {
+      await this.stopShell();
+      if (options.clearScrollback) {
+        this.terminal.reset();
+        this.wipeHistory();
+      } else {
+        // Leave the alternate screen, then DECSTR soft reset
+        await writeAsync(this.terminal, "\x1b[?1049l\x1b[!p\r\n[terminal-mcp] Shell restarted\r\n");
+      }
+      this.exited = false;
+      this.exitCode = null;
+      this.reportedCwd = null;
+      this.disposed = false;
+      await this.spawnShell();
+    } finally {
+      this.restarting = false;
+    }
+    await this.runInitCommands();
+  }
+
+  /**
+   * True between a shell exiting and its replacement starting in restart()
+   */
+  isRestarting(): boolean {
+    return this.restarting;
+  }
+
+  private signalShell(signal: NodeJS.Signals): void {
+    try {
+      this.ptyProcess.kill(signal);
+    } catch {
+      // Already gone, or signals unsupported (Windows)
+    }
+    if (signal === "SIGKILL" && this.ptyProcess.pid !== null) {
+      try {
+        // The shell leads its own process group; take its children with it
+        process.kill(-this.ptyProcess.pid, signal);
+      } catch {
+        // No such group
+      }
+    }
+  }
+
   /**
    * Dispose of the terminal session
    */
   dispose(): void {
-    if (!this.disposed) {
+    if (!this.cleanedUp) {
+      this.cleanedUp = true;
       this.disposed = true;
-      this.ptyProcess.kill();
+      this.stopHistoryTimer();
+      if (!this.exited) {
+        // Exiting without a shutdown() still leaves a persisted shell running
+        if (this.ptyProcess instanceof PersistBackend) {
+          this.ptyProcess.detach();
+        } else {
+          this.ptyProcess.kill();
+        }
+      }
+      this.hyperlinks.dispose();
       this.terminal.dispose();
 
       // Clean up temp rc files
@@ -539,6 +2040,11 @@ ${bannerCmd}
           // Ignore cleanup errors
         }
       }
+      void this.container?.remove();
+      const backend = this.spawnOptions.backend;
+      if (backend?.type === "tmux" && backend.ephemeral) {
+        killTmuxSession(backend);
+      }
     }
   }
 }
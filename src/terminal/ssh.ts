@@ -0,0 +1,263 @@
+/**
+ * SSH backend (--backend ssh --host user@server): the session's PTY is a
+ * pseudo-terminal on a remote host, opened with the ssh2 library, so every
+ * tool works on the remote shell exactly as on a local one.
+ */
+import { createHmac } from "crypto";
+import { createRequire } from "module";
+import * as fs from "fs";
+import * as os from "os";
+import * as path from "path";
+import type { Client, ClientChannel, ConnectConfig } from "ssh2";
+import {
+  BackendEmitter,
+  buildLaunchScript,
+  shellQuote,
+  type BackendDisposable,
+  type RemoteLaunch,
+  type SshTarget,
+  type TerminalBackend,
+} from "./backend.js";
+
+const require = createRequire(import.meta.url);
+
+const DEFAULT_SSH_PORT = 22;
+const CONNECT_TIMEOUT_MS = 15000;
+const DEFAULT_IDENTITY_FILES = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/**
+ * Parse [user@]host[:port]. The user defaults to the local one.
+ */
+export function parseSshTarget(value: string): Pick<SshTarget, "host" | "port" | "username"> {
+  const match = /^(?:([^@\s]+)@)?(\[[^\]]+\]|[^:@\s]+)(?::(\d+))?$/.exec(value.trim());
+  if (!match) {
+    throw new Error(`expected [user@]host[:port], got '${value}'`);
+  }
+  const port = match[3] ? parseInt(match[3], 10) : DEFAULT_SSH_PORT;
+  if (port < 1 || port > 65535) {
+    throw new Error(`invalid port in '${value}'`);
+  }
+  return {
+    username: match[1] ?? os.userInfo().username,
+    host: match[2].replace(/^\[|\]$/g, ""),
+    port,
+  };
+}
+
+/**
+ * The command line sent to the remote login shell. Everything runs under
+ * sh so it doesn't matter whether that shell is bash, zsh, or fish.
+ */
+export function buildRemoteCommand(launch: RemoteLaunch): string {
+  return `exec sh -c ${shellQuote(buildLaunchScript(launch))}`;
+}
+
+function globToRegExp(pattern: string): RegExp {
+  const source = pattern.replace(/[.+^${}()|[\]\\]/g, "\\$&").replace(/\*/g, ".*").replace(/\?/g, ".");
+  return new RegExp(`^${source}$`, "i");
+}
+
+function matchesKnownHost(field: string, names: string[]): boolean {
+  if (field.startsWith("|1|")) {
+    // Hashed entry: |1|base64(salt)|base64(HMAC-SHA1(salt, name))
+    const [, , salt, hash] = field.split("|");
+    return names.some(
+      (name) => createHmac("sha1", Buffer.from(salt, "base64")).update(name).digest("base64") === hash
+    );
+  }
+  let matched = false;
+  for (const pattern of field.split(",")) {
+    const negated = pattern.startsWith("!");
+    const regex = globToRegExp(negated ? pattern.slice(1) : pattern);
+    if (names.some((name) => regex.test(name))) {
+      if (negated) return false;
+      matched = true;
+    }
+  }
+  return matched;
+}
+
+/**
+ * Check a host key against known_hosts the way OpenSSH does. Returns why
+ * it isn't trusted, or null if it is.
+ */
+export function checkHostKey(key: Buffer, target: SshTarget): string | null {
+  const file = target.knownHostsFile ?? path.join(os.homedir(), ".ssh", "known_hosts");
+  const name = target.port === DEFAULT_SSH_PORT ? target.host : `[${target.host}]:${target.port}`;
+  let lines: string[];
+  try {
+    lines = fs.readFileSync(file, "utf-8").split("\n");
+  } catch {
+    return `${file} can't be read, so the host key of ${name} can't be checked; connect once with ssh to create it`;
+  }
+
+  let known = false;
+  for (const line of lines) {
+    const fields = line.trim().split(/\s+/);
+    if (!fields[0] || fields[0].startsWith("#")) continue;
+    const marker = fields[0].startsWith("@") ? fields.shift() : undefined;
+    if (marker === "@cert-authority" || fields.length < 3) continue;
+    if (!matchesKnownHost(fields[0], [name])) continue;
+    const matches = Buffer.from(fields[2], "base64").equals(key);
+    if (marker === "@revoked") {
+      if (matches) return `the host key of ${name} is marked revoked in ${file}`;
+      continue;
+    }
+    if (matches) return null;
+    known = true;
+  }
+  return known
+    ? `the host key of ${name} doesn't match the one in ${file}; someone may be intercepting the connection`
+    : `${name} isn't in ${file}; connect once with ssh to check and add its host key`;
+}
+
+function loadSsh2(): typeof import("ssh2") {
+  try {
+    return require("ssh2");
+  } catch {
+    throw new Error("--backend ssh requires the ssh2 package. Install it with: npm install ssh2");
+  }
+}
+
+function authConfig(target: SshTarget): Partial<ConnectConfig> {
+  const config: Partial<ConnectConfig> = {};
+  if (process.env.SSH_AUTH_SOCK) config.agent = process.env.SSH_AUTH_SOCK;
+  if (process.env.TERMINAL_MCP_SSH_PASSWORD) config.password = process.env.TERMINAL_MCP_SSH_PASSWORD;
+  if (process.env.TERMINAL_MCP_SSH_PASSPHRASE) config.passphrase = process.env.TERMINAL_MCP_SSH_PASSPHRASE;
+  const identityFile =
+    target.identityFile ??
+    DEFAULT_IDENTITY_FILES.map((name) => path.join(os.homedir(), ".ssh", name)).find((file) => fs.existsSync(file));
+  if (identityFile) {
+    try {
+      config.privateKey = fs.readFileSync(identityFile);
+    } catch (error) {
+      // Only a key the user named has to be readable
+      if (target.identityFile) {
+        throw new Error(`Cannot read identity file ${identityFile}: ${(error as Error).message}`);
+      }
+    }
+  }
+  return config;
+}
+
+/**
+ * A PTY on a remote host, running one RemoteLaunch over its own connection
+ */
+export class SshBackend implements TerminalBackend {
+  readonly pid = null;
+  private dataEmitter = new BackendEmitter<string>();
+  private exitEmitter = new BackendEmitter<{ exitCode: number; signal?: number }>();
+  private exited = false;
+  private exitCode: number | null = null;
+
+  private constructor(
+    private client: Client,
+    private channel: ClientChannel
+  ) {
+    channel.setEncoding("utf-8");
+    channel.on("data", (data: string) => this.dataEmitter.emit(data));
+    channel.stderr.on("data", (data: Buffer) => this.dataEmitter.emit(data.toString("utf-8")));
+    channel.on("exit", (code: number | null, signalName?: string) => {
+      const signal = signalName ? os.constants.signals[`SIG${signalName}` as NodeJS.Signals] : undefined;
+      this.exitCode = code ?? (signal !== undefined ? 128 + signal : 255);
+    });
+    channel.on("close", () => this.finish());
+    client.on("close", () => this.finish());
+  }
+
+  /**
+   * Connect, verify the host key against known_hosts, and start the
+   * launch on a new PTY of the given size
+   */
+  static connect(target: SshTarget, launch: RemoteLaunch, cols: number, rows: number): Promise<SshBackend> {
+    const { Client } = loadSsh2();
+    const client = new Client();
+    let hostKeyProblem: string | null = null;
+
+    return new Promise((resolve, reject) => {
+      const fail = (message: string) => {
+        client.end();
+        reject(new Error(message));
+      };
+      client.once("error", (error) => fail(hostKeyProblem ?? `SSH connection to ${target.host} failed: ${error.message}`));
+      client.once("ready", () => {
+        client.exec(
+          buildRemoteCommand(launch),
+          { pty: { term: "xterm-256color", cols, rows, width: 0, height: 0 } },
+          (error, channel) => {
+            if (error) {
+              fail(`Cannot start a shell on ${target.host}: ${error.message}`);
+              return;
+            }
+            resolve(new SshBackend(client, channel));
+          }
+        );
+      });
+      try {
+        client.connect({
+          host: target.host,
+          port: target.port,
+          username: target.username,
+          readyTimeout: CONNECT_TIMEOUT_MS,
+          keepaliveInterval: 30000,
+          hostVerifier: (key: Buffer) => {
+            hostKeyProblem = checkHostKey(key, target);
+            return hostKeyProblem === null;
+          },
+          ...authConfig(target),
+        });
+      } catch (error) {
+        // e.g. an encrypted key without TERMINAL_MCP_SSH_PASSPHRASE
+        fail(`SSH connection to ${target.host} failed: ${(error as Error).message}`);
+      }
+    });
+  }
+
+  private finish(): void {
+    if (this.exited) return;
+    this.exited = true;
+    this.client.end();
+    this.exitEmitter.emit({ exitCode: this.exitCode ?? 255 });
+  }
+
+  write(data: string): void {
+    if (!this.exited) this.channel.write(data);
+  }
+
+  resize(cols: number, rows: number): void {
+    if (!this.exited) this.channel.setWindow(rows, cols, 0, 0);
+  }
+
+  pause(): void {
+    this.channel.pause();
+    this.channel.stderr.pause();
+  }
+
+  resume(): void {
+    this.channel.resume();
+    this.channel.stderr.resume();
+  }
+
+  /**
+   * Signals other than SIGHUP and SIGKILL are forwarded (if the server
+   * allows it); those two, or none, close the connection, which hangs up
+   * the remote shell
+   */
+  kill(signal?: string): void {
+    if (this.exited) return;
+    if (signal && signal !== "SIGHUP" && signal !== "SIGKILL") {
+      this.channel.signal(signal.replace(/^SIG/, ""));
+      return;
+    }
+    this.channel.close();
+    this.client.end();
+  }
+
+  onData(listener: (data: string) => void): BackendDisposable {
+    return this.dataEmitter.on(listener);
+  }
+
+  onExit(listener: (event: { exitCode: number; signal?: number }) => void): BackendDisposable {
+    return this.exitEmitter.on(listener);
+  }
+}
@@ -0,0 +1,61 @@
+/**
+ * tmux backend (--backend tmux --tmux-session <name>): the session's PTY
+ * runs a tmux client attached to a named tmux session, creating it with
+ * our shell if it doesn't exist. Stopping the client only detaches, so the
+ * shell and everything running in it outlive the server, and the next
+ * server attaches to the same session.
+ */
+import { spawn } from "child_process";
+import type { TmuxTarget } from "./backend.js";
+
+/**
+ * Session names tmux accepts as a target without further quoting
+ */
+export function checkTmuxSessionName(name: string): string | null {
+  if (!name) return "the session name is empty";
+  if (/[:.]/.test(name)) return `tmux session names can't contain ':' or '.' ('${name}')`;
+  return null;
+}
+
+/**
+ * The command that attaches to target's session, or creates it running
+ * shell with env (tmux 3.0+ for -e). Options after -A only apply when the
+ * session is created.
+ */
+export function tmuxAttachCommand(
+  target: TmuxTarget,
+  shell: string,
+  args: string[],
+  env: Record<string, string>,
+  cwd?: string
+): { cmd: string; args: string[] } {
+  const envFlags = Object.entries(env).flatMap(([key, value]) => ["-e", `${key}=${value}`]);
+  return {
+    cmd: "tmux",
+    args: [
+      ...(target.socketName ? ["-L", target.socketName] : []),
+      "new-session",
+      "-A",
+      "-s",
+      target.session,
+      ...(cwd ? ["-c", cwd] : []),
+      ...envFlags,
+      "--",
+      shell,
+      ...args,
+    ],
+  };
+}
+
+/**
+ * End target's tmux session and everything in it, without waiting
+ */
+export function killTmuxSession(target: TmuxTarget): void {
+  const child = spawn(
+    "tmux",
+    [...(target.socketName ? ["-L", target.socketName] : []), "kill-session", "-t", `=${target.session}`],
+    { stdio: "ignore" }
+  );
+  child.on("error", () => {});
+  child.unref();
+}
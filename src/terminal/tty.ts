@@ -0,0 +1,17 @@
+/**
+ * Line discipline repair for a session's PTY. Programs that die in raw mode,
+ * or binary output a shell takes for stty-like sequences, can leave echo
+ * off and canonical mode disabled, which no escape sequence undoes.
+ */
+import { spawnSync } from "child_process";
+
+/**
+ * Run `stty sane` on the terminal at ptsPath: echo, canonical line editing,
+ * signal keys, and CR/NL translation back on. False if stty can't reach it.
+ */
+export function sttySane(ptsPath: string): boolean {
+  if (process.platform === "win32") return false;
+  const flag = process.platform === "linux" ? "-F" : "-f";
+  const result = spawnSync("stty", [flag, ptsPath, "sane"], { timeout: 1000 });
+  return result.status === 0;
+}
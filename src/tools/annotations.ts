@@ -0,0 +1,124 @@
+/**
+ * MCP tool annotations: hints that let clients decide, for example, to
+ * auto-approve tools that only read the terminal while still asking before
+ * anything is typed into it. Attached to tool definitions in tools/list.
+ */
+
+export interface ToolAnnotations {
+  /** Human-readable name for the tool */
+  title?: string;
+  /** The tool doesn't change the terminal or anything outside the server */
+  readOnlyHint?: boolean;
+  /** A non-read-only tool may destroy or overwrite state (ignored when readOnlyHint is true) */
+  destructiveHint?: boolean;
+  /** Repeating a call with the same arguments has no further effect (ignored when readOnlyHint is true) */
+  idempotentHint?: boolean;
+  /** The tool can reach the open world, e.g. by running arbitrary commands */
+  openWorldHint?: boolean;
+}
+
+// Reading the screen or server-side state only
+const READ_ONLY: ToolAnnotations = { readOnlyHint: true, openWorldHint: false };
+
+// Input reaches whatever program is running, so anything can happen
+const TERMINAL_INPUT: ToolAnnotations = {
+  readOnlyHint: false,
+  destructiveHint: true,
+  idempotentHint: false,
+  openWorldHint: true,
+};
+
+export const toolAnnotations: Record<string, ToolAnnotations> = {
+  type: { title: "Type Text", ...TERMINAL_INPUT },
+  secureType: { title: "Type Secret", ...TERMINAL_INPUT },
+  sendKey: { title: "Send Keys", ...TERMINAL_INPUT },
+  sendMouse: { title: "Send Mouse Event", ...TERMINAL_INPUT },
+  getContent: { title: "Get Terminal Content", ...READ_ONLY },
+  takeScreenshot: { title: "Take Screenshot", ...READ_ONLY },
+  search: { title: "Search Buffer", ...READ_ONLY },
+  getLinks: { title: "Get Hyperlinks", ...READ_ONLY },
+  getClipboard: { title: "Get Clipboard", ...READ_ONLY },
+  getStatus: { title: "Get Status", ...READ_ONLY },
+  getCursor: { title: "Get Cursor", ...READ_ONLY },
+  describeScreen: { title: "Describe Screen", ...READ_ONLY },
+  getSelection: { title: "Get Selection", ...READ_ONLY },
+  getForegroundProcess: { title: "Get Foreground Process", ...READ_ONLY },
+  isIdle: { title: "Is Idle", ...READ_ONLY },
+  waitForExit: { title: "Wait For Exit", ...READ_ONLY },
+  getTitle: { title: "Get Window Title", ...READ_ONLY },
+  getEnvironment: { title: "Get Environment", ...READ_ONLY },
+  diffScreen: { title: "Diff Screen", ...READ_ONLY },
+  listSnapshots: { title: "List Snapshots", ...READ_ONLY },
+  getSnapshot: { title: "Get Snapshot", ...READ_ONLY },
+  listSessions: { title: "List Sessions", ...READ_ONLY },
+  testPromptPattern: { title: "Test Prompt Pattern", ...READ_ONLY },
+  // Runs '<command> --help' or man in a hidden process, never the shell
+  lookupHelp: { title: "Look Up Help", ...READ_ONLY },
+  subscribeOutput: { title: "Subscribe to Output", ...READ_ONLY },
+  unsubscribeOutput: { title: "Unsubscribe from Output", ...READ_ONLY },
+  // Only server-side bookkeeping; nothing reaches the shell
+  mark: {
+    title: "Mark Output",
+    readOnlyHint: false,
+    destructiveHint: false,
+    idempotentHint: false,
+    openWorldHint: false,
+  },
+  // Wipes the emulator's screen and history, never the shell
+  clear: {
+    title: "Clear Screen",
+    readOnlyHint: false,
+    destructiveHint: true,
+    idempotentHint: true,
+    openWorldHint: false,
+  },
+  reset: {
+    title: "Reset Terminal",
+    readOnlyHint: false,
+    destructiveHint: true,
+    idempotentHint: true,
+    openWorldHint: false,
+  },
+  startRecording: {
+    title: "Start Recording",
+    readOnlyHint: false,
+    destructiveHint: false,
+    idempotentHint: false,
+    openWorldHint: false,
+  },
+  stopRecording: {
+    title: "Stop Recording",
+    readOnlyHint: false,
+    destructiveHint: false,
+    idempotentHint: true,
+    openWorldHint: false,
+  },
+  createSession: {
+    title: "Create Session",
+    readOnlyHint: false,
+    destructiveHint: false,
+    idempotentHint: false,
+    openWorldHint: true,
+  },
+  restartShell: {
+    title: "Restart Shell",
+    readOnlyHint: false,
+    destructiveHint: true,
+    idempotentHint: false,
+    openWorldHint: false,
+  },
+  destroySession: {
+    title: "Destroy Session",
+    readOnlyHint: false,
+    destructiveHint: true,
+    idempotentHint: true,
+    openWorldHint: false,
+  },
+};
+
+/**
+ * Attach annotations to tool definitions
+ */
+export function withAnnotations<T extends { name: string }>(tools: T[]): Array<T & { annotations?: ToolAnnotations }> {
+  return tools.map((tool) => ({ ...tool, annotations: toolAnnotations[tool.name] }));
+}
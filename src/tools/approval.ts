@@ -0,0 +1,76 @@
+import type { Server } from "@modelcontextprotocol/sdk/server/index.js";
+import type { TerminalManager, DangerousCommand } from "../terminal/index.js";
+
+/**
+ * Human approval for tool input that would run a dangerous command. Where
+ * the client supports MCP elicitation, the human is asked to approve or
+ * deny; otherwise the input is rejected outright.
+ */
+
+export type ApprovalOutcome = "approved" | "denied" | "unavailable";
+
+export interface ApprovalRequest extends DangerousCommand {
+  sessionId?: string;
+}
+
+export type InputApprover = (request: ApprovalRequest) => Promise<ApprovalOutcome>;
+
+// The human may be away from the keyboard; give up (and deny) after this
+const APPROVAL_TIMEOUT_MS = 5 * 60 * 1000;
+
+/**
+ * Ask through elicitation/create on the given server's client
+ */
+export function createElicitationApprover(server: Server): InputApprover {
+  return async (request) => {
+    if (!server.getClientCapabilities()?.elicitation) return "unavailable";
+    try {
+      const result = await server.elicitInput(
+        {
+          message:
+            `The AI wants to run a command flagged as ${request.reason}` +
+            `${request.sessionId ? ` in session '${request.sessionId}'` : ""}:\n\n${request.command}`,
+          requestedSchema: {
+            type: "object",
+            properties: {
+              approve: {
+                type: "boolean",
+                title: "Run this command",
+                description: "Approve sending this input to the terminal",
+              },
+            },
+            required: ["approve"],
+          },
+        },
+        { timeout: APPROVAL_TIMEOUT_MS }
+      );
+      return result.action === "accept" && result.content?.approve === true ? "approved" : "denied";
+    } catch {
+      return "denied";
+    }
+  };
+}
+
+/**
+ * Throw unless `data` is safe to write or a human approved the dangerous
+ * command it would submit. Rejections are reported as policy violations.
+ */
+export async function confirmInput(
+  manager: TerminalManager,
+  data: string,
+  sessionId: string | undefined,
+  approve?: InputApprover
+): Promise<void> {
+  const dangerous = manager.findDangerousInput(data, sessionId);
+  if (!dangerous) return;
+
+  const outcome = approve ? await approve({ ...dangerous, sessionId }) : "unavailable";
+  if (outcome === "approved") return;
+
+  const message = outcome === "denied"
+    ? `The user denied running '${dangerous.command}' (${dangerous.reason})`
+    : `Input not sent: '${dangerous.command}' needs a human's approval (${dangerous.reason}), ` +
+      "and this client can't ask for it. Ask the user to run it themselves.";
+  manager.reportPolicyViolation(message, sessionId, { ...dangerous, outcome });
+  throw new Error(message);
+}
@@ -0,0 +1,63 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const clearSchema = z.object({
+  scrollback: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Also empty the scrollback and forget snapshot history, delta generations, and marks"),
+  hard: z.boolean().optional().default(false).describe("Full terminal reset (RIS) instead of a clear; implies scrollback"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type ClearArgs = z.infer<typeof clearSchema>;
+
+export const clearTool = {
+  name: "clear",
+  description: "Clear the screen so later reads start fresh. Nothing is sent to the shell: the emulator's screen is wiped and the cursor's line (the prompt and anything typed after it) is redrawn on the top row. Typing 'clear' only clears the visible screen, so old output still turns up in getContent, search, and snapshots; set scrollback=true to also empty the scrollback (ESC[3J) and forget the snapshot history, getContent delta generations, marks, and output a --persist shell would replay. Set hard=true for a full terminal reset (RIS, ESC c) instead, which also undoes modes a program left on. A full-screen program using the alternate screen would redraw over a clear, so only hard works then. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      scrollback: {
+        type: "boolean",
+        description: "Also empty the scrollback and forget snapshot history, delta generations, marks, and persisted replay (default: false)",
+        default: false,
+      },
+      hard: {
+        type: "boolean",
+        description: "Full terminal reset (RIS) instead of a clear; implies scrollback (default: false)",
+        default: false,
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export async function handleClear(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = clearSchema.parse(args ?? {});
+  // Output already received lands before the clear, not after it
+  await manager.waitForReadable(parsed.sessionId);
+  await manager.clear(parsed.sessionId, { scrollback: parsed.scrollback, hard: parsed.hard });
+
+  const text = parsed.hard
+    ? "Reset the terminal and cleared the scrollback"
+    : parsed.scrollback
+      ? "Cleared the screen and scrollback"
+      : "Cleared the screen";
+  return {
+    content: [
+      {
+        type: "text",
+        text,
+      },
+    ],
+  };
+}
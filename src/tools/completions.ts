@@ -0,0 +1,85 @@
+/**
+ * completion/complete: argument value suggestions for MCP inspectors and
+ * other interactive clients.
+ *
+ * Besides the standard prompt and resource template references, tool
+ * arguments can be completed with the non-standard { type: "ref/tool",
+ * name } reference: session IDs, sendKey key names, and any argument whose
+ * input schema lists an enum (screenshot formats, themes, ...).
+ */
+
+import { z } from "zod";
+import type { TerminalManager } from "../terminal/index.js";
+import { getAvailableKeys } from "../utils/keys.js";
+
+// The MCP spec caps a completion response at 100 values
+const MAX_COMPLETIONS = 100;
+
+export const CompleteRequestSchema = z.object({
+  method: z.literal("completion/complete"),
+  params: z
+    .object({
+      ref: z.union([
+        z.object({ type: z.literal("ref/prompt"), name: z.string() }).passthrough(),
+        z.object({ type: z.literal("ref/resource"), uri: z.string() }).passthrough(),
+        z.object({ type: z.literal("ref/tool"), name: z.string() }).passthrough(),
+      ]),
+      argument: z.object({ name: z.string(), value: z.string() }).passthrough(),
+    })
+    .passthrough(),
+});
+
+export type CompleteParams = z.infer<typeof CompleteRequestSchema>["params"];
+
+export interface CompletionResult {
+  completion: {
+    values: string[];
+    total: number;
+    hasMore: boolean;
+  };
+}
+
+interface CompletableTool {
+  name: string;
+  inputSchema: { properties: Record<string, unknown> };
+}
+
+function candidates(
+  manager: TerminalManager,
+  tools: CompletableTool[],
+  ref: CompleteParams["ref"],
+  argument: string
+): string[] {
+  if (argument === "sessionId") {
+    return manager.listSessions().sessions.map((session) => session.sessionId);
+  }
+  if (ref.type !== "ref/tool") return [];
+
+  if (ref.name === "sendKey" && (argument === "key" || argument === "keys")) {
+    return getAvailableKeys();
+  }
+  const tool = tools.find((t) => t.name === ref.name);
+  const property = tool?.inputSchema.properties[argument] as { enum?: unknown[] } | undefined;
+  return (property?.enum ?? []).map(String);
+}
+
+/**
+ * Values for one argument that start with what has been typed so far
+ * (case-insensitive)
+ */
+export function completeArgument(
+  manager: TerminalManager,
+  tools: CompletableTool[],
+  params: CompleteParams
+): CompletionResult {
+  const prefix = params.argument.value.toLowerCase();
+  const matches = candidates(manager, tools, params.ref, params.argument.name)
+    .filter((value) => value.toLowerCase().startsWith(prefix));
+  return {
+    completion: {
+      values: matches.slice(0, MAX_COMPLETIONS),
+      total: matches.length,
+      hasMore: matches.length > MAX_COMPLETIONS,
+    },
+  };
+}
@@ -2,6 +2,34 @@
  * Shared tool definitions used by both MCP client and UI
  */
 
+import type { ToolExample } from "./examples.js";
+import type { ToolAnnotations } from "./annotations.js";
+import { testPromptPatternTool } from "./testPromptPattern.js";
+import { sendMouseTool } from "./sendMouse.js";
+import { getStatusTool } from "./getStatus.js";
+import { lookupHelpTool } from "./lookupHelp.js";
+import { getTitleTool } from "./getTitle.js";
+import { diffScreenTool } from "./diffScreen.js";
+import { listSnapshotsTool } from "./listSnapshots.js";
+import { getSnapshotTool } from "./getSnapshot.js";
+import { getEnvironmentTool } from "./getEnvironment.js";
+import { getCursorTool } from "./getCursor.js";
+import { describeScreenTool } from "./describeScreen.js";
+import { getSelectionTool } from "./getSelection.js";
+import { markTool } from "./mark.js";
+import { clearTool } from "./clear.js";
+import { resetTool } from "./reset.js";
+import { searchTool } from "./search.js";
+import { getLinksTool } from "./getLinks.js";
+import { getClipboardTool } from "./getClipboard.js";
+import { subscribeOutputTool } from "./subscribeOutput.js";
+import { unsubscribeOutputTool } from "./unsubscribeOutput.js";
+import { restartShellTool } from "./restartShell.js";
+import { getForegroundProcessTool } from "./getForegroundProcess.js";
+import { isIdleTool } from "./isIdle.js";
+import { waitForExitTool } from "./waitForExit.js";
+import { secureTypeTool } from "./secureType.js";
+
 export interface ToolDefinition {
   name: string;
   description: string;
@@ -10,6 +38,16 @@ export interface ToolDefinition {
     properties: Record<string, unknown>;
     required?: string[];
   };
+  /** JSON Schema of the structuredContent the tool returns, for tools that always return one */
+  outputSchema?: {
+    type: "object";
+    properties: Record<string, unknown>;
+    required?: string[];
+  };
+  /** Worked argument/result pairs; see examples.ts */
+  examples?: ToolExample[];
+  /** Read-only/destructive hints; see annotations.ts */
+  annotations?: ToolAnnotations;
 }
 
 export const toolDefinitions: ToolDefinition[] = [
@@ -38,8 +76,20 @@ export const toolDefinitions: ToolDefinition[] = [
           description:
             "The key to send (e.g., enter, tab, escape, up, down, left, right, ctrl+c, ctrl+d)",
         },
+        keys: {
+          type: "array",
+          items: { type: "string" },
+          description: "Sequence of keys to send in order, instead of a single key",
+        },
+        count: {
+          type: "number",
+          description: "Number of times to send the key (or the whole keys sequence). Default: 1",
+        },
+        delayMs: {
+          type: "number",
+          description: "Delay in milliseconds between key presses. Default: 0",
+        },
       },
-      required: ["key"],
     },
   },
   {
@@ -52,21 +102,136 @@ export const toolDefinitions: ToolDefinition[] = [
           type: "boolean",
           description: "If true, only return visible content (default: false)",
         },
+        codeFence: {
+          type: "boolean",
+          description: "Wrap output in a Markdown code fence with a language inferred from the last command (default: false)",
+        },
+        language: {
+          type: "string",
+          description: "Code fence language tag, overriding inference",
+        },
+        width: {
+          type: "number",
+          description: "Rewrap long lines to this many columns without resizing the terminal",
+        },
+        unwrap: {
+          type: "boolean",
+          description: "Rejoin soft-wrapped rows into logical lines (default: false)",
+        },
+        since: {
+          type: "number",
+          description: "Delta mode: only lines changed since this generation (0 to start)",
+        },
+        format: {
+          type: "string",
+          enum: ["plain", "compact"],
+          description: "'compact' folds blank runs into '[N blank lines]' and trims a shared left margin (default: 'plain')",
+        },
+        sinceMark: {
+          type: "string",
+          description: "Only return lines from this mark (see mark) on",
+        },
+        filter: {
+          type: "string",
+          description: "Only return lines matching this regex",
+        },
+        filterFlags: {
+          type: "string",
+          description: "Regex flags for filter, e.g. 'i'",
+        },
+        context: {
+          type: "number",
+          description: "Lines of context around each filter match (default: 0)",
+        },
+        lineNumbers: {
+          type: "boolean",
+          description: "Prefix each line with its screen row or buffer line number (default: false)",
+        },
+        tables: {
+          type: "boolean",
+          description: "Rewrite box-drawn, barred, and column-aligned tables as Markdown tables (default: false)",
+        },
+        normalize: {
+          type: "boolean",
+          description: "Replace values that change from run to run with placeholders (default: false): ISO and date-style timestamps (<TIMESTAMP>), dates (<DATE>), times of day (<TIME>), durations like 1.2s, 450ms, 1m30s, or 0m0.004s (<DURATION>), and PIDs after 'pid', in 'name[1234]:', and in job notices (<PID>). Cannot be combined with includeCursor.",
+        },
+        replacements: {
+          type: "array",
+          items: {
+            type: "object",
+            properties: {
+              pattern: { type: "string", description: "Regex (JavaScript syntax) to replace on each line" },
+              replacement: { type: "string", description: "Replacement text; $1 etc. refer to groups (default: '')" },
+              flags: { type: "string", description: "Regex flags, e.g. 'i' (g is implied)" },
+            },
+            required: ["pattern"],
+          },
+          description: "Regex replacements for other variable output, e.g. [{\"pattern\": \"[0-9a-f]{12}\", \"replacement\": \"<ID>\"}] for container IDs. Applied to each line in order, after normalize's rules (max 50). Cannot be combined with includeCursor.",
+        },
+        maxLines: {
+          type: "number",
+          description: "Return at most this many lines, the last ones",
+        },
+        maxBytes: {
+          type: "number",
+          description: "Return at most this many bytes, the last ones (default: 262144)",
+        },
+        pageToken: {
+          type: "string",
+          description: "pageToken from a truncated result, to read the lines before it",
+        },
+        includeCursor: {
+          type: "boolean",
+          description: "Insert a marker at the cursor and report its line/column (default: false)",
+        },
+        cursorMarker: {
+          type: "string",
+          description: "Cursor marker text (default: '▮'); implies includeCursor",
+        },
+        startRow: {
+          type: "number",
+          description: "Crop: first screen row (0-indexed)",
+        },
+        endRow: {
+          type: "number",
+          description: "Crop: last screen row, inclusive",
+        },
+        startCol: {
+          type: "number",
+          description: "Crop: first column (0-indexed)",
+        },
+        endCol: {
+          type: "number",
+          description: "Crop: last column, inclusive",
+        },
       },
     },
   },
   {
     name: "takeScreenshot",
     description:
-      "Take a screenshot of the terminal. Format 'text' (default) returns plain JSON. Format 'ansi' returns JSON with ANSI color escape codes preserved. Format 'png' returns a color screenshot image.",
+      "Take a screenshot of the terminal. Format 'text' (default) returns plain JSON. Format 'compact' returns token-lean plain text. Format 'ansi' returns JSON with ANSI color escape codes preserved. Format 'json' returns per-row styled runs. Format 'html' returns a self-contained HTML snippet. Format 'svg' returns an SVG document. Format 'png' returns a color screenshot image.",
     inputSchema: {
       type: "object",
       properties: {
         format: {
           type: "string",
-          enum: ["text", "ansi", "png"],
+          enum: ["text", "compact", "ansi", "json", "html", "svg", "png"],
           description:
-            "Output format: 'text' (default) plain JSON, 'ansi' for colored text with ANSI codes, 'png' for color screenshot image",
+            "Output format: 'text' (default) plain JSON, 'compact' for plain text with blank runs folded, 'ansi' for colored text with ANSI codes, 'json' for per-row styled runs, 'html' for an HTML snippet, 'svg' for a scalable vector image, 'png' for color screenshot image",
+        },
+        theme: {
+          type: "string",
+          enum: ["one-dark", "one-light", "high-contrast", "colorblind"],
+          description: "Color theme for rendered formats (html, svg, png): 'one-dark' (default), 'one-light', 'high-contrast', or 'colorblind'",
+        },
+        width: {
+          type: "number",
+          description: "Render at this many columns, rewrapping long lines, without resizing the terminal",
+        },
+        lineNumbers: {
+          type: "boolean",
+          description: "Prefix each row of the text and compact formats with its screen row (default: false)",
         },
       },
     },
@@ -109,6 +274,11 @@ export const toolDefinitions: ToolDefinition[] = [
           description:
             "Stop recording after N seconds of no terminal output (default: 600 = 10 minutes). Resets on each output event.",
         },
+        captureInput: {
+          type: "boolean",
+          description:
+            "Also record input (typed text and keys) as asciicast \"i\" events (default: false).",
+        },
       },
     },
   },
@@ -127,6 +297,31 @@ export const toolDefinitions: ToolDefinition[] = [
       required: ["recordingId"],
     },
   },
+  testPromptPatternTool,
+  sendMouseTool,
+  getStatusTool,
+  lookupHelpTool,
+  getTitleTool,
+  diffScreenTool,
+  listSnapshotsTool,
+  getSnapshotTool,
+  getEnvironmentTool,
+  getCursorTool,
+  describeScreenTool,
+  getSelectionTool,
+  markTool,
+  clearTool,
+  resetTool,
+  searchTool,
+  getLinksTool,
+  getClipboardTool,
+  subscribeOutputTool,
+  unsubscribeOutputTool,
+  restartShellTool,
+  getForegroundProcessTool,
+  isIdleTool,
+  waitForExitTool,
+  secureTypeTool,
 ];
 
 /**
@@ -0,0 +1,53 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+import { describeScreen } from "../utils/describe.js";
+
+export const describeScreenSchema = z.object({
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type DescribeScreenArgs = z.infer<typeof describeScreenSchema>;
+
+export const describeScreenTool = {
+  name: "describeScreen",
+  description: "Summarize what is on screen to decide the next action, using the color and attribute data a plain text dump drops: the prompt line (and whether the cursor sits on it), stretches drawn in inverse video or on a distinct background (the selection in menus, fzf, and file pickers), lists built around the selected row with its index, progress bars and percentages, and dialog buttons like '< OK >' or '[ Cancel ]' with the highlighted one flagged. Rows and columns are 0-indexed screen positions. Findings are heuristic; confirm with getContent before acting on one that matters. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export async function handleDescribeScreen(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }>; structuredContent: Record<string, unknown> }> {
+  const parsed = describeScreenSchema.parse(args ?? {});
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const terminal = session.getTerminal();
+  const buffer = terminal.buffer.active;
+
+  const result = {
+    title: session.getTitle(),
+    dimensions: { cols: terminal.cols, rows: terminal.rows },
+    cursor: { row: buffer.cursorY, col: buffer.cursorX, visible: session.isCursorVisible() },
+    alternateScreen: buffer.type === "alternate",
+    ...describeScreen(terminal, session.getPromptPattern()),
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+    structuredContent: result,
+  };
+}
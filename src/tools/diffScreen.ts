@@ -0,0 +1,138 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+import { diffScreens, formatUnifiedDiff } from "../utils/screenDiff.js";
+
+export const diffScreenSchema = z.object({
+  action: z
+    .enum(["snapshot", "diff"])
+    .optional()
+    .default("diff")
+    .describe("'snapshot' stores the current screen; 'diff' (default) compares the current screen against the stored one"),
+  name: z
+    .string()
+    .min(1)
+    .max(64)
+    .optional()
+    .default("default")
+    .describe("Snapshot name, to keep several baselines (default: 'default')"),
+  format: z
+    .enum(["regions", "unified"])
+    .optional()
+    .default("regions")
+    .describe("'regions' (default) returns changed row ranges as JSON; 'unified' returns a unified diff"),
+  update: z
+    .boolean()
+    .optional()
+    .default(true)
+    .describe("After diffing, replace the snapshot with the current screen so the next diff shows only newer changes (default: true)"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type DiffScreenArgs = z.infer<typeof diffScreenSchema>;
+
+export const diffScreenTool = {
+  name: "diffScreen",
+  description: "Compare the visible screen against a stored snapshot and return only the rows that changed. Call with action 'snapshot' to store a baseline, then action 'diff' to get changed regions (or a unified diff). By default each diff advances the baseline, so repeated calls while polling a slowly-updating TUI or a long build return only what's new. If no snapshot exists yet, diff stores one and returns the full screen. Much cheaper than re-reading the whole screen with getContent.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      action: {
+        type: "string",
+        enum: ["snapshot", "diff"],
+        description: "'snapshot' stores the current screen; 'diff' (default) compares the current screen against the stored one",
+      },
+      name: {
+        type: "string",
+        description: "Snapshot name, to keep several baselines (default: 'default')",
+      },
+      format: {
+        type: "string",
+        enum: ["regions", "unified"],
+        description: "'regions' (default) returns changed row ranges as JSON; 'unified' returns a unified diff",
+      },
+      update: {
+        type: "boolean",
+        description: "After diffing, replace the snapshot with the current screen (default: true)",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export async function handleDiffScreen(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = diffScreenSchema.parse(args ?? {});
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const current = session.captureSnapshot();
+  const previous = session.getSnapshot(parsed.name);
+
+  if (parsed.action === "snapshot" || !previous) {
+    session.saveSnapshot(parsed.name, current);
+    const result: Record<string, unknown> = {
+      snapshot: parsed.name,
+      takenAt: current.takenAt,
+      rows: current.rows.length,
+    };
+    // A diff with nothing to compare against returns the baseline itself
+    if (parsed.action === "diff") {
+      result.message = "No snapshot existed; stored the current screen as the baseline";
+      result.content = current.rows.join("\n").replace(/\n+$/, "");
+    }
+    return {
+      content: [
+        {
+          type: "text",
+          text: JSON.stringify(result, null, 2),
+        },
+      ],
+    };
+  }
+
+  const diff = diffScreens(previous, current);
+  if (parsed.update) {
+    session.saveSnapshot(parsed.name, current);
+  }
+
+  if (parsed.format === "unified") {
+    const text = diff.changed
+      ? [
+          formatUnifiedDiff(diff, `${parsed.name} ${previous.takenAt}`, `current ${current.takenAt}`),
+          diff.cursorMoved ? `cursor: (${previous.cursor.x},${previous.cursor.y}) -> (${current.cursor.x},${current.cursor.y})` : "",
+          diff.resized ? `resized: ${previous.cols}x${previous.rows.length} -> ${current.cols}x${current.rows.length}` : "",
+        ].filter(Boolean).join("\n")
+      : "No changes";
+    return {
+      content: [
+        {
+          type: "text",
+          text,
+        },
+      ],
+    };
+  }
+
+  const result = {
+    changed: diff.changed,
+    changedRows: diff.changedRows,
+    since: previous.takenAt,
+    regions: diff.regions.map(({ startRow, endRow, after }) => ({ startRow, endRow, lines: after })),
+    cursor: current.cursor,
+    ...(diff.cursorMoved && { previousCursor: previous.cursor }),
+    ...(diff.resized && { dimensions: { cols: current.cols, rows: current.rows.length } }),
+  };
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+  };
+}
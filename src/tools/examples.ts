@@ -0,0 +1,427 @@
+/**
+ * Worked examples for each tool: argument/result pairs captured from real
+ * calls against a bash session (120x40). Served via the `tools/examples`
+ * extension method and attached to tool definitions.
+ */
+
+import { z } from "zod";
+
+export interface ToolExample {
+  /** What the example demonstrates */
+  description: string;
+  arguments: Record<string, unknown>;
+  /** Text of the first content block returned */
+  result: string;
+}
+
+export const toolExamples: Record<string, ToolExample[]> = {
+  type: [
+    {
+      description: "Type a command (Enter is not sent automatically)",
+      arguments: { text: "ls -la" },
+      result: "Typed 6 character(s) to terminal",
+    },
+  ],
+  secureType: [
+    {
+      description: "Enter the sudo password the user gave you and submit it",
+      arguments: { text: "correct horse battery staple", submit: true },
+      result: "Typed 28 secret character(s) to terminal and pressed Enter",
+    },
+  ],
+  sendKey: [
+    {
+      description: "Execute the typed command",
+      arguments: { key: "Enter" },
+      result: "Sent key: Enter",
+    },
+    {
+      description: "Move three items down a menu and select",
+      arguments: { keys: ["ArrowDown", "ArrowDown", "ArrowDown", "Enter"] },
+      result: "Sent keys: ArrowDown, ArrowDown, ArrowDown, Enter",
+    },
+    {
+      description: "Interrupt a running command",
+      arguments: { key: "Ctrl+C" },
+      result: "Sent key: Ctrl+C",
+    },
+  ],
+  sendMouse: [
+    {
+      description: "Click a row in htop (mouse reporting enabled by the app)",
+      arguments: { action: "click", row: 5, col: 12 },
+      result: "Sent mouse click at row 5, col 12 (2 event(s), vt200 tracking, sgr encoding)",
+    },
+  ],
+  getContent: [
+    {
+      description: "Read only what's on screen after running a command",
+      arguments: { visibleOnly: true },
+      result: "⚡ mcp  user@host:~/project$ echo hello\nhello\n⚡ mcp  user@host:~/project$",
+    },
+  ],
+  takeScreenshot: [
+    {
+      description: "Check cursor position and whether a full-screen app is running",
+      arguments: { format: "text" },
+      result: JSON.stringify({
+        content: "⚡ mcp  user@host:~$",
+        cursor: { x: 21, y: 0, style: { shape: "block", blink: false } },
+        dimensions: { cols: 120, rows: 40 },
+        modes: {
+          alternateScreen: false,
+          applicationCursorKeys: false,
+          applicationKeypad: false,
+          bracketedPaste: true,
+          mouseTracking: "none",
+        },
+      }, null, 2),
+    },
+  ],
+  getStatus: [
+    {
+      description: "Find out whether vim is still open before sending keys",
+      arguments: {},
+      result: JSON.stringify({
+        sessionId: "3029d",
+        isDefault: true,
+        shell: "/bin/bash",
+        title: "[terminal-mcp] ~/project",
+        dimensions: { cols: 120, rows: 40 },
+        cursor: { x: 0, y: 12, style: { shape: "block", blink: true } },
+        modes: {
+          alternateScreen: true,
+          applicationCursorKeys: true,
+          applicationKeypad: false,
+          bracketedPaste: true,
+          mouseTracking: "none",
+        },
+        atPrompt: false,
+        cwd: { path: "/home/user/project", source: "osc7" },
+        secretPrompt: { detected: false, echoOff: false, prompt: null },
+        controller: "shared",
+      }, null, 2),
+    },
+  ],
+  diffScreen: [
+    {
+      description: "Poll a build: only rows that changed since the last call",
+      arguments: {},
+      result: JSON.stringify({
+        changed: true,
+        changedRows: 2,
+        since: "2026-04-25T12:58:01.072Z",
+        regions: [
+          { startRow: 14, endRow: 15, lines: ["[ 87%] Building CXX object src/CMakeFiles/app.dir/render.cpp.o", "[ 93%] Linking CXX executable app"] },
+        ],
+        cursor: { x: 0, y: 16 },
+        previousCursor: { x: 0, y: 14 },
+      }, null, 2),
+    },
+  ],
+  listSnapshots: [
+    {
+      description: "Find the error a build printed before the script cleared the screen",
+      arguments: { limit: 3 },
+      result: JSON.stringify({
+        total: 14,
+        snapshots: [
+          { n: 1, id: 31, takenAt: "2026-04-25T13:02:44.510Z", trigger: "tool", tool: "getContent", preview: "⚡ mcp  ~/project $" },
+          { n: 2, id: 30, takenAt: "2026-04-25T13:02:41.093Z", trigger: "clear", preview: "error: linking with `cc` failed: exit status: 1" },
+          { n: 3, id: 29, takenAt: "2026-04-25T13:02:30.877Z", trigger: "tool", tool: "sendKey", preview: "⚡ mcp  ~/project $ ./build.sh" },
+        ],
+      }, null, 2),
+    },
+  ],
+  getSnapshot: [
+    {
+      description: "Read the screen as it was just before it was cleared",
+      arguments: { id: 30 },
+      result: JSON.stringify({
+        id: 30,
+        takenAt: "2026-04-25T13:02:41.093Z",
+        trigger: "clear",
+        cursor: { x: 0, y: 6 },
+        content: "   Compiling app v0.3.1 (/home/me/project)\nerror: linking with `cc` failed: exit status: 1\n  = note: /usr/bin/ld: cannot find -lssl",
+      }, null, 2),
+    },
+  ],
+  search: [
+    {
+      description: "Find the most recent error in a long build log",
+      arguments: { pattern: "error", flags: "i", context: 1, maxMatches: 1 },
+      result: JSON.stringify({
+        pattern: "error",
+        flags: "i",
+        linesSearched: 612,
+        totalMatches: 3,
+        matches: [
+          {
+            line: 588,
+            screenRow: 16,
+            index: 0,
+            match: "error",
+            text: "error[E0308]: mismatched types",
+            before: ["   Compiling app v0.3.1 (/home/me/project)"],
+            after: ["  --> src/main.rs:14:5"],
+          },
+        ],
+      }, null, 2),
+    },
+  ],
+  getLinks: [
+    {
+      description: "Find the pull request URL that gh printed as a link",
+      arguments: { visibleOnly: true },
+      result: JSON.stringify({
+        links: [
+          {
+            uri: "https://github.com/acme/app/pull/42",
+            text: "https://github.com/acme/app/pull/42",
+            start: { line: 310, col: 0 },
+            end: { line: 310, col: 35 },
+            screenRow: 12,
+          },
+        ],
+      }, null, 2),
+    },
+  ],
+  getClipboard: [
+    {
+      description: "Read the lines just yanked in neovim",
+      arguments: {},
+      result: JSON.stringify({
+        selection: "c",
+        text: "fn main() {\n    println!(\"hello\");\n}\n",
+        copiedAt: "2026-05-02T09:14:03.511Z",
+      }, null, 2),
+    },
+  ],
+  subscribeOutput: [
+    {
+      description: "Watch a long build without polling; each chunk arrives as a notification",
+      arguments: { debounceMs: 500 },
+      result: JSON.stringify({
+        subscribed: "default",
+        notification: "notifications/terminal/output",
+        subscriptions: ["default"],
+      }, null, 2),
+    },
+  ],
+  unsubscribeOutput: [
+    {
+      description: "Stop streaming once the build has finished",
+      arguments: {},
+      result: JSON.stringify({ unsubscribed: ["default"], subscriptions: [] }, null, 2),
+    },
+  ],
+  restartShell: [
+    {
+      description: "Bring back the default session after the shell was exited by mistake",
+      arguments: {},
+      result: JSON.stringify({ sessionId: "a41f2", shell: "/bin/bash", pid: 48213, clearedScrollback: false }, null, 2),
+    },
+  ],
+  getCursor: [
+    {
+      description: "Check where typing will land inside vim",
+      arguments: {},
+      result: JSON.stringify({
+        row: 12,
+        col: 4,
+        visible: true,
+        style: { shape: "block", blink: false },
+        alternateScreen: true,
+      }, null, 2),
+    },
+  ],
+  describeScreen: [
+    {
+      description: "Find out which menu item is selected before pressing arrow keys",
+      arguments: {},
+      result: JSON.stringify({
+        title: "htop",
+        dimensions: { cols: 80, rows: 24 },
+        cursor: { row: 23, col: 0, visible: false },
+        alternateScreen: true,
+        prompt: null,
+        highlighted: [{ row: 4, col: 2, text: "Display options", by: "inverse" }],
+        lists: [{ startRow: 2, endRow: 6, items: ["Meters", "Display options", "Colors", "Columns", "Screens"], selected: 1 }],
+        progress: [],
+        buttons: [{ row: 22, col: 30, label: "Done", highlighted: false }],
+      }, null, 2),
+    },
+  ],
+  getSelection: [
+    {
+      description: "See which file fzf has selected after pressing ArrowUp",
+      arguments: {},
+      result: JSON.stringify({
+        selected: { row: 20, col: 0, text: "> src/index.ts", by: "background" },
+        list: { startRow: 17, endRow: 21, items: ["src/utils/keys.ts", "src/tools/type.ts", "src/server.ts", "> src/index.ts", "README.md"], selected: 3 },
+        highlighted: [{ row: 20, col: 0, text: "> src/index.ts", by: "background" }],
+      }, null, 2),
+    },
+  ],
+  mark: [
+    {
+      description: "Bookmark the output before starting the test run, to read only its output later",
+      arguments: { name: "tests" },
+      result: JSON.stringify({
+        mark: { name: "tests", line: 212, markedAt: "2026-04-25T13:05:19.307Z" },
+        marks: [{ name: "tests", line: 212, markedAt: "2026-04-25T13:05:19.307Z" }],
+      }, null, 2),
+    },
+  ],
+  clear: [
+    {
+      description: "Start a test run from an empty buffer so old failures don't show up in later reads",
+      arguments: { scrollback: true },
+      result: "Cleared the screen and scrollback",
+    },
+  ],
+  reset: [
+    {
+      description: "Recover after cat-ing a binary file left the prompt in line-drawing characters",
+      arguments: {},
+      result: "Reset the terminal's modes, character sets, and colors\nRestored the TTY settings (stty sane)",
+    },
+  ],
+  getForegroundProcess: [
+    {
+      description: "Check what Enter would go to before confirming a prompt",
+      arguments: {},
+      result: JSON.stringify({
+        pid: 51230,
+        pgid: 51230,
+        command: "ssh",
+        args: ["ssh", "deploy@build-01"],
+        cwd: "/home/user/project",
+        state: "S",
+        isShell: false,
+        kind: "remote",
+      }, null, 2),
+    },
+  ],
+  isIdle: [
+    {
+      description: "Check that a build finished before typing the next command",
+      arguments: {},
+      result: JSON.stringify({
+        idle: false,
+        reasons: ["'make' is running in the foreground", "the last command hasn't finished", "output 120 ms ago"],
+        atPrompt: false,
+        alternateScreen: false,
+        foreground: "make",
+        commandRunning: true,
+        msSinceOutput: 120,
+      }, null, 2),
+    },
+  ],
+  waitForExit: [
+    {
+      description: "Wait for a test run to finish and get its exit code",
+      arguments: { timeoutMs: 300000 },
+      result: JSON.stringify({
+        exited: true,
+        exitCode: 1,
+        command: "npm test",
+        pid: 48533,
+        waitedMs: 84210,
+      }, null, 2),
+    },
+  ],
+  getTitle: [
+    {
+      description: "Read the title set by the shell",
+      arguments: {},
+      result: "[terminal-mcp] ~/project",
+    },
+  ],
+  lookupHelp: [
+    {
+      description: "Get usage for a command without opening a pager",
+      arguments: { command: "tar", maxLines: 3 },
+      result: "# tar (--help)\n\nUsage: tar [OPTION...] [FILE]...\nGNU 'tar' saves many files together into a single tape or disk archive, and can\nrestore individual files from the archive.\n\n[truncated: showing 3 of 142 lines; raise maxLines for more]",
+    },
+  ],
+  testPromptPattern: [
+    {
+      description: "Check whether a custom prompt pattern detects the current prompt",
+      arguments: { pattern: "\\$\\s*$", visibleOnly: true, maxMatches: 1 },
+      result: JSON.stringify({
+        pattern: "\\$\\s*$",
+        flags: "",
+        cursorLine: "⚡ mcp  user@host:~$ ",
+        cursorLineMatches: true,
+        linesTested: 40,
+        totalMatches: 2,
+        matches: [
+          { line: 0, inViewport: true, text: "⚡ mcp  user@host:~$", match: "$", column: 19 },
+        ],
+        configuredPattern: "(?:⚡ mcp|[$#%>❯›»])\\s*$",
+        configuredPatternMatchesCursorLine: true,
+      }, null, 2),
+    },
+  ],
+  createSession: [
+    {
+      description: "Start a second shell for a long-running build",
+      arguments: { cols: 100, rows: 30 },
+      result: JSON.stringify({
+        sessionId: "a41f9",
+        shell: "/bin/bash",
+        cols: 100,
+        rows: 30,
+        createdAt: "2026-04-25T12:58:01.072Z",
+        lastActivityAt: "2026-04-25T12:58:01.072Z",
+        isDefault: false,
+      }, null, 2),
+    },
+  ],
+  startRecording: [
+    {
+      description: "Record the session until stopRecording is called",
+      arguments: { mode: "always" },
+      result: JSON.stringify({
+        recordingId: "9f2c4e1ab7d03c55",
+        path: "/home/user/.local/state/terminal-mcp/recordings/terminal-1714049881072-9f2c4e1ab7d03c55.cast",
+        format: "v2",
+        mode: "always",
+        maxDuration: 3600,
+        inactivityTimeout: 600,
+        message: "Recording will auto-stop after 60 minutes or 10 minutes of inactivity",
+      }, null, 2),
+    },
+  ],
+};
+
+/**
+ * Get examples for one tool, or for all tools when name is omitted
+ */
+export function getToolExamples(name?: string): Record<string, ToolExample[]> {
+  if (name === undefined) {
+    return toolExamples;
+  }
+  return { [name]: toolExamples[name] ?? [] };
+}
+
+/**
+ * Non-standard `tools/examples` request. Params: { name?: string }.
+ */
+export const ToolExamplesRequestSchema = z.object({
+  method: z.literal("tools/examples"),
+  params: z
+    .object({
+      name: z.string().optional(),
+    })
+    .passthrough()
+    .optional(),
+});
+
+/**
+ * Attach examples to a list of tool definitions for tools/list
+ */
+export function withExamples<T extends { name: string }>(tools: T[]): Array<T & { examples?: ToolExample[] }> {
+  return tools.map((tool) => ({ ...tool, examples: toolExamples[tool.name] }));
+}
@@ -0,0 +1,64 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const getClipboardSchema = z.object({
+  all: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Return every retained clipboard write (newest first) instead of just the latest"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetClipboardArgs = z.infer<typeof getClipboardSchema>;
+
+export const getClipboardTool = {
+  name: "getClipboard",
+  description: "Get text that a program inside the terminal copied to the clipboard with an OSC 52 escape sequence. tmux (set-clipboard on), neovim, and vim-oscyank copy this way, so yanking a selection in a TUI is a reliable way to get exact text out of it without scraping the screen. Returns the latest copy, or with all=true the last 10 newest first. The real system clipboard is never read or written.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      all: {
+        type: "boolean",
+        description: "Return every retained clipboard write, newest first (default: false, latest only)",
+        default: false,
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export function handleGetClipboard(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }>; isError?: boolean } {
+  const parsed = getClipboardSchema.parse(args ?? {});
+  const entries = manager.resolveSessionSync(parsed.sessionId).getClipboard();
+
+  if (entries.length === 0) {
+    return {
+      content: [
+        {
+          type: "text",
+          text: "Nothing has been copied via OSC 52 in this session",
+        },
+      ],
+      isError: true,
+    };
+  }
+
+  const result = parsed.all ? { entries: [...entries].reverse() } : entries[entries.length - 1];
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+  };
+}
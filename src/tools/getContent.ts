@@ -1,5 +1,17 @@
 import { z } from "zod";
 import { TerminalManager } from "../terminal/index.js";
+import { fenceCodeBlock, inferLanguage } from "../utils/codeFence.js";
+import { compactText, lineNumberPrefix, numberLines } from "../utils/compact.js";
+import { convertTables } from "../utils/tables.js";
+import { DEFAULT_NORMALIZE_RULES, compileReplacements, normalizeText } from "../utils/normalize.js";
+import { MAX_REFLOW_COLS, MIN_REFLOW_COLS, getTextLines, joinWrappedRows, reflowTerminal } from "../utils/reflow.js";
+import type { HeadlessTerminal } from "../utils/emulate.js";
+import type { IBufferLine } from "@xterm/headless";
+
+const DEFAULT_CURSOR_MARKER = "▮";
+// Keeps a full 1000-line buffer of an ordinary terminal in one page
+const DEFAULT_MAX_BYTES = 256 * 1024;
+const MIN_MAX_BYTES = 1024;
 
 export const getContentSchema = z.object({
   visibleOnly: z
@@ -7,6 +19,102 @@ export const getContentSchema = z.object({
     .optional()
     .default(false)
     .describe("If true, only return the visible viewport content. If false, include scrollback buffer."),
+  codeFence: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Wrap the output in a Markdown code fence tagged with a language inferred from the last command"),
+  language: z
+    .string()
+    .regex(/^[\w+#.-]*$/)
+    .optional()
+    .describe("Language tag for the code fence, overriding inference (implies codeFence)"),
+  width: z
+    .number()
+    .int()
+    .min(MIN_REFLOW_COLS)
+    .max(MAX_REFLOW_COLS)
+    .optional()
+    .describe("Rewrap long lines as if the terminal were this many columns wide. The live terminal is not resized."),
+  unwrap: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Rejoin soft-wrapped rows into logical lines, so a long line comes back as one line"),
+  includeCursor: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Insert a marker at the cursor position and report the cursor's line/column in the returned text"),
+  cursorMarker: z
+    .string()
+    .max(16)
+    .optional()
+    .describe("Marker inserted at the cursor (default: '▮'); implies includeCursor. Use '' to only report the position."),
+  startRow: z.number().int().min(0).optional().describe("Crop: first screen row (0-indexed). Any crop bound limits output to the visible screen."),
+  endRow: z.number().int().min(0).optional().describe("Crop: last screen row, inclusive (default: bottom row)"),
+  startCol: z.number().int().min(0).optional().describe("Crop: first column (0-indexed)"),
+  endCol: z.number().int().min(0).optional().describe("Crop: last column, inclusive (default: last column)"),
+  since: z
+    .number()
+    .int()
+    .min(0)
+    .optional()
+    .describe("Delta mode: return only lines changed since this generation (0 = start tracking)"),
+  format: z
+    .enum(["plain", "compact"])
+    .optional()
+    .default("plain")
+    .describe("'compact' drops trailing spaces, folds runs of blank lines into '[N blank lines]', and trims a shared left margin"),
+  sinceMark: z.string().optional().describe("Return only the lines from this mark (set with the mark tool) on"),
+  filter: z.string().min(1).optional().describe("Only return lines matching this regex (JavaScript syntax)"),
+  filterFlags: z.string().optional().default("").describe("Regex flags for filter, e.g. 'i' for case-insensitive (g and y are ignored)"),
+  context: z
+    .number()
+    .int()
+    .min(0)
+    .max(20)
+    .optional()
+    .default(0)
+    .describe("Lines of context to keep before and after each filter match (default: 0)"),
+  lineNumbers: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Prefix each line with its number: the screen row for visibleOnly and crops, else the buffer line"),
+  tables: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Rewrite tables drawn with box characters, bars, or aligned columns as Markdown tables"),
+  normalize: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Replace timestamps, times, dates, durations, and PIDs with placeholders such as <TIMESTAMP> and <PID>"),
+  replacements: z
+    .array(
+      z.object({
+        pattern: z.string().min(1).describe("Regex (JavaScript syntax) to replace on each line"),
+        replacement: z.string().optional().default("").describe("Replacement text; $1 etc. refer to groups"),
+        flags: z.string().optional().default("").describe("Regex flags, e.g. 'i' (g is implied)"),
+      })
+    )
+    .max(50)
+    .optional()
+    .describe("Regex replacements for other variable output, applied after normalize's"),
+  maxLines: z.number().int().min(1).optional().describe("Return at most this many lines, the last ones; earlier lines are paged"),
+  maxBytes: z
+    .number()
+    .int()
+    .min(MIN_MAX_BYTES)
+    .optional()
+    .describe(`Return at most this many bytes of text, the last ones (default: ${DEFAULT_MAX_BYTES})`),
+  pageToken: z
+    .string()
+    .regex(/^before:\d+$/)
+    .optional()
+    .describe("Continue a truncated read: the pageToken from the previous call's truncated block"),
   sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
 });
 
@@ -14,7 +122,7 @@ export type GetContentArgs = z.infer<typeof getContentSchema>;
 
 export const getContentTool = {
   name: "getContent",
-  description: "Get terminal content as plain text. Use after sending commands to see output. Returns full scrollback buffer by default (up to 1000 lines). Set visibleOnly=true for just the current viewport. Prefer this over takeScreenshot for reading command output. Pass sessionId to read a specific session.",
+  description: `Get terminal content as plain text. Use after sending commands to see output. Returns full scrollback buffer by default (up to 1000 lines). Set visibleOnly=true for just the current viewport. Set codeFence=true to get the output wrapped in a Markdown code fence whose language is inferred from the last command (e.g. json for 'cat package.json', diff for 'git diff'). Set width to rewrap output from a wide terminal into fewer columns (render-only; the live terminal keeps its size). Set unwrap=true to rejoin long lines the terminal wrapped at its width, so each command or log line comes back whole for regexing and diffing. Set startRow/endRow/startCol/endCol (0-indexed, inclusive) to return only that rectangle of the visible screen, e.g. one pane or the status bar. Set includeCursor=true to see where the cursor is: a marker (default '▮') is inserted at the cursor cell and a second block reports its line and column, which helps decide the next keystroke inside editors and forms. Set format='compact' to save tokens on sparse screens: trailing spaces are dropped, runs of blank lines become '[N blank lines]', and a left margin shared by every line is trimmed (noted on the first line). Set sinceMark to the name of a mark set with the mark tool to get only the lines from that mark on, e.g. everything since a test run started. Set filter to a regex to get back only the matching lines, with context lines around each if you like; runs of skipped lines show as '--', as in grep. Set lineNumbers=true to prefix each line with its number ('17: ...'): the 0-indexed screen row with visibleOnly or a crop, ready to pass back as startRow/endRow, and the buffer line (0 = oldest scrollback line) otherwise. Set tables=true to get tables drawn with box characters or bars (psql, TUI panels) or aligned under upper-case headers (docker ps, kubectl get) back as Markdown tables. Set normalize=true to replace values that change from run to run (timestamps, times, dates, durations, PIDs) with placeholders like <TIMESTAMP> and <PID>, and add your own regex replacements, so a screen can be compared against a golden copy. Large results are cut to their last maxBytes (default ${DEFAULT_MAX_BYTES}) or maxLines: a truncated block then reports the lines shown and a pageToken; pass it back to read the lines before them. When polling a slow command, use delta mode: pass since=0, then the returned generation on each later call to get only the lines that changed (JSON: generation, full, lineCount, changed[{line, screenRow, text}]). Prefer this over takeScreenshot for reading command output. Pass sessionId to read a specific session.`,
   inputSchema: {
     type: "object" as const,
     properties: {
@@ -23,6 +131,115 @@ export const getContentTool = {
         description: "If true, only return the visible viewport content. If false (default), include scrollback buffer.",
         default: false,
       },
+      codeFence: {
+        type: "boolean",
+        description: "Wrap the output in a Markdown code fence tagged with a language inferred from the last command (default: false)",
+        default: false,
+      },
+      language: {
+        type: "string",
+        description: "Language tag for the code fence, overriding inference (e.g. 'json'). Implies codeFence.",
+      },
+      width: {
+        type: "number",
+        description: `Rewrap long lines as if the terminal were this many columns wide (${MIN_REFLOW_COLS}-${MAX_REFLOW_COLS}). Render-only: the live terminal is not resized.`,
+      },
+      unwrap: {
+        type: "boolean",
+        description: "Rejoin rows the terminal soft-wrapped at its width into single logical lines (default: false). Lines ended by a real newline stay separate. Cannot be combined with crop bounds.",
+        default: false,
+      },
+      includeCursor: {
+        type: "boolean",
+        description: "Insert a marker at the cursor position and report the cursor's line/column (default: false)",
+        default: false,
+      },
+      cursorMarker: {
+        type: "string",
+        description: "Marker inserted at the cursor, e.g. '[CURSOR]' (default: '▮'). Implies includeCursor. Use '' to only report the position.",
+      },
+      startRow: {
+        type: "number",
+        description: "Crop: first screen row (0-indexed). Any crop bound limits output to the visible screen.",
+      },
+      endRow: {
+        type: "number",
+        description: "Crop: last screen row, inclusive (default: bottom row)",
+      },
+      startCol: {
+        type: "number",
+        description: "Crop: first column (0-indexed)",
+      },
+      endCol: {
+        type: "number",
+        description: "Crop: last column, inclusive (default: last column)",
+      },
+      since: {
+        type: "number",
+        description: "Delta mode: pass 0 on the first call, then the returned generation. Returns JSON with only the lines changed since that generation. Only combines with visibleOnly.",
+      },
+      format: {
+        type: "string",
+        enum: ["plain", "compact"],
+        description: "'plain' (default) returns the text as on screen. 'compact' drops trailing spaces, folds runs of blank lines into '[N blank lines]', and trims a left margin shared by every line. Cannot be combined with includeCursor.",
+        default: "plain",
+      },
+      sinceMark: {
+        type: "string",
+        description: "Return only the lines from the line marked with this name (see the mark tool) to the end of the buffer. Cannot be combined with visibleOnly, crop bounds, or width.",
+      },
+      filter: {
+        type: "string",
+        description: "Only return lines matching this regex (JavaScript syntax), e.g. 'FAILED|Error'. Skipped runs of lines show as '--'. Cannot be combined with includeCursor.",
+      },
+      filterFlags: {
+        type: "string",
+        description: "Regex flags for filter, e.g. 'i' for case-insensitive (g and y are ignored)",
+      },
+      context: {
+        type: "number",
+        description: "Lines of context before and after each filter match (default: 0, max: 20)",
+      },
+      lineNumbers: {
+        type: "boolean",
+        description: "Prefix each line with its number, e.g. '17: ' (default: false). Numbers are 0-indexed screen rows with visibleOnly or a crop, buffer lines (0 = oldest scrollback line) otherwise. Lines dropped by filter keep their numbers. Cannot be combined with tables, unwrap, or width.",
+        default: false,
+      },
+      tables: {
+        type: "boolean",
+        description: "Rewrite tables drawn with box characters or | bars (psql, TUI panels) and column-aligned tables under an upper-case header (docker ps, kubectl get) as Markdown tables (default: false). Other text is unchanged. Cannot be combined with includeCursor.",
+        default: false,
+      },
+      normalize: {
+        type: "boolean",
+        description: "Replace values that change from run to run with placeholders (default: false): ISO and date-style timestamps (<TIMESTAMP>), dates (<DATE>), times of day (<TIME>), durations like 1.2s, 450ms, 1m30s, or 0m0.004s (<DURATION>), and PIDs after 'pid', in 'name[1234]:', and in job notices (<PID>). Cannot be combined with includeCursor.",
+        default: false,
+      },
+      replacements: {
+        type: "array",
+        items: {
+          type: "object",
+          properties: {
+            pattern: { type: "string", description: "Regex (JavaScript syntax) to replace on each line" },
+            replacement: { type: "string", description: "Replacement text; $1 etc. refer to groups (default: '')" },
+            flags: { type: "string", description: "Regex flags, e.g. 'i' (g is implied)" },
+          },
+          required: ["pattern"],
+        },
+        description: "Regex replacements for other variable output, e.g. [{\"pattern\": \"[0-9a-f]{12}\", \"replacement\": \"<ID>\"}] for container IDs. Applied to each line in order, after normalize's rules (max 50). Cannot be combined with includeCursor.",
+      },
+      maxLines: {
+        type: "number",
+        description: "Return at most this many lines, the last ones. Earlier lines can be read with the returned pageToken.",
+      },
+      maxBytes: {
+        type: "number",
+        description: `Return at most this many bytes of text, the last ones (default: ${DEFAULT_MAX_BYTES}, minimum: ${MIN_MAX_BYTES}). Earlier lines can be read with the returned pageToken.`,
+      },
+      pageToken: {
+        type: "string",
+        description: "pageToken from a truncated result: returns the page of lines just before the ones that call showed",
+      },
       sessionId: {
         type: "string",
         description: "Target session ID. Omit to target the default session.",
@@ -32,19 +249,386 @@ export const getContentTool = {
   },
 };
 
-export function handleGetContent(manager: TerminalManager, args: unknown): { content: Array<{ type: "text"; text: string }> } {
+export async function handleGetContent(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
   const parsed = getContentSchema.parse(args);
+  await manager.waitForReadable(parsed.sessionId);
+
+  if (parsed.since !== undefined) {
+    return readChanges(manager, parsed);
+  }
+
+  const includeCursor = parsed.includeCursor || parsed.cursorMarker !== undefined;
+  const cropped = [parsed.startRow, parsed.endRow, parsed.startCol, parsed.endCol].some((v) => v !== undefined);
+  if (cropped && parsed.unwrap) {
+    throw new Error("unwrap cannot be combined with startRow/endRow/startCol/endCol");
+  }
+  const rewrite = parsed.filter !== undefined
+    ? "filter"
+    : parsed.tables
+      ? "tables"
+      : parsed.format === "compact"
+        ? "format 'compact'"
+        : parsed.normalize || parsed.replacements
+          ? "normalize and replacements"
+          : null;
+  if (rewrite && includeCursor) {
+    // Dropped or rewritten lines would put the cursor somewhere else
+    throw new Error(`${rewrite} cannot be combined with includeCursor or cursorMarker`);
+  }
+  // Tables replace border lines and reflow rows, and unwrap and width
+  // return lines that aren't buffer rows, so numbers wouldn't line up with
+  // search, mark, or crop bounds
+  const renumbered = parsed.tables ? "tables" : parsed.unwrap ? "unwrap" : parsed.width !== undefined ? "width" : null;
+  if (parsed.lineNumbers && renumbered) {
+    throw new Error(`lineNumbers cannot be combined with ${renumbered}`);
+  }
+  let fromLine: number | null = null;
+  if (parsed.sinceMark !== undefined) {
+    const incompatible: string[] = [
+      ...(parsed.visibleOnly ? ["visibleOnly"] : []),
+      ...(["width", "startRow", "endRow", "startCol", "endCol"] as const).filter((key) => parsed[key] !== undefined),
+    ];
+    if (incompatible.length > 0) {
+      throw new Error(`sinceMark cannot be combined with ${incompatible.join(", ")}`);
+    }
+    fromLine = manager.resolveSessionSync(parsed.sessionId).getMarkLine(parsed.sinceMark);
+  }
+  let filter: RegExp | null = null;
+  if (parsed.filter !== undefined) {
+    try {
+      filter = new RegExp(parsed.filter, parsed.filterFlags.replace(/[gy]/g, ""));
+    } catch (error) {
+      throw new Error(`Invalid filter regex: ${(error as Error).message}`);
+    }
+  }
+  const rules = [
+    ...(parsed.normalize ? DEFAULT_NORMALIZE_RULES : []),
+    ...compileReplacements(parsed.replacements ?? []),
+  ];
+  const readOptions: ReadOptions = {
+    visibleOnly: parsed.visibleOnly || cropped,
+    marker: includeCursor ? parsed.cursorMarker ?? DEFAULT_CURSOR_MARKER : null,
+    crop: cropped ? parsed : null,
+    unwrap: parsed.unwrap,
+    from: fromLine ?? 0,
+  };
+
+  let content: string;
+  let cursor: CursorPosition | null = null;
+  if (parsed.width !== undefined) {
+    const reflowed = await reflowTerminal(manager.getTerminal(parsed.sessionId), parsed.width);
+    try {
+      ({ content, cursor } = readContent(reflowed, readOptions));
+    } finally {
+      reflowed.dispose();
+    }
+  } else if (includeCursor || cropped || parsed.unwrap || fromLine !== null) {
+    ({ content, cursor } = readContent(manager.getTerminal(parsed.sessionId), readOptions));
+  } else {
+    content = parsed.visibleOnly
+      ? manager.getVisibleContent(parsed.sessionId)
+      : manager.getContent(parsed.sessionId);
+  }
+
+  // Before filter, so a filter sees (and can match) the placeholders
+  if (rules.length > 0) content = normalizeText(content, rules);
+  // Screen row of the first line for visibleOnly and crops, its buffer line otherwise
+  const numberFrom = parsed.lineNumbers ? (cropped ? parsed.startRow ?? 0 : fromLine ?? 0) : null;
+  if (filter) {
+    content = filterLines(content, filter, parsed.context, numberFrom);
+    if (!content) {
+      return { content: [{ type: "text", text: `(no lines match /${filter.source}/${filter.flags})` }] };
+    }
+  }
+  if (parsed.tables) content = convertTables(content);
+  if (parsed.format === "compact") {
+    content = compactText(content, filter ? null : numberFrom);
+  } else if (numberFrom !== null && !filter) {
+    content = numberLines(content, numberFrom);
+  }
+  let page: PageInfo | null = null;
+  ({ content, page } = paginate(content, parsed));
+
+  const text = !content || (!parsed.codeFence && parsed.language === undefined)
+    ? content || "(empty terminal)"
+    : fenceCodeBlock(content, parsed.language ?? inferLanguage(manager.getLastCommand(parsed.sessionId)));
+
+  const result: Array<{ type: "text"; text: string }> = [
+    {
+      type: "text",
+      text,
+    },
+  ];
+  if (cursor) {
+    result.push({
+      type: "text",
+      text: JSON.stringify({ cursor }),
+    });
+  }
+  if (page) {
+    result.push({
+      type: "text",
+      text: JSON.stringify({ truncated: page }),
+    });
+  }
+  return { content: result };
+}
 
-  const content = parsed.visibleOnly
-    ? manager.getVisibleContent(parsed.sessionId)
-    : manager.getContent(parsed.sessionId);
+/**
+ * Lines matching the filter with `context` lines around each, and "--"
+ * where lines were skipped in between, as grep prints them. Lines are
+ * numbered from numberFrom unless it is null.
+ */
+function filterLines(content: string, filter: RegExp, context: number, numberFrom: number | null): string {
+  const lines = content.split("\n");
+  const last = (numberFrom ?? 0) + lines.length - 1;
+  const keep = new Array<boolean>(lines.length).fill(false);
+  lines.forEach((line, i) => {
+    if (!filter.test(line)) return;
+    for (let j = Math.max(0, i - context); j <= Math.min(lines.length - 1, i + context); j++) keep[j] = true;
+  });
+  const out: string[] = [];
+  let previous = -1;
+  lines.forEach((line, i) => {
+    if (!keep[i]) return;
+    if (previous !== -1 && i > previous + 1) out.push("--");
+    out.push(numberFrom === null ? line : lineNumberPrefix(numberFrom + i, last) + line);
+    previous = i;
+  });
+  return out.join("\n");
+}
+
+interface PageInfo {
+  /** First and last line shown, 0-indexed into the whole content (as cursor.line is) */
+  shownLines: [number, number];
+  totalLines: number;
+  /** Lines before the page, still to be read */
+  omittedBefore: number;
+  /** Lines after the page, already returned by earlier calls */
+  omittedAfter: number;
+  /** Only the end of the first shown line fit in maxBytes */
+  partialLine?: boolean;
+  /** Pass back to read the lines before this page; absent on the first page */
+  pageToken?: string;
+}
+
+/**
+ * Cut content to its last maxLines/maxBytes, ending just before the line
+ * named by pageToken. Pages count lines from the top of the buffer, so a
+ * buffer whose scrollback is full shifts under them while output arrives.
+ */
+function paginate(
+  content: string,
+  { maxLines, maxBytes = DEFAULT_MAX_BYTES, pageToken }: GetContentArgs
+): { content: string; page: PageInfo | null } {
+  if (!pageToken && maxLines === undefined && Buffer.byteLength(content) <= maxBytes) {
+    return { content, page: null };
+  }
+  const lines = content.split("\n");
+  const end = pageToken ? Math.min(parseInt(pageToken.slice("before:".length), 10), lines.length) : lines.length;
+  if (end === 0) {
+    throw new Error("Nothing before this page: the whole buffer has been read");
+  }
+
+  let start = end;
+  let bytes = 0;
+  while (start > 0 && (maxLines === undefined || end - start < maxLines)) {
+    // Plus the newline joining it to the line below
+    const size = Buffer.byteLength(lines[start - 1]) + (start < end ? 1 : 0);
+    if (start < end && bytes + size > maxBytes) break;
+    bytes += size;
+    start--;
+  }
+  const shown = lines.slice(start, end);
+  let partialLine = false;
+  if (bytes > maxBytes) {
+    // A single line longer than maxBytes: keep its end, which is nearest
+    // the output that follows
+    let line = shown[0].slice(-maxBytes);
+    while (Buffer.byteLength(line) > maxBytes) line = line.slice(1);
+    shown[0] = line;
+    partialLine = true;
+  }
+
+  const page: PageInfo = {
+    shownLines: [start, end - 1],
+    totalLines: lines.length,
+    omittedBefore: start,
+    omittedAfter: lines.length - end,
+  };
+  if (partialLine) page.partialLine = true;
+  if (start > 0) page.pageToken = `before:${start}`;
+  // Everything fit after all
+  if (start === 0 && end === lines.length && !partialLine) {
+    return { content, page: null };
+  }
+  return { content: shown.join("\n"), page };
+}
+
+/**
+ * Delta mode: lines changed since the caller's last generation
+ */
+function readChanges(
+  manager: TerminalManager,
+  parsed: GetContentArgs
+): { content: Array<{ type: "text"; text: string }> } {
+  const incompatible: string[] = [
+    ...([
+      "width", "startRow", "endRow", "startCol", "endCol", "language", "cursorMarker", "sinceMark", "filter", "replacements",
+      "maxLines", "maxBytes", "pageToken",
+    ] as const)
+      .filter((key) => parsed[key] !== undefined),
+    ...(["codeFence", "unwrap", "includeCursor", "lineNumbers", "tables", "normalize"] as const).filter((key) => parsed[key]),
+    ...(parsed.format === "compact" ? ["format"] : []),
+  ];
+  if (incompatible.length > 0) {
+    throw new Error(`since cannot be combined with ${incompatible.join(", ")}`);
+  }
+
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const changes = session.getContentChanges(parsed.since!, parsed.visibleOnly);
+  const baseY = session.getTerminal().buffer.active.baseY;
+  const result = {
+    generation: changes.generation,
+    since: parsed.since,
+    full: changes.full,
+    lineCount: changes.lineCount,
+    changed: changes.changed.map(({ line, text, inViewport }) => ({
+      line,
+      screenRow: inViewport ? line - baseY : null,
+      text,
+    })),
+  };
 
   return {
     content: [
       {
         type: "text",
-        text: content || "(empty terminal)",
+        text: JSON.stringify(result),
       },
     ],
   };
 }
+
+interface CursorPosition {
+  /** 0-indexed line of the content, not counting a code fence's opening line */
+  line: number;
+  /** 0-indexed column, relative to startCol when cropping and counted from the start of the logical line when unwrapping */
+  col: number;
+  /** False when the cursor lies outside the crop rectangle (no marker is inserted) */
+  inRegion?: boolean;
+}
+
+interface CropBounds {
+  startRow?: number;
+  endRow?: number;
+  startCol?: number;
+  endCol?: number;
+}
+
+interface ReadOptions {
+  visibleOnly: boolean;
+  /** Marker to insert at the cursor, or null to not report the cursor */
+  marker: string | null;
+  /** Screen rectangle to return; implies visibleOnly */
+  crop: CropBounds | null;
+  /** Join soft-wrapped rows into logical lines; not used with crop */
+  unwrap: boolean;
+  /** First buffer line of the full buffer to read (sinceMark) */
+  from: number;
+}
+
+/**
+ * Text of columns [startCol, endCol] of a line, right-trimmed unless
+ * trimRight is false. A wide character cut in half by either edge becomes
+ * a space, so columns in the result still line up with the screen.
+ */
+function cropLine(line: IBufferLine | undefined, startCol: number, endCol: number, trimRight = true): string {
+  if (!line) return "";
+  let text = "";
+  for (let x = startCol; x <= endCol && x < line.length; x++) {
+    const cell = line.getCell(x);
+    if (!cell) break;
+    const width = cell.getWidth();
+    if (width === 0) {
+      // Right half of a wide character; its left half is outside the crop
+      if (x === startCol) text += " ";
+      continue;
+    }
+    text += width === 2 && x === endCol ? " " : cell.getChars() || " ";
+  }
+  return trimRight ? text.replace(/ +$/, "") : text;
+}
+
+/**
+ * Read buffer text from a terminal, optionally cropped to a rectangle of
+ * the screen or with soft-wrapped rows rejoined, and with a marker
+ * inserted at the cursor. Trailing blank
+ * lines are dropped from the full buffer (as in getContent), but never
+ * above the cursor's line.
+ */
+function readContent(
+  terminal: HeadlessTerminal,
+  { visibleOnly, marker, crop, unwrap, from }: ReadOptions
+): { content: string; cursor: CursorPosition | null } {
+  const buffer = terminal.buffer.active;
+  let start = visibleOnly ? buffer.baseY : from;
+  let end = visibleOnly ? buffer.baseY + terminal.rows : buffer.length;
+  let startCol = 0;
+  let endCol = terminal.cols - 1;
+  if (crop) {
+    const startRow = crop.startRow ?? 0;
+    const endRow = Math.min(crop.endRow ?? terminal.rows - 1, terminal.rows - 1);
+    startCol = crop.startCol ?? 0;
+    endCol = Math.min(crop.endCol ?? terminal.cols - 1, terminal.cols - 1);
+    if (startRow > endRow || startCol > endCol) {
+      throw new Error(
+        `Empty crop rectangle: rows ${startRow}-${endRow}, cols ${startCol}-${endCol} (screen is ${terminal.cols}x${terminal.rows})`
+      );
+    }
+    start = buffer.baseY + startRow;
+    end = buffer.baseY + endRow + 1;
+  }
+
+  let lines = crop
+    ? Array.from({ length: end - start }, (_, i) => cropLine(buffer.getLine(start + i), startCol, endCol))
+    : getTextLines(terminal, start, end, unwrap);
+  let cursorLine = buffer.baseY + buffer.cursorY - start;
+  const inRegion = cursorLine >= 0 && cursorLine < lines.length &&
+    buffer.cursorX >= startCol && buffer.cursorX <= endCol;
+
+  if (marker !== null && inRegion) {
+    // String offset of the cursor column; differs from the column when
+    // wide characters precede it
+    const offset = cropLine(buffer.getLine(buffer.baseY + buffer.cursorY), startCol, buffer.cursorX - 1, false).length;
+    const line = lines[cursorLine].padEnd(offset);
+    lines[cursorLine] = line.slice(0, offset) + marker + line.slice(offset);
+  }
+
+  // With unwrap, the column also counts the full rows wrapped before the cursor
+  let cursorCol = buffer.cursorX - startCol;
+  if (unwrap) {
+    const joined = joinWrappedRows(terminal, start, lines);
+    lines = joined.lines;
+    if (inRegion) {
+      const row = cursorLine;
+      cursorLine = joined.lineOf[row];
+      cursorCol += terminal.cols * (row - joined.lineOf.indexOf(cursorLine));
+    }
+  }
+
+  const keep = marker !== null && inRegion ? cursorLine + 1 : 0;
+  while (!visibleOnly && lines.length > keep && lines[lines.length - 1].trim() === "") {
+    lines.pop();
+  }
+
+  let cursor: CursorPosition | null = null;
+  if (marker !== null) {
+    cursor = { line: cursorLine, col: cursorCol };
+    if (crop) cursor.inRegion = inRegion;
+  }
+  return { content: lines.join("\n"), cursor };
+}
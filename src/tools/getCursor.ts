@@ -0,0 +1,69 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const getCursorSchema = z.object({
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetCursorArgs = z.infer<typeof getCursorSchema>;
+
+export const getCursorTool = {
+  name: "getCursor",
+  description: "Get the cursor's position and appearance: 0-indexed row and col on the visible screen, whether it is visible (programs hide it with DECTCEM while drawing or in menus), and its shape and blink as set by the application (DECSCUSR; block by default). Cheaper than takeScreenshot when you only need to know where typing will land. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+  outputSchema: {
+    type: "object" as const,
+    properties: {
+      row: { type: "number", description: "0-indexed row on the visible screen" },
+      col: { type: "number", description: "0-indexed column" },
+      visible: { type: "boolean" },
+      style: {
+        type: "object",
+        properties: {
+          shape: { type: "string", enum: ["block", "underline", "bar"] },
+          blink: { type: "boolean" },
+        },
+        required: ["shape", "blink"],
+      },
+      alternateScreen: { type: "boolean", description: "True while a full-screen program is running" },
+    },
+    required: ["row", "col", "visible", "style", "alternateScreen"],
+  },
+};
+
+export async function handleGetCursor(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }>; structuredContent: Record<string, unknown> }> {
+  const parsed = getCursorSchema.parse(args ?? {});
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const buffer = session.getTerminal().buffer.active;
+
+  const result = {
+    row: buffer.cursorY,
+    col: buffer.cursorX,
+    visible: session.isCursorVisible(),
+    style: session.getCursorStyle(),
+    alternateScreen: buffer.type === "alternate",
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+    structuredContent: result,
+  };
+}
@@ -0,0 +1,63 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+import { globToRegExp } from "../utils/envRedact.js";
+
+export const getEnvironmentSchema = z.object({
+  names: z
+    .array(z.string().min(1))
+    .optional()
+    .describe("Only return these variables; '*' wildcards allowed (e.g. ['PATH', 'NODE_*'])"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetEnvironmentArgs = z.infer<typeof getEnvironmentSchema>;
+
+export const getEnvironmentTool = {
+  name: "getEnvironment",
+  description: "Get the environment variables the session's shell was started with. Values of credential-like variables (AWS_*, *_TOKEN, *_SECRET, *_PASSWORD, ...) are masked as '[redacted]' and listed in 'redacted'; only the server operator can disable masking. Variables exported later inside the shell are not included — run `printenv NAME` for those. Pass names to filter.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      names: {
+        type: "array",
+        items: { type: "string" },
+        description: "Only return these variables; '*' wildcards allowed (e.g. ['PATH', 'NODE_*'])",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export function handleGetEnvironment(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }> } {
+  const parsed = getEnvironmentSchema.parse(args ?? {});
+  const { env, redacted } = manager.getEnvironment(parsed.sessionId);
+
+  const filters = parsed.names?.map(globToRegExp);
+  const keep = (name: string) => !filters || filters.some((re) => re.test(name));
+  const variables = Object.fromEntries(
+    Object.entries(env)
+      .filter(([name]) => keep(name))
+      .sort(([a], [b]) => a.localeCompare(b))
+  );
+
+  const result = {
+    variables,
+    redacted: redacted.filter(keep).sort(),
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+  };
+}
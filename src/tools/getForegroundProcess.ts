@@ -0,0 +1,113 @@
+import { z } from "zod";
+import * as path from "path";
+import { TerminalManager } from "../terminal/index.js";
+
+export const getForegroundProcessSchema = z.object({
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetForegroundProcessArgs = z.infer<typeof getForegroundProcessSchema>;
+
+/**
+ * What kind of program holds the terminal, which decides what keys mean:
+ * Ctrl+C and Enter go to a remote host under ssh, ':q' means something
+ * only to vim, and anything typed at a password prompt is a secret
+ */
+export type ProcessKind =
+  | "shell"
+  | "editor"
+  | "pager"
+  | "remote"
+  | "multiplexer"
+  | "repl"
+  | "password_prompt"
+  | "other";
+
+const KINDS: Array<[ProcessKind, RegExp]> = [
+  ["password_prompt", /^(sudo|su|doas|passwd|pinentry(-\w+)?|ssh-add|gpg|kinit)$/],
+  ["shell", /^-?((ba|z|k|mk|da|tc|c|a)?sh|fish|nu|elvish|xonsh|yash|pwsh)$/],
+  ["editor", /^(n?vim?|vi|view|nano|pico|micro|emacs(client)?|hx|helix|kak|joe|ed|mg)$/],
+  ["pager", /^(less|more|most|man|bat|delta|w3m)$/],
+  ["remote", /^(ssh|mosh(-client)?|telnet|rlogin|nc|ncat|socat|kubectl|docker|podman|minicom)$/],
+  ["multiplexer", /^(tmux|screen|zellij|byobu|abduco|dtach)$/],
+  ["repl", /^(python[\d.]*|ipython\d*|node|deno|bun|irb|pry|ruby|psql|mysql|mariadb|sqlite3|redis-cli|mongosh?|ghci|iex|erl|R|julia|lua(jit)?|php|sbcl|clojure|scala|jshell|gdb|lldb|pdb|ftp|sftp)$/],
+];
+
+// These only count as remote with a session-like subcommand (docker exec, kubectl exec)
+const REMOTE_SUBCOMMANDS: Record<string, string[]> = {
+  kubectl: ["exec", "attach", "run", "debug"],
+  docker: ["exec", "attach", "run"],
+  podman: ["exec", "attach", "run"],
+};
+
+export function classifyProcess(command: string, args: string[], isShell: boolean): ProcessKind {
+  if (isShell) return "shell";
+  const name = path.basename(command);
+  const kind = KINDS.find(([, pattern]) => pattern.test(name))?.[0] ?? "other";
+  if (kind === "remote" && REMOTE_SUBCOMMANDS[name]) {
+    return args.slice(1).some((arg) => REMOTE_SUBCOMMANDS[name].includes(arg)) ? "remote" : "other";
+  }
+  return kind;
+}
+
+export const getForegroundProcessTool = {
+  name: "getForegroundProcess",
+  description: "Report the program that currently holds the terminal (the PTY's foreground process group leader), read from /proc: pid, process group, command name, full args, working directory, run state, and a kind (shell, editor, pager, remote, multiplexer, repl, password_prompt, or other). Use it before sending keys whose meaning depends on the program: whether Enter runs a shell command or a line in a remote host or REPL, whether Ctrl+C interrupts a job or closes an ssh connection, or whether the terminal is waiting for a password. kind is a guess from the command name; sudo and su keep the terminal while the command they started runs, so password_prompt only means a password may be asked for. Linux only. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+  outputSchema: {
+    type: "object" as const,
+    properties: {
+      pid: { type: "number" },
+      pgid: { type: "number", description: "Foreground process group (pid of its leader)" },
+      command: { type: "string", description: "Executable name" },
+      args: { type: "array", items: { type: "string" } },
+      cwd: { type: ["string", "null"] },
+      state: { type: "string", description: "R running, S sleeping, D disk wait, T stopped, Z zombie" },
+      isShell: { type: "boolean", description: "The session's shell itself holds the terminal" },
+      kind: {
+        type: "string",
+        enum: ["shell", "editor", "pager", "remote", "multiplexer", "repl", "password_prompt", "other"],
+      },
+    },
+    required: ["pid", "pgid", "command", "args", "cwd", "state", "isShell", "kind"],
+  },
+};
+
+export function handleGetForegroundProcess(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }>; structuredContent: Record<string, unknown> } {
+  const parsed = getForegroundProcessSchema.parse(args ?? {});
+  const group = manager.getForegroundGroup(parsed.sessionId);
+  if (!group) {
+    if (manager.resolveSessionSync(parsed.sessionId).getPid() === null) {
+      throw new Error("getForegroundProcess reads the local /proc, so it can't see a shell on a remote host");
+    }
+    throw new Error(
+      process.platform === "linux"
+        ? "The session's shell has exited"
+        : "getForegroundProcess reads /proc, which is only available on Linux"
+    );
+  }
+
+  const result = { ...group, kind: classifyProcess(group.command, group.args, group.isShell) };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+    structuredContent: result,
+  };
+}
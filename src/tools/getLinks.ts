@@ -0,0 +1,55 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const getLinksSchema = z.object({
+  visibleOnly: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("If true, only return links on the visible screen. If false, include scrollback."),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetLinksArgs = z.infer<typeof getLinksSchema>;
+
+export const getLinksTool = {
+  name: "getLinks",
+  description: "List hyperlinks that programs printed with OSC 8 escape sequences (gh, delta, cargo, ls --hyperlink, ...). These links are invisible in getContent, which only shows their anchor text. Each link has its uri, its anchor text, and its position: start/end buffer line and column (end exclusive; line 0 = oldest scrollback line), plus the 0-indexed screen row when visible (null if scrolled off). Links whose text has since been overwritten or cleared are omitted. Oldest first.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      visibleOnly: {
+        type: "boolean",
+        description: "If true, only return links on the visible screen. If false (default), include scrollback.",
+        default: false,
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export async function handleGetLinks(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = getLinksSchema.parse(args ?? {});
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+
+  const result = {
+    links: session.getLinks(parsed.visibleOnly),
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+  };
+}
@@ -0,0 +1,43 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+import { findSelection } from "../utils/describe.js";
+
+export const getSelectionSchema = z.object({
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetSelectionArgs = z.infer<typeof getSelectionSchema>;
+
+export const getSelectionTool = {
+  name: "getSelection",
+  description: "Find the highlighted item in a TUI: the row or cells drawn in inverse video or on a distinct background, as fzf, menus, and file pickers mark the current selection. Returns the best guess as selected {row, col, text, by}, the list it belongs to with its items and the selected index, and every highlighted stretch on screen (full-width bars such as status lines are flagged bar: true). Call it before and after arrow keys to see where the selection moved. Rows and columns are 0-indexed screen positions. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export async function handleGetSelection(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }>; structuredContent: Record<string, unknown> }> {
+  const parsed = getSelectionSchema.parse(args ?? {});
+  await manager.waitForReadable(parsed.sessionId);
+  const result = { ...findSelection(manager.getTerminal(parsed.sessionId)) };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+    structuredContent: result,
+  };
+}
@@ -0,0 +1,86 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const getSnapshotSchema = z.object({
+  n: z
+    .number()
+    .int()
+    .min(1)
+    .optional()
+    .describe("How far back to go: 1 = most recent snapshot (default), 2 = the one before, ..."),
+  id: z
+    .number()
+    .int()
+    .optional()
+    .describe("Snapshot id from listSnapshots. Takes precedence over n."),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetSnapshotArgs = z.infer<typeof getSnapshotSchema>;
+
+export const getSnapshotTool = {
+  name: "getSnapshot",
+  description: "Retrieve a screen from the session's automatic snapshot history, by position (n=1 is the most recent) or by id from listSnapshots. Returns the screen text as it was at that moment, with its time, trigger, and cursor position. Useful for recovering output that was cleared or overwritten before you read it.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      n: {
+        type: "number",
+        description: "How far back to go: 1 = most recent snapshot (default), 2 = the one before, ...",
+      },
+      id: {
+        type: "number",
+        description: "Snapshot id from listSnapshots. Takes precedence over n.",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export function handleGetSnapshot(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }>; isError?: boolean } {
+  const parsed = getSnapshotSchema.parse(args ?? {});
+  const history = manager.resolveSessionSync(parsed.sessionId).getSnapshotHistory();
+
+  const snapshot = parsed.id !== undefined
+    ? history.find((snap) => snap.id === parsed.id)
+    : history[history.length - (parsed.n ?? 1)];
+
+  if (!snapshot) {
+    const which = parsed.id !== undefined ? `id ${parsed.id}` : `n=${parsed.n ?? 1}`;
+    return {
+      content: [
+        {
+          type: "text",
+          text: history.length === 0
+            ? "Snapshot history is empty"
+            : `No snapshot with ${which} (history holds ${history.length}, ids ${history[0].id}-${history[history.length - 1].id})`,
+        },
+      ],
+      isError: true,
+    };
+  }
+
+  const result = {
+    id: snapshot.id,
+    takenAt: snapshot.takenAt,
+    trigger: snapshot.trigger,
+    ...(snapshot.tool && { tool: snapshot.tool }),
+    cursor: snapshot.cursor,
+    content: snapshot.rows.join("\n").replace(/\n+$/, ""),
+  };
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+  };
+}
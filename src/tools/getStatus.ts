@@ -0,0 +1,162 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const getStatusSchema = z.object({
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetStatusArgs = z.infer<typeof getStatusSchema>;
+
+export const getStatusTool = {
+  name: "getStatus",
+  description: "Get a compact status report for a session: window title, dimensions, cursor, whether a full-screen TUI is active (alternate screen), application cursor/keypad modes, mouse tracking, whether the cursor row looks like a shell prompt, the shell's working directory (as reported with OSC 7 at the last prompt, or read from /proc on Linux; no need to run pwd), whether it is waiting for a password (echo turned off on the PTY, or a prompt like 'Password:' on the cursor row; hand those to the user rather than guessing credentials), who currently controls input, and whether the shell (or the command given after -- on the command line) is still running or what exit code it finished with. With --proc-tracking (Linux), also the shell's working directory and the foreground command read from /proc. Cheaper than a screenshot when you only need to know what state the terminal is in. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+  outputSchema: {
+    type: "object" as const,
+    properties: {
+      sessionId: { type: "string" },
+      isDefault: { type: "boolean" },
+      shell: { type: "string" },
+      command: { type: "array", items: { type: "string" }, description: "Set when the session runs a command instead of a shell" },
+      running: { type: "boolean" },
+      exitCode: { type: ["number", "null"], description: "Exit code once the command has finished" },
+      title: { type: "string" },
+      dimensions: {
+        type: "object",
+        properties: { cols: { type: "number" }, rows: { type: "number" } },
+        required: ["cols", "rows"],
+      },
+      cursor: {
+        type: "object",
+        properties: {
+          x: { type: "number" },
+          y: { type: "number" },
+          style: {
+            type: "object",
+            properties: {
+              shape: { type: "string", enum: ["block", "underline", "bar"] },
+              blink: { type: "boolean" },
+            },
+            required: ["shape", "blink"],
+          },
+          visible: { type: "boolean" },
+        },
+        required: ["x", "y", "style", "visible"],
+      },
+      modes: {
+        type: "object",
+        properties: {
+          alternateScreen: { type: "boolean" },
+          applicationCursorKeys: { type: "boolean" },
+          applicationKeypad: { type: "boolean" },
+          bracketedPaste: { type: "boolean" },
+          mouseTracking: { type: "string", enum: ["none", "x10", "vt200", "drag", "any"] },
+        },
+        required: ["alternateScreen", "applicationCursorKeys", "applicationKeypad", "bracketedPaste", "mouseTracking"],
+      },
+      atPrompt: { type: "boolean", description: "Whether the cursor row matches the prompt pattern" },
+      cwd: {
+        type: ["object", "null"],
+        description: "Shell working directory; null when the shell doesn't report it and /proc is unavailable",
+        properties: {
+          path: { type: "string" },
+          source: { type: "string", enum: ["osc7", "proc"] },
+          host: { type: "string", description: "Set when the directory is on another machine (e.g. over ssh)" },
+        },
+        required: ["path", "source"],
+      },
+      secretPrompt: {
+        type: "object",
+        description: "Whether the terminal is waiting for a password or other secret",
+        properties: {
+          detected: { type: "boolean" },
+          echoOff: { type: ["boolean", "null"], description: "Echo is off on the PTY while reading a line; null when it can't be read" },
+          prompt: { type: ["string", "null"], description: "The cursor row, when it reads like a password prompt" },
+        },
+        required: ["detected", "echoOff", "prompt"],
+      },
+      controller: { type: "string", enum: ["shared", "human"] },
+      flow: {
+        type: "object",
+        description: "Handling of output arriving faster than it can be parsed (--flood-policy)",
+        properties: {
+          paused: { type: "boolean", description: "Reading is paused until the screen catches up" },
+          pauses: { type: "number" },
+          skippedBytes: { type: "number", description: "Output left off the screen with --flood-policy drop" },
+        },
+        required: ["paused", "pauses", "skippedBytes"],
+      },
+      process: {
+        type: "object",
+        description: "Only with --proc-tracking on Linux",
+        properties: {
+          cwd: { type: ["string", "null"] },
+          foreground: {
+            type: ["object", "null"],
+            properties: {
+              pid: { type: "number" },
+              command: { type: "string" },
+              args: { type: "array", items: { type: "string" } },
+              cwd: { type: ["string", "null"] },
+            },
+          },
+        },
+      },
+    },
+    required: ["sessionId", "isDefault", "shell", "running", "exitCode", "title", "dimensions", "cursor", "modes", "atPrompt", "cwd", "secretPrompt", "controller", "flow"],
+  },
+};
+
+export function handleGetStatus(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }>; structuredContent: Record<string, unknown> } {
+  const parsed = getStatusSchema.parse(args ?? {});
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const metadata = manager.getSessionMetadata(parsed.sessionId);
+  const buffer = session.getTerminal().buffer.active;
+  const processInfo = manager.getProcessInfo(parsed.sessionId);
+
+  const result = {
+    sessionId: metadata.sessionId,
+    isDefault: metadata.isDefault,
+    shell: metadata.shell,
+    ...(metadata.command && { command: metadata.command }),
+    running: session.getExitCode() === null,
+    exitCode: session.getExitCode(),
+    title: session.getTitle(),
+    dimensions: session.getDimensions(),
+    cursor: {
+      x: buffer.cursorX,
+      y: buffer.cursorY,
+      style: session.getCursorStyle(),
+      visible: session.isCursorVisible(),
+    },
+    modes: session.getModes(),
+    atPrompt: session.isAtPrompt(),
+    cwd: manager.getCwd(parsed.sessionId),
+    secretPrompt: session.getSecretPrompt(),
+    controller: manager.getController(parsed.sessionId),
+    flow: session.getFlowStats(),
+    ...(processInfo && { process: processInfo }),
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+    structuredContent: result,
+  };
+}
@@ -0,0 +1,40 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const getTitleSchema = z.object({
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type GetTitleArgs = z.infer<typeof getTitleSchema>;
+
+export const getTitleTool = {
+  name: "getTitle",
+  description: "Get the terminal window title as last set by the shell or running program (OSC 0/2). Shells commonly put the current directory or running command in the title, so this is a cheap way to see where the shell is or what it's doing. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export function handleGetTitle(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }> } {
+  const parsed = getTitleSchema.parse(args ?? {});
+  const title = manager.resolveSessionSync(parsed.sessionId).getTitle();
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: title || "(no title set)",
+      },
+    ],
+  };
+}
@@ -3,7 +3,9 @@ import {
   ListToolsRequestSchema,
   CallToolRequestSchema,
 } from "@modelcontextprotocol/sdk/types.js";
-import { TerminalManager } from "../terminal/index.js";
+import { OutputStreamer, OUTPUT_NOTIFICATION, TerminalManager } from "../terminal/index.js";
+import { ToolExamplesRequestSchema, getToolExamples, withExamples } from "./examples.js";
+import { withAnnotations } from "./annotations.js";
 
 import { typeTool, handleType } from "./type.js";
 import { sendKeyTool, handleSendKey } from "./sendKey.js";
@@ -14,6 +16,36 @@ import { stopRecordingTool, handleStopRecording } from "./stopRecording.js";
 import { createSessionTool, handleCreateSession } from "./createSession.js";
 import { listSessionsTool, handleListSessions } from "./listSessions.js";
 import { destroySessionTool, handleDestroySession } from "./destroySession.js";
+import { testPromptPatternTool, handleTestPromptPattern } from "./testPromptPattern.js";
+import { sendMouseTool, handleSendMouse } from "./sendMouse.js";
+import { getStatusTool, handleGetStatus } from "./getStatus.js";
+import { lookupHelpTool, handleLookupHelp } from "./lookupHelp.js";
+import { getTitleTool, handleGetTitle } from "./getTitle.js";
+import { diffScreenTool, handleDiffScreen } from "./diffScreen.js";
+import { listSnapshotsTool, handleListSnapshots } from "./listSnapshots.js";
+import { getSnapshotTool, handleGetSnapshot } from "./getSnapshot.js";
+import { getEnvironmentTool, handleGetEnvironment } from "./getEnvironment.js";
+import { getCursorTool, handleGetCursor } from "./getCursor.js";
+import { describeScreenTool, handleDescribeScreen } from "./describeScreen.js";
+import { getSelectionTool, handleGetSelection } from "./getSelection.js";
+import { markTool, handleMark } from "./mark.js";
+import { clearTool, handleClear } from "./clear.js";
+import { resetTool, handleReset } from "./reset.js";
+import { searchTool, handleSearch } from "./search.js";
+import { getLinksTool, handleGetLinks } from "./getLinks.js";
+import { getClipboardTool, handleGetClipboard } from "./getClipboard.js";
+import { subscribeOutputTool, handleSubscribeOutput } from "./subscribeOutput.js";
+import { unsubscribeOutputTool, handleUnsubscribeOutput } from "./unsubscribeOutput.js";
+import { restartShellTool, handleRestartShell } from "./restartShell.js";
+import { getForegroundProcessTool, handleGetForegroundProcess } from "./getForegroundProcess.js";
+import { isIdleTool, handleIsIdle } from "./isIdle.js";
+import { waitForExitTool, handleWaitForExit } from "./waitForExit.js";
+import { secureTypeTool, handleSecureType } from "./secureType.js";
+import { PROGRESS_NOTIFICATION, type ProgressSender } from "./progress.js";
+import { CompleteRequestSchema, completeArgument } from "./completions.js";
+import { createElicitationApprover } from "./approval.js";
+import { createInitGuard } from "../utils/initGuard.js";
+import { EXCLUSIVE_TOOLS, withToolTimeout } from "../utils/toolLimits.js";
 
 const tools = [
   typeTool,
@@ -25,61 +57,213 @@ const tools = [
   createSessionTool,
   listSessionsTool,
   destroySessionTool,
+  testPromptPatternTool,
+  sendMouseTool,
+  getStatusTool,
+  lookupHelpTool,
+  getTitleTool,
+  diffScreenTool,
+  listSnapshotsTool,
+  getSnapshotTool,
+  getEnvironmentTool,
+  getCursorTool,
+  describeScreenTool,
+  getSelectionTool,
+  markTool,
+  clearTool,
+  resetTool,
+  searchTool,
+  getLinksTool,
+  getClipboardTool,
+  subscribeOutputTool,
+  unsubscribeOutputTool,
+  restartShellTool,
+  getForegroundProcessTool,
+  isIdleTool,
+  waitForExitTool,
+  secureTypeTool,
 ];
 
-export function registerTools(server: Server, manager: TerminalManager): void {
+export interface RegisterToolsOptions {
+  /** Reject tools/call before the initialize handshake completes (default: true) */
+  strictInit?: boolean;
+}
+
+export function registerTools(server: Server, manager: TerminalManager, options: RegisterToolsOptions = {}): void {
+  const assertInitialized = createInitGuard(server, options.strictInit ?? true);
+  // Dangerous commands are put to the human through elicitation
+  const approve = createElicitationApprover(server);
+  // Each connected client has its own allowance
+  const rateLimiter = manager.createRateLimiter();
+
+  // Output pushed to this client by subscribeOutput
+  const streamer = new OutputStreamer(manager, (params) => {
+    server.notification({ method: OUTPUT_NOTIFICATION, params: { ...params } }).catch(() => {});
+  });
+  const onclose = server.onclose;
+  server.onclose = () => {
+    streamer.dispose();
+    onclose?.();
+  };
+
   // Register list tools handler
   server.setRequestHandler(ListToolsRequestSchema, async () => ({
-    tools,
+    tools: withExamples(withAnnotations(tools)),
   }));
 
+  // Extension method: worked argument/result examples per tool
+  server.setRequestHandler(ToolExamplesRequestSchema, async (request) => ({
+    examples: getToolExamples(request.params?.name),
+  }));
+
+  // Argument value completion (session IDs, key names, enums)
+  server.setRequestHandler(CompleteRequestSchema, async (request) =>
+    completeArgument(manager, tools, request.params)
+  );
+
   // Register call tool handler
-  server.setRequestHandler(CallToolRequestSchema, async (request) => {
+  server.setRequestHandler(CallToolRequestSchema, async (request, extra) => {
+    // Thrown outside the try below so it becomes a JSON-RPC error, not a tool result
+    assertInitialized();
     const { name, arguments: args } = request.params;
+    const progressToken = request.params._meta?.progressToken;
+    const progress: ProgressSender | undefined = progressToken === undefined
+      ? undefined
+      : (update) => {
+          extra.sendNotification({ method: PROGRESS_NOTIFICATION, params: { progressToken, ...update } }).catch(() => {});
+        };
+
+    const sessionId = typeof args?.sessionId === "string" ? args.sessionId : undefined;
+    // Thrown here so it becomes a JSON-RPC error with the retry delay in its data
+    rateLimiter?.admit(name, args);
+    return manager.auditToolCall(name, args, () => {
+      const run = async () => {
+        try {
+          manager.recordToolSnapshot(name, sessionId);
+
+          return await withToolTimeout(name, manager.getToolTimeout(name), async () => {
+            switch (name) {
+              case "type":
+                return await handleType(manager, args, approve);
+
+              case "sendKey":
+                return await handleSendKey(manager, args, progress, approve);
+
+              case "getContent":
+                return await handleGetContent(manager, args);
+
+              case "takeScreenshot":
+                return await handleScreenshot(manager, args);
+
+              case "startRecording":
+                return handleStartRecording(manager, args);
+
+              case "stopRecording":
+                return await handleStopRecording(manager, args);
+
+              case "createSession":
+                return await handleCreateSession(manager, args);
+
+              case "listSessions":
+                return handleListSessions(manager, args);
+
+              case "destroySession":
+                return handleDestroySession(manager, args);
+
+              case "testPromptPattern":
+                return handleTestPromptPattern(manager, args);
+
+              case "sendMouse":
+                return handleSendMouse(manager, args);
+
+              case "getStatus":
+                return handleGetStatus(manager, args);
+
+              case "lookupHelp":
+                return await handleLookupHelp(manager, args, progress);
+
+              case "getTitle":
+                return handleGetTitle(manager, args);
+
+              case "diffScreen":
+                return await handleDiffScreen(manager, args);
+
+              case "listSnapshots":
+                return handleListSnapshots(manager, args);
+
+              case "getSnapshot":
+                return handleGetSnapshot(manager, args);
+
+              case "getEnvironment":
+                return handleGetEnvironment(manager, args);
+
+              case "getCursor":
+                return await handleGetCursor(manager, args);
+
+              case "describeScreen":
+                return await handleDescribeScreen(manager, args);
+
+              case "getSelection":
+                return await handleGetSelection(manager, args);
+
+              case "mark":
+                return await handleMark(manager, args);
+
+              case "clear":
+                return await handleClear(manager, args);
+
+              case "reset":
+                return await handleReset(manager, args);
+
+              case "search":
+                return await handleSearch(manager, args);
 
-    try {
-      switch (name) {
-        case "type":
-          return handleType(manager, args);
+              case "getLinks":
+                return await handleGetLinks(manager, args);
 
-        case "sendKey":
-          return handleSendKey(manager, args);
+              case "getClipboard":
+                return handleGetClipboard(manager, args);
 
-        case "getContent":
-          return handleGetContent(manager, args);
+              case "subscribeOutput":
+                return handleSubscribeOutput(manager, args, streamer);
 
-        case "takeScreenshot":
-          return handleScreenshot(manager, args);
+              case "unsubscribeOutput":
+                return handleUnsubscribeOutput(manager, args, streamer);
 
-        case "startRecording":
-          return handleStartRecording(manager, args);
+              case "restartShell":
+                return await handleRestartShell(manager, args);
 
-        case "stopRecording":
-          return await handleStopRecording(manager, args);
+              case "getForegroundProcess":
+                return handleGetForegroundProcess(manager, args);
 
-        case "createSession":
-          return await handleCreateSession(manager, args);
+              case "isIdle":
+                return await handleIsIdle(manager, args);
 
-        case "listSessions":
-          return handleListSessions(manager, args);
+              case "waitForExit":
+                return await handleWaitForExit(manager, args, progress);
 
-        case "destroySession":
-          return handleDestroySession(manager, args);
+              case "secureType":
+                return await handleSecureType(manager, args);
 
-        default:
-          throw new Error(`Unknown tool: ${name}`);
-      }
-    } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
-      return {
-        content: [
-          {
-            type: "text" as const,
-            text: `Error: ${message}`,
-          },
-        ],
-        isError: true,
+              default:
+                throw new Error(`Unknown tool: ${name}`);
+            }
+          });
+        } catch (error) {
+          const message = error instanceof Error ? error.message : String(error);
+          return {
+            content: [
+              {
+                type: "text" as const,
+                text: `Error: ${message}`,
+              },
+            ],
+            isError: true,
+          };
+        }
       };
-    }
+      // Busy errors are thrown outside the try so they become JSON-RPC errors
+      return EXCLUSIVE_TOOLS.has(name) ? manager.runExclusive(name, sessionId, run) : run();
+    });
   });
 }
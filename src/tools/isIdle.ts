@@ -0,0 +1,95 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+const DEFAULT_QUIET_MS = 500;
+
+export const isIdleSchema = z.object({
+  quietMs: z
+    .number()
+    .int()
+    .nonnegative()
+    .max(60000)
+    .optional()
+    .default(DEFAULT_QUIET_MS)
+    .describe(`Output within this many ms counts as activity. Default: ${DEFAULT_QUIET_MS}`),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type IsIdleArgs = z.infer<typeof isIdleSchema>;
+
+export const isIdleTool = {
+  name: "isIdle",
+  description: "Cheaply check whether the shell is idle at its prompt, ready for the next command, or still busy. Idle means all of: the cursor row looks like a prompt, no full-screen program is open, no foreground job holds the terminal (read from /proc on Linux), the shell hasn't been given a command it hasn't reported finishing (bash and zsh report exit status), and there was no output in the last quietMs (default 500). reasons lists every signal that says busy. Use it before typing the next command instead of taking a screenshot. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      quietMs: {
+        type: "number",
+        description: `Output within this many ms counts as activity (max 60000). Default: ${DEFAULT_QUIET_MS}`,
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+  outputSchema: {
+    type: "object" as const,
+    properties: {
+      idle: { type: "boolean" },
+      reasons: { type: "array", items: { type: "string" }, description: "Why the session counts as busy; empty when idle" },
+      atPrompt: { type: "boolean" },
+      alternateScreen: { type: "boolean" },
+      foreground: { type: ["string", "null"], description: "Command of the foreground job; null at the prompt or without /proc" },
+      commandRunning: { type: ["boolean", "null"], description: "null when the shell doesn't report exit status" },
+      msSinceOutput: { type: ["number", "null"] },
+    },
+    required: ["idle", "reasons", "atPrompt", "alternateScreen", "foreground", "commandRunning", "msSinceOutput"],
+  },
+};
+
+export async function handleIsIdle(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }>; structuredContent: Record<string, unknown> }> {
+  const parsed = isIdleSchema.parse(args ?? {});
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+
+  const exitCode = session.getExitCode();
+  const atPrompt = session.isAtPrompt();
+  const alternateScreen = session.getModes().alternateScreen;
+  const group = exitCode === null ? manager.getForegroundGroup(parsed.sessionId) : null;
+  const foreground = group && !group.isShell ? group.command : null;
+  const commandRunning = session.isCommandRunning();
+  const msSinceOutput = session.getMsSinceOutput();
+
+  const reasons: string[] = [];
+  if (exitCode !== null) reasons.push(`the shell exited with code ${exitCode}`);
+  if (foreground) reasons.push(`'${foreground}' is running in the foreground`);
+  if (commandRunning) reasons.push("the last command hasn't finished");
+  if (alternateScreen) reasons.push("a full-screen program is open");
+  if (!atPrompt) reasons.push("the cursor row doesn't look like a prompt");
+  if (msSinceOutput !== null && msSinceOutput < parsed.quietMs) reasons.push(`output ${msSinceOutput} ms ago`);
+
+  const result = {
+    idle: reasons.length === 0,
+    reasons,
+    atPrompt,
+    alternateScreen,
+    foreground,
+    commandRunning,
+    msSinceOutput,
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+    structuredContent: result,
+  };
+}
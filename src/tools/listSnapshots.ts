@@ -0,0 +1,61 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const listSnapshotsSchema = z.object({
+  limit: z
+    .number()
+    .int()
+    .min(1)
+    .optional()
+    .describe("Only list the most recent N snapshots"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type ListSnapshotsArgs = z.infer<typeof listSnapshotsSchema>;
+
+export const listSnapshotsTool = {
+  name: "listSnapshots",
+  description: "List the session's automatic screen snapshot history, newest first. A snapshot is taken before each tool call, just before the screen is cleared or a full-screen app takes over, and optionally on a timer. Use this to find output that flashed by, such as an error printed right before `clear`, and then fetch it with getSnapshot. Each entry has an id, time, trigger, and a one-line preview.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      limit: {
+        type: "number",
+        description: "Only list the most recent N snapshots",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export function handleListSnapshots(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }> } {
+  const parsed = listSnapshotsSchema.parse(args ?? {});
+  const history = manager.resolveSessionSync(parsed.sessionId).getSnapshotHistory();
+  const newestFirst = [...history].reverse().slice(0, parsed.limit);
+
+  const snapshots = newestFirst.map((snap, i) => ({
+    n: i + 1,
+    id: snap.id,
+    takenAt: snap.takenAt,
+    trigger: snap.trigger,
+    ...(snap.tool && { tool: snap.tool }),
+    // Last non-blank row is usually the most recent output or the prompt
+    preview: [...snap.rows].reverse().find((row) => row.trim() !== "")?.trim() ?? "",
+  }));
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify({ total: history.length, snapshots }, null, 2),
+      },
+    ],
+  };
+}
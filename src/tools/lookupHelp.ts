@@ -0,0 +1,187 @@
+import { z } from "zod";
+import { execFile } from "child_process";
+import { TerminalManager } from "../terminal/index.js";
+import { stripAnsi } from "../utils/ansi.js";
+import { startProgress, type ProgressSender } from "./progress.js";
+
+const LOOKUP_TIMEOUT_MS = 5000;
+const MAX_OUTPUT_BYTES = 1024 * 1024;
+
+// Bare command/page names only — no paths, no shell metacharacters. Lookups
+// resolve through PATH/MANPATH just like typing the name at a prompt.
+const NAME_PATTERN = /^[A-Za-z0-9_][A-Za-z0-9._+-]*$/;
+
+export const lookupHelpSchema = z.object({
+  command: z
+    .string()
+    .regex(NAME_PATTERN, "Must be a bare command or man page name (no paths or shell syntax)")
+    .describe("Command or man page name (e.g., 'tar', 'git-rebase')"),
+  source: z
+    .enum(["auto", "help", "man"])
+    .optional()
+    .default("auto")
+    .describe("'help' runs '<command> --help', 'man' reads the man page, 'auto' (default) tries --help then man"),
+  section: z
+    .string()
+    .regex(/^[0-9n][a-z0-9]*$/i)
+    .optional()
+    .describe("Man page section (e.g., '1', '5', '3p')"),
+  maxLines: z.number().int().positive().optional().default(200).describe("Maximum lines to return (default: 200)"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type LookupHelpArgs = z.infer<typeof lookupHelpSchema>;
+
+export const lookupHelpTool = {
+  name: "lookupHelp",
+  description: "Look up usage information for a command without touching the interactive terminal. Runs '<command> --help' or reads the man page in a separate, hidden process (never the visible PTY, so there's no pager to fight and the screen isn't polluted) and returns cleaned plain text. Only bare command names are accepted.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      command: {
+        type: "string",
+        description: "Command or man page name (e.g., 'tar', 'git-rebase')",
+      },
+      source: {
+        type: "string",
+        enum: ["auto", "help", "man"],
+        description: "'help' runs '<command> --help', 'man' reads the man page, 'auto' (default) tries --help then man",
+      },
+      section: {
+        type: "string",
+        description: "Man page section (e.g., '1', '5', '3p')",
+      },
+      maxLines: {
+        type: "number",
+        description: "Maximum lines to return (default: 200)",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: ["command"],
+  },
+};
+
+/**
+ * Remove roff overstrike (bold "x\bx", underline "_\bx") and escape
+ * sequences (colors, OSC 8 hyperlinks) that man and some --help
+ * implementations emit.
+ */
+function cleanHelpText(text: string): string {
+  return stripAnsi(text)
+    .replace(/.\x08/g, "")
+    .replace(/\r\n/g, "\n")
+    .replace(/[ \t]+$/gm, "")
+    .replace(/\n{3,}/g, "\n\n")
+    .trim();
+}
+
+async function runHidden(
+  manager: TerminalManager,
+  cmd: string,
+  args: string[],
+  cols: number,
+  env: Record<string, string>
+): Promise<string | null> {
+  let spawnCmd = cmd;
+  let spawnArgs = args;
+
+  // Keep lookups inside the same sandbox as the shell
+  const sandbox = manager.getSandboxController();
+  if (sandbox?.isActive()) {
+    const wrapped = await sandbox.wrapShellCommand(cmd, args);
+    spawnCmd = wrapped.cmd;
+    spawnArgs = wrapped.args;
+  }
+
+  return new Promise((resolve) => {
+    execFile(
+      spawnCmd,
+      spawnArgs,
+      {
+        timeout: LOOKUP_TIMEOUT_MS,
+        maxBuffer: MAX_OUTPUT_BYTES,
+        env: {
+          // The shell's environment, so variables kept from it stay out here too
+          ...env,
+          MANPAGER: "cat",
+          PAGER: "cat",
+          MANWIDTH: String(cols),
+          COLUMNS: String(cols),
+          NO_COLOR: "1",
+          TERM: "dumb",
+        },
+      },
+      (error, stdout, stderr) => {
+        // Many tools print --help to stderr or exit non-zero after printing it
+        const output = cleanHelpText(`${stdout}${stdout && stderr ? "\n" : ""}${stderr}`);
+        if (error && (error as NodeJS.ErrnoException).code === "ENOENT") {
+          resolve(null);
+          return;
+        }
+        resolve(output.length > 0 ? output : null);
+      }
+    );
+  });
+}
+
+export async function handleLookupHelp(
+  manager: TerminalManager,
+  args: unknown,
+  progress?: ProgressSender
+): Promise<{ content: Array<{ type: "text"; text: string }>; isError?: boolean }> {
+  const parsed = lookupHelpSchema.parse(args);
+  const { cols } = manager.getDimensions(parsed.sessionId);
+  const env = manager.resolveSessionSync(parsed.sessionId).getSpawnEnv();
+
+  let text: string | null = null;
+  let usedSource: "help" | "man" | null = null;
+  let running = "";
+  const stopProgress = startProgress(progress, { status: () => `running ${running}` });
+
+  try {
+    if (parsed.source !== "man") {
+      running = `${parsed.command} --help`;
+      text = await runHidden(manager, parsed.command, ["--help"], cols, env);
+      if (text) usedSource = "help";
+    }
+    if (!text && parsed.source !== "help") {
+      const manArgs = parsed.section ? ["-P", "cat", parsed.section, parsed.command] : ["-P", "cat", parsed.command];
+      running = `man ${parsed.section ? `${parsed.section} ` : ""}${parsed.command}`;
+      text = await runHidden(manager, "man", manArgs, cols, env);
+      if (text) usedSource = "man";
+    }
+  } finally {
+    stopProgress();
+  }
+
+  if (!text || !usedSource) {
+    return {
+      content: [
+        {
+          type: "text",
+          text: `No help found for '${parsed.command}' (source: ${parsed.source})`,
+        },
+      ],
+      isError: true,
+    };
+  }
+
+  const lines = text.split("\n");
+  const truncated = lines.length > parsed.maxLines;
+  const body = truncated ? lines.slice(0, parsed.maxLines).join("\n") : text;
+  const footer = truncated
+    ? `\n\n[truncated: showing ${parsed.maxLines} of ${lines.length} lines; raise maxLines for more]`
+    : "";
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: `# ${parsed.command} (${usedSource === "help" ? "--help" : "man"})\n\n${body}${footer}`,
+      },
+    ],
+  };
+}
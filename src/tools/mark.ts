@@ -0,0 +1,53 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const markSchema = z.object({
+  name: z
+    .string()
+    .min(1)
+    .max(64)
+    .regex(/^[\w.:-]+$/)
+    .describe("Name for the mark, e.g. 'test-run' (letters, digits, _ . : -)"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type MarkArgs = z.infer<typeof markSchema>;
+
+export const markTool = {
+  name: "mark",
+  description: "Bookmark the current position in the output under a name: the cursor's line, which follows the output as it scrolls. Later, getContent with sinceMark returns everything from that line on, so 'give me everything since I started the test run' is one call: mark 'tests', type the command, then getContent sinceMark='tests'. Marking a name again moves it. Returns the mark's buffer line and every mark of the session (line null once it has scrolled out of the scrollback). Not available while a full-screen program uses the alternate screen. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      name: {
+        type: "string",
+        description: "Name for the mark, e.g. 'test-run' (letters, digits, _ . : -)",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: ["name"],
+  },
+};
+
+export async function handleMark(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = markSchema.parse(args);
+  // Output already sent by the shell belongs before the mark
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const mark = session.setMark(parsed.name);
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify({ mark, marks: session.getMarks() }, null, 2),
+      },
+    ],
+  };
+}
@@ -0,0 +1,79 @@
+import type { TerminalSession } from "../terminal/index.js";
+import { stripAnsi } from "../utils/ansi.js";
+
+/**
+ * MCP progress notifications for tool calls that wait. A client opts in by
+ * sending `_meta.progressToken` with the call; the transport turns that
+ * into a ProgressSender and handlers that block pass it to startProgress().
+ */
+
+export const PROGRESS_NOTIFICATION = "notifications/progress";
+export const PROGRESS_INTERVAL_MS = 1000;
+
+const TAIL_BUFFER_CHARS = 4096;
+const MAX_TAIL_CHARS = 200;
+
+export interface ProgressUpdate {
+  /** Milliseconds since the call started; increases with every update as MCP requires */
+  progress: number;
+  message: string;
+}
+
+export type ProgressSender = (update: ProgressUpdate) => void;
+
+export interface ProgressOptions {
+  /** Session whose newest output line is included in each message */
+  session?: TerminalSession;
+  /** What the call is doing right now, e.g. "12/40 keys sent" */
+  status?: () => string;
+}
+
+/**
+ * Last non-empty line of raw output as it would appear on screen: escape
+ * sequences removed and text overwritten by a carriage return dropped
+ */
+function lastLine(output: string): string | null {
+  const lines = stripAnsi(output).split("\n");
+  for (let i = lines.length - 1; i >= 0; i--) {
+    const line = lines[i].split("\r").filter((part) => part.trim()).pop()?.trim();
+    if (line) {
+      return line.length > MAX_TAIL_CHARS ? `…${line.slice(-MAX_TAIL_CHARS)}` : line;
+    }
+  }
+  return null;
+}
+
+/**
+ * Send a progress update every PROGRESS_INTERVAL_MS until the returned
+ * function is called. Does nothing when the client didn't ask for progress.
+ */
+export function startProgress(send: ProgressSender | undefined, options: ProgressOptions = {}): () => void {
+  if (!send) return () => {};
+
+  const startedAt = Date.now();
+  let output = "";
+  const unsubscribe = options.session?.onData((data) => {
+    output = (output + data).slice(-TAIL_BUFFER_CHARS);
+  });
+
+  const timer = setInterval(() => {
+    const elapsed = Date.now() - startedAt;
+    const parts = [`${Math.round(elapsed / 1000)}s elapsed`];
+    const status = options.status?.();
+    if (status) parts.push(status);
+    if (options.session) {
+      const tail = lastLine(output);
+      parts.push(tail === null ? "no output yet" : `last output: ${tail}`);
+    }
+    try {
+      send({ progress: elapsed, message: parts.join("; ") });
+    } catch {
+      // Progress is best effort; the call itself carries on
+    }
+  }, PROGRESS_INTERVAL_MS);
+
+  return () => {
+    clearInterval(timer);
+    unsubscribe?.();
+  };
+}
@@ -0,0 +1,61 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const resetSchema = z.object({
+  clearScrollback: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("Full reset (RIS) that also empties the scrollback and forgets snapshot history, delta generations, and marks"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type ResetArgs = z.infer<typeof resetSchema>;
+
+export const resetTool = {
+  name: "reset",
+  description: "Recover a wedged terminal, e.g. after cat-ing a binary file or a program crashing in raw mode: garbled line-drawing characters, stuck colors, no echo, or Enter not working. Like the reset command, but it works even when typing doesn't. The emulator leaves the alternate screen and turns off the modes, character sets, and colors a program left on; the scrollback is kept unless clearScrollback=true, which does a full reset (RIS). The PTY gets 'stty sane' to turn echo and line editing back on; that needs a local PTY, so the result says whether it happened and, if not (ssh, docker, or --persist shells), what to type instead. Nothing is sent to the program. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      clearScrollback: {
+        type: "boolean",
+        description: "Full reset (RIS) that also empties the scrollback and forgets snapshot history, delta generations, and marks (default: false)",
+        default: false,
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export async function handleReset(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = resetSchema.parse(args ?? {});
+  // Let the garbage already received land before resetting over it
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const { ttyReset } = await session.resetTerminal({ clearScrollback: parsed.clearScrollback });
+
+  const lines = [
+    parsed.clearScrollback
+      ? "Reset the terminal and cleared the scrollback"
+      : "Reset the terminal's modes, character sets, and colors",
+    ttyReset
+      ? "Restored the TTY settings (stty sane)"
+      : "Could not restore the TTY settings here; if input still isn't echoed, type 'stty sane' and press Enter (Ctrl+J if Enter doesn't work)",
+  ];
+  return {
+    content: [
+      {
+        type: "text",
+        text: lines.join("\n"),
+      },
+    ],
+  };
+}
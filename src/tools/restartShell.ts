@@ -0,0 +1,52 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const restartShellSchema = z.object({
+  clearScrollback: z.boolean().optional().default(false).describe("Start with an empty screen and scrollback"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type RestartShellArgs = z.infer<typeof restartShellSchema>;
+
+export const restartShellTool = {
+  name: "restartShell",
+  description: "Start a new shell in a session after its shell exited (exit, crash, killed), or replace a running shell that is wedged. A running shell gets SIGHUP, then SIGKILL after 2s. The session keeps its ID, recordings, and subscriptions; scrollback is kept below a '[terminal-mcp] Shell restarted' marker unless clearScrollback=true. Shell state such as cwd, variables, and history is lost. In a session running a command (terminal-mcp -- <command>), runs the command again.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      clearScrollback: {
+        type: "boolean",
+        description: "Start with an empty screen and scrollback (default: false)",
+        default: false,
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export async function handleRestartShell(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = restartShellSchema.parse(args ?? {});
+  const metadata = await manager.restartShell(parsed.sessionId, parsed.clearScrollback);
+  const pid = manager.resolveSessionSync(parsed.sessionId).getPid();
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify({
+          sessionId: metadata.sessionId,
+          shell: metadata.shell,
+          pid,
+          clearedScrollback: parsed.clearScrollback,
+        }, null, 2),
+      },
+    ],
+  };
+}
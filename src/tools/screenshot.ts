@@ -1,10 +1,25 @@
 import { z } from "zod";
 import { TerminalManager } from "../terminal/index.js";
-import { renderTerminalToPng } from "../utils/render.js";
+import { renderTerminalToPng, renderTerminalToSvg, THEME_NAMES, type ThemeName } from "../utils/render.js";
+import { dumpScreen, getStyledRows } from "../utils/screenDump.js";
+import { renderTerminalToHtml } from "../utils/html.js";
+import { MAX_REFLOW_COLS, MIN_REFLOW_COLS, getTextLines, reflowTerminal, runsToAnsi } from "../utils/reflow.js";
+import type { HeadlessTerminal } from "../utils/emulate.js";
+import { compactText, numberLines } from "../utils/compact.js";
+import { findSelection } from "../utils/describe.js";
 
 export const screenshotSchema = z.object({
-  format: z.enum(["text", "ansi", "png"]).optional().describe(
-    "Output format: 'text' (default) returns plain JSON, 'ansi' returns text with ANSI color codes, 'png' returns a color screenshot image"
+  format: z.enum(["text", "compact", "ansi", "json", "html", "svg", "png"]).optional().describe(
+    "Output format: 'text' (default) returns plain JSON, 'compact' returns the screen as token-lean plain text, 'ansi' returns text with ANSI color codes, 'json' returns per-row styled runs, 'html' returns a self-contained HTML snippet, 'svg' returns a scalable SVG document, 'png' returns a color screenshot image"
+  ),
+  theme: z.enum(THEME_NAMES as [ThemeName, ...ThemeName[]]).optional().describe(
+    "Color theme for rendered formats: 'one-dark' (default), 'one-light', 'high-contrast', or 'colorblind' (deuteranopia-safe)"
+  ),
+  width: z.number().int().min(MIN_REFLOW_COLS).max(MAX_REFLOW_COLS).optional().describe(
+    "Render as if the terminal were this many columns wide, rewrapping long lines. Render-only: the live terminal is not resized. Not available while a full-screen app uses the alternate screen."
+  ),
+  lineNumbers: z.boolean().optional().default(false).describe(
+    "Prefix each row of the 'text' and 'compact' formats with its 0-indexed screen row"
   ),
   sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
 });
@@ -14,15 +29,30 @@ export type ScreenshotArgs = z.infer<typeof screenshotSchema>;
 export const screenshotTool = {
   name: "takeScreenshot",
   description:
-    "Capture terminal state. Format 'text' (default) returns plain JSON with content, cursor, dimensions. Format 'ansi' returns JSON with ANSI color escape codes preserved in the content field. Format 'png' returns a color screenshot image.",
+    "Capture terminal state. Format 'text' (default) returns plain JSON with content, cursor, dimensions. Format 'compact' returns the screen as bare text with trailing spaces dropped, runs of blank lines folded into '[N blank lines]', and a shared left margin trimmed, followed by a line with the size and cursor position; the cheapest way to look at a sparse screen. Format 'ansi' returns JSON with ANSI color escape codes preserved in the content field. Format 'json' returns a machine-readable structure: per-row runs of text with fg/bg color and attributes, plus OSC 8 hyperlinks on screen and the highlighted stretches (inverse or distinct background) that mark a TUI's selection. Format 'html' returns a self-contained <pre> snippet with inline styles for reports and PR comments. Format 'svg' returns a resolution-independent SVG document for documentation. Format 'png' returns a color screenshot image. Set lineNumbers=true to prefix each row of the 'text' or 'compact' content with its 0-indexed screen row ('17: ...'), for crop bounds and cursor math. Pass width to render a narrower (or wider) copy of the screen with long lines rewrapped, without resizing the live terminal.",
   inputSchema: {
     type: "object" as const,
     properties: {
       format: {
         type: "string",
-        enum: ["text", "ansi", "png"],
+        enum: ["text", "compact", "ansi", "json", "html", "svg", "png"],
         description:
-          "Output format: 'text' (default) plain JSON, 'ansi' for colored text with ANSI codes, 'png' for color screenshot image",
+          "Output format: 'text' (default) plain JSON, 'compact' for token-lean plain text with blank runs folded, 'ansi' for colored text with ANSI codes, 'json' for per-row styled runs, 'html' for an HTML snippet, 'svg' for a scalable vector image, 'png' for color screenshot image",
+      },
+      theme: {
+        type: "string",
+        enum: THEME_NAMES,
+        description:
+          "Color theme for rendered formats (html, svg, png): 'one-dark' (default), 'one-light', 'high-contrast', or 'colorblind' (deuteranopia-safe)",
+      },
+      width: {
+        type: "number",
+        description: `Render as if the terminal were this many columns wide (${MIN_REFLOW_COLS}-${MAX_REFLOW_COLS}), rewrapping long lines. Render-only: the live terminal is not resized.`,
+      },
+      lineNumbers: {
+        type: "boolean",
+        description: "Prefix each row of the 'text' and 'compact' formats with its 0-indexed screen row, e.g. '17: ' (default: false)",
+        default: false,
       },
       sessionId: {
         type: "string",
@@ -33,23 +63,57 @@ export const screenshotTool = {
   },
 };
 
-export function handleScreenshot(
+type ScreenshotContent = Array<{ type: "text"; text: string } | { type: "image"; data: string; mimeType: string }>;
+// The JSON formats (text, ansi, json) also return their object as structuredContent
+type ScreenshotResult = { content: ScreenshotContent; structuredContent?: Record<string, unknown> };
+
+export async function handleScreenshot(
   manager: TerminalManager,
   args: unknown
-): { content: Array<{ type: "text"; text: string } | { type: "image"; data: string; mimeType: string }> } {
+): Promise<ScreenshotResult> {
   const parsed = screenshotSchema.parse(args);
+  await manager.waitForReadable(parsed.sessionId);
+
+  if (parsed.width === undefined) {
+    return renderScreenshot(manager, parsed, null);
+  }
+  const reflowed = await reflowTerminal(manager.getTerminal(parsed.sessionId), parsed.width);
+  try {
+    return renderScreenshot(manager, parsed, reflowed);
+  } finally {
+    reflowed.dispose();
+  }
+}
+
+/**
+ * Render the screenshot from the live terminal, or from a reflowed copy
+ * when one is given.
+ */
+function renderScreenshot(
+  manager: TerminalManager,
+  parsed: ScreenshotArgs,
+  reflowed: HeadlessTerminal | null
+): ScreenshotResult {
   const format = parsed.format || "text";
+  if (parsed.lineNumbers && format !== "text" && format !== "compact") {
+    throw new Error(`lineNumbers applies to the 'text' and 'compact' formats, not '${format}'`);
+  }
+  const terminal = reflowed ?? manager.getTerminal(parsed.sessionId);
 
   if (format === "ansi") {
-    const content = manager.getAnsiContent(true, parsed.sessionId);
-    const buffer = manager.getTerminal(parsed.sessionId).buffer.active;
+    const session = manager.resolveSessionSync(parsed.sessionId);
+    const buffer = terminal.buffer.active;
+    const content = reflowed
+      ? getStyledRows(reflowed, buffer.baseY, buffer.baseY + reflowed.rows).map((row) => runsToAnsi(row.runs)).join("\n")
+      : manager.getAnsiContent(true, parsed.sessionId);
     const result = {
       content,
-      cursor: { x: buffer.cursorX, y: buffer.cursorY },
+      cursor: { x: buffer.cursorX, y: buffer.cursorY, style: session.getCursorStyle() },
       dimensions: {
-        cols: manager.getDimensions(parsed.sessionId).cols,
-        rows: manager.getDimensions(parsed.sessionId).rows,
+        cols: terminal.cols,
+        rows: terminal.rows,
       },
+      modes: session.getModes(),
     };
     return {
       content: [
@@ -58,31 +122,128 @@ export function handleScreenshot(
           text: JSON.stringify(result, null, 2),
         },
       ],
+      structuredContent: result,
     };
   }
 
-  if (format === "png") {
-    const terminal = manager.getTerminal(parsed.sessionId);
-    const pngBuffer = renderTerminalToPng(terminal);
+  if (format === "compact") {
+    const buffer = terminal.buffer.active;
+    const screen = compactText(
+      getTextLines(terminal, buffer.baseY, buffer.baseY + terminal.rows).join("\n"),
+      parsed.lineNumbers ? 0 : null
+    );
+    const status = `[${terminal.cols}x${terminal.rows}, cursor at row ${buffer.cursorY}, col ${buffer.cursorX}]`;
+    return {
+      content: [
+        {
+          type: "text",
+          text: screen ? `${screen}\n${status}` : status,
+        },
+      ],
+    };
+  }
+
+  if (format === "json") {
+    const session = manager.resolveSessionSync(parsed.sessionId);
+    const dump = dumpScreen(terminal);
+    const baseY = terminal.buffer.active.baseY;
+    // OSC 8 links on screen, in screen coordinates. Link positions refer to
+    // the live layout, so they are left out of a reflowed dump.
+    const links = reflowed
+      ? undefined
+      : session.getLinks(true).map((link) => ({
+          uri: link.uri,
+          text: link.text,
+          row: link.start.line - baseY,
+          col: link.start.col,
+          endRow: link.end.line - baseY,
+          endCol: link.end.col,
+        }));
+    const result = {
+      dimensions: dump.dimensions,
+      cursor: { ...dump.cursor, style: session.getCursorStyle() },
+      modes: session.getModes(),
+      rows: dump.rows,
+      links,
+      highlighted: findSelection(terminal).highlighted,
+    };
+    return {
+      content: [
+        {
+          type: "text" as const,
+          text: JSON.stringify(result),
+        },
+      ],
+      structuredContent: result,
+    };
+  }
+
+  if (format === "html") {
+    return {
+      content: [
+        {
+          type: "text",
+          text: renderTerminalToHtml(terminal, { theme: parsed.theme }),
+        },
+      ],
+    };
+  }
 
+  if (format === "svg") {
+    const { svg } = renderTerminalToSvg(terminal, {
+      theme: parsed.theme,
+      fallbackFonts: manager.getFontOptions().fallbackFonts,
+    });
     return {
       content: [
         {
-          type: "image",
-          data: pngBuffer.toString("base64"),
-          mimeType: "image/png",
+          type: "text",
+          text: svg,
         },
       ],
     };
   }
 
+  if (format === "png") {
+    const { png, warnings } = renderTerminalToPng(terminal, {
+      ...manager.getFontOptions(),
+      theme: parsed.theme,
+    });
+
+    const content: ScreenshotContent = [
+      {
+        type: "image",
+        data: png.toString("base64"),
+        mimeType: "image/png",
+      },
+    ];
+    // Report glyphs drawn as placeholders so the caller knows the image is lossy
+    if (warnings.length > 0) {
+      content.push({
+        type: "text",
+        text: JSON.stringify({ warnings }, null, 2),
+      });
+    }
+    return { content };
+  }
+
   // Default text format
   const screenshot = manager.takeScreenshot(parsed.sessionId);
-  const result = {
-    content: screenshot.content,
-    cursor: screenshot.cursor,
-    dimensions: screenshot.dimensions,
-  };
+  const result = reflowed
+    ? {
+        content: getTextLines(reflowed, reflowed.buffer.active.baseY, reflowed.buffer.active.baseY + reflowed.rows).join("\n"),
+        cursor: { ...screenshot.cursor, x: reflowed.buffer.active.cursorX, y: reflowed.buffer.active.cursorY },
+        dimensions: { cols: reflowed.cols, rows: reflowed.rows },
+        modes: screenshot.modes,
+      }
+    : {
+        content: screenshot.content,
+        cursor: screenshot.cursor,
+        dimensions: screenshot.dimensions,
+        modes: screenshot.modes,
+      };
+
+  if (parsed.lineNumbers) result.content = numberLines(result.content, 0);
 
   return {
     content: [
@@ -91,5 +252,6 @@ export function handleScreenshot(
         text: JSON.stringify(result, null, 2),
       },
     ],
+    structuredContent: result,
   };
 }
@@ -0,0 +1,117 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const searchSchema = z.object({
+  pattern: z.string().min(1).describe("Regex to search for (JavaScript syntax)"),
+  flags: z.string().optional().default("").describe("Regex flags, e.g. 'i' for case-insensitive (g and y are ignored)"),
+  context: z
+    .number()
+    .int()
+    .min(0)
+    .max(20)
+    .optional()
+    .default(2)
+    .describe("Lines of context to include before and after each match (default: 2)"),
+  visibleOnly: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("If true, only search the visible viewport. If false, include scrollback."),
+  maxMatches: z.number().int().positive().optional().default(20).describe("Maximum number of matches to return (default: 20)"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type SearchArgs = z.infer<typeof searchSchema>;
+
+export const searchTool = {
+  name: "search",
+  description: "Search the visible screen and scrollback with a regex and return only the matching lines, each with surrounding context lines. Much cheaper than getContent when you need to find something specific, e.g. the line containing 'error' in a long build log. Each match reports its buffer line (0 = oldest scrollback line), its screen row when visible (0-indexed, null if scrolled off), and the character offset of the match within the line. Newest matches are returned first.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      pattern: {
+        type: "string",
+        description: "Regex to search for (JavaScript syntax), e.g. 'error|failed'",
+      },
+      flags: {
+        type: "string",
+        description: "Regex flags, e.g. 'i' for case-insensitive (g and y are ignored)",
+      },
+      context: {
+        type: "number",
+        description: "Lines of context before and after each match (default: 2, max: 20)",
+      },
+      visibleOnly: {
+        type: "boolean",
+        description: "If true, only search the visible viewport. If false (default), include scrollback.",
+        default: false,
+      },
+      maxMatches: {
+        type: "number",
+        description: "Maximum number of matches to return (default: 20)",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: ["pattern"],
+  },
+};
+
+export async function handleSearch(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = searchSchema.parse(args);
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+
+  let regex: RegExp;
+  try {
+    regex = new RegExp(parsed.pattern, parsed.flags.replace(/[gy]/g, ""));
+  } catch (error) {
+    throw new Error(`Invalid regex: ${(error as Error).message}`);
+  }
+
+  const lines = session.getBufferLines(parsed.visibleOnly);
+  const baseY = session.getTerminal().buffer.active.baseY;
+
+  // Scan newest first: the most recent output is usually what's wanted
+  const matches = [];
+  let totalMatches = 0;
+  for (let i = lines.length - 1; i >= 0; i--) {
+    const match = regex.exec(lines[i].text);
+    if (!match) continue;
+    totalMatches++;
+    if (matches.length >= parsed.maxMatches) continue;
+
+    const { line, text, inViewport } = lines[i];
+    matches.push({
+      line,
+      screenRow: inViewport ? line - baseY : null,
+      index: match.index,
+      match: match[0],
+      text,
+      before: lines.slice(Math.max(0, i - parsed.context), i).map((l) => l.text),
+      after: lines.slice(i + 1, i + 1 + parsed.context).map((l) => l.text),
+    });
+  }
+
+  const result = {
+    pattern: regex.source,
+    flags: regex.flags,
+    linesSearched: lines.length,
+    totalMatches,
+    matches,
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+  };
+}
@@ -0,0 +1,70 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+
+export const secureTypeSchema = z.object({
+  text: z.string().min(1).describe("The secret to type, as given by the user"),
+  submit: z.boolean().optional().default(false).describe("Press Enter after the secret. Default: false"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type SecureTypeArgs = z.infer<typeof secureTypeSchema>;
+
+export const secureTypeTool = {
+  name: "secureType",
+  description: "Type a password, passphrase, or other secret the user has given you into a prompt waiting for it. Unlike type, the text is never written to the audit log or recordings and is not repeated in the result, which only gives the number of characters typed. Only works while the terminal has echo turned off at a prompt (getStatus' secretPrompt.echoOff), so it can't be used to run commands unseen; a prompt like 'Password:' is only trusted when the terminal modes can't be read, and then submit is refused. The text may not contain control characters; pass submit=true to press Enter after it. Never guess or make up credentials. Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      text: {
+        type: "string",
+        description: "The secret to type, as given by the user",
+      },
+      submit: {
+        type: "boolean",
+        description: "Press Enter after the secret. Default: false",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: ["text"],
+  },
+};
+
+export async function handleSecureType(
+  manager: TerminalManager,
+  args: unknown
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = secureTypeSchema.parse(args);
+  // The error must not quote the text
+  if (/[\x00-\x1f\x7f]/.test(parsed.text)) {
+    throw new Error("secureType text may not contain control characters; use submit to press Enter");
+  }
+  await manager.waitForReadable(parsed.sessionId);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  // A row that merely reads like a prompt ("~/passcode>") can be a shell,
+  // so the text only decides when the termios flags can't be read
+  const { echoOff, prompt } = session.getSecretPrompt();
+  if (!(echoOff === true || (echoOff === null && prompt !== null))) {
+    throw new Error(
+      "The terminal isn't waiting for a password or other secret with echo off (see getStatus' secretPrompt); use type for other input"
+    );
+  }
+  if (parsed.submit && echoOff !== true) {
+    throw new Error(
+      "Can't confirm that echo is off, so secureType won't press Enter; send it with sendKey once the secret is typed"
+    );
+  }
+
+  manager.write(parsed.submit ? `${parsed.text}\r` : parsed.text, parsed.sessionId, { secret: true });
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: `Typed ${parsed.text.length} secret character(s) to terminal${parsed.submit ? " and pressed Enter" : ""}`,
+      },
+    ],
+  };
+}
@@ -1,11 +1,39 @@
 import { z } from "zod";
 import { TerminalManager } from "../terminal/index.js";
-import { getKeySequence, getAvailableKeys } from "../utils/keys.js";
+import { getKeySequence, getAvailableKeys, type KeyEncodingModes } from "../utils/keys.js";
+import { startProgress, type ProgressSender } from "./progress.js";
+import { confirmInput, type InputApprover } from "./approval.js";
 
-export const sendKeySchema = z.object({
-  key: z.string().describe("The key to send (e.g., 'Enter', 'Tab', 'Ctrl+C', 'ArrowUp')"),
-  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
-});
+const MAX_KEY_PRESSES = 500;
+
+export const sendKeySchema = z
+  .object({
+    key: z.string().optional().describe("The key to send (e.g., 'Enter', 'Tab', 'Ctrl+C', 'ArrowUp', 'U+00E9', '0x1b 0x5b 0x41')"),
+    keys: z
+      .array(z.string())
+      .min(1)
+      .optional()
+      .describe("Sequence of keys to send in order (e.g., ['ArrowDown', 'ArrowDown', 'Enter'])"),
+    count: z
+      .number()
+      .int()
+      .positive()
+      .optional()
+      .default(1)
+      .describe("Number of times to send the key (or the whole keys sequence). Default: 1"),
+    delayMs: z
+      .number()
+      .int()
+      .nonnegative()
+      .max(5000)
+      .optional()
+      .default(0)
+      .describe("Delay in milliseconds between key presses. Default: 0 (send all at once)"),
+    sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+  })
+  .refine((args) => (args.key === undefined) !== (args.keys === undefined), {
+    message: "Provide exactly one of 'key' or 'keys'",
+  });
 
 export type SendKeyArgs = z.infer<typeof sendKeySchema>;
 
@@ -13,41 +41,105 @@ const availableKeys = getAvailableKeys();
 
 export const sendKeyTool = {
   name: "sendKey",
-  description: "Send a special key or key combination to the terminal. Common keys: Enter, Tab, Escape, Backspace, Delete, ArrowUp/Down/Left/Right, Home, End, PageUp, PageDown. Control sequences: Ctrl+C (interrupt), Ctrl+D (EOF), Ctrl+Z (suspend), Ctrl+L (clear screen), Ctrl+A (line start), Ctrl+E (line end), Ctrl+U (clear line). Function keys: F1-F12. Pass sessionId to target a specific session.",
+  description: "Send a special key or key combination to the terminal. Common keys: Enter, Tab, Escape, Backspace, Delete, ArrowUp/Down/Left/Right, Home, End, PageUp, PageDown. Control sequences: Ctrl+C (interrupt), Ctrl+D (EOF), Ctrl+Z (suspend), Ctrl+L (clear screen), Ctrl+A (line start), Ctrl+E (line end), Ctrl+U (clear line). Function keys: F1-F12. Keypad: Numpad0-9, NumpadEnter, NumpadAdd, etc. Any other character or byte sequence can be given by value: a Unicode codepoint like 'U+00E9' (é), or raw bytes in hex like '0x1b 0x5b 0x41' (ESC [ A, sent as-is whatever the input modes); bytes must form valid UTF-8. Arrow, Home/End and keypad keys automatically use application-mode encoding when the running program has enabled it. Use count to repeat a key (e.g. ArrowDown 10 times) or keys to send a sequence in one call (e.g. ['ArrowDown', 'ArrowDown', 'Enter']), with optional delayMs between presses for apps that drop fast input. Pass sessionId to target a specific session.",
   inputSchema: {
     type: "object" as const,
     properties: {
       key: {
         type: "string",
-        description: "The key to send (e.g., 'Enter', 'Tab', 'Ctrl+C', 'ArrowUp', 'Escape')",
+        description: "The key to send (e.g., 'Enter', 'Tab', 'Ctrl+C', 'ArrowUp', 'Escape'), a codepoint ('U+00E9'), or raw bytes in hex ('0x1b 0x5b 0x41')",
+      },
+      keys: {
+        type: "array",
+        items: { type: "string" },
+        description: "Sequence of keys to send in order, instead of a single key (e.g., ['ArrowDown', 'ArrowDown', 'Enter'])",
+      },
+      count: {
+        type: "number",
+        description: "Number of times to send the key (or the whole keys sequence). Default: 1",
+      },
+      delayMs: {
+        type: "number",
+        description: "Delay in milliseconds between key presses (max 5000). Default: 0 (send all at once)",
       },
       sessionId: {
         type: "string",
         description: "Target session ID. Omit to target the default session.",
       },
     },
-    required: ["key"],
+    required: [],
   },
 };
 
-export function handleSendKey(manager: TerminalManager, args: unknown): { content: Array<{ type: "text"; text: string }> } {
-  const parsed = sendKeySchema.parse(args);
-  const sequence = getKeySequence(parsed.key);
-
+function resolveKey(key: string, modes: KeyEncodingModes): string {
+  const sequence = getKeySequence(key, modes);
   if (sequence === null) {
-    const available = getAvailableKeys();
     throw new Error(
-      `Unknown key: "${parsed.key}". Available keys include: ${available.slice(0, 15).join(", ")}...`
+      `Unknown key: "${key}". Available keys include: ${availableKeys.slice(0, 15).join(", ")}..., ` +
+        `or give a codepoint ("U+00E9") or raw bytes in hex ("0x1b 0x5b 0x41")`
     );
   }
+  return sequence;
+}
+
+export async function handleSendKey(
+  manager: TerminalManager,
+  args: unknown,
+  progress?: ProgressSender,
+  approve?: InputApprover
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
+  const parsed = sendKeySchema.parse(args);
+  const names = parsed.keys ?? [parsed.key!];
+
+  // Arrow/Home/End and keypad keys are encoded differently once the app
+  // switches to application cursor/keypad mode (vim, less, etc.)
+  const modes = manager.resolveSessionSync(parsed.sessionId).getModes();
+
+  // Resolve every key up front so an unknown name fails before anything is sent
+  const sequences = names.map((name) => resolveKey(name, modes));
+
+  const presses: string[] = [];
+  for (let i = 0; i < parsed.count; i++) {
+    presses.push(...sequences);
+  }
+  if (presses.length > MAX_KEY_PRESSES) {
+    throw new Error(`Too many key presses (${presses.length}); the limit is ${MAX_KEY_PRESSES} per call`);
+  }
+  // Usually the Enter that submits a command typed earlier
+  await confirmInput(manager, presses.join(""), parsed.sessionId, approve);
+
+  if (parsed.delayMs === 0) {
+    manager.write(presses.join(""), parsed.sessionId);
+  } else {
+    let sent = 0;
+    const stopProgress = startProgress(progress, {
+      session: manager.resolveSessionSync(parsed.sessionId),
+      status: () => `${sent}/${presses.length} keys sent`,
+    });
+    try {
+      for (; sent < presses.length; sent++) {
+        if (sent > 0) {
+          await new Promise((resolve) => setTimeout(resolve, parsed.delayMs));
+        }
+        manager.write(presses[sent], parsed.sessionId);
+      }
+    } finally {
+      stopProgress();
+    }
+  }
 
-  manager.write(sequence, parsed.sessionId);
+  const label = names.join(", ");
+  const text = presses.length === 1
+    ? `Sent key: ${label}`
+    : parsed.count > 1
+      ? `Sent ${parsed.keys ? `[${label}]` : label} x${parsed.count} (${presses.length} key presses)`
+      : `Sent keys: ${label}`;
 
   return {
     content: [
       {
         type: "text",
-        text: `Sent key: ${parsed.key}`,
+        text,
       },
     ],
   };
@@ -0,0 +1,171 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+import { encodeMouseEvent, modeReports, type MouseEvent } from "../utils/mouse.js";
+
+export const sendMouseSchema = z.object({
+  action: z
+    .enum(["click", "doubleClick", "press", "release", "scroll", "drag", "move"])
+    .describe("Mouse action to perform"),
+  row: z.number().int().nonnegative().describe("Target row (0-indexed, relative to the visible viewport)"),
+  col: z.number().int().nonnegative().describe("Target column (0-indexed)"),
+  button: z.enum(["left", "middle", "right"]).optional().default("left").describe("Mouse button (default: left)"),
+  modifiers: z.array(z.enum(["shift", "alt", "ctrl"])).optional().describe("Modifier keys held during the event"),
+  direction: z.enum(["up", "down"]).optional().describe("Scroll direction (required for action 'scroll')"),
+  amount: z.number().int().positive().max(100).optional().default(1).describe("Number of scroll steps (default: 1)"),
+  toRow: z.number().int().nonnegative().optional().describe("Drag end row (required for action 'drag')"),
+  toCol: z.number().int().nonnegative().optional().describe("Drag end column (required for action 'drag')"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type SendMouseArgs = z.infer<typeof sendMouseSchema>;
+
+export const sendMouseTool = {
+  name: "sendMouse",
+  description: "Send mouse input to a TUI that has enabled mouse reporting (htop, lazygit, tig, vim with mouse=a, etc.). Actions: click, doubleClick, press, release, scroll (with direction/amount), drag (from row/col to toRow/toCol), move. Coordinates are 0-indexed within the visible screen, matching takeScreenshot's cursor position. Uses SGR (1006) encoding when the application requested it, otherwise the legacy encoding. Fails if the application has not enabled mouse reporting.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      action: {
+        type: "string",
+        enum: ["click", "doubleClick", "press", "release", "scroll", "drag", "move"],
+        description: "Mouse action to perform",
+      },
+      row: {
+        type: "number",
+        description: "Target row (0-indexed, relative to the visible viewport)",
+      },
+      col: {
+        type: "number",
+        description: "Target column (0-indexed)",
+      },
+      button: {
+        type: "string",
+        enum: ["left", "middle", "right"],
+        description: "Mouse button (default: left)",
+      },
+      modifiers: {
+        type: "array",
+        items: { type: "string", enum: ["shift", "alt", "ctrl"] },
+        description: "Modifier keys held during the event",
+      },
+      direction: {
+        type: "string",
+        enum: ["up", "down"],
+        description: "Scroll direction (required for action 'scroll')",
+      },
+      amount: {
+        type: "number",
+        description: "Number of scroll steps (default: 1)",
+      },
+      toRow: {
+        type: "number",
+        description: "Drag end row (required for action 'drag')",
+      },
+      toCol: {
+        type: "number",
+        description: "Drag end column (required for action 'drag')",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: ["action", "row", "col"],
+  },
+};
+
+/**
+ * Expand a high-level action into the individual mouse events a real
+ * terminal would report for it.
+ */
+function buildEvents(args: SendMouseArgs): MouseEvent[] {
+  const { row, col, button, modifiers } = args;
+  const press: MouseEvent = { row, col, button, action: "press", modifiers };
+  const release: MouseEvent = { row, col, button, action: "release", modifiers };
+
+  switch (args.action) {
+    case "click":
+      return [press, release];
+    case "doubleClick":
+      return [press, release, press, release];
+    case "press":
+      return [press];
+    case "release":
+      return [release];
+    case "move":
+      return [{ row, col, button: "none", action: "motion", modifiers }];
+    case "scroll": {
+      if (!args.direction) {
+        throw new Error("action 'scroll' requires a direction ('up' or 'down')");
+      }
+      const wheel: MouseEvent["button"] = args.direction === "up" ? "wheelUp" : "wheelDown";
+      return Array.from({ length: args.amount }, (): MouseEvent => ({
+        row, col, button: wheel, action: "press", modifiers,
+      }));
+    }
+    case "drag": {
+      if (args.toRow === undefined || args.toCol === undefined) {
+        throw new Error("action 'drag' requires toRow and toCol");
+      }
+      return [
+        press,
+        { row: args.toRow, col: args.toCol, button, action: "motion", modifiers },
+        { row: args.toRow, col: args.toCol, button, action: "release", modifiers },
+      ];
+    }
+  }
+}
+
+export function handleSendMouse(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }> } {
+  const parsed = sendMouseSchema.parse(args);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const { cols, rows } = session.getDimensions();
+  const { tracking, encoding } = session.getMouseState();
+
+  if (tracking === "none") {
+    throw new Error(
+      "The application has not enabled mouse reporting. Use sendKey for keyboard navigation instead."
+    );
+  }
+
+  const events = buildEvents(parsed);
+  for (const event of events) {
+    if (event.row >= rows || event.col >= cols) {
+      throw new Error(`Position (${event.row}, ${event.col}) is outside the ${cols}x${rows} screen`);
+    }
+  }
+
+  let sequence = "";
+  let sent = 0;
+  let buttonHeld = false;
+  for (const event of events) {
+    if (modeReports(tracking, event.action, buttonHeld)) {
+      const encoded = encodeMouseEvent(event, encoding);
+      if (encoded === null) {
+        throw new Error(
+          "Position is beyond column/row 223, which the application's legacy mouse encoding can't represent"
+        );
+      }
+      sequence += encoded;
+      sent++;
+    }
+    if (event.action === "press" && !event.button.startsWith("wheel")) buttonHeld = true;
+    if (event.action === "release") buttonHeld = false;
+  }
+
+  if (sequence) {
+    manager.write(sequence, parsed.sessionId);
+  }
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: `Sent mouse ${parsed.action} at row ${parsed.row}, col ${parsed.col} (${sent} event(s), ${tracking} tracking, ${encoding} encoding)`,
+      },
+    ],
+  };
+}
@@ -8,11 +8,12 @@ export const startRecordingSchema = z.object({
   idleTimeLimit: z.number().optional().default(2),
   maxDuration: z.number().optional().default(3600),
   inactivityTimeout: z.number().optional().default(600),
+  captureInput: z.boolean().optional().default(false),
 });
 
 export const startRecordingTool = {
   name: "startRecording",
-  description: "Start recording terminal output to an asciicast v2 file. Returns the recording ID and path where the file will be saved. Only one recording can be active at a time. Recording captures output from all active sessions in the process.",
+  description: "Start recording terminal output to an asciicast v2 file. Returns the recording ID and path where the file will be saved. Only one recording can be active at a time. Recording captures output from all active sessions in the process; set captureInput to also record keystrokes sent to the terminal.",
   inputSchema: {
     type: "object" as const,
     properties: {
@@ -42,6 +43,10 @@ export const startRecordingTool = {
         type: "number",
         description: "Stop recording after N seconds of no terminal output (default: 600 = 10 minutes). Resets on each output event.",
       },
+      captureInput: {
+        type: "boolean",
+        description: "Also record input (typed text and keys) as asciicast \"i\" events (default: false). Input may include secrets such as passwords.",
+      },
     },
     required: [],
   },
@@ -81,6 +86,7 @@ export function handleStartRecording(
     idleTimeLimit: parsed.idleTimeLimit,
     maxDuration: parsed.maxDuration,
     inactivityTimeout: parsed.inactivityTimeout,
+    captureInput: parsed.captureInput,
   });
 
   // Get current dimensions and start recording
@@ -110,6 +116,7 @@ export function handleStartRecording(
     mode: parsed.mode,
     maxDuration: parsed.maxDuration,
     inactivityTimeout: parsed.inactivityTimeout,
+    captureInput: parsed.captureInput,
     message: timeoutMessage,
   };
 
@@ -0,0 +1,77 @@
+import { z } from "zod";
+import { DEFAULT_OUTPUT_DEBOUNCE_MS, OUTPUT_NOTIFICATION, TerminalManager, type OutputStreamer } from "../terminal/index.js";
+
+export const subscribeOutputSchema = z.object({
+  debounceMs: z
+    .number()
+    .int()
+    .min(20)
+    .max(10000)
+    .optional()
+    .describe(`Coalesce output for this long before each notification (default: ${DEFAULT_OUTPUT_DEBOUNCE_MS})`),
+  raw: z.boolean().optional().default(false).describe("Keep escape sequences in the streamed output"),
+  collapseProgress: z
+    .boolean()
+    .optional()
+    .default(true)
+    .describe("Send only the final state of lines redrawn with carriage returns (ignored with raw)"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type SubscribeOutputArgs = z.infer<typeof subscribeOutputSchema>;
+
+export const subscribeOutputTool = {
+  name: "subscribeOutput",
+  description: `Stream a session's new output to this client as '${OUTPUT_NOTIFICATION}' notifications ({sessionId, seq, data}) instead of polling getContent. Output is coalesced for debounceMs (default ${DEFAULT_OUTPUT_DEBOUNCE_MS}) after the first new chunk, with escape sequences stripped unless raw=true and progress-bar redraws (carriage returns) collapsed to their final state unless collapseProgress=false; bursts over 16K characters are cut to their tail and report 'dropped'. Useful for watching long builds. Only works if your client surfaces server notifications; stop with unsubscribeOutput. Calling again for the same session changes its settings.`,
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      debounceMs: {
+        type: "number",
+        description: `Coalesce output for this long before each notification (20-10000, default: ${DEFAULT_OUTPUT_DEBOUNCE_MS})`,
+      },
+      raw: {
+        type: "boolean",
+        description: "Keep escape sequences (colors, cursor movement) in the streamed output (default: false)",
+        default: false,
+      },
+      collapseProgress: {
+        type: "boolean",
+        description:
+          "Send only the final state of lines redrawn with carriage returns, such as pip, cargo, or docker pull progress bars, instead of every redraw (default: true; ignored with raw)",
+        default: true,
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+};
+
+export function handleSubscribeOutput(
+  manager: TerminalManager,
+  args: unknown,
+  streamer: OutputStreamer
+): { content: Array<{ type: "text"; text: string }> } {
+  const parsed = subscribeOutputSchema.parse(args ?? {});
+  const sessionId = streamer.subscribe(parsed.sessionId, {
+    debounceMs: parsed.debounceMs,
+    raw: parsed.raw,
+    collapseProgress: parsed.collapseProgress,
+  });
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify({
+          subscribed: sessionId,
+          notification: OUTPUT_NOTIFICATION,
+          subscriptions: streamer.getSubscriptions(),
+        }, null, 2),
+      },
+    ],
+  };
+}
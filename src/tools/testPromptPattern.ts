@@ -0,0 +1,83 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+import { compilePromptPattern, findPromptMatches } from "../terminal/prompt.js";
+
+export const testPromptPatternSchema = z.object({
+  pattern: z.string().describe("Candidate prompt regex (JavaScript syntax)"),
+  flags: z.string().optional().default("").describe("Regex flags, e.g. 'i' (g and y are ignored)"),
+  visibleOnly: z
+    .boolean()
+    .optional()
+    .default(false)
+    .describe("If true, only test lines in the visible viewport. If false, include scrollback."),
+  maxMatches: z.number().int().positive().optional().default(50).describe("Maximum number of matching lines to return (default: 50)"),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type TestPromptPatternArgs = z.infer<typeof testPromptPatternSchema>;
+
+export const testPromptPatternTool = {
+  name: "testPromptPattern",
+  description: "Evaluate a candidate prompt regex against the current screen and scrollback without changing any configuration. Reports which lines match and whether the cursor row would be detected as a prompt right now, alongside the result for the currently configured pattern (--prompt-pattern). Use this to tune a prompt pattern for unusual shells or themes.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      pattern: {
+        type: "string",
+        description: "Candidate prompt regex (JavaScript syntax), e.g. '\\\\$\\\\s*$'",
+      },
+      flags: {
+        type: "string",
+        description: "Regex flags, e.g. 'i' (g and y are ignored)",
+      },
+      visibleOnly: {
+        type: "boolean",
+        description: "If true, only test lines in the visible viewport. If false (default), include scrollback.",
+        default: false,
+      },
+      maxMatches: {
+        type: "number",
+        description: "Maximum number of matching lines to return (default: 50)",
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: ["pattern"],
+  },
+};
+
+export function handleTestPromptPattern(
+  manager: TerminalManager,
+  args: unknown
+): { content: Array<{ type: "text"; text: string }> } {
+  const parsed = testPromptPatternSchema.parse(args);
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const regex = compilePromptPattern(parsed.pattern, parsed.flags);
+
+  const lines = session.getBufferLines(parsed.visibleOnly);
+  const allMatches = findPromptMatches(lines, regex);
+  const configured = session.getPromptPattern();
+
+  const result = {
+    pattern: regex.source,
+    flags: regex.flags,
+    cursorLine: session.getCursorLineText(),
+    cursorLineMatches: session.isAtPrompt(regex),
+    linesTested: lines.length,
+    totalMatches: allMatches.length,
+    matches: allMatches.slice(0, parsed.maxMatches),
+    configuredPattern: configured.source,
+    configuredPatternMatchesCursorLine: session.isAtPrompt(),
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+  };
+}
@@ -1,5 +1,6 @@
 import { z } from "zod";
 import { TerminalManager } from "../terminal/index.js";
+import { confirmInput, type InputApprover } from "./approval.js";
 
 export const typeSchema = z.object({
   text: z.string().describe("The text to type into the terminal"),
@@ -27,8 +28,13 @@ export const typeTool = {
   },
 };
 
-export function handleType(manager: TerminalManager, args: unknown): { content: Array<{ type: "text"; text: string }> } {
+export async function handleType(
+  manager: TerminalManager,
+  args: unknown,
+  approve?: InputApprover
+): Promise<{ content: Array<{ type: "text"; text: string }> }> {
   const parsed = typeSchema.parse(args);
+  await confirmInput(manager, parsed.text, parsed.sessionId, approve);
   manager.write(parsed.text, parsed.sessionId);
 
   return {
@@ -0,0 +1,57 @@
+import { z } from "zod";
+import { TerminalManager, type OutputStreamer } from "../terminal/index.js";
+
+export const unsubscribeOutputSchema = z.object({
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+  all: z.boolean().optional().default(false).describe("Stop streaming every session"),
+});
+
+export type UnsubscribeOutputArgs = z.infer<typeof unsubscribeOutputSchema>;
+
+export const unsubscribeOutputTool = {
+  name: "unsubscribeOutput",
+  description: "Stop streaming a session's output started with subscribeOutput. Output still buffered is sent first. Pass all=true to stop every subscription of this client.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+      all: {
+        type: "boolean",
+        description: "Stop streaming every session (default: false)",
+        default: false,
+      },
+    },
+    required: [],
+  },
+};
+
+export function handleUnsubscribeOutput(
+  manager: TerminalManager,
+  args: unknown,
+  streamer: OutputStreamer
+): { content: Array<{ type: "text"; text: string }> } {
+  const parsed = unsubscribeOutputSchema.parse(args ?? {});
+  let unsubscribed: string[];
+  if (parsed.all) {
+    unsubscribed = streamer.getSubscriptions().filter((id) => streamer.unsubscribe(id));
+  } else {
+    // Resolve the default session's ID, but allow sessions that have since exited
+    const sessionId = parsed.sessionId ?? manager.getSessionMetadata().sessionId;
+    unsubscribed = streamer.unsubscribe(sessionId) ? [sessionId] : [];
+  }
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify({
+          unsubscribed,
+          subscriptions: streamer.getSubscriptions(),
+        }, null, 2),
+      },
+    ],
+  };
+}
@@ -0,0 +1,174 @@
+import { z } from "zod";
+import { TerminalManager } from "../terminal/index.js";
+import { startProgress, type ProgressSender } from "./progress.js";
+
+const DEFAULT_TIMEOUT_MS = 60000;
+const MAX_TIMEOUT_MS = 600000;
+const POLL_MS = 100;
+// After a foreground job leaves, how long to wait for the shell's exit status report
+const STATUS_GRACE_MS = 500;
+
+export const waitForExitSchema = z.object({
+  pid: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe("Wait for this process instead of the foreground command"),
+  timeoutMs: z
+    .number()
+    .int()
+    .positive()
+    .max(MAX_TIMEOUT_MS)
+    .optional()
+    .default(DEFAULT_TIMEOUT_MS)
+    .describe(`Give up after this many ms. Default: ${DEFAULT_TIMEOUT_MS}`),
+  sessionId: z.string().optional().describe("Target session ID. Omit to target the default session."),
+});
+
+export type WaitForExitArgs = z.infer<typeof waitForExitSchema>;
+
+export const waitForExitTool = {
+  name: "waitForExit",
+  description: "Block until the command running in the foreground finishes (or the process given by pid exits), then return its exit code. Unlike waiting for a prompt, this can't be fooled by output that looks like one. The foreground command is followed with the shell's exit status reports (bash and zsh) or, on Linux, the terminal's foreground process group in /proc; for a session running a command instead of a shell, the session's own process. exitCode is null when nothing reports it (always for a pid outside the terminal's foreground job). Fails at once if nothing is running. Returns exited=false after timeoutMs (default 60000, max 600000). Pass sessionId to target a specific session.",
+  inputSchema: {
+    type: "object" as const,
+    properties: {
+      pid: {
+        type: "number",
+        description: "Wait for this process instead of the foreground command",
+      },
+      timeoutMs: {
+        type: "number",
+        description: `Give up after this many ms (max ${MAX_TIMEOUT_MS}). Default: ${DEFAULT_TIMEOUT_MS}`,
+      },
+      sessionId: {
+        type: "string",
+        description: "Target session ID. Omit to target the default session.",
+      },
+    },
+    required: [],
+  },
+  outputSchema: {
+    type: "object" as const,
+    properties: {
+      exited: { type: "boolean", description: "False when timeoutMs ran out first" },
+      exitCode: { type: ["number", "null"] },
+      command: { type: ["string", "null"], description: "What was waited for, as far as it is known" },
+      pid: { type: ["number", "null"] },
+      waitedMs: { type: "number" },
+    },
+    required: ["exited", "exitCode", "command", "pid", "waitedMs"],
+  },
+};
+
+function isAlive(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch (error) {
+    // EPERM: it exists but belongs to someone else
+    return (error as NodeJS.ErrnoException).code === "EPERM";
+  }
+}
+
+function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+export async function handleWaitForExit(
+  manager: TerminalManager,
+  args: unknown,
+  progress?: ProgressSender
+): Promise<{ content: Array<{ type: "text"; text: string }>; structuredContent: Record<string, unknown> }> {
+  const parsed = waitForExitSchema.parse(args ?? {});
+  const session = manager.resolveSessionSync(parsed.sessionId);
+  const startedAt = Date.now();
+  const deadline = startedAt + parsed.timeoutMs;
+
+  // The shell's exit status report for the command running now, if one comes
+  let reported = null as { command: string | null; exitCode: number } | null;
+  const unsubscribe = session.onCommandFinished((command, exitCode) => {
+    reported ??= { command, exitCode };
+  });
+
+  let exited = false;
+  let exitCode: number | null = null;
+  let command: string | null = null;
+  let pid: number | null = null;
+
+  const isCommandSession = session.getCommand() !== null;
+  const group = manager.getForegroundGroup(parsed.sessionId);
+  const stopProgress = startProgress(progress, {
+    session,
+    status: () => `waiting for ${command ?? (pid === null ? "the foreground command" : `pid ${pid}`)} to exit`,
+  });
+
+  try {
+    if (parsed.pid !== undefined) {
+      pid = parsed.pid;
+      if (!isAlive(pid)) throw new Error(`No process with pid ${pid} is running`);
+      // Only the foreground job's status is reported by the shell
+      const inForeground = group !== null && !group.isShell && group.pgid === pid;
+      command = inForeground ? group.command : null;
+      while (isAlive(pid) && Date.now() < deadline) await sleep(POLL_MS);
+      exited = !isAlive(pid);
+      if (exited && inForeground) {
+        const graceEnd = Date.now() + STATUS_GRACE_MS;
+        while (!reported && Date.now() < graceEnd) await sleep(POLL_MS);
+        exitCode = reported?.exitCode ?? null;
+      }
+    } else if (isCommandSession) {
+      // The session's own process is the command
+      command = session.getCommand()!.join(" ");
+      pid = session.getPid();
+      while (session.getExitCode() === null && Date.now() < deadline) await sleep(POLL_MS);
+      exitCode = session.getExitCode();
+      exited = exitCode !== null;
+    } else if (group && !group.isShell) {
+      // Follow the foreground process group until the shell has the terminal back
+      command = group.args.join(" ") || group.command;
+      pid = group.pid;
+      const stillForeground = () => {
+        const current = manager.getForegroundGroup(parsed.sessionId);
+        return current !== null && !current.isShell && current.pgid === group.pgid;
+      };
+      while (stillForeground() && Date.now() < deadline) await sleep(POLL_MS);
+      exited = !stillForeground();
+      if (exited && session.isCommandRunning() !== null) {
+        const graceEnd = Date.now() + STATUS_GRACE_MS;
+        while (!reported && Date.now() < graceEnd) await sleep(POLL_MS);
+      }
+      exitCode = reported?.exitCode ?? null;
+    } else if (session.isCommandRunning()) {
+      // No /proc, but the shell reports when the command finishes
+      command = session.getLastCommand();
+      while (!reported && Date.now() < deadline) await sleep(POLL_MS);
+      exited = reported !== null;
+      exitCode = reported?.exitCode ?? null;
+    } else {
+      throw new Error("Nothing is running in the foreground; the shell is at its prompt");
+    }
+  } finally {
+    stopProgress();
+    unsubscribe();
+  }
+
+  const result = {
+    exited,
+    exitCode,
+    command: command ?? reported?.command ?? null,
+    pid,
+    waitedMs: Date.now() - startedAt,
+  };
+
+  return {
+    content: [
+      {
+        type: "text",
+        text: JSON.stringify(result, null, 2),
+      },
+    ],
+    structuredContent: result,
+  };
+}
@@ -0,0 +1,80 @@
+import { createHash, timingSafeEqual } from "crypto";
+
+/**
+ * TLS certificate and key (PEM) for a network transport
+ */
+export interface TlsOptions {
+  cert: Buffer;
+  key: Buffer;
+}
+
+/**
+ * Compare a presented token with the configured one in constant time
+ */
+export function tokenMatches(presented: string, expected: string): boolean {
+  // Hashing first makes the lengths equal, as timingSafeEqual requires
+  const a = createHash("sha256").update(presented).digest();
+  const b = createHash("sha256").update(expected).digest();
+  return timingSafeEqual(a, b);
+}
+
+/**
+ * Who may open an HTTP-based connection (the WebSocket handshake)
+ */
+export interface HttpAccessOptions {
+  /** Required as "Authorization: Bearer <token>" when set */
+  authToken?: string;
+  /**
+   * Origins browsers may connect from, e.g. "https://app.example.com".
+   * Requests with any other Origin header are refused; requests without
+   * one (non-browser clients) are not affected.
+   */
+  allowedOrigins?: string[];
+  /**
+   * Host header values accepted, lowercase. Refusing other names keeps a
+   * DNS-rebound page, whose requests carry its own host name, out of a
+   * server on loopback. Undefined accepts any.
+   */
+  allowedHosts?: string[];
+}
+
+export function isLoopbackHost(host: string): boolean {
+  return host === "localhost" || host === "::1" || host.startsWith("127.");
+}
+
+/**
+ * Host headers a server listening on host:port can legitimately see when
+ * host is loopback; undefined for other hosts, which are reached by names
+ * we can't know
+ */
+export function loopbackHostNames(host: string, port: number): string[] | undefined {
+  if (!isLoopbackHost(host)) return undefined;
+  return ["localhost", "127.0.0.1", "[::1]", host.includes(":") ? `[${host}]` : host].flatMap((name) => [
+    name,
+    `${name}:${port}`,
+  ]);
+}
+
+/**
+ * Why a request must be refused (403), or null to let it through
+ */
+export function checkHttpAccess(
+  headers: Record<string, string | string[] | undefined>,
+  options: HttpAccessOptions
+): string | null {
+  const host = typeof headers.host === "string" ? headers.host.toLowerCase() : "";
+  if (options.allowedHosts && !options.allowedHosts.includes(host)) {
+    return `Host '${host}' is not allowed`;
+  }
+  const origin = headers.origin;
+  if (typeof origin === "string" && !(options.allowedOrigins ?? []).includes(origin)) {
+    return `Origin '${origin}' is not allowed`;
+  }
+  if (options.authToken) {
+    const match = /^Bearer\s+(\S+)\s*$/i.exec(typeof headers.authorization === "string" ? headers.authorization : "");
+    if (!match || !tokenMatches(match[1], options.authToken)) {
+      return "missing or invalid bearer token";
+    }
+  }
+  return null;
+}
@@ -1 +1,14 @@
 export { SocketTransport, createSocketServer, createToolProxyServer } from "./socket.js";
+export { WebSocketConnection, WebSocketTransport, createWebSocketServer } from "./websocket.js";
+export type { WebSocketServerOptions, WebSocketTransportOptions } from "./websocket.js";
+export {
+  DEFAULT_LISTEN_HOST,
+  DEFAULT_LISTEN_PORT,
+  formatListenAddress,
+  parseListenAddress,
+} from "./listen.js";
+export type { ListenAddress } from "./listen.js";
+export { createTcpServer } from "./tcp.js";
+export type { TcpServerOptions } from "./tcp.js";
+export { tokenMatches, checkHttpAccess, isLoopbackHost, loopbackHostNames } from "./auth.js";
+export type { TlsOptions, HttpAccessOptions } from "./auth.js";
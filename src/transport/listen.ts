@@ -0,0 +1,33 @@
+/**
+ * Where a network transport listens (--listen [host:]port)
+ */
+export interface ListenAddress {
+  host: string;
+  port: number;
+}
+
+export const DEFAULT_LISTEN_HOST = "127.0.0.1";
+export const DEFAULT_LISTEN_PORT = 9300;
+
+/**
+ * Parse [host:]port; IPv6 hosts go in brackets ([::1]:9300). The host
+ * defaults to loopback, so nothing is exposed to the network by accident.
+ */
+export function parseListenAddress(value: string): ListenAddress {
+  const match = /^(?:(\[[^\]]+\]|[^:]+):)?(\d+)$/.exec(value.trim());
+  if (!match) {
+    throw new Error(`expected [host:]port, got '${value}'`);
+  }
+  const port = parseInt(match[2], 10);
+  if (port < 1 || port > 65535) {
+    throw new Error(`invalid port in '${value}'`);
+  }
+  return { host: match[1]?.replace(/^\[|\]$/g, "") ?? DEFAULT_LISTEN_HOST, port };
+}
+
+/**
+ * host:port for messages, with IPv6 hosts in brackets
+ */
+export function formatListenAddress(address: ListenAddress): string {
+  return address.host.includes(":") ? `[${address.host}]:${address.port}` : `${address.host}:${address.port}`;
+}
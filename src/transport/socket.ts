@@ -1,8 +1,8 @@
 import * as fs from "fs";
 import { Server as NetServer, Socket } from "net";
 import { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
-import { JSONRPCMessage } from "@modelcontextprotocol/sdk/types.js";
-import { TerminalManager } from "../terminal/index.js";
+import { JSONRPCMessage, McpError } from "@modelcontextprotocol/sdk/types.js";
+import { OutputStreamer, OUTPUT_NOTIFICATION, TerminalManager } from "../terminal/index.js";
 import { getStats } from "../utils/stats.js";
 
 // Tool handlers
@@ -12,17 +12,52 @@ import { handleGetContent } from "../tools/getContent.js";
 import { handleScreenshot } from "../tools/screenshot.js";
 import { handleStartRecording } from "../tools/startRecording.js";
 import { handleStopRecording } from "../tools/stopRecording.js";
+import { handleTestPromptPattern } from "../tools/testPromptPattern.js";
+import { handleSendMouse } from "../tools/sendMouse.js";
+import { handleGetStatus } from "../tools/getStatus.js";
+import { handleLookupHelp } from "../tools/lookupHelp.js";
+import { handleGetTitle } from "../tools/getTitle.js";
+import { handleDiffScreen } from "../tools/diffScreen.js";
+import { handleListSnapshots } from "../tools/listSnapshots.js";
+import { handleGetSnapshot } from "../tools/getSnapshot.js";
+import { handleGetEnvironment } from "../tools/getEnvironment.js";
+import { handleGetCursor } from "../tools/getCursor.js";
+import { handleDescribeScreen } from "../tools/describeScreen.js";
+import { handleGetSelection } from "../tools/getSelection.js";
+import { handleMark } from "../tools/mark.js";
+import { handleClear } from "../tools/clear.js";
+import { handleReset } from "../tools/reset.js";
+import { handleSearch } from "../tools/search.js";
+import { handleGetLinks } from "../tools/getLinks.js";
+import { handleGetClipboard } from "../tools/getClipboard.js";
+import { handleSubscribeOutput } from "../tools/subscribeOutput.js";
+import { handleUnsubscribeOutput } from "../tools/unsubscribeOutput.js";
+import { handleRestartShell } from "../tools/restartShell.js";
+import { handleGetForegroundProcess } from "../tools/getForegroundProcess.js";
+import { handleIsIdle } from "../tools/isIdle.js";
+import { handleWaitForExit } from "../tools/waitForExit.js";
+import { handleSecureType } from "../tools/secureType.js";
+import { PROGRESS_NOTIFICATION, type ProgressSender } from "../tools/progress.js";
+import { LOG_NOTIFICATION, toLogMessage } from "../logging/index.js";
+import { listResources, readResource } from "../resources/index.js";
+import { completeArgument, type CompleteParams } from "../tools/completions.js";
+import { toolDefinitions } from "../tools/definitions.js";
+import { EXCLUSIVE_TOOLS, withToolTimeout } from "../utils/toolLimits.js";
+import { RateLimitedError, type RateLimiter } from "../utils/rateLimit.js";
 
 interface SocketRequest {
   id: number;
   method: string;
   params?: Record<string, unknown>;
+  /** Set when the MCP client asked for progress notifications */
+  progressToken?: string | number;
 }
 
 interface SocketResponse {
   id: number;
   result?: unknown;
-  error?: { message: string };
+  /** code and data are set for JSON-RPC errors, e.g. terminal busy */
+  error?: { message: string; code?: number; data?: unknown };
 }
 
 /**
@@ -133,6 +168,21 @@ export function createToolProxyServer(
 
   const server = new NetServer((socket) => {
     let buffer = "";
+    // One MCP client per connection, with its own allowance
+    const rateLimiter = manager.createRateLimiter();
+    // Notifications carry a method and no id, so the client can tell them
+    // apart from responses
+    const streamer = new OutputStreamer(manager, (params) => {
+      if (!socket.destroyed) {
+        socket.write(JSON.stringify({ method: OUTPUT_NOTIFICATION, params }) + "\n");
+      }
+    });
+    // Every log entry is sent; the client filters by the level its MCP client set
+    const unsubscribeLog = manager.onLog((entry) => {
+      if (!socket.destroyed) {
+        socket.write(JSON.stringify({ method: LOG_NOTIFICATION, params: toLogMessage(entry) }) + "\n");
+      }
+    });
 
     socket.on("data", async (data) => {
       buffer += data.toString();
@@ -143,7 +193,20 @@ export function createToolProxyServer(
         if (line.trim()) {
           try {
             const request = JSON.parse(line) as SocketRequest;
-            const response = await handleToolRequest(manager, request);
+            const { progressToken } = request;
+            const progress: ProgressSender | undefined = progressToken === undefined
+              ? undefined
+              : (update) => {
+                  if (!socket.destroyed) {
+                    socket.write(JSON.stringify({ method: PROGRESS_NOTIFICATION, params: { progressToken, ...update } }) + "\n");
+                  }
+                };
+            const response = isToolCall(request.method)
+              ? admit(rateLimiter, request) ??
+                (await manager.auditToolCall(request.method, request.params, () =>
+                  handleLimited(manager, request, () => handleToolRequest(manager, request, streamer, progress))
+                ))
+              : await handleToolRequest(manager, request, streamer, progress);
             socket.write(JSON.stringify(response) + "\n");
           } catch (error) {
             const errorMessage =
@@ -162,6 +225,11 @@ export function createToolProxyServer(
     socket.on("error", () => {
       // Client disconnected, ignore
     });
+
+    socket.on("close", () => {
+      streamer.dispose();
+      unsubscribeLog();
+    });
   });
 
   server.listen(socketPath);
@@ -169,12 +237,57 @@ export function createToolProxyServer(
   return server;
 }
 
+// Resource and completion requests from the MCP client proxy aren't tool calls
+function isToolCall(method: string): boolean {
+  return method !== "listResources" && method !== "readResource" && method !== "complete";
+}
+
+/**
+ * Count a tool call against the client's rate limits. Returns the error
+ * response for a refused call, or null to go ahead.
+ */
+function admit(rateLimiter: RateLimiter | null, request: SocketRequest): SocketResponse | null {
+  try {
+    rateLimiter?.admit(request.method, request.params);
+    return null;
+  } catch (error) {
+    if (!(error instanceof RateLimitedError)) throw error;
+    return { id: request.id, error: { message: error.message, code: error.code, data: error.data } };
+  }
+}
+
+/**
+ * Apply --tool-timeout, and hold the session for tools that drive it,
+ * answering with the terminal busy error if it stays taken
+ */
+async function handleLimited(
+  manager: TerminalManager,
+  request: SocketRequest,
+  handle: () => Promise<SocketResponse>
+): Promise<SocketResponse> {
+  const { id, method, params } = request;
+  const run = () =>
+    withToolTimeout(method, manager.getToolTimeout(method), handle).catch(
+      (error: Error): SocketResponse => ({ id, error: { message: error.message } })
+    );
+  if (!EXCLUSIVE_TOOLS.has(method)) return run();
+  const sessionId = params?.sessionId;
+  try {
+    return await manager.runExclusive(method, typeof sessionId === "string" ? sessionId : undefined, run);
+  } catch (error) {
+    if (!(error instanceof McpError)) throw error;
+    return { id, error: { message: error.message, code: error.code, data: error.data } };
+  }
+}
+
 /**
  * Handle a tool request from the MCP client
  */
 async function handleToolRequest(
   manager: TerminalManager,
-  request: SocketRequest
+  request: SocketRequest,
+  streamer: OutputStreamer,
+  progress?: ProgressSender
 ): Promise<SocketResponse> {
   const { id, method, params } = request;
   const stats = getStats();
@@ -182,25 +295,30 @@ async function handleToolRequest(
   try {
     let result: unknown;
 
+    if (isToolCall(method)) {
+      const sessionId = (params as { sessionId?: unknown } | undefined)?.sessionId;
+      manager.recordToolSnapshot(method, typeof sessionId === "string" ? sessionId : undefined);
+    }
+
     switch (method) {
       case "type":
         stats.recordToolCall("type");
-        result = handleType(manager, params);
+        result = await handleType(manager, params);
         break;
 
       case "sendKey":
         stats.recordToolCall("sendKey");
-        result = handleSendKey(manager, params);
+        result = await handleSendKey(manager, params, progress);
         break;
 
       case "getContent":
         stats.recordToolCall("getContent");
-        result = handleGetContent(manager, params);
+        result = await handleGetContent(manager, params);
         break;
 
       case "takeScreenshot":
         stats.recordToolCall("takeScreenshot");
-        result = handleScreenshot(manager, params);
+        result = await handleScreenshot(manager, params);
         break;
 
       case "startRecording":
@@ -213,6 +331,143 @@ async function handleToolRequest(
         result = await handleStopRecording(manager, params);
         break;
 
+      case "testPromptPattern":
+        stats.recordToolCall("testPromptPattern");
+        result = handleTestPromptPattern(manager, params);
+        break;
+
+      case "sendMouse":
+        stats.recordToolCall("sendMouse");
+        result = handleSendMouse(manager, params);
+        break;
+
+      case "getStatus":
+        stats.recordToolCall("getStatus");
+        result = handleGetStatus(manager, params);
+        break;
+
+      case "lookupHelp":
+        stats.recordToolCall("lookupHelp");
+        result = await handleLookupHelp(manager, params, progress);
+        break;
+
+      case "getTitle":
+        stats.recordToolCall("getTitle");
+        result = handleGetTitle(manager, params);
+        break;
+
+      case "diffScreen":
+        stats.recordToolCall("diffScreen");
+        result = await handleDiffScreen(manager, params);
+        break;
+
+      case "listSnapshots":
+        stats.recordToolCall("listSnapshots");
+        result = handleListSnapshots(manager, params);
+        break;
+
+      case "getSnapshot":
+        stats.recordToolCall("getSnapshot");
+        result = handleGetSnapshot(manager, params);
+        break;
+
+      case "getEnvironment":
+        stats.recordToolCall("getEnvironment");
+        result = handleGetEnvironment(manager, params);
+        break;
+
+      case "listResources":
+        result = listResources(manager);
+        break;
+
+      case "readResource":
+        result = await readResource(manager, String(params?.uri ?? ""));
+        break;
+
+      case "complete":
+        result = completeArgument(manager, toolDefinitions, params as unknown as CompleteParams);
+        break;
+
+      case "getCursor":
+        stats.recordToolCall("getCursor");
+        result = await handleGetCursor(manager, params);
+        break;
+
+      case "describeScreen":
+        stats.recordToolCall("describeScreen");
+        result = await handleDescribeScreen(manager, params);
+        break;
+
+      case "getSelection":
+        stats.recordToolCall("getSelection");
+        result = await handleGetSelection(manager, params);
+        break;
+
+      case "mark":
+        stats.recordToolCall("mark");
+        result = await handleMark(manager, params);
+        break;
+
+      case "clear":
+        stats.recordToolCall("clear");
+        result = await handleClear(manager, params);
+        break;
+
+      case "reset":
+        stats.recordToolCall("reset");
+        result = await handleReset(manager, params);
+        break;
+
+      case "search":
+        stats.recordToolCall("search");
+        result = await handleSearch(manager, params);
+        break;
+
+      case "getLinks":
+        stats.recordToolCall("getLinks");
+        result = await handleGetLinks(manager, params);
+        break;
+
+      case "getClipboard":
+        stats.recordToolCall("getClipboard");
+        result = handleGetClipboard(manager, params);
+        break;
+
+      case "subscribeOutput":
+        stats.recordToolCall("subscribeOutput");
+        result = handleSubscribeOutput(manager, params, streamer);
+        break;
+
+      case "unsubscribeOutput":
+        stats.recordToolCall("unsubscribeOutput");
+        result = handleUnsubscribeOutput(manager, params, streamer);
+        break;
+
+      case "restartShell":
+        stats.recordToolCall("restartShell");
+        result = await handleRestartShell(manager, params);
+        break;
+
+      case "getForegroundProcess":
+        stats.recordToolCall("getForegroundProcess");
+        result = handleGetForegroundProcess(manager, params);
+        break;
+
+      case "isIdle":
+        stats.recordToolCall("isIdle");
+        result = await handleIsIdle(manager, params);
+        break;
+
+      case "waitForExit":
+        stats.recordToolCall("waitForExit");
+        result = await handleWaitForExit(manager, params, progress);
+        break;
+
+      case "secureType":
+        stats.recordToolCall("secureType");
+        result = await handleSecureType(manager, params);
+        break;
+
       default:
         return {
           id,
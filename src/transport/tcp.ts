@@ -0,0 +1,82 @@
+/**
+ * TCP transport (--transport tcp): newline-delimited JSON-RPC, the same
+ * framing as stdio, on a TCP port, optionally wrapped in TLS. Like the
+ * WebSocket transport it accepts any number of clients sharing the
+ * sessions.
+ *
+ * Before any JSON-RPC, a client sends one line authenticating itself:
+ *
+ *   {"auth": "<token>"}
+ *
+ * Connections that send anything else, or nothing within AUTH_TIMEOUT_MS,
+ * are answered with an error line and closed.
+ */
+import * as net from "net";
+import * as tls from "tls";
+import { SocketTransport } from "./socket.js";
+import { tokenMatches, type TlsOptions } from "./auth.js";
+import type { ListenAddress } from "./listen.js";
+
+const AUTH_TIMEOUT_MS = 10000;
+// Longest auth line read before giving up on the connection
+const MAX_AUTH_LINE_BYTES = 4096;
+
+export interface TcpServerOptions {
+  authToken: string;
+  tls?: TlsOptions;
+}
+
+/**
+ * Listen for MCP clients on a TCP port. onConnection gets a transport for
+ * each client once it has authenticated.
+ */
+export function createTcpServer(
+  listen: ListenAddress,
+  options: TcpServerOptions,
+  onConnection: (transport: SocketTransport, remoteAddress: string) => void
+): net.Server {
+  const accept = (socket: net.Socket) => {
+    const remoteAddress = socket.remoteAddress ?? "unknown";
+    socket.on("error", () => {});
+    let buffered = "";
+    const reject = (reason: string) => {
+      clearTimeout(timer);
+      socket.removeListener("data", onData);
+      socket.end(JSON.stringify({ error: reason }) + "\n");
+    };
+    const timer = setTimeout(() => reject("authentication timed out"), AUTH_TIMEOUT_MS);
+    const onData = (chunk: Buffer) => {
+      buffered += chunk.toString("utf-8");
+      const newline = buffered.indexOf("\n");
+      if (newline === -1) {
+        if (buffered.length > MAX_AUTH_LINE_BYTES) reject("unauthorized");
+        return;
+      }
+      let token: unknown;
+      try {
+        token = (JSON.parse(buffered.slice(0, newline)) as { auth?: unknown }).auth;
+      } catch {
+        token = undefined;
+      }
+      if (typeof token !== "string" || !tokenMatches(token, options.authToken)) {
+        reject("unauthorized");
+        return;
+      }
+      clearTimeout(timer);
+      socket.removeListener("data", onData);
+      socket.pause();
+      // Whatever came after the auth line is the start of the JSON-RPC stream
+      const rest = buffered.slice(newline + 1);
+      if (rest) socket.unshift(Buffer.from(rest, "utf-8"));
+      onConnection(new SocketTransport(socket), remoteAddress);
+      socket.resume();
+    };
+    socket.on("data", onData);
+  };
+
+  const server = options.tls
+    ? tls.createServer({ cert: options.tls.cert, key: options.tls.key }, accept)
+    : net.createServer(accept);
+  server.listen(listen.port, listen.host);
+  return server;
+}
@@ -0,0 +1,362 @@
+/**
+ * WebSocket transport (--transport websocket): one server process accepts
+ * any number of MCP clients. Each connection gets its own MCP Server on the
+ * shared TerminalManager, so responses go back to the client that asked
+ * while manager-wide notifications (log messages) reach every client.
+ *
+ * Only the server side of RFC 6455 that MCP needs is implemented: text
+ * messages (fragmented or not), binary messages for screenshots (sent
+ * only; clients send text), ping/pong, and close.
+ */
+import { createHash } from "crypto";
+import * as http from "http";
+import * as https from "https";
+import type { Duplex } from "stream";
+import { Transport } from "@modelcontextprotocol/sdk/shared/transport.js";
+import { JSONRPCMessage } from "@modelcontextprotocol/sdk/types.js";
+import type { ListenAddress } from "./listen.js";
+import { checkHttpAccess, type HttpAccessOptions, type TlsOptions } from "./auth.js";
+
+const WEBSOCKET_GUID = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+// Larger messages close the connection (1009); screenshots are the biggest replies
+const MAX_MESSAGE_BYTES = 16 * 1024 * 1024;
+
+const OPCODE_CONTINUATION = 0x0;
+const OPCODE_TEXT = 0x1;
+const OPCODE_BINARY = 0x2;
+const OPCODE_CLOSE = 0x8;
+const OPCODE_PING = 0x9;
+const OPCODE_PONG = 0xa;
+
+// _meta key naming the binary frame that carries a content item's payload
+const BINARY_FRAME_META = "terminal-mcp/binaryFrame";
+
+/**
+ * One accepted WebSocket connection
+ */
+export class WebSocketConnection {
+  private buffer = Buffer.alloc(0);
+  private fragments: Buffer[] = [];
+  private fragmentBytes = 0;
+  // Opcode of the fragmented message being received, if any
+  private messageOpcode: number | null = null;
+  private closed = false;
+  private finished = false;
+
+  onmessage?: (text: string) => void;
+  onclose?: () => void;
+
+  private constructor(private socket: Duplex) {
+    socket.on("data", (chunk: Buffer) => this.receive(chunk));
+    socket.on("close", () => this.finish());
+    socket.on("error", () => this.finish());
+  }
+
+  /**
+   * Complete the opening handshake for an HTTP upgrade request. Answers
+   * 400 and returns null when it isn't a WebSocket upgrade.
+   */
+  static accept(req: http.IncomingMessage, socket: Duplex, head: Buffer): WebSocketConnection | null {
+    const key = req.headers["sec-websocket-key"];
+    if (req.headers.upgrade?.toLowerCase() !== "websocket" || typeof key !== "string") {
+      socket.end("HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n");
+      return null;
+    }
+    const accept = createHash("sha1").update(key + WEBSOCKET_GUID).digest("base64");
+    socket.write(
+      "HTTP/1.1 101 Switching Protocols\r\n" +
+        "Upgrade: websocket\r\n" +
+        "Connection: Upgrade\r\n" +
+        `Sec-WebSocket-Accept: ${accept}\r\n\r\n`
+    );
+    const connection = new WebSocketConnection(socket);
+    if (head.length > 0) connection.receive(head);
+    return connection;
+  }
+
+  /**
+   * Send one text message
+   */
+  send(text: string): Promise<void> {
+    return this.sendFrame(OPCODE_TEXT, Buffer.from(text, "utf-8"));
+  }
+
+  /**
+   * Send one binary message
+   */
+  sendBinary(data: Buffer): Promise<void> {
+    return this.sendFrame(OPCODE_BINARY, data);
+  }
+
+  /**
+   * Start the closing handshake; the socket ends once the client answers
+   * or gives up
+   */
+  close(code = 1000): void {
+    if (this.closed) return;
+    const payload = Buffer.alloc(2);
+    payload.writeUInt16BE(code);
+    this.sendFrame(OPCODE_CLOSE, payload).catch(() => {});
+    this.closed = true;
+    this.socket.end();
+  }
+
+  private sendFrame(opcode: number, payload: Buffer): Promise<void> {
+    if (this.closed) return Promise.reject(new Error("WebSocket is closed"));
+    let header: Buffer;
+    if (payload.length < 126) {
+      header = Buffer.from([0x80 | opcode, payload.length]);
+    } else if (payload.length < 0x10000) {
+      header = Buffer.alloc(4);
+      header[0] = 0x80 | opcode;
+      header[1] = 126;
+      header.writeUInt16BE(payload.length, 2);
+    } else {
+      header = Buffer.alloc(10);
+      header[0] = 0x80 | opcode;
+      header[1] = 127;
+      header.writeBigUInt64BE(BigInt(payload.length), 2);
+    }
+    return new Promise((resolve, reject) => {
+      this.socket.write(Buffer.concat([header, payload]), (error) => (error ? reject(error) : resolve()));
+    });
+  }
+
+  private receive(chunk: Buffer): void {
+    this.buffer = this.buffer.length > 0 ? Buffer.concat([this.buffer, chunk]) : chunk;
+    while (!this.closed && this.buffer.length >= 2) {
+      const fin = (this.buffer[0] & 0x80) !== 0;
+      const opcode = this.buffer[0] & 0x0f;
+      // No extensions are negotiated, so the reserved bits must be clear
+      if ((this.buffer[0] & 0x70) !== 0) {
+        this.close(1002);
+        return;
+      }
+      const masked = (this.buffer[1] & 0x80) !== 0;
+      let length = this.buffer[1] & 0x7f;
+      let offset = 2;
+      if (length === 126) {
+        if (this.buffer.length < 4) return;
+        length = this.buffer.readUInt16BE(2);
+        offset = 4;
+      } else if (length === 127) {
+        if (this.buffer.length < 10) return;
+        const long = this.buffer.readBigUInt64BE(2);
+        if (long > BigInt(MAX_MESSAGE_BYTES)) {
+          this.close(1009);
+          return;
+        }
+        length = Number(long);
+        offset = 10;
+      }
+      // Clients must mask every frame
+      if (!masked) {
+        this.close(1002);
+        return;
+      }
+      if (length > MAX_MESSAGE_BYTES) {
+        this.close(1009);
+        return;
+      }
+      if (this.buffer.length < offset + 4 + length) return;
+      const mask = this.buffer.subarray(offset, offset + 4);
+      const payload = Buffer.from(this.buffer.subarray(offset + 4, offset + 4 + length));
+      for (let i = 0; i < payload.length; i++) {
+        payload[i] ^= mask[i % 4];
+      }
+      this.buffer = this.buffer.subarray(offset + 4 + length);
+      this.handleFrame(fin, opcode, payload);
+    }
+  }
+
+  private handleFrame(fin: boolean, opcode: number, payload: Buffer): void {
+    // Control frames can't be fragmented and carry at most 125 bytes
+    if ((opcode & 0x8) !== 0 && (!fin || payload.length > 125)) {
+      this.close(1002);
+      return;
+    }
+    switch (opcode) {
+      case OPCODE_TEXT:
+      case OPCODE_BINARY:
+      case OPCODE_CONTINUATION:
+        // A continuation needs a message to continue, and a new message
+        // can't start in the middle of another
+        if ((opcode === OPCODE_CONTINUATION) !== (this.messageOpcode !== null)) {
+          this.close(1002);
+          return;
+        }
+        // Every message from a client is JSON text
+        if (opcode === OPCODE_BINARY) {
+          this.close(1003);
+          return;
+        }
+        if (opcode === OPCODE_TEXT) this.messageOpcode = opcode;
+        this.fragments.push(payload);
+        this.fragmentBytes += payload.length;
+        if (this.fragmentBytes > MAX_MESSAGE_BYTES) {
+          this.close(1009);
+          return;
+        }
+        if (fin) {
+          const message = Buffer.concat(this.fragments).toString("utf-8");
+          this.fragments = [];
+          this.fragmentBytes = 0;
+          this.messageOpcode = null;
+          this.onmessage?.(message);
+        }
+        break;
+      case OPCODE_PING:
+        this.sendFrame(OPCODE_PONG, payload).catch(() => {});
+        break;
+      case OPCODE_PONG:
+        break;
+      case OPCODE_CLOSE:
+        this.close(payload.length >= 2 ? payload.readUInt16BE(0) : 1000);
+        break;
+      default:
+        this.close(1002);
+    }
+  }
+
+  private finish(): void {
+    if (this.finished) return;
+    this.finished = true;
+    this.closed = true;
+    this.onclose?.();
+  }
+}
+
+type Payload = Record<string, unknown>;
+
+/**
+ * Move the base64 payloads of a result (image and audio content, resource
+ * blobs) out of the message. Each one is replaced by an empty string and a
+ * _meta entry holding its index among the returned buffers. The message
+ * itself is left untouched; changed objects are copied.
+ */
+function extractBinaryPayloads(message: JSONRPCMessage): { message: JSONRPCMessage; frames: Buffer[] } {
+  const frames: Buffer[] = [];
+  const result = (message as { result?: Payload }).result;
+  if (!result) return { message, frames };
+
+  const detach = (item: Payload, field: "data" | "blob"): Payload => {
+    const value = item[field];
+    if (typeof value !== "string" || value.length === 0) return item;
+    frames.push(Buffer.from(value, "base64"));
+    const meta = (item._meta as Payload | undefined) ?? {};
+    return { ...item, [field]: "", _meta: { ...meta, [BINARY_FRAME_META]: frames.length - 1 } };
+  };
+
+  const copy: Payload = { ...result };
+  if (Array.isArray(result.content)) {
+    // Tool results
+    copy.content = (result.content as Payload[]).map((item) => {
+      if (item.type === "image" || item.type === "audio") return detach(item, "data");
+      if (item.type === "resource" && item.resource) {
+        const resource = detach(item.resource as Payload, "blob");
+        return resource === item.resource ? item : { ...item, resource };
+      }
+      return item;
+    });
+  }
+  if (Array.isArray(result.contents)) {
+    // resources/read
+    copy.contents = (result.contents as Payload[]).map((item) => detach(item, "blob"));
+  }
+  if (frames.length === 0) return { message, frames };
+  return { message: { ...message, result: copy } as JSONRPCMessage, frames };
+}
+
+export interface WebSocketTransportOptions {
+  /**
+   * Send image, audio, and blob payloads as binary messages just before the
+   * JSON-RPC message that refers to them, instead of inline base64
+   */
+  binaryFrames?: boolean;
+}
+
+/**
+ * MCP transport over a WebSocket connection, one JSON-RPC message per
+ * text message
+ */
+export class WebSocketTransport implements Transport {
+  onmessage?: (message: JSONRPCMessage) => void;
+  onerror?: (error: Error) => void;
+  onclose?: () => void;
+
+  constructor(
+    private connection: WebSocketConnection,
+    private options: WebSocketTransportOptions = {}
+  ) {
+    connection.onmessage = (text) => {
+      try {
+        this.onmessage?.(JSON.parse(text) as JSONRPCMessage);
+      } catch {
+        this.onerror?.(new Error(`Failed to parse message: ${text}`));
+      }
+    };
+    connection.onclose = () => this.onclose?.();
+  }
+
+  async start(): Promise<void> {
+    // The handshake is already done
+  }
+
+  async close(): Promise<void> {
+    this.connection.close();
+  }
+
+  async send(message: JSONRPCMessage): Promise<void> {
+    if (!this.options.binaryFrames) {
+      await this.connection.send(JSON.stringify(message));
+      return;
+    }
+    const extracted = extractBinaryPayloads(message);
+    // Queue every frame before yielding, so another send can't slip between
+    // the binary messages and the JSON that refers to them
+    const writes = extracted.frames.map((frame) => this.connection.sendBinary(frame));
+    writes.push(this.connection.send(JSON.stringify(extracted.message)));
+    await Promise.all(writes);
+  }
+}
+
+export interface WebSocketServerOptions extends HttpAccessOptions {
+  /** Serve wss:// instead of ws:// */
+  tls?: TlsOptions;
+}
+
+/**
+ * Listen for WebSocket connections on any path. Handshakes from a
+ * disallowed Host or Origin, or without the bearer token, get 403; plain
+ * HTTP requests get 426 Upgrade Required. Clients that connect with
+ * ?binary=1 get screenshots as binary messages.
+ */
+export function createWebSocketServer(
+  listen: ListenAddress,
+  options: WebSocketServerOptions,
+  onConnection: (transport: WebSocketTransport, req: http.IncomingMessage) => void
+): http.Server {
+  const onRequest = (_req: http.IncomingMessage, res: http.ServerResponse) => {
+    res.writeHead(426, { Upgrade: "websocket", "Content-Type": "text/plain" });
+    res.end("terminal-mcp expects a WebSocket connection\n");
+  };
+  const { tls } = options;
+  const server = tls ? https.createServer({ cert: tls.cert, key: tls.key }, onRequest) : http.createServer(onRequest);
+  server.on("upgrade", (req: http.IncomingMessage, socket: Duplex, head: Buffer) => {
+    socket.on("error", () => {});
+    const refused = checkHttpAccess(req.headers, options);
+    if (refused) {
+      console.error(`[terminal-mcp] Refused WebSocket connection from ${req.socket.remoteAddress}: ${refused}`);
+      socket.end("HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\nForbidden\n");
+      return;
+    }
+    const connection = WebSocketConnection.accept(req, socket, head);
+    if (connection) {
+      const url = new URL(req.url ?? "/", "http://localhost");
+      const binaryFrames = url.searchParams.get("binary") === "1";
+      onConnection(new WebSocketTransport(connection, { binaryFrames }), req);
+    }
+  });
+  server.listen(listen.port, listen.host);
+  return server;
+}
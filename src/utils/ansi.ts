@@ -0,0 +1,124 @@
+/**
+ * Removal of terminal escape sequences from raw program output.
+ *
+ * A regex for CSI alone lets OSC titles and hyperlinks, DCS replies
+ * (tmux passthrough, XTGETTCAP), and APC payloads (kitty graphics) leak
+ * into "plain" text, so this walks the string as a small state machine
+ * following the ECMA-48 / VT500 parser's escape classes:
+ *
+ * - CSI  ESC [ ... final byte 0x40-0x7e
+ * - OSC  ESC ] ... terminated by BEL or ST (ESC \)
+ * - DCS  ESC P, SOS ESC X, PM ESC ^, APC ESC _ ... terminated by ST
+ * - other ESC sequences: ESC, intermediates 0x20-0x2f, final 0x30-0x7e
+ *
+ * The 8-bit C1 introducers (0x9b CSI, 0x9d OSC, 0x90 DCS, 0x98 SOS,
+ * 0x9e PM, 0x9f APC, 0x9c ST) are recognized too. An unterminated string
+ * sequence swallows the rest of the input, as a terminal would.
+ */
+
+const ESC = "\x1b";
+const BEL = "\x07";
+
+type State = "ground" | "escape" | "csi" | "string" | "stringEscape";
+
+// Byte after ESC that opens a string sequence (OSC, DCS, SOS, PM, APC),
+// and the equivalent 8-bit C1 codes
+const STRING_INTRODUCERS = new Set(["]", "P", "X", "^", "_"]);
+const C1_STRING_INTRODUCERS = new Set(["\x9d", "\x90", "\x98", "\x9e", "\x9f"]);
+
+export function stripAnsi(text: string): string {
+  let out = "";
+  let state: State = "ground";
+  // Only OSC may also end with BEL
+  let belEnds = false;
+
+  for (let i = 0; i < text.length; i++) {
+    const ch = text[i];
+    const code = ch.charCodeAt(0);
+
+    switch (state) {
+      case "ground":
+        if (ch === ESC) {
+          state = "escape";
+        } else if (ch === "\x9b") {
+          state = "csi";
+        } else if (C1_STRING_INTRODUCERS.has(ch)) {
+          state = "string";
+          belEnds = ch === "\x9d";
+        } else {
+          out += ch;
+        }
+        break;
+
+      case "escape":
+        if (ch === "[") {
+          state = "csi";
+        } else if (STRING_INTRODUCERS.has(ch)) {
+          state = "string";
+          belEnds = ch === "]";
+        } else if (code >= 0x20 && code <= 0x2f) {
+          // Intermediate byte (e.g. ESC ( B); stay until the final byte
+        } else if (ch === ESC) {
+          // ESC ESC: the first one is abandoned
+        } else {
+          state = "ground";
+        }
+        break;
+
+      case "csi":
+        // Parameters and intermediates run until a final byte
+        if (code >= 0x40 && code <= 0x7e) {
+          state = "ground";
+        } else if (ch === ESC) {
+          state = "escape";
+        }
+        break;
+
+      case "string":
+        if (ch === ESC) {
+          state = "stringEscape";
+        } else if (ch === "\x9c" || (ch === BEL && belEnds)) {
+          state = "ground";
+        }
+        break;
+
+      case "stringEscape":
+        // ESC \ is ST; any other ESC aborts the string and starts a new sequence
+        if (ch === "\\") {
+          state = "ground";
+        } else {
+          state = "escape";
+          i--;
+        }
+        break;
+    }
+  }
+
+  return out;
+}
+
+// Erase in Line (EL 0 or 2): the line is redrawn from scratch after it
+const ERASE_LINE = /\x1b\[[02]?K/;
+
+/**
+ * Reduce lines redrawn with carriage returns (progress bars from pip,
+ * cargo, docker pull, curl) to what the last redraw left on screen, and
+ * remove escape sequences; CRLF becomes LF. A shorter redraw leaves the tail of the longer
+ * one before it, as on a terminal, unless it erased the line first.
+ */
+export function collapseCarriageReturns(text: string): string {
+  return text
+    .split("\n")
+    .map((line) => {
+      line = line.replace(/\r$/, "");
+      if (!line.includes("\r")) return stripAnsi(line);
+      let shown = "";
+      for (const segment of line.split("\r")) {
+        if (ERASE_LINE.test(segment)) shown = "";
+        const visible = stripAnsi(segment);
+        shown = visible + shown.slice(visible.length);
+      }
+      return shown;
+    })
+    .join("\n");
+}
@@ -0,0 +1,141 @@
+/**
+ * Markdown code fences for terminal output, with a language tag inferred
+ * from the command that produced it (e.g. `cat package.json` → json).
+ */
+
+import * as path from "path";
+
+const EXTENSION_LANGUAGES: Record<string, string> = {
+  ".json": "json",
+  ".jsonl": "json",
+  ".js": "javascript",
+  ".mjs": "javascript",
+  ".cjs": "javascript",
+  ".jsx": "jsx",
+  ".ts": "typescript",
+  ".tsx": "tsx",
+  ".py": "python",
+  ".rb": "ruby",
+  ".rs": "rust",
+  ".go": "go",
+  ".java": "java",
+  ".kt": "kotlin",
+  ".c": "c",
+  ".h": "c",
+  ".cc": "cpp",
+  ".cpp": "cpp",
+  ".hpp": "cpp",
+  ".cs": "csharp",
+  ".php": "php",
+  ".swift": "swift",
+  ".sh": "bash",
+  ".bash": "bash",
+  ".zsh": "zsh",
+  ".ps1": "powershell",
+  ".sql": "sql",
+  ".yaml": "yaml",
+  ".yml": "yaml",
+  ".toml": "toml",
+  ".ini": "ini",
+  ".cfg": "ini",
+  ".xml": "xml",
+  ".html": "html",
+  ".htm": "html",
+  ".css": "css",
+  ".scss": "scss",
+  ".md": "markdown",
+  ".diff": "diff",
+  ".patch": "diff",
+  ".csv": "csv",
+  ".lua": "lua",
+  ".tf": "hcl",
+  ".proto": "protobuf",
+};
+
+const FILENAME_LANGUAGES: Record<string, string> = {
+  "dockerfile": "dockerfile",
+  "makefile": "makefile",
+  "gemfile": "ruby",
+  "cargo.lock": "toml",
+  ".bashrc": "bash",
+  ".zshrc": "zsh",
+  ".gitignore": "gitignore",
+};
+
+// Commands whose output is the (unchanged) content of their file arguments
+const FILE_VIEWERS = new Set(["cat", "bat", "batcat", "head", "tail", "less", "more", "nl"]);
+
+const DEFAULT_LANGUAGE = "text";
+
+function languageForFile(file: string): string | null {
+  const base = path.basename(file).toLowerCase();
+  return FILENAME_LANGUAGES[base] ?? EXTENSION_LANGUAGES[path.extname(base)] ?? null;
+}
+
+/**
+ * Split a command line into words. Handles simple quoting only; good
+ * enough for picking out a program name and file arguments.
+ */
+function splitWords(command: string): string[] {
+  const words: string[] = [];
+  const re = /"([^"]*)"|'([^']*)'|(\S+)/g;
+  let match: RegExpExecArray | null;
+  while ((match = re.exec(command)) !== null) {
+    words.push(match[1] ?? match[2] ?? match[3]);
+  }
+  return words;
+}
+
+function inferStage(stage: string): string | null {
+  const words = splitWords(stage);
+  // Skip env assignments and privilege wrappers
+  while (words.length > 0 && (/^\w+=/.test(words[0]) || ["sudo", "env", "time", "command"].includes(words[0]))) {
+    words.shift();
+  }
+  if (words.length === 0) return null;
+
+  const program = path.basename(words[0]);
+  const args = words.slice(1);
+
+  if (FILE_VIEWERS.has(program)) {
+    const file = args.filter((a) => !a.startsWith("-")).pop();
+    return file ? languageForFile(file) : null;
+  }
+  if (program === "jq" || program === "gron") return "json";
+  if (program === "yq") return "yaml";
+  if (program === "diff" || program === "colordiff") return "diff";
+  if (program === "git" && ["diff", "show"].includes(args[0])) return "diff";
+  if (program === "git" && args[0] === "log" && args.some((a) => a === "-p" || a === "--patch")) return "diff";
+
+  // kubectl/gh/aws and friends: -o json / --output yaml
+  const outIndex = args.findIndex((a) => a === "-o" || a === "--output");
+  const output = outIndex >= 0 ? args[outIndex + 1] : args.find((a) => a.startsWith("--output="))?.slice(9);
+  if (output === "json" || output === "yaml") return output;
+  if (args.includes("--json")) return "json";
+
+  return null;
+}
+
+/**
+ * Guess a Markdown language tag for a command's output. For pipelines the
+ * last stage that says anything wins (`curl ... | jq .` → json).
+ */
+export function inferLanguage(command: string | null | undefined): string {
+  if (!command) return DEFAULT_LANGUAGE;
+  const stages = command.split(/(?<!\|)\|(?!\|)/).map((s) => s.trim()).filter(Boolean);
+  for (let i = stages.length - 1; i >= 0; i--) {
+    const language = inferStage(stages[i]);
+    if (language) return language;
+  }
+  return DEFAULT_LANGUAGE;
+}
+
+/**
+ * Wrap text in a Markdown code fence long enough not to be closed by any
+ * backtick run inside the text.
+ */
+export function fenceCodeBlock(text: string, language: string): string {
+  const longestRun = Math.max(0, ...(text.match(/`+/g) ?? []).map((run) => run.length));
+  const fence = "`".repeat(Math.max(3, longestRun + 1));
+  return `${fence}${language}\n${text}\n${fence}`;
+}
@@ -0,0 +1,59 @@
+/**
+ * Compact rendering of terminal text (format: "compact"): a 120x40 screen
+ * of a simple shell is mostly padding, which costs tokens and tells the
+ * reader nothing. Trailing spaces are dropped, runs of blank lines become
+ * one `[N blank lines]` note, and a left margin every line shares is cut
+ * and noted once at the top. With line numbers, each line kept says which
+ * row it came from.
+ */
+
+// Shorter runs are kept as they are; a note would be no shorter
+const MIN_BLANK_RUN = 2;
+
+export function compactText(text: string, firstLine: number | null = null): string {
+  const lines = text.split("\n").map((line) => line.replace(/\s+$/, ""));
+  const number = (i: number, line: string) =>
+    firstLine === null ? line : lineNumberPrefix(firstLine + i, firstLine + lines.length - 1) + line;
+
+  const filled = lines.filter((line) => line !== "");
+  if (filled.length === 0) {
+    return lines.length > 1 ? `[${lines.length} blank lines]` : "";
+  }
+  const margin = Math.min(...filled.map((line) => /^ */.exec(line)![0].length));
+
+  const out: string[] = [];
+  if (margin > 0) out.push(`[left margin of ${margin} columns trimmed]`);
+  let blank = 0;
+  const flush = (next: number) => {
+    if (blank >= MIN_BLANK_RUN) out.push(`[${blank} blank lines]`);
+    else for (let i = next - blank; i < next; i++) out.push(number(i, "").trimEnd());
+    blank = 0;
+  };
+  lines.forEach((line, i) => {
+    if (line === "") {
+      blank++;
+      return;
+    }
+    flush(i);
+    out.push(number(i, line.slice(margin)));
+  });
+  flush(lines.length);
+  return out.join("\n");
+}
+
+/**
+ * "17: " for line 17, padded so the text of every line up to `last` starts
+ * in the same column
+ */
+export function lineNumberPrefix(line: number, last: number): string {
+  return `${String(line).padStart(String(last).length)}: `;
+}
+
+/**
+ * Prefix each line with its number, counting from `first`
+ */
+export function numberLines(text: string, first: number): string {
+  const lines = text.split("\n");
+  const last = first + lines.length - 1;
+  return lines.map((line, i) => line ? lineNumberPrefix(first + i, last) + line : String(first + i).padStart(String(last).length) + ":").join("\n");
+}
@@ -0,0 +1,283 @@
+/**
+ * A structured reading of the screen for deciding the next keystroke: the
+ * prompt, menus and lists with the selected item, progress bars, and dialog
+ * buttons. It leans on the cell attributes a plain text dump throws away,
+ * since TUIs mark the selection with inverse video, a background color, or
+ * bold. Heuristic by nature; every finding carries its screen row so the
+ * caller can check it against getContent.
+ */
+
+import type { Terminal } from "@xterm/headless";
+import { getStyledRows, type CellColor, type StyledRun } from "./screenDump.js";
+
+export type HighlightKind = "inverse" | "background" | "bold";
+
+export interface Highlight {
+  row: number;
+  col: number;
+  text: string;
+  by: HighlightKind;
+  /** Spans the whole width, like a status line; not a selection */
+  bar?: true;
+}
+
+export interface ListDescription {
+  startRow: number;
+  endRow: number;
+  items: string[];
+  /** Index into items of the highlighted one */
+  selected: number;
+}
+
+export interface ProgressDescription {
+  row: number;
+  /** Read from 'NN%' on the row, or from how full the bar is */
+  percent: number;
+  text: string;
+}
+
+export interface ButtonDescription {
+  row: number;
+  col: number;
+  label: string;
+  highlighted: boolean;
+}
+
+export interface ScreenDescription {
+  /** The cursor row if it is at a prompt, else the last row on screen that looks like one */
+  prompt: { row: number; text: string; atCursor: boolean } | null;
+  highlighted: Highlight[];
+  lists: ListDescription[];
+  progress: ProgressDescription[];
+  buttons: ButtonDescription[];
+}
+
+// A list needs at least this many aligned rows around its selection
+const MIN_LIST_ITEMS = 2;
+// Items may be indented by a selection marker like "> " or "* "
+const LIST_INDENT_SLACK = 2;
+
+const PERCENT = /(\d{1,3}(?:\.\d+)?)\s?%/;
+// [#####-----], [====>    ], |████░░░░|
+const ASCII_BAR = /[[|]([#=*>\-. ]{5,})[\]|]/;
+const BLOCK_BAR = /([█▉▊▋▌▍▎▏■━]+)([░▒▓ ─╸╺·□]*)/;
+// < OK >, <Cancel>, [ Yes ], [Save]
+const BUTTON = /<\s?([A-Za-z][\w'&-]*(?: [\w'&-]+)?)\s?>|\[\s?([A-Z][\w'&-]*(?: [\w'&-]+)?)\s?\]/g;
+
+interface Row {
+  text: string;
+  runs: StyledRun[];
+}
+
+function isBlank(text: string): boolean {
+  return text.trim() === "";
+}
+
+/**
+ * The background most text on screen sits on; only a different one marks
+ * a highlight
+ */
+function dominantBackground(rows: Row[]): CellColor | undefined {
+  const counts = new Map<CellColor | undefined, number>();
+  for (const { runs } of rows) {
+    for (const run of runs) {
+      if (isBlank(run.text) || run.inverse) continue;
+      counts.set(run.bg, (counts.get(run.bg) ?? 0) + run.width);
+    }
+  }
+  let best: CellColor | undefined;
+  let bestCount = -1;
+  for (const [bg, count] of counts) {
+    if (count > bestCount) {
+      best = bg;
+      bestCount = count;
+    }
+  }
+  return best;
+}
+
+function highlightKind(run: StyledRun, background: CellColor | undefined): HighlightKind | null {
+  if (run.inverse) return "inverse";
+  if (run.bg !== background) return "background";
+  return null;
+}
+
+/**
+ * Stretches of a row drawn in inverse video or on an unusual background.
+ * Those spanning the whole width are bars (vim's status line, tmux), not
+ * selections.
+ */
+function findHighlights(rows: Row[], cols: number, background: CellColor | undefined): Highlight[] {
+  const highlights: Highlight[] = [];
+  rows.forEach(({ runs }, row) => {
+    let current: (Highlight & { width: number }) | null = null;
+    for (const run of runs) {
+      const kind = highlightKind(run, background);
+      if (kind && current && current.by === kind) {
+        current.text += run.text;
+        current.width += run.width;
+      } else if (kind) {
+        current = { row, col: run.col, text: run.text, by: kind, width: run.width };
+        highlights.push(current);
+      } else {
+        current = null;
+      }
+      if (current && current.col === 0 && current.width >= cols) current.bar = true;
+    }
+  });
+  return highlights
+    .map(({ width: _width, ...h }) => {
+      const lead = h.text.length - h.text.trimStart().length;
+      return { ...h, col: h.col + lead, text: h.text.trim() };
+    })
+    .filter((h) => h.text !== "");
+}
+
+function firstColumn(text: string): number {
+  return text.length - text.trimStart().length;
+}
+
+function isAllBold(row: Row): boolean {
+  const text = row.runs.filter((run) => !isBlank(run.text));
+  return text.length > 0 && text.every((run) => run.bold);
+}
+
+/**
+ * Rows that line up with a highlighted row above and below it, taken as the
+ * menu or list it selects from. Without any inverse or colored selection,
+ * a lone bold row among plain aligned rows counts as selected.
+ */
+function findLists(rows: Row[], highlights: Highlight[]): { lists: ListDescription[]; bold: Highlight[] } {
+  const lists: ListDescription[] = [];
+  const bold: Highlight[] = [];
+  const used = new Set(highlights.filter((h) => h.bar).map((h) => h.row));
+
+  const aligned = (row: number, col: number) =>
+    row >= 0 && row < rows.length && !isBlank(rows[row].text) &&
+    Math.abs(firstColumn(rows[row].text) - col) <= LIST_INDENT_SLACK;
+
+  const build = (row: number, col: number) => {
+    let start = row;
+    let end = row;
+    while (aligned(start - 1, col) && !used.has(start - 1)) start--;
+    while (aligned(end + 1, col) && !used.has(end + 1)) end++;
+    if (end - start + 1 < MIN_LIST_ITEMS) return;
+    for (let r = start; r <= end; r++) used.add(r);
+    lists.push({
+      startRow: start,
+      endRow: end,
+      items: rows.slice(start, end + 1).map((r) => r.text.trim()),
+      selected: row - start,
+    });
+  };
+
+  for (const highlight of highlights) {
+    if (used.has(highlight.row)) continue;
+    build(highlight.row, firstColumn(rows[highlight.row].text));
+  }
+
+  if (highlights.every((h) => h.bar)) {
+    rows.forEach((row, index) => {
+      if (used.has(index) || !isAllBold(row)) return;
+      const col = firstColumn(row.text);
+      const neighbors = [index - 1, index + 1].filter((r) => aligned(r, col));
+      if (neighbors.length === 0 || neighbors.some((r) => isAllBold(rows[r]))) return;
+      bold.push({ row: index, col, text: row.text.trim(), by: "bold" });
+      build(index, col);
+    });
+  }
+  return { lists, bold };
+}
+
+function findProgress(rows: Row[]): ProgressDescription[] {
+  const progress: ProgressDescription[] = [];
+  rows.forEach(({ text }, row) => {
+    const percent = PERCENT.exec(text);
+    const ascii = ASCII_BAR.exec(text);
+    const blocks = BLOCK_BAR.exec(text);
+    let fill: number | null = null;
+    if (ascii) {
+      const bar = ascii[1];
+      fill = (bar.length - bar.replace(/[#=*>]/g, "").length) / bar.length;
+      // A row of dashes is a rule, not an empty bar
+      if (fill === 0 && !percent && !/[ .]/.test(bar)) return;
+    } else if (blocks && blocks[0].length >= 5 && blocks[2].length > 0) {
+      fill = blocks[1].length / blocks[0].length;
+    }
+    if (!percent && fill === null) return;
+    const value = percent ? Math.min(parseFloat(percent[1]), 100) : Math.round(fill! * 100);
+    progress.push({ row, percent: value, text: text.trim() });
+  });
+  return progress;
+}
+
+function findButtons(rows: Row[], highlights: Highlight[]): ButtonDescription[] {
+  const buttons: ButtonDescription[] = [];
+  rows.forEach(({ text }, row) => {
+    for (const match of text.matchAll(BUTTON)) {
+      const label = (match[1] ?? match[2]).trim();
+      const col = match.index!;
+      const end = col + match[0].length;
+      const highlighted = highlights.some((h) => h.row === row && h.col < end && h.col + h.text.length > col);
+      buttons.push({ row, col, label, highlighted });
+    }
+  });
+  return buttons;
+}
+
+function readRows(terminal: Terminal): Row[] {
+  const buffer = terminal.buffer.active;
+  return getStyledRows(terminal, buffer.baseY, buffer.baseY + terminal.rows).map((styled, i) => ({
+    text: buffer.getLine(buffer.baseY + i)?.translateToString(true) ?? "",
+    runs: styled.runs,
+  }));
+}
+
+export interface Selection {
+  /** Best guess at the selected item: a list's selection, else the first highlight that isn't a bar */
+  selected: Highlight | null;
+  /** The list the selection belongs to, if any */
+  list: ListDescription | null;
+  highlighted: Highlight[];
+}
+
+/**
+ * Which rows and cells are highlighted on screen, and which of them looks
+ * like the current selection
+ */
+export function findSelection(terminal: Terminal): Selection {
+  const rows = readRows(terminal);
+  const found = findHighlights(rows, terminal.cols, dominantBackground(rows));
+  const { lists, bold } = findLists(rows, found);
+  const highlighted = [...found, ...bold];
+  const list = lists[0] ?? null;
+  const selected = list
+    ? highlighted.find((h) => h.row === list.startRow + list.selected) ?? null
+    : highlighted.find((h) => !h.bar) ?? null;
+  return { selected, list, highlighted };
+}
+
+export function describeScreen(terminal: Terminal, promptRegex: RegExp): ScreenDescription {
+  const buffer = terminal.buffer.active;
+  const rows = readRows(terminal);
+
+  // What is typed after the prompt is tested only up to the cursor
+  const beforeCursor = buffer.getLine(buffer.baseY + buffer.cursorY)?.translateToString(false, 0, buffer.cursorX) ?? "";
+  let prompt: ScreenDescription["prompt"] = promptRegex.test(beforeCursor.replace(/\s+$/, ""))
+    ? { row: buffer.cursorY, text: rows[buffer.cursorY].text, atCursor: true }
+    : null;
+  for (let row = rows.length - 1; row >= 0 && !prompt; row--) {
+    const text = rows[row].text;
+    if (text && promptRegex.test(text)) prompt = { row, text, atCursor: false };
+  }
+
+  const highlighted = findHighlights(rows, terminal.cols, dominantBackground(rows));
+  const { lists, bold } = findLists(rows, highlighted);
+  return {
+    prompt,
+    highlighted: [...highlighted, ...bold],
+    lists,
+    progress: findProgress(rows),
+    buttons: findButtons(rows, highlighted),
+  };
+}
@@ -0,0 +1,64 @@
+/**
+ * Standalone headless emulator helpers for offline rendering (the replay
+ * and render subcommands), outside of any PTY session.
+ */
+
+import xtermHeadless from "@xterm/headless";
+const { Terminal } = xtermHeadless;
+import { dumpScreen } from "./screenDump.js";
+import { renderTerminalToHtml } from "./html.js";
+import { renderTerminalToSvg, type ThemeName } from "./render.js";
+
+export type HeadlessTerminal = InstanceType<typeof Terminal>;
+
+/** Text-based screen formats; PNG goes through renderTerminalToPng */
+export type ScreenFormat = 'text' | 'json' | 'html' | 'svg';
+
+export const SCREEN_FORMATS: ScreenFormat[] = ['text', 'json', 'html', 'svg'];
+
+export interface EmulatorOptions {
+  /** Treat bare LF as CRLF, for output captured without a PTY */
+  convertEol?: boolean;
+}
+
+export function createEmulator(cols: number, rows: number, options: EmulatorOptions = {}): HeadlessTerminal {
+  return new Terminal({
+    cols,
+    rows,
+    scrollback: 1000,
+    allowProposedApi: true,
+    convertEol: options.convertEol ?? false,
+  });
+}
+
+/**
+ * Write to the emulator and resolve once the parser has processed the data
+ */
+export function writeAsync(terminal: HeadlessTerminal, data: string | Uint8Array): Promise<void> {
+  return new Promise((resolve) => terminal.write(data, resolve));
+}
+
+/**
+ * Render the visible screen in a text-based format
+ */
+export function renderScreen(terminal: HeadlessTerminal, format: ScreenFormat, theme?: ThemeName): string {
+  switch (format) {
+    case 'json':
+      return JSON.stringify(dumpScreen(terminal));
+    case 'html':
+      return renderTerminalToHtml(terminal, { theme });
+    case 'svg':
+      return renderTerminalToSvg(terminal, { theme }).svg;
+    case 'text': {
+      const buffer = terminal.buffer.active;
+      const lines: string[] = [];
+      for (let y = buffer.baseY; y < buffer.baseY + terminal.rows; y++) {
+        lines.push(buffer.getLine(y)?.translateToString(true) ?? '');
+      }
+      while (lines.length > 0 && lines[lines.length - 1].trim() === '') {
+        lines.pop();
+      }
+      return lines.join('\n');
+    }
+  }
+}
@@ -0,0 +1,122 @@
+import * as fs from "fs";
+import { DEFAULT_REDACT_PATTERNS, globToRegExp } from "./envRedact.js";
+
+/**
+ * Environment handed to spawned shells: variables from --env and
+ * --env-file, and which of the server's own variables are passed on
+ * (--no-inherit-env, --env-allow, --env-deny).
+ */
+
+// Kept from the parent environment even when it isn't inherited, since
+// shells and most programs misbehave without them
+const ESSENTIAL_ENV_VARS = [
+  "HOME",
+  "USER",
+  "LOGNAME",
+  "PATH",
+  "SHELL",
+  "LANG",
+  "LC_ALL",
+  "LC_CTYPE",
+  "TMPDIR",
+  "TZ",
+  // Windows
+  "SYSTEMROOT",
+  "SYSTEMDRIVE",
+  "WINDIR",
+  "COMSPEC",
+  "PATHEXT",
+  "USERPROFILE",
+  "APPDATA",
+  "LOCALAPPDATA",
+  "TEMP",
+  "TMP",
+];
+
+const NAME_PATTERN = /^[A-Za-z_][A-Za-z0-9_]*$/;
+
+/**
+ * Parse one KEY=VALUE assignment. The value may be wrapped in single or
+ * double quotes; double-quoted values understand \n, \t, \" and \\.
+ * Throws if the name is missing or invalid.
+ */
+export function parseEnvAssignment(assignment: string): [string, string] {
+  const eq = assignment.indexOf("=");
+  const name = (eq === -1 ? assignment : assignment.slice(0, eq)).trim();
+  if (eq === -1 || !NAME_PATTERN.test(name)) {
+    throw new Error(`expected KEY=VALUE, got '${assignment}'`);
+  }
+  let value = assignment.slice(eq + 1).trim();
+  const quote = value[0];
+  if ((quote === '"' || quote === "'") && value.length >= 2 && value.endsWith(quote)) {
+    value = value.slice(1, -1);
+    if (quote === '"') {
+      value = value.replace(/\\([nt"\\])/g, (_, ch: string) =>
+        ch === "n" ? "\n" : ch === "t" ? "\t" : ch
+      );
+    }
+  }
+  return [name, value];
+}
+
+/**
+ * Read a dotenv-style file: KEY=VALUE per line, optionally prefixed with
+ * `export`, with blank lines and # comments ignored. Throws with the line
+ * number on a malformed line.
+ */
+export function loadEnvFile(filePath: string): Record<string, string> {
+  const env: Record<string, string> = {};
+  const lines = fs.readFileSync(filePath, "utf8").split(/\r?\n/);
+  lines.forEach((raw, index) => {
+    const line = raw.trim();
+    if (!line || line.startsWith("#")) return;
+    try {
+      const [name, value] = parseEnvAssignment(line.replace(/^export\s+/, ""));
+      env[name] = value;
+    } catch (error) {
+      throw new Error(`${filePath}:${index + 1}: ${(error as Error).message}`);
+    }
+  });
+  return env;
+}
+
+/**
+ * Which of the server's variables a shell inherits
+ */
+export interface EnvPolicy {
+  /**
+   * Inherit everything (default: true). When false, only
+   * ESSENTIAL_ENV_VARS and names matching `allow` are passed on.
+   */
+  inherit?: boolean;
+  /** Name globs inherited on top of the essentials; implies inherit: false */
+  allow?: string[];
+  /** Name globs never inherited, applied after everything else */
+  deny?: string[];
+  /** Also deny the credential patterns getEnvironment masks (AWS_*, *_TOKEN, ...) */
+  denySecrets?: boolean;
+}
+
+/**
+ * The parent environment a shell starts from under a policy. Names match
+ * globs case-insensitively, where `*` matches any run of characters.
+ */
+export function baseEnv(policy: EnvPolicy = {}): Record<string, string> {
+  const inheritAll = (policy.inherit ?? true) && !policy.allow?.length;
+  const allow = (policy.allow ?? []).map(globToRegExp);
+  const deny = [...(policy.deny ?? []), ...(policy.denySecrets ? DEFAULT_REDACT_PATTERNS : [])].map(globToRegExp);
+
+  const env: Record<string, string> = {};
+  for (const [name, value] of Object.entries(process.env)) {
+    if (value === undefined) continue;
+    // Windows variable names are case-insensitive
+    const allowed =
+      inheritAll ||
+      ESSENTIAL_ENV_VARS.includes(name.toUpperCase()) ||
+      allow.some((pattern) => pattern.test(name));
+    if (allowed && !deny.some((pattern) => pattern.test(name))) {
+      env[name] = value;
+    }
+  }
+  return env;
+}
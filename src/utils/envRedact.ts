@@ -0,0 +1,58 @@
+/**
+ * Masking of credential-like environment variables before they are shown
+ * to an AI client. Patterns are case-insensitive globs where `*` matches
+ * any run of characters.
+ */
+
+export const DEFAULT_REDACT_PATTERNS = [
+  "AWS_*",
+  "AZURE_*",
+  "GOOGLE_APPLICATION_CREDENTIALS",
+  "*_TOKEN",
+  "*_TOKEN_*",
+  "*_SECRET",
+  "*_SECRET_*",
+  "*_PASSWORD",
+  "*_PASSWD",
+  "*_API_KEY",
+  "*_PRIVATE_KEY",
+  "*_ACCESS_KEY*",
+  "*_CREDENTIALS",
+  "DATABASE_URL",
+  "SSH_AUTH_SOCK",
+];
+
+export const REDACTED_VALUE = "[redacted]";
+
+export function globToRegExp(pattern: string): RegExp {
+  const source = pattern
+    .split("*")
+    .map((part) => part.replace(/[.+?^${}()|[\]\\]/g, "\\$&"))
+    .join(".*");
+  return new RegExp(`^${source}$`, "i");
+}
+
+export interface RedactedEnv {
+  env: Record<string, string>;
+  /** Names whose values were masked */
+  redacted: string[];
+}
+
+/**
+ * Mask values of variables matching any pattern. Names are kept so the
+ * caller can still see that a credential is configured.
+ */
+export function redactEnv(env: Record<string, string>, patterns: string[]): RedactedEnv {
+  const matchers = patterns.map(globToRegExp);
+  const result: Record<string, string> = {};
+  const redacted: string[] = [];
+  for (const [name, value] of Object.entries(env)) {
+    if (matchers.some((re) => re.test(name))) {
+      result[name] = REDACTED_VALUE;
+      redacted.push(name);
+    } else {
+      result[name] = value;
+    }
+  }
+  return { env: result, redacted };
+}
@@ -0,0 +1,160 @@
+/**
+ * Font fallback and glyph coverage for the PNG renderer.
+ *
+ * resvg falls back through the CSS font-family list, but when no installed
+ * font has a glyph it draws an empty "tofu" box. We can't query glyph tables
+ * without a font parser, so coverage is estimated per script class from the
+ * font files present in the search directories.
+ */
+
+import * as fs from "fs";
+import * as path from "path";
+
+/** Tried in order after the primary font. Missing families are skipped by resvg. */
+export const DEFAULT_FALLBACK_FONTS = [
+  'Noto Color Emoji',
+  'Apple Color Emoji',
+  'Segoe UI Emoji',
+  'Noto Sans Mono CJK SC',
+  'Noto Sans CJK SC',
+  'PingFang SC',
+  'Microsoft YaHei',
+  'Symbols Nerd Font',
+  'DejaVu Sans Mono',
+];
+
+export type GlyphClass = 'basic' | 'emoji' | 'cjk' | 'private' | 'unassigned';
+
+export interface GlyphCoverage {
+  emoji: boolean;
+  cjk: boolean;
+  /** Private Use Area icons (Nerd Fonts, Powerline) */
+  private: boolean;
+}
+
+const FONT_FILE = /\.(ttf|otf|ttc|otc)$/i;
+const EMOJI_FONT = /emoji/i;
+const CJK_FONT = /cjk|sourcehan|source-han|pingfang|hiragino|yahei|msyh|simsun|simhei|wqy|droidsansfallback|notosans(sc|tc|jp|kr)|notoserif(sc|tc|jp|kr)|malgun|mincho/i;
+const PRIVATE_FONT = /nerd|powerline/i;
+
+const MAX_SCAN_DEPTH = 4;
+const coverageCache = new Map<string, GlyphCoverage>();
+
+function collectFontFiles(dir: string, depth: number, out: string[]): void {
+  if (depth > MAX_SCAN_DEPTH) return;
+  let entries: fs.Dirent[];
+  try {
+    entries = fs.readdirSync(dir, { withFileTypes: true });
+  } catch {
+    return;
+  }
+  for (const entry of entries) {
+    if (entry.isDirectory()) {
+      collectFontFiles(path.join(dir, entry.name), depth + 1, out);
+    } else if (FONT_FILE.test(entry.name)) {
+      out.push(entry.name);
+    }
+  }
+}
+
+/**
+ * Estimate which glyph classes the fonts in fontDirs can draw.
+ * Results are cached per directory list.
+ */
+export function getGlyphCoverage(fontDirs: string[], families: string[] = []): GlyphCoverage {
+  const key = `${fontDirs.join('\0')}\n${families.join('\0')}`;
+  const cached = coverageCache.get(key);
+  if (cached) return cached;
+
+  const files: string[] = [];
+  for (const dir of fontDirs) {
+    collectFontFiles(dir, 0, files);
+  }
+  const names = files.map((f) => f.replace(/[\s_]/g, ''));
+  const has = (pattern: RegExp) => names.some((n) => pattern.test(n));
+
+  const coverage: GlyphCoverage = {
+    emoji: has(EMOJI_FONT),
+    cjk: has(CJK_FONT),
+    private: has(PRIVATE_FONT) || families.some((f) => PRIVATE_FONT.test(f)),
+  };
+  coverageCache.set(key, coverage);
+  return coverage;
+}
+
+/**
+ * Classify a cell's grapheme by the kind of font needed to draw it.
+ */
+export function classifyGlyph(char: string): GlyphClass {
+  const code = char.codePointAt(0) ?? 0;
+  if (code < 0x2000) return 'basic';
+  if (/\p{Co}/u.test(char)) return 'private';
+  if (/\p{Cn}/u.test(char)) return 'unassigned';
+  if (/\p{Extended_Pictographic}/u.test(char) && (code >= 0x1f000 || char.includes('\uFE0F'))) {
+    return 'emoji';
+  }
+  if (/[\p{Script=Han}\p{Script=Hiragana}\p{Script=Katakana}\p{Script=Hangul}\p{Script=Bopomofo}\u3000-\u303f\uff00-\uffef]/u.test(char)) {
+    return 'cjk';
+  }
+  return 'basic';
+}
+
+export function isRenderable(glyphClass: GlyphClass, coverage: GlyphCoverage): boolean {
+  switch (glyphClass) {
+    case 'basic':
+      return true;
+    case 'emoji':
+      return coverage.emoji;
+    case 'cjk':
+      return coverage.cjk;
+    case 'private':
+      return coverage.private;
+    case 'unassigned':
+      return false;
+  }
+}
+
+const FONT_HINTS: Record<Exclude<GlyphClass, 'basic'>, string> = {
+  emoji: 'no emoji font found (e.g. Noto Color Emoji)',
+  cjk: 'no CJK font found (e.g. Noto Sans CJK)',
+  private: 'private-use icon; needs a Nerd Font',
+  unassigned: 'unassigned code point',
+};
+
+export interface MissingGlyph {
+  char: string;
+  glyphClass: GlyphClass;
+  row: number;
+  col: number;
+}
+
+/**
+ * Summarize missing glyphs as human-readable warnings, one per distinct
+ * code point, capped at maxWarnings.
+ */
+export function formatGlyphWarnings(missing: MissingGlyph[], maxWarnings = 20): string[] {
+  const seen = new Map<string, { glyph: MissingGlyph; count: number }>();
+  for (const glyph of missing) {
+    const entry = seen.get(glyph.char);
+    if (entry) {
+      entry.count++;
+    } else {
+      seen.set(glyph.char, { glyph, count: 1 });
+    }
+  }
+
+  const warnings: string[] = [];
+  for (const { glyph, count } of seen.values()) {
+    if (warnings.length >= maxWarnings) {
+      warnings.push(`...and ${seen.size - maxWarnings} more distinct glyph(s)`);
+      break;
+    }
+    const codes = Array.from(glyph.char)
+      .map((c) => `U+${c.codePointAt(0)!.toString(16).toUpperCase().padStart(4, '0')}`)
+      .join(' ');
+    const hint = glyph.glyphClass === 'basic' ? '' : `: ${FONT_HINTS[glyph.glyphClass]}`;
+    const where = count > 1 ? `${count} cells, first at row ${glyph.row}, col ${glyph.col}` : `row ${glyph.row}, col ${glyph.col}`;
+    warnings.push(`${codes} replaced with placeholder (${where})${hint}`);
+  }
+  return warnings;
+}
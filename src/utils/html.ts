@@ -0,0 +1,92 @@
+/**
+ * Renders the terminal screen to a self-contained HTML snippet: a <pre>
+ * block with one inline-styled <span> per styled run. No external CSS or
+ * scripts, so it can be pasted into reports, issues, and PR comments.
+ */
+
+import type { Terminal } from "@xterm/headless";
+import { THEMES, color256, type RenderTheme, type ThemeName } from "./render.js";
+import { getStyledRows, type CellColor, type StyledRun } from "./screenDump.js";
+
+export interface HtmlRenderOptions {
+  /** Color theme. Default: 'one-dark' */
+  theme?: ThemeName;
+}
+
+function escapeHtml(s: string): string {
+  return s.replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;").replace(/"/g, "&quot;");
+}
+
+function cssColor(color: CellColor | undefined, fallback: string, theme: RenderTheme): string {
+  if (color === undefined) return fallback;
+  if (typeof color === "string") return color;
+  return color256(color, theme);
+}
+
+function runStyle(run: StyledRun, theme: RenderTheme): string {
+  let fg = cssColor(run.fg, theme.foreground, theme);
+  let bg = cssColor(run.bg, theme.background, theme);
+  if (run.inverse) {
+    [fg, bg] = [bg, fg];
+  }
+
+  const decls: string[] = [];
+  if (run.invisible) {
+    decls.push("color:transparent");
+  } else if (fg !== theme.foreground) {
+    decls.push(`color:${fg}`);
+  }
+  if (bg !== theme.background) decls.push(`background:${bg}`);
+  if (run.bold) decls.push("font-weight:bold");
+  if (run.dim) decls.push("opacity:0.6");
+  if (run.italic) decls.push("font-style:italic");
+
+  const decorations: string[] = [];
+  if (run.underline) decorations.push("underline");
+  if (run.overline) decorations.push("overline");
+  if (run.strikethrough) decorations.push("line-through");
+  if (decorations.length > 0) {
+    if (run.underlineStyle) decorations.push(run.underlineStyle === "curly" ? "wavy" : run.underlineStyle);
+    if (run.underlineColor !== undefined) decorations.push(cssColor(run.underlineColor, fg, theme));
+    decls.push(`text-decoration:${decorations.join(" ")}`);
+  }
+
+  return decls.join(";");
+}
+
+function renderRun(run: StyledRun, theme: RenderTheme): string {
+  const style = runStyle(run, theme);
+  const text = escapeHtml(run.text);
+  return style ? `<span style="${style}">${text}</span>` : text;
+}
+
+/**
+ * Render the visible viewport of an xterm.js Terminal to an HTML snippet.
+ */
+export function renderTerminalToHtml(terminal: Terminal, options: HtmlRenderOptions = {}): string {
+  const { theme: themeName = "one-dark" } = options;
+  const theme = THEMES[themeName];
+  const buffer = terminal.buffer.active;
+  const rows = getStyledRows(terminal, buffer.baseY, buffer.baseY + terminal.rows);
+
+  // Drop trailing empty rows so short sessions don't produce a tall block
+  let last = rows.length;
+  while (last > 0 && rows[last - 1].runs.length === 0) {
+    last--;
+  }
+
+  const lines = rows.slice(0, last).map((row) => row.runs.map((run) => renderRun(run, theme)).join(""));
+
+  const preStyle = [
+    `background:${theme.background}`,
+    `color:${theme.foreground}`,
+    "font-family:'JetBrains Mono',Menlo,Consolas,monospace",
+    "font-size:13px",
+    "line-height:1.3",
+    "padding:12px 16px",
+    "border-radius:8px",
+    "overflow-x:auto",
+  ].join(";");
+
+  return `<pre class="terminal-mcp-screen" style="${preStyle}">${lines.join("\n")}</pre>`;
+}
@@ -0,0 +1,30 @@
+import { Server } from "@modelcontextprotocol/sdk/server/index.js";
+import { ErrorCode, McpError } from "@modelcontextprotocol/sdk/types.js";
+
+/**
+ * MCP lifecycle check: a client must send initialize and then the
+ * notifications/initialized notification before calling tools. Clients
+ * that skip the handshake otherwise fail later in confusing ways, e.g.
+ * without the capabilities they were meant to negotiate.
+ *
+ * Returns a function that throws a JSON-RPC InvalidRequest error until the
+ * handshake has completed. With strict off (--no-strict-init) it never
+ * throws.
+ */
+export function createInitGuard(server: Server, strict: boolean): () => void {
+  let initialized = false;
+  const oninitialized = server.oninitialized;
+  server.oninitialized = () => {
+    initialized = true;
+    oninitialized?.();
+  };
+
+  return () => {
+    if (!strict || initialized) return;
+    throw new McpError(
+      ErrorCode.InvalidRequest,
+      "Server not initialized: send initialize and then notifications/initialized before calling tools " +
+        "(start the server with --no-strict-init to allow this)"
+    );
+  };
+}
@@ -73,33 +73,147 @@ export const KEY_SEQUENCES: Record<string, string> = {
 
   // Common shortcuts
   "Ctrl+Space": "\x00",
+
+  // Numeric keypad (normal mode sends the plain character)
+  "Numpad0": "0",
+  "Numpad1": "1",
+  "Numpad2": "2",
+  "Numpad3": "3",
+  "Numpad4": "4",
+  "Numpad5": "5",
+  "Numpad6": "6",
+  "Numpad7": "7",
+  "Numpad8": "8",
+  "Numpad9": "9",
+  "NumpadEnter": "\r",
+  "NumpadAdd": "+",
+  "NumpadSubtract": "-",
+  "NumpadMultiply": "*",
+  "NumpadDivide": "/",
+  "NumpadDecimal": ".",
 };
 
 /**
- * Get the escape sequence for a key name
- * @param key - The key name (e.g., "Enter", "Ctrl+C", "ArrowUp")
- * @returns The escape sequence or null if not found
+ * Overrides used when the application has enabled application cursor key
+ * mode (DECCKM). Full-screen apps like vim and less expect SS3 (ESC O)
+ * sequences instead of CSI for these keys.
+ */
+export const APPLICATION_CURSOR_SEQUENCES: Record<string, string> = {
+  "ArrowUp": "\x1bOA",
+  "ArrowDown": "\x1bOB",
+  "ArrowRight": "\x1bOC",
+  "ArrowLeft": "\x1bOD",
+  "Home": "\x1bOH",
+  "End": "\x1bOF",
+};
+
+/**
+ * Overrides used when the application has enabled application keypad mode
+ * (DECKPAM).
  */
-export function getKeySequence(key: string): string | null {
+export const APPLICATION_KEYPAD_SEQUENCES: Record<string, string> = {
+  "Numpad0": "\x1bOp",
+  "Numpad1": "\x1bOq",
+  "Numpad2": "\x1bOr",
+  "Numpad3": "\x1bOs",
+  "Numpad4": "\x1bOt",
+  "Numpad5": "\x1bOu",
+  "Numpad6": "\x1bOv",
+  "Numpad7": "\x1bOw",
+  "Numpad8": "\x1bOx",
+  "Numpad9": "\x1bOy",
+  "NumpadEnter": "\x1bOM",
+  "NumpadAdd": "\x1bOk",
+  "NumpadSubtract": "\x1bOm",
+  "NumpadMultiply": "\x1bOj",
+  "NumpadDivide": "\x1bOo",
+  "NumpadDecimal": "\x1bOn",
+};
+
+export interface KeyEncodingModes {
+  applicationCursorKeys?: boolean;
+  applicationKeypad?: boolean;
+}
+
+/**
+ * Find the canonical key name for a (possibly differently cased) key name
+ */
+function canonicalKeyName(key: string): string | null {
   // Normalize key name
   const normalized = key.trim();
 
   // Check direct match
-  if (KEY_SEQUENCES[normalized]) {
-    return KEY_SEQUENCES[normalized];
+  if (normalized in KEY_SEQUENCES) {
+    return normalized;
   }
 
   // Check case-insensitive match
   const lowerKey = normalized.toLowerCase();
-  for (const [name, seq] of Object.entries(KEY_SEQUENCES)) {
+  for (const name of Object.keys(KEY_SEQUENCES)) {
     if (name.toLowerCase() === lowerKey) {
-      return seq;
+      return name;
     }
   }
 
   return null;
 }
 
+/**
+ * Decode a key given by value rather than name: a Unicode codepoint
+ * ("U+00E9") or raw bytes in hex ("0x1b 0x5b 0x41"). Returns null when the
+ * key is in neither form and throws when it is but can't be sent. Input
+ * reaches the PTY as UTF-8 text, so bytes must form valid UTF-8.
+ */
+function rawKeySequence(key: string): string | null {
+  const normalized = key.trim();
+
+  const codepoint = /^U\+([0-9a-f]{1,6})$/i.exec(normalized);
+  if (codepoint) {
+    const value = parseInt(codepoint[1], 16);
+    if (value > 0x10ffff || (value >= 0xd800 && value <= 0xdfff)) {
+      throw new Error(`Invalid codepoint: "${normalized}" is not a Unicode scalar value`);
+    }
+    return String.fromCodePoint(value);
+  }
+
+  if (/^0x[0-9a-f]{1,2}(?:[\s,]+0x[0-9a-f]{1,2})*$/i.test(normalized)) {
+    const bytes = normalized.split(/[\s,]+/).map((byte) => parseInt(byte, 16));
+    try {
+      return new TextDecoder("utf-8", { fatal: true }).decode(Uint8Array.from(bytes));
+    } catch {
+      throw new Error(
+        `Invalid byte sequence: "${normalized}" is not valid UTF-8, and input is sent to the terminal as UTF-8`
+      );
+    }
+  }
+
+  return null;
+}
+
+/**
+ * Get the escape sequence for a key name
+ * @param key - The key name (e.g., "Enter", "Ctrl+C", "ArrowUp"), a
+ *   codepoint ("U+00E9"), or raw bytes in hex ("0x1b 0x5b 0x41")
+ * @param modes - Terminal input modes; selects the application-mode
+ *   encoding for cursor and keypad keys when the app has enabled it
+ * @returns The escape sequence or null if not found
+ */
+export function getKeySequence(key: string, modes: KeyEncodingModes = {}): string | null {
+  const name = canonicalKeyName(key);
+  if (name === null) {
+    return rawKeySequence(key);
+  }
+
+  if (modes.applicationCursorKeys && APPLICATION_CURSOR_SEQUENCES[name]) {
+    return APPLICATION_CURSOR_SEQUENCES[name];
+  }
+  if (modes.applicationKeypad && APPLICATION_KEYPAD_SEQUENCES[name]) {
+    return APPLICATION_KEYPAD_SEQUENCES[name];
+  }
+
+  return KEY_SEQUENCES[name];
+}
+
 /**
  * Get all available key names
  */
@@ -0,0 +1,89 @@
+/**
+ * Mouse event encoding for terminal applications that enable mouse reporting.
+ * Supports SGR (DECSET 1006) encoding and the legacy X10/normal encoding.
+ */
+
+export type MouseButton = "left" | "middle" | "right";
+export type MouseModifier = "shift" | "alt" | "ctrl";
+export type MouseTrackingMode = "none" | "x10" | "vt200" | "drag" | "any";
+export type MouseEncoding = "default" | "sgr";
+
+export interface MouseEvent {
+  /** 0-indexed column */
+  col: number;
+  /** 0-indexed row */
+  row: number;
+  button: MouseButton | "wheelUp" | "wheelDown" | "none";
+  action: "press" | "release" | "motion";
+  modifiers?: MouseModifier[];
+}
+
+const BUTTON_CODES: Record<MouseEvent["button"], number> = {
+  left: 0,
+  middle: 1,
+  right: 2,
+  none: 3,
+  wheelUp: 64,
+  wheelDown: 65,
+};
+
+const MODIFIER_CODES: Record<MouseModifier, number> = {
+  shift: 4,
+  alt: 8,
+  ctrl: 16,
+};
+
+const MOTION_FLAG = 32;
+
+/**
+ * Encode a single mouse event as an escape sequence.
+ * Returns null if the event can't be represented (legacy encoding only
+ * covers coordinates up to 223).
+ */
+export function encodeMouseEvent(event: MouseEvent, encoding: MouseEncoding): string | null {
+  let code = BUTTON_CODES[event.button];
+  for (const mod of event.modifiers ?? []) {
+    code |= MODIFIER_CODES[mod];
+  }
+  if (event.action === "motion") {
+    code |= MOTION_FLAG;
+  }
+
+  const x = event.col + 1;
+  const y = event.row + 1;
+
+  if (encoding === "sgr") {
+    // SGR reports the button on release too, terminated with 'm'
+    const final = event.action === "release" ? "m" : "M";
+    return `\x1b[<${code};${x};${y}${final}`;
+  }
+
+  // Legacy encoding: release is reported as button 3, values offset by 32
+  if (event.action === "release") {
+    code = (code & ~0b11) | BUTTON_CODES.none;
+  }
+  if (x > 223 || y > 223) {
+    return null;
+  }
+  return `\x1b[M${String.fromCharCode(code + 32)}${String.fromCharCode(x + 32)}${String.fromCharCode(y + 32)}`;
+}
+
+/**
+ * Whether a tracking mode reports a given event type.
+ * x10 only reports presses; vt200 adds releases; drag adds motion while a
+ * button is held; any reports all motion.
+ */
+export function modeReports(mode: MouseTrackingMode, action: MouseEvent["action"], buttonHeld: boolean): boolean {
+  switch (mode) {
+    case "none":
+      return false;
+    case "x10":
+      return action === "press";
+    case "vt200":
+      return action !== "motion";
+    case "drag":
+      return action !== "motion" || buttonHeld;
+    case "any":
+      return true;
+  }
+}
@@ -0,0 +1,76 @@
+/**
+ * Output normalization for golden-screen comparisons: timestamps, PIDs,
+ * and durations differ on every run, so a CI check that compares a screen
+ * against a stored copy fails for no reason. Each rule replaces a variable
+ * value with a placeholder. Rules run line by line, so line counts stay
+ * the same.
+ */
+
+export interface NormalizeRule {
+  pattern: RegExp;
+  replacement: string;
+}
+
+export interface ReplacementSpec {
+  pattern: string;
+  replacement: string;
+  flags: string;
+}
+
+const MONTH = "(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)";
+const WEEKDAY = "(?:Mon|Tue|Wed|Thu|Fri|Sat|Sun)";
+
+/**
+ * The built-in rules, in the order they run: whole timestamps go before
+ * the times of day inside them
+ */
+export const DEFAULT_NORMALIZE_RULES: readonly NormalizeRule[] = [
+  // 2026-04-25T13:02:41.093Z, 2026-04-25 13:02:41+02:00
+  {
+    pattern: /\b\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?(?![\d:])/g,
+    replacement: "<TIMESTAMP>",
+  },
+  // `date` (Sat Apr 25 13:02:41 UTC 2026), syslog and ls -l (Apr 25 13:02)
+  {
+    pattern: new RegExp(
+      `\\b(?:${WEEKDAY},? )?${MONTH} [ \\d]\\d,? \\d{2}:\\d{2}(?::\\d{2})?(?: [A-Z]{2,5})?(?: \\d{4})?\\b`,
+      "g"
+    ),
+    replacement: "<TIMESTAMP>",
+  },
+  { pattern: /\b\d{4}-\d{2}-\d{2}\b/g, replacement: "<DATE>" },
+  { pattern: /\b\d{1,2}:\d{2}:\d{2}(?:[.,]\d+)?\b/g, replacement: "<TIME>" },
+  // 1.23s, 450ms, 12µs, 1m30s, `time`'s 0m0.004s, 12 seconds. A bare m or h
+  // only counts next to another unit, so 250m and 4h are left alone
+  {
+    pattern: /\b(?:\d+(?:\.\d+)?(?:ns|µs|us|ms|h|m|s)){2,3}\b|\b\d+(?:\.\d+)?(?:ns|µs|us)\b|\b\d+(?:\.\d+)? ?(?:s|ms|seconds?|secs?|minutes?|mins?|hours?|millis(?:econds?)?)\b/g,
+    replacement: "<DURATION>",
+  },
+  // pid 4821, PID: 4821, pid=4821
+  { pattern: /\b(pid|PID|Pid)([ =:#]+)\d+\b/g, replacement: "$1$2<PID>" },
+  // sshd[4821]: in syslog lines
+  { pattern: /\b([\w.-]+)\[\d+\]:/g, replacement: "$1[<PID>]:" },
+  // The shell's job notice for `cmd &`: [1] 4821
+  { pattern: /^(\[\d+\]\s+)\d+$/g, replacement: "$1<PID>" },
+];
+
+/**
+ * Compile caller-supplied replacements, naming the one that fails
+ */
+export function compileReplacements(specs: readonly ReplacementSpec[]): NormalizeRule[] {
+  return specs.map(({ pattern, replacement, flags }, i) => {
+    try {
+      // Every occurrence is replaced; y would stop at the first miss
+      return { pattern: new RegExp(pattern, flags.replace(/[gy]/g, "") + "g"), replacement };
+    } catch (error) {
+      throw new Error(`Invalid replacement pattern at index ${i}: ${(error as Error).message}`);
+    }
+  });
+}
+
+export function normalizeText(text: string, rules: readonly NormalizeRule[]): string {
+  return text
+    .split("\n")
+    .map((line) => rules.reduce((result, { pattern, replacement }) => result.replace(pattern, replacement), line))
+    .join("\n");
+}
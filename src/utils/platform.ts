@@ -1,3 +1,4 @@
+import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
 
@@ -19,6 +20,33 @@ export function getDefaultRecordDir(): string {
   return path.join(stateHome, 'terminal-mcp', 'recordings');
 }
 
+/**
+ * Directory for --persist sessions' sockets and saved output
+ */
+export function getDefaultPersistDir(): string {
+  if (process.env.TERMINAL_MCP_PERSIST_DIR) {
+    return process.env.TERMINAL_MCP_PERSIST_DIR;
+  }
+  const stateHome = process.env.XDG_STATE_HOME
+    || path.join(os.homedir(), '.local', 'state');
+
+  return path.join(stateHome, 'terminal-mcp', 'sessions');
+}
+
+/**
+ * Directory where headless servers listen for `terminal-mcp attach`, one
+ * socket per server named after its pid
+ */
+export function getDefaultAttachDir(): string {
+  if (process.env.TERMINAL_MCP_ATTACH_DIR) {
+    return process.env.TERMINAL_MCP_ATTACH_DIR;
+  }
+  if (process.env.XDG_RUNTIME_DIR) {
+    return path.join(process.env.XDG_RUNTIME_DIR, 'terminal-mcp', 'attach');
+  }
+  return path.join(os.tmpdir(), `terminal-mcp-${os.userInfo().uid}`, 'attach');
+}
+
 /**
  * Get the default IPC path for cross-platform communication.
  * Uses named pipes on Windows, Unix sockets elsewhere.
@@ -39,3 +67,55 @@ export function getDefaultShell(): string {
   }
   return process.env.SHELL || "/bin/bash";
 }
+
+/**
+ * Shell used when the configured one can't be started
+ */
+export const FALLBACK_SHELL = "/bin/sh";
+
+/**
+ * Resolve a shell to the file that would be executed: absolute and
+ * relative paths as given, bare names looked up on PATH. Returns null
+ * when a bare name isn't found.
+ */
+export function resolveExecutable(command: string): string | null {
+  if (command.includes("/") || command.includes("\\")) {
+    return path.resolve(command);
+  }
+  const extensions = process.platform === "win32"
+    ? ["", ...(process.env.PATHEXT ?? ".EXE;.CMD;.BAT").split(";")]
+    : [""];
+  for (const dir of (process.env.PATH ?? "").split(path.delimiter)) {
+    if (!dir) continue;
+    for (const ext of extensions) {
+      const candidate = path.join(dir, command + ext);
+      if (fs.existsSync(candidate)) return candidate;
+    }
+  }
+  return null;
+}
+
+/**
+ * Check that a shell can be spawned. Returns a short description of the
+ * problem ("does not exist", "is not executable", ...) or null when it looks fine.
+ */
+export function checkShell(shell: string): string | null {
+  if (!shell.trim()) return "is empty";
+  const resolved = resolveExecutable(shell);
+  if (!resolved) return "was not found on PATH";
+  let stat: fs.Stats;
+  try {
+    stat = fs.statSync(resolved);
+  } catch {
+    return "does not exist";
+  }
+  if (stat.isDirectory()) return "is a directory";
+  if (process.platform !== "win32") {
+    try {
+      fs.accessSync(resolved, fs.constants.X_OK);
+    } catch {
+      return "is not executable";
+    }
+  }
+  return null;
+}
@@ -0,0 +1,143 @@
+/**
+ * Linux /proc inspection for shells we can't instrument (dash, ash,
+ * restricted shells). Reads the shell's cwd and the terminal's foreground
+ * process group without ptrace or shell integration.
+ */
+
+import * as fs from "fs";
+
+export interface ForegroundProcess {
+  pid: number;
+  /** Executable name (argv[0] basename, or comm when cmdline is unreadable) */
+  command: string;
+  args: string[];
+  cwd: string | null;
+}
+
+export interface ProcessInfo {
+  /** Working directory of the shell process */
+  cwd: string | null;
+  /** Job running in the foreground, or null when the shell itself is */
+  foreground: ForegroundProcess | null;
+}
+
+/**
+ * The process group holding the terminal, described by a live member
+ * (normally its leader)
+ */
+export interface ForegroundGroup extends ForegroundProcess {
+  pgid: number;
+  /** From /proc/<pid>/stat: R running, S sleeping, D disk wait, T stopped, Z zombie */
+  state: string;
+  /** The shell itself holds the terminal (at its prompt, or reading input) */
+  isShell: boolean;
+}
+
+interface ProcStat {
+  comm: string;
+  state: string;
+  pgrp: number;
+  tpgid: number;
+}
+
+export function isProcfsAvailable(): boolean {
+  return process.platform === "linux" && fs.existsSync("/proc/self/stat");
+}
+
+function readStat(pid: number): ProcStat | null {
+  try {
+    const raw = fs.readFileSync(`/proc/${pid}/stat`, "utf8");
+    // comm is parenthesized and may itself contain spaces or ')'
+    const open = raw.indexOf("(");
+    const close = raw.lastIndexOf(")");
+    const fields = raw.slice(close + 2).split(" ");
+    // fields[0] is state (field 3); pgrp is field 5, tpgid is field 8
+    return {
+      comm: raw.slice(open + 1, close),
+      state: fields[0],
+      pgrp: parseInt(fields[2], 10),
+      tpgid: parseInt(fields[5], 10),
+    };
+  } catch {
+    return null;
+  }
+}
+
+function readCmdline(pid: number): string[] {
+  try {
+    return fs.readFileSync(`/proc/${pid}/cmdline`, "utf8").split("\0").filter((arg) => arg.length > 0);
+  } catch {
+    return [];
+  }
+}
+
+export function readCwd(pid: number): string | null {
+  try {
+    return fs.readlinkSync(`/proc/${pid}/cwd`);
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Find a live member of a process group. The group leader usually is one,
+ * but it may have exited while other members (e.g. in a pipeline) remain.
+ */
+function findGroupMember(pgrp: number): number | null {
+  if (readStat(pgrp)) return pgrp;
+  try {
+    for (const entry of fs.readdirSync("/proc")) {
+      if (!/^\d+$/.test(entry)) continue;
+      const pid = parseInt(entry, 10);
+      if (readStat(pid)?.pgrp === pgrp) return pid;
+    }
+  } catch {
+    // /proc unreadable
+  }
+  return null;
+}
+
+function describeProcess(pid: number): ForegroundProcess {
+  const args = readCmdline(pid);
+  const command = args.length > 0
+    ? args[0].split("/").pop()!
+    : readStat(pid)?.comm ?? "";
+  return { pid, command, args, cwd: readCwd(pid) };
+}
+
+/**
+ * Inspect a shell running on a PTY. Returns null when /proc is unavailable
+ * or the shell has exited.
+ */
+export function getProcessInfo(shellPid: number): ProcessInfo | null {
+  if (!isProcfsAvailable()) return null;
+  const shell = readStat(shellPid);
+  if (!shell) return null;
+
+  let foreground: ForegroundProcess | null = null;
+  if (shell.tpgid > 0 && shell.tpgid !== shell.pgrp) {
+    const pid = findGroupMember(shell.tpgid);
+    if (pid !== null) {
+      foreground = describeProcess(pid);
+    }
+  }
+
+  return { cwd: readCwd(shellPid), foreground };
+}
+
+/**
+ * The terminal's foreground process group, including when that is the
+ * shell itself. Returns null when /proc is unavailable or the shell has
+ * exited.
+ */
+export function getForegroundGroup(shellPid: number): ForegroundGroup | null {
+  if (!isProcfsAvailable()) return null;
+  const shell = readStat(shellPid);
+  if (!shell) return null;
+
+  const isShell = shell.tpgid <= 0 || shell.tpgid === shell.pgrp;
+  const pid = isShell ? shellPid : findGroupMember(shell.tpgid);
+  const stat = pid === null ? null : readStat(pid);
+  if (pid === null || !stat) return null;
+  return { ...describeProcess(pid), pgid: stat.pgrp, state: stat.state, isShell };
+}
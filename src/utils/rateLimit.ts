@@ -0,0 +1,109 @@
+import { McpError } from "@modelcontextprotocol/sdk/types.js";
+
+/**
+ * Per-client caps on tool calls and typed input (--rate-limit-calls,
+ * --rate-limit-input), so an agent stuck in a loop can't hammer the shell.
+ * Each MCP connection gets its own RateLimiter; limits are counted over a
+ * sliding one-minute window.
+ */
+
+// JSON-RPC error code for a call refused by a rate limit (next to TERMINAL_BUSY)
+export const RATE_LIMITED = -32002;
+
+const WINDOW_MS = 60_000;
+
+// Calls whose arguments are written to the PTY and count toward the input quota
+const INPUT_TOOLS = new Set(["type", "secureType", "sendKey"]);
+
+export interface RateLimits {
+  /** Tool calls per minute */
+  callsPerMinute?: number;
+  /** Bytes of input (type, secureType, sendKey) per minute */
+  inputBytesPerMinute?: number;
+}
+
+export type RateLimitKind = "calls" | "input";
+
+export class RateLimitedError extends McpError {
+  constructor(limit: RateLimitKind, max: number, retryAfterMs: number | null) {
+    const what = limit === "calls" ? `${max} tool calls` : `${max} bytes of input`;
+    const flag = limit === "calls" ? "--rate-limit-calls" : "--rate-limit-input";
+    super(
+      RATE_LIMITED,
+      retryAfterMs === null
+        ? `Rate limit exceeded: this call alone is more than ${what} per minute (${flag}). Split it into smaller calls`
+        : `Rate limit exceeded: more than ${what} per minute (${flag}). Retry in ${Math.ceil(retryAfterMs / 1000)}s`,
+      { limit, max, windowMs: WINDOW_MS, retryAfterMs }
+    );
+  }
+}
+
+/**
+ * Bytes a tool call will write to the PTY, estimated from its arguments;
+ * each key press counts as one byte
+ */
+export function inputBytes(tool: string, args: Record<string, unknown> | undefined): number {
+  if (!INPUT_TOOLS.has(tool) || !args) return 0;
+  if (tool === "sendKey") {
+    const keys = Array.isArray(args.keys) ? args.keys.length : 1;
+    const count = typeof args.count === "number" && args.count > 0 ? args.count : 1;
+    return keys * count;
+  }
+  return typeof args.text === "string" ? Buffer.byteLength(args.text) : 0;
+}
+
+/**
+ * Parse --rate-limit-input: a bare number of bytes, or a size like 64K
+ */
+export function parseInputQuota(value: string): number {
+  const match = /^(\d+)\s*([KMG])?i?B?$/i.exec(value.trim());
+  if (!match || parseInt(match[1], 10) <= 0) {
+    throw new Error(`expected a byte count like 4096 or 64K, got '${value}'`);
+  }
+  const unit = { K: 1024, M: 1024 ** 2, G: 1024 ** 3 }[(match[2] ?? "").toUpperCase()] ?? 1;
+  return parseInt(match[1], 10) * unit;
+}
+
+/**
+ * Counts one client's calls and input over the last minute
+ */
+export class RateLimiter {
+  // Admitted calls, oldest first
+  private events: Array<{ at: number; bytes: number }> = [];
+
+  constructor(private limits: RateLimits) {}
+
+  /**
+   * Admit a call, or throw RateLimitedError with how long to wait. Refused
+   * calls don't count.
+   */
+  admit(tool: string, args: Record<string, unknown> | undefined): void {
+    const now = Date.now();
+    while (this.events.length > 0 && now - this.events[0].at >= WINDOW_MS) {
+      this.events.shift();
+    }
+    const { callsPerMinute, inputBytesPerMinute } = this.limits;
+    if (callsPerMinute && this.events.length >= callsPerMinute) {
+      // Wait for enough of the oldest calls to leave the window
+      const freedAt = this.events[this.events.length - callsPerMinute].at + WINDOW_MS;
+      throw new RateLimitedError("calls", callsPerMinute, freedAt - now);
+    }
+    const bytes = inputBytes(tool, args);
+    if (inputBytesPerMinute && bytes > 0) {
+      if (bytes > inputBytesPerMinute) {
+        throw new RateLimitedError("input", inputBytesPerMinute, null);
+      }
+      let used = this.events.reduce((sum, event) => sum + event.bytes, 0);
+      if (used + bytes > inputBytesPerMinute) {
+        let freedAt = now;
+        for (const event of this.events) {
+          used -= event.bytes;
+          freedAt = event.at + WINDOW_MS;
+          if (used + bytes <= inputBytesPerMinute) break;
+        }
+        throw new RateLimitedError("input", inputBytesPerMinute, freedAt - now);
+      }
+    }
+    this.events.push({ at: now, bytes });
+  }
+}
@@ -0,0 +1,210 @@
+/**
+ * Render-only reflow: copy a session's buffer into a scratch emulator and
+ * resize that, so content can be read at a different width without
+ * resizing the live PTY (which would SIGWINCH the running program).
+ *
+ * Wrapped lines are copied as continuous text so the scratch emulator marks
+ * them wrapped too; xterm.js then rejoins and rewraps them on resize like a
+ * real terminal would. Lines ended by a hard newline stay separate.
+ */
+
+import { createEmulator, writeAsync, type HeadlessTerminal } from "./emulate.js";
+import {
+  getStyledRows,
+  sameStyle,
+  type CellColor,
+  type CellStyle,
+  type StyledRun,
+  type UnderlineStyle,
+} from "./screenDump.js";
+
+export const MIN_REFLOW_COLS = 20;
+export const MAX_REFLOW_COLS = 500;
+
+const UNDERLINE_STYLE_SGR: Record<UnderlineStyle, string> = {
+  double: "4:2",
+  curly: "4:3",
+  dotted: "4:4",
+  dashed: "4:5",
+};
+
+function colorSgr(color: CellColor, base: 30 | 40 | 50): string {
+  // SGR 58 (underline color) has no 16-color form
+  if (base === 50 && typeof color === "number") return `58;5;${color}`;
+  if (typeof color === "string") {
+    const rgb = parseInt(color.slice(1), 16);
+    return `${base + 8};2;${(rgb >> 16) & 0xff};${(rgb >> 8) & 0xff};${rgb & 0xff}`;
+  }
+  if (color < 8) return `${base + color}`;
+  if (color < 16) return `${base + 60 + color - 8}`;
+  return `${base + 8};5;${color}`;
+}
+
+function underlineSgr(style: CellStyle): string {
+  return style.underlineStyle ? UNDERLINE_STYLE_SGR[style.underlineStyle] : "4";
+}
+
+/**
+ * Parameters that set `style` from scratch, starting with a reset
+ */
+function fullSgr(style: CellStyle): string[] {
+  const params = ["0"];
+  if (style.bold) params.push("1");
+  if (style.dim) params.push("2");
+  if (style.italic) params.push("3");
+  if (style.underline) params.push(underlineSgr(style));
+  if (style.blink) params.push("5");
+  if (style.inverse) params.push("7");
+  if (style.invisible) params.push("8");
+  if (style.strikethrough) params.push("9");
+  if (style.overline) params.push("53");
+  if (style.fg !== undefined) params.push(colorSgr(style.fg, 30));
+  if (style.bg !== undefined) params.push(colorSgr(style.bg, 40));
+  if (style.underlineColor !== undefined) params.push(colorSgr(style.underlineColor, 50));
+  return params;
+}
+
+// On/off codes for attributes that toggle independently
+const TOGGLES: Array<[keyof CellStyle, string, string]> = [
+  ["italic", "3", "23"],
+  ["blink", "5", "25"],
+  ["inverse", "7", "27"],
+  ["invisible", "8", "28"],
+  ["strikethrough", "9", "29"],
+  ["overline", "53", "55"],
+];
+
+/**
+ * Parameters that change only what differs between `from` and `to`
+ */
+function deltaSgr(from: CellStyle, to: CellStyle): string[] {
+  const params: string[] = [];
+  // SGR 22 clears bold and dim together
+  const normalIntensity = (from.bold && !to.bold) || (from.dim && !to.dim);
+  if (normalIntensity) params.push("22");
+  if (to.bold && (!from.bold || normalIntensity)) params.push("1");
+  if (to.dim && (!from.dim || normalIntensity)) params.push("2");
+  for (const [attr, on, off] of TOGGLES) {
+    if (from[attr] !== to[attr]) params.push(to[attr] ? on : off);
+  }
+  if (from.underline !== to.underline || from.underlineStyle !== to.underlineStyle) {
+    params.push(to.underline ? underlineSgr(to) : "24");
+  }
+  if (from.fg !== to.fg) params.push(to.fg === undefined ? "39" : colorSgr(to.fg, 30));
+  if (from.bg !== to.bg) params.push(to.bg === undefined ? "49" : colorSgr(to.bg, 40));
+  if (from.underlineColor !== to.underlineColor) {
+    params.push(to.underlineColor === undefined ? "59" : colorSgr(to.underlineColor, 50));
+  }
+  return params;
+}
+
+/**
+ * The shorter SGR sequence that switches from one style to another: only
+ * the attributes that change, or a reset followed by the new style
+ */
+function transitionSgr(from: CellStyle, to: CellStyle): string {
+  const delta = deltaSgr(from, to).join(";");
+  const full = fullSgr(to).join(";");
+  return `\x1b[${delta.length < full.length ? delta : full}m`;
+}
+
+/**
+ * Serialize one row of styled runs as text with SGR sequences, ending with
+ * a reset if any styling was emitted. Only attribute changes between runs
+ * are encoded, so busy screens (htop, colored logs) stay compact.
+ */
+export function runsToAnsi(runs: StyledRun[]): string {
+  let out = "";
+  let current: CellStyle = {};
+  for (const { col: _col, width: _width, text, ...style } of runs) {
+    if (!sameStyle(current, style)) {
+      out += Object.keys(style).length === 0 ? "\x1b[0m" : transitionSgr(current, style);
+      current = style;
+    }
+    out += text;
+  }
+  return Object.keys(current).length > 0 ? out + "\x1b[0m" : out;
+}
+
+/**
+ * Copy the terminal's buffer into a new emulator reflowed to `cols`. The
+ * caller owns the returned terminal and must dispose() it. Throws while
+ * the alternate screen is active: full-screen programs draw for the live
+ * width and have no wrapped lines to reflow.
+ */
+export async function reflowTerminal(source: HeadlessTerminal, cols: number): Promise<HeadlessTerminal> {
+  const buffer = source.buffer.active;
+  if (buffer.type === "alternate") {
+    throw new Error(
+      "Cannot reflow: a full-screen program is using the alternate screen and draws for the live terminal width"
+    );
+  }
+  if (!Number.isInteger(cols) || cols < MIN_REFLOW_COLS || cols > MAX_REFLOW_COLS) {
+    throw new Error(`Reflow width must be an integer between ${MIN_REFLOW_COLS} and ${MAX_REFLOW_COLS}`);
+  }
+
+  const rows = getStyledRows(source, 0, buffer.length);
+  let data = "";
+  for (let y = 0; y < rows.length; y++) {
+    const runs = rows[y].runs;
+    data += runsToAnsi(runs);
+    if (buffer.getLine(y + 1)?.isWrapped) {
+      // Fill the row so the next line's text auto-wraps onto it
+      const last = runs[runs.length - 1];
+      const used = last ? last.col + last.width : 0;
+      data += " ".repeat(Math.max(0, source.cols - used));
+    } else if (y < rows.length - 1) {
+      data += "\r\n";
+    }
+  }
+  // Cursor position is relative to the viewport, which lines up because the
+  // copy has the same number of lines
+  data += `\x1b[${buffer.cursorY + 1};${buffer.cursorX + 1}H`;
+
+  const copy = createEmulator(source.cols, source.rows);
+  await writeAsync(copy, data);
+  if (cols !== source.cols) {
+    copy.resize(cols, source.rows);
+  }
+  return copy;
+}
+
+/**
+ * Buffer lines [start, end) of a terminal as plain text, right-trimmed.
+ * With keepWrapped, rows that continue onto a soft-wrapped next row keep
+ * their trailing spaces so joinWrappedRows() can rejoin them exactly.
+ */
+export function getTextLines(terminal: HeadlessTerminal, start: number, end: number, keepWrapped = false): string[] {
+  const buffer = terminal.buffer.active;
+  const lines: string[] = [];
+  for (let y = start; y < end; y++) {
+    const trim = !keepWrapped || !buffer.getLine(y + 1)?.isWrapped || y + 1 >= end;
+    lines.push(buffer.getLine(y)?.translateToString(trim) ?? "");
+  }
+  return lines;
+}
+
+/**
+ * Merge rows (the texts of buffer lines start, start + 1, ...) into logical
+ * lines, appending each soft-wrapped row to the one before it. `lineOf[i]`
+ * is the index of the logical line that row i ended up in. A first row
+ * that continues a line above `start` begins a new logical line.
+ */
+export function joinWrappedRows(
+  terminal: HeadlessTerminal,
+  start: number,
+  rows: string[]
+): { lines: string[]; lineOf: number[] } {
+  const buffer = terminal.buffer.active;
+  const lines: string[] = [];
+  const lineOf: number[] = [];
+  rows.forEach((row, i) => {
+    if (i > 0 && buffer.getLine(start + i)?.isWrapped) {
+      lines[lines.length - 1] += row;
+    } else {
+      lines.push(row);
+    }
+    lineOf.push(lines.length - 1);
+  });
+  return { lines, lineOf };
+}
@@ -1,5 +1,5 @@
 /**
- * Renders the terminal buffer to a PNG image via SVG.
+ * Renders the terminal buffer to SVG, and to a PNG image via that SVG.
  * Uses @xterm/headless buffer cell API for color extraction
  * and @resvg/resvg-js for SVG-to-PNG conversion.
  */
@@ -9,6 +9,15 @@ import * as os from "os";
 import * as path from "path";
 import * as fs from "fs";
 import type { Terminal } from "@xterm/headless";
+import {
+  DEFAULT_FALLBACK_FONTS,
+  classifyGlyph,
+  formatGlyphWarnings,
+  getGlyphCoverage,
+  isRenderable,
+  type GlyphCoverage,
+  type MissingGlyph,
+} from "./fonts.js";
 
 const require = createRequire(import.meta.url);
 
@@ -40,17 +49,58 @@ const CM_P16 = 16777216;    // 0x1000000 — 16-color palette (SGR 30-37, 90-97)
 const CM_P256 = 33554432;   // 0x2000000 — 256-color palette (SGR 38;5;N)
 const CM_RGB = 50331648;    // 0x3000000 — 24-bit truecolor (SGR 38;2;R;G;B)
 
-// One Dark color palette for the basic 16 ANSI colors
-const palette16 = [
-  '#282c34', '#e06c75', '#98c379', '#e5c07b', '#61afef', '#c678dd', '#56b6c2', '#abb2bf',
-  '#5c6370', '#e06c75', '#98c379', '#e5c07b', '#61afef', '#c678dd', '#56b6c2', '#ffffff',
-];
+export type ThemeName = 'one-dark' | 'one-light' | 'high-contrast' | 'colorblind';
 
-const BG_COLOR = '#282c34';
-const FG_COLOR = '#abb2bf';
+export interface RenderTheme {
+  background: string;
+  foreground: string;
+  /** The basic 16 ANSI colors (0-7 normal, 8-15 bright) */
+  palette16: string[];
+}
+
+export const THEMES: Record<ThemeName, RenderTheme> = {
+  // One Dark color palette — the default
+  'one-dark': {
+    background: '#282c34',
+    foreground: '#abb2bf',
+    palette16: [
+      '#282c34', '#e06c75', '#98c379', '#e5c07b', '#61afef', '#c678dd', '#56b6c2', '#abb2bf',
+      '#5c6370', '#e06c75', '#98c379', '#e5c07b', '#61afef', '#c678dd', '#56b6c2', '#ffffff',
+    ],
+  },
+  // One Light — for documents and PR comments on a white page
+  'one-light': {
+    background: '#fafafa',
+    foreground: '#383a42',
+    palette16: [
+      '#383a42', '#e45649', '#50a14f', '#c18401', '#4078f2', '#a626a4', '#0184bc', '#a0a1a7',
+      '#696c77', '#e45649', '#50a14f', '#c18401', '#4078f2', '#a626a4', '#0184bc', '#fafafa',
+    ],
+  },
+  // Pure black/white with fully saturated colors, for low-vision reviewers
+  'high-contrast': {
+    background: '#000000',
+    foreground: '#ffffff',
+    palette16: [
+      '#000000', '#ff4040', '#00ff00', '#ffff00', '#4080ff', '#ff40ff', '#00ffff', '#ffffff',
+      '#808080', '#ff8080', '#80ff80', '#ffff80', '#80b0ff', '#ff80ff', '#80ffff', '#ffffff',
+    ],
+  },
+  // Okabe-Ito based palette: red/green stay distinguishable with deuteranopia
+  'colorblind': {
+    background: '#1e1e1e',
+    foreground: '#e0e0e0',
+    palette16: [
+      '#1e1e1e', '#d55e00', '#009e73', '#f0e442', '#0072b2', '#cc79a7', '#56b4e9', '#e0e0e0',
+      '#6e6e6e', '#ff8c42', '#33c49b', '#f5ec7a', '#3d9be0', '#e0a3c5', '#8fd0f2', '#ffffff',
+    ],
+  },
+};
+
+export const THEME_NAMES = Object.keys(THEMES) as ThemeName[];
 
-function color256(n: number): string {
-  if (n < 16) return palette16[n];
+export function color256(n: number, theme: RenderTheme): string {
+  if (n < 16) return theme.palette16[n];
   if (n < 232) {
     n -= 16;
     const r = Math.floor(n / 36) * 51;
@@ -62,16 +112,16 @@ function color256(n: number): string {
   return `rgb(${gray},${gray},${gray})`;
 }
 
-function resolveColor(color: number, mode: number, isBackground: boolean): string {
+function resolveColor(color: number, mode: number, isBackground: boolean, theme: RenderTheme): string {
+  const fallback = isBackground ? theme.background : theme.foreground;
   if (mode === CM_DEFAULT || color < 0) {
-    return isBackground ? BG_COLOR : FG_COLOR;
+    return fallback;
   }
   if (mode === CM_P16) {
-    return palette16[color] || (isBackground ? BG_COLOR : FG_COLOR);
+    return theme.palette16[color] || fallback;
   }
   if (mode === CM_P256) {
-    if (color < 16) return palette16[color];
-    return color256(color);
+    return color256(color, theme);
   }
   if (mode === CM_RGB) {
     const r = (color >> 16) & 0xff;
@@ -79,39 +129,69 @@ function resolveColor(color: number, mode: number, isBackground: boolean): strin
     const b = color & 0xff;
     return `rgb(${r},${g},${b})`;
   }
-  return isBackground ? BG_COLOR : FG_COLOR;
+  return fallback;
 }
 
 function escapeXml(s: string): string {
   return s.replace(/&/g, '&amp;').replace(/</g, '&lt;').replace(/>/g, '&gt;').replace(/"/g, '&quot;');
 }
 
-export interface RenderOptions {
+export interface SvgRenderOptions {
   /** Font family name (must be available to resvg). Default: 'JetBrains Mono' */
   fontFamily?: string;
-  /** Directories to search for font files */
-  fontDirs?: string[];
+  /** Families tried after fontFamily for glyphs it lacks. Default: emoji, CJK, and symbol fonts */
+  fallbackFonts?: string[];
   /** Show macOS-style window chrome (traffic lights). Default: true */
   windowChrome?: boolean;
+  /** Color theme. Default: 'one-dark' */
+  theme?: ThemeName;
+  /**
+   * When set, glyphs no available font covers are drawn as a placeholder
+   * box instead of being left to render as tofu.
+   */
+  glyphCoverage?: GlyphCoverage;
+}
+
+export interface RenderOptions extends SvgRenderOptions {
+  /** Directories to search for font files */
+  fontDirs?: string[];
   /** Output scale multiplier. Default: 2 (retina) */
   scale?: number;
 }
 
+export interface SvgRender {
+  svg: string;
+  width: number;
+  height: number;
+  /** Glyphs replaced with a placeholder (only when glyphCoverage is set) */
+  missingGlyphs: MissingGlyph[];
+}
+
+export interface PngRender {
+  png: Buffer;
+  /** One entry per distinct glyph that couldn't be drawn */
+  warnings: string[];
+}
+
+function cssFontFamily(primary: string, fallbacks: string[]): string {
+  return [primary, ...fallbacks].map((f) => `'${f.replace(/'/g, '')}'`).join(', ') + ', monospace';
+}
+
 /**
- * Render an xterm.js Terminal buffer to a PNG image buffer.
+ * Render an xterm.js Terminal buffer to a standalone SVG document.
+ * Each cell is a positioned <text> element with a <rect> behind it when
+ * its background is not the theme default.
  */
-export function renderTerminalToPng(terminal: Terminal, options: RenderOptions = {}): Buffer {
+export function renderTerminalToSvg(terminal: Terminal, options: SvgRenderOptions = {}): SvgRender {
   const {
     fontFamily = 'JetBrains Mono',
-    fontDirs: userFontDirs = [],
+    fallbackFonts = DEFAULT_FALLBACK_FONTS,
     windowChrome = true,
-    scale = 2,
+    theme: themeName = 'one-dark',
+    glyphCoverage,
   } = options;
-
-  // Default font search paths if none provided
-  const fontDirs = userFontDirs.length > 0
-    ? userFontDirs
-    : defaultFontDirs();
+  const theme = THEMES[themeName];
+  const missingGlyphs: MissingGlyph[] = [];
 
   const cols = terminal.cols;
   const rows = terminal.rows;
@@ -131,10 +211,10 @@ export function renderTerminalToPng(terminal: Terminal, options: RenderOptions =
   let svg = `<svg xmlns="http://www.w3.org/2000/svg" width="${width}" height="${height}" viewBox="0 0 ${width} ${height}">
 <defs>
   <style>
-    text { font-family: '${fontFamily}'; font-size: 13px; }
+    text { font-family: ${cssFontFamily(fontFamily, fallbackFonts)}; font-size: 13px; }
   </style>
 </defs>
-<rect width="${width}" height="${height}" rx="${cornerR}" ry="${cornerR}" fill="${BG_COLOR}"/>`;
+<rect width="${width}" height="${height}" rx="${cornerR}" ry="${cornerR}" fill="${theme.background}"/>`;
 
   if (windowChrome) {
     svg += `
@@ -147,35 +227,77 @@ export function renderTerminalToPng(terminal: Terminal, options: RenderOptions =
   // Render each character individually at its exact grid position
   // (grouping into text runs causes drift with Unicode box/block characters)
   for (let y = 0; y < rows; y++) {
-    const line = buffer.getLine(y);
+    const line = buffer.getLine(buffer.baseY + y);
     if (!line) continue;
 
     for (let x = 0; x < cols; x++) {
       const cell = line.getCell(x);
       if (!cell) continue;
 
+      // Wide characters occupy this cell and an empty spacer cell after it
+      // (width 0), which getChars() reports as '' and is skipped here
       const char = cell.getChars();
       if (!char || char === ' ') continue;
 
-      const bgColor = resolveColor(cell.getBgColor(), cell.getBgColorMode(), true);
-      const fgColor = resolveColor(cell.getFgColor(), cell.getFgColorMode(), false);
+      const bgColor = resolveColor(cell.getBgColor(), cell.getBgColorMode(), true, theme);
+      const fgColor = resolveColor(cell.getFgColor(), cell.getFgColorMode(), false, theme);
       const bold = cell.isBold() === 1;
 
       const cx = padX + x * charW;
       const cy = padY + y * charH;
+      const cellW = charW * Math.max(1, cell.getWidth());
 
       // Render non-default backgrounds
-      if (bgColor !== BG_COLOR) {
-        svg += `<rect x="${cx}" y="${cy}" width="${charW}" height="${charH}" fill="${bgColor}"/>`;
+      if (bgColor !== theme.background) {
+        svg += `<rect x="${cx}" y="${cy}" width="${cellW}" height="${charH}" fill="${bgColor}"/>`;
+      }
+
+      // Draw a visible placeholder instead of tofu for glyphs no font covers
+      if (glyphCoverage) {
+        const glyphClass = classifyGlyph(char);
+        if (!isRenderable(glyphClass, glyphCoverage)) {
+          missingGlyphs.push({ char, glyphClass, row: y, col: x });
+          svg += `<rect x="${cx + 1}" y="${cy + 2}" width="${cellW - 2}" height="${charH - 4}" fill="none" stroke="${fgColor}" stroke-width="1" stroke-dasharray="2,1"/>`;
+          svg += `<text x="${cx + cellW / 2}" y="${cy + charH - 5}" fill="${fgColor}" font-size="10" text-anchor="middle">?</text>\n`;
+          continue;
+        }
       }
 
       const weight = bold ? ' font-weight="bold"' : '';
-      svg += `<text x="${cx}" y="${cy + charH - 4}" fill="${fgColor}"${weight}>${escapeXml(char)}</text>\n`;
+      // Fallback fonts draw CJK and emoji at their own advance; pin the
+      // glyph to exactly its grid cells so the rest of the row lines up
+      const fit = cell.getWidth() === 2
+        ? ` textLength="${cellW}" lengthAdjust="spacingAndGlyphs"`
+        : '';
+      svg += `<text x="${cx}" y="${cy + charH - 4}" fill="${fgColor}"${weight}${fit}>${escapeXml(char)}</text>\n`;
     }
   }
 
   svg += '</svg>';
 
+  return { svg, width, height, missingGlyphs };
+}
+
+/**
+ * Render an xterm.js Terminal buffer to a PNG image buffer, along with
+ * warnings for any glyphs that had to be replaced with a placeholder.
+ */
+export function renderTerminalToPng(terminal: Terminal, options: RenderOptions = {}): PngRender {
+  const {
+    fontFamily = 'JetBrains Mono',
+    fontDirs: userFontDirs = [],
+    fallbackFonts = DEFAULT_FALLBACK_FONTS,
+    scale = 2,
+  } = options;
+
+  // Default font search paths if none provided
+  const fontDirs = userFontDirs.length > 0
+    ? userFontDirs
+    : defaultFontDirs();
+
+  const glyphCoverage = getGlyphCoverage(fontDirs, [fontFamily, ...fallbackFonts]);
+  const { svg, width, missingGlyphs } = renderTerminalToSvg(terminal, { ...options, glyphCoverage });
+
   // Convert SVG to PNG
   // Dynamic import to avoid hard failure if resvg is not installed
   let Resvg: any;
@@ -197,5 +319,8 @@ export function renderTerminalToPng(terminal: Terminal, options: RenderOptions =
   });
 
   const pngData = resvg.render();
-  return Buffer.from(pngData.asPng());
+  return {
+    png: Buffer.from(pngData.asPng()),
+    warnings: formatGlyphWarnings(missingGlyphs),
+  };
 }
@@ -0,0 +1,124 @@
+import { resolveExecutable } from "./platform.js";
+
+/**
+ * Resource limits for the spawned shell and everything it runs
+ * (--limit-cpu, --limit-memory, --limit-procs, --limit-fsize, --cgroup).
+ * Applied by wrapping the spawn command, so they are inherited by every
+ * child and can't be raised from inside the shell.
+ */
+export interface ResourceLimits {
+  /** CPU seconds each process may use (RLIMIT_CPU) */
+  cpuSeconds?: number;
+  /**
+   * Bytes of address space per process (RLIMIT_AS), or of memory for the
+   * whole process tree with cgroup
+   */
+  memoryBytes?: number;
+  /**
+   * Processes the user may have (RLIMIT_NPROC, counted across all of the
+   * user's processes), or in the process tree with cgroup
+   */
+  maxProcesses?: number;
+  /** Largest file a process may write, in bytes (RLIMIT_FSIZE) */
+  fileSizeBytes?: number;
+  /** Run the shell in its own cgroup through systemd-run (Linux) */
+  cgroup?: boolean;
+}
+
+const SIZE_UNITS: Record<string, number> = { K: 1024, M: 1024 ** 2, G: 1024 ** 3, T: 1024 ** 4 };
+
+/**
+ * Parse a size like 512M, 2G, or 100K (powers of 1024). A bare number is
+ * megabytes. Throws on anything else.
+ */
+export function parseByteSize(value: string): number {
+  const match = /^(\d+(?:\.\d+)?)\s*([KMGT])?i?B?$/i.exec(value.trim());
+  if (!match) {
+    throw new Error(`expected a size like 512M or 2G, got '${value}'`);
+  }
+  const unit = SIZE_UNITS[(match[2] ?? "M").toUpperCase()];
+  return Math.floor(parseFloat(match[1]) * unit);
+}
+
+/**
+ * A byte count for people: 512 B, 40.0 KB, 12.3 MB (powers of 1024)
+ */
+export function formatByteSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  const units = ["KB", "MB", "GB", "TB"];
+  let value = bytes / 1024;
+  let unit = 0;
+  while (value >= 1024 && unit < units.length - 1) {
+    value /= 1024;
+    unit++;
+  }
+  return `${value.toFixed(1)} ${units[unit]}`;
+}
+
+/**
+ * Why these limits can't be applied here, or null if they can
+ */
+export function checkResourceLimits(limits: ResourceLimits): string | null {
+  if (process.platform === "win32") {
+    return "resource limits are not supported on Windows";
+  }
+  if (limits.cgroup) {
+    if (process.platform !== "linux") return "--cgroup is only supported on Linux";
+    if (!resolveExecutable("systemd-run")) return "--cgroup needs systemd-run, which was not found on PATH";
+  }
+  return null;
+}
+
+function hasRlimits(limits: ResourceLimits): boolean {
+  const tree = limits.cgroup;
+  return (
+    limits.cpuSeconds !== undefined ||
+    limits.fileSizeBytes !== undefined ||
+    (!tree && (limits.memoryBytes !== undefined || limits.maxProcesses !== undefined))
+  );
+}
+
+/**
+ * prlimit (util-linux) takes exact values; without it, fall back to the
+ * shell's ulimit, whose units and flags are the POSIX/bash ones
+ */
+function wrapWithRlimits(cmd: string, args: string[], limits: ResourceLimits): { cmd: string; args: string[] } {
+  const tree = limits.cgroup;
+  const prlimit = resolveExecutable("prlimit");
+  if (prlimit) {
+    const flags: string[] = [];
+    if (limits.cpuSeconds !== undefined) flags.push(`--cpu=${limits.cpuSeconds}`);
+    if (limits.fileSizeBytes !== undefined) flags.push(`--fsize=${limits.fileSizeBytes}`);
+    if (!tree && limits.memoryBytes !== undefined) flags.push(`--as=${limits.memoryBytes}`);
+    if (!tree && limits.maxProcesses !== undefined) flags.push(`--nproc=${limits.maxProcesses}`);
+    return { cmd: prlimit, args: [...flags, "--", cmd, ...args] };
+  }
+
+  const ulimits: string[] = [];
+  if (limits.cpuSeconds !== undefined) ulimits.push(`ulimit -t ${limits.cpuSeconds}`);
+  // 512-byte blocks
+  if (limits.fileSizeBytes !== undefined) ulimits.push(`ulimit -f ${Math.ceil(limits.fileSizeBytes / 512)}`);
+  if (!tree && limits.memoryBytes !== undefined) ulimits.push(`ulimit -v ${Math.ceil(limits.memoryBytes / 1024)}`);
+  if (!tree && limits.maxProcesses !== undefined) ulimits.push(`ulimit -u ${limits.maxProcesses}`);
+  return { cmd: "/bin/sh", args: ["-c", `${ulimits.join(" && ")} && exec "$@"`, "terminal-mcp", cmd, ...args] };
+}
+
+/**
+ * The command that runs `cmd args` under these limits. Every wrapper execs
+ * the command, so the PTY's pid is still the shell's.
+ */
+export function wrapWithLimits(cmd: string, args: string[], limits: ResourceLimits): { cmd: string; args: string[] } {
+  let wrapped = hasRlimits(limits) ? wrapWithRlimits(cmd, args, limits) : { cmd, args };
+  if (limits.cgroup) {
+    const properties: string[] = [];
+    if (limits.memoryBytes !== undefined) properties.push("-p", `MemoryMax=${limits.memoryBytes}`, "-p", "MemorySwapMax=0");
+    if (limits.maxProcesses !== undefined) properties.push("-p", `TasksMax=${limits.maxProcesses}`);
+    // A transient scope under the user's manager, or the system's for root
+    const scope = process.getuid?.() === 0 ? ["--scope"] : ["--user", "--scope"];
+    wrapped = {
+      cmd: resolveExecutable("systemd-run") ?? "systemd-run",
+      args: [...scope, "--quiet", "--collect", ...properties, "--", wrapped.cmd, ...wrapped.args],
+    };
+  }
+  return wrapped;
+}
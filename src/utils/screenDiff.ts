@@ -0,0 +1,101 @@
+/**
+ * Row-by-row comparison of two screen snapshots. Screens have a fixed
+ * number of rows, so changes are reported per row position rather than
+ * via a line-matching (LCS) diff.
+ */
+
+export interface ScreenSnapshot {
+  /** Visible rows, right-trimmed */
+  rows: string[];
+  cursor: { x: number; y: number };
+  cols: number;
+  takenAt: string;
+}
+
+export interface ChangedRegion {
+  /** First changed row (0-indexed) */
+  startRow: number;
+  /** Last changed row, inclusive */
+  endRow: number;
+  before: string[];
+  after: string[];
+}
+
+export interface ScreenDiff {
+  changed: boolean;
+  changedRows: number;
+  regions: ChangedRegion[];
+  cursorMoved: boolean;
+  resized: boolean;
+}
+
+/**
+ * Group changed rows into contiguous regions. Rows separated by fewer than
+ * mergeGap unchanged rows are merged into one region.
+ */
+export function diffScreens(before: ScreenSnapshot, after: ScreenSnapshot, mergeGap = 1): ScreenDiff {
+  const rowCount = Math.max(before.rows.length, after.rows.length);
+  const changedRowIndexes: number[] = [];
+  for (let y = 0; y < rowCount; y++) {
+    if ((before.rows[y] ?? "") !== (after.rows[y] ?? "")) {
+      changedRowIndexes.push(y);
+    }
+  }
+
+  const regions: ChangedRegion[] = [];
+  for (const y of changedRowIndexes) {
+    const last = regions[regions.length - 1];
+    if (last && y - last.endRow <= mergeGap) {
+      last.endRow = y;
+    } else {
+      regions.push({ startRow: y, endRow: y, before: [], after: [] });
+    }
+  }
+  for (const region of regions) {
+    for (let y = region.startRow; y <= region.endRow; y++) {
+      region.before.push(before.rows[y] ?? "");
+      region.after.push(after.rows[y] ?? "");
+    }
+  }
+
+  const cursorMoved = before.cursor.x !== after.cursor.x || before.cursor.y !== after.cursor.y;
+  const resized = before.cols !== after.cols || before.rows.length !== after.rows.length;
+  return {
+    changed: regions.length > 0 || cursorMoved || resized,
+    changedRows: changedRowIndexes.length,
+    regions,
+    cursorMoved,
+    resized,
+  };
+}
+
+/**
+ * Render a diff as unified-diff hunks. Row numbers are 1-indexed as in
+ * diff(1); unchanged rows merged into a region appear as context.
+ */
+export function formatUnifiedDiff(diff: ScreenDiff, beforeLabel = "snapshot", afterLabel = "current"): string {
+  if (diff.regions.length === 0) return "";
+  const lines = [`--- ${beforeLabel}`, `+++ ${afterLabel}`];
+  for (const region of diff.regions) {
+    const count = region.endRow - region.startRow + 1;
+    lines.push(`@@ -${region.startRow + 1},${count} +${region.startRow + 1},${count} @@`);
+    const removed: string[] = [];
+    const added: string[] = [];
+    const flush = () => {
+      lines.push(...removed.map((l) => `-${l}`), ...added.map((l) => `+${l}`));
+      removed.length = 0;
+      added.length = 0;
+    };
+    for (let i = 0; i < count; i++) {
+      if (region.before[i] === region.after[i]) {
+        flush();
+        lines.push(` ${region.after[i]}`);
+      } else {
+        removed.push(region.before[i]);
+        added.push(region.after[i]);
+      }
+    }
+    flush();
+  }
+  return lines.join("\n");
+}
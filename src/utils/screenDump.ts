@@ -0,0 +1,183 @@
+/**
+ * Structured screen extraction from the xterm.js cell buffer.
+ * Groups adjacent cells with identical styling into runs so consumers get
+ * machine-readable text + color + attribute data without parsing ANSI.
+ */
+
+import type { Terminal, IBufferCell } from "@xterm/headless";
+
+// xterm.js CellColorMode bitmask values (see render.ts)
+const CM_P16 = 0x1000000;
+const CM_P256 = 0x2000000;
+const CM_RGB = 0x3000000;
+
+/**
+ * A cell color: palette index (0-255) or "#rrggbb" for truecolor.
+ * Omitted when the cell uses the terminal's default color.
+ */
+export type CellColor = number | string;
+
+/** Underline variants set with SGR 4:n; a plain underline is just `underline` */
+export type UnderlineStyle = "double" | "curly" | "dotted" | "dashed";
+
+export interface CellStyle {
+  fg?: CellColor;
+  bg?: CellColor;
+  bold?: true;
+  dim?: true;
+  italic?: true;
+  underline?: true;
+  underlineStyle?: UnderlineStyle;
+  /** SGR 58; omitted when the underline takes the foreground color */
+  underlineColor?: CellColor;
+  blink?: true;
+  inverse?: true;
+  invisible?: true;
+  strikethrough?: true;
+  overline?: true;
+}
+
+/**
+ * Underline style and color aren't part of the public IBufferCell API, but
+ * the cells xterm.js hands out carry these accessors at runtime
+ */
+interface ExtendedCell {
+  getUnderlineStyle?(): number;
+  isUnderlineColorDefault?(): boolean;
+  getUnderlineColor?(): number;
+  getUnderlineColorMode?(): number;
+}
+
+const UNDERLINE_STYLES: Record<number, UnderlineStyle> = {
+  2: "double",
+  3: "curly",
+  4: "dotted",
+  5: "dashed",
+};
+
+export interface StyledRun extends CellStyle {
+  /** Starting column (0-indexed) */
+  col: number;
+  /** Number of terminal columns the run occupies (wide chars count as 2) */
+  width: number;
+  text: string;
+}
+
+export interface StyledRow {
+  /** Row index relative to the dump start */
+  row: number;
+  runs: StyledRun[];
+}
+
+export interface ScreenDump {
+  dimensions: { cols: number; rows: number };
+  cursor: { x: number; y: number };
+  rows: StyledRow[];
+}
+
+function cellColor(value: number, mode: number): CellColor | undefined {
+  if (mode === CM_P16 || mode === CM_P256) return value;
+  if (mode === CM_RGB) return `#${value.toString(16).padStart(6, "0")}`;
+  return undefined;
+}
+
+export function getCellStyle(cell: IBufferCell): CellStyle {
+  const style: CellStyle = {};
+  const fg = cellColor(cell.getFgColor(), cell.getFgColorMode());
+  const bg = cellColor(cell.getBgColor(), cell.getBgColorMode());
+  if (fg !== undefined) style.fg = fg;
+  if (bg !== undefined) style.bg = bg;
+  if (cell.isBold()) style.bold = true;
+  if (cell.isDim()) style.dim = true;
+  if (cell.isItalic()) style.italic = true;
+  if (cell.isUnderline()) {
+    style.underline = true;
+    const extended = cell as IBufferCell & ExtendedCell;
+    const underlineStyle = UNDERLINE_STYLES[extended.getUnderlineStyle?.() ?? 1];
+    if (underlineStyle) style.underlineStyle = underlineStyle;
+    if (extended.isUnderlineColorDefault?.() === false) {
+      const color = cellColor(extended.getUnderlineColor!(), extended.getUnderlineColorMode!());
+      if (color !== undefined) style.underlineColor = color;
+    }
+  }
+  if (cell.isBlink()) style.blink = true;
+  if (cell.isInverse()) style.inverse = true;
+  if (cell.isInvisible()) style.invisible = true;
+  if (cell.isStrikethrough()) style.strikethrough = true;
+  if (cell.isOverline()) style.overline = true;
+  return style;
+}
+
+export function sameStyle(a: CellStyle, b: CellStyle): boolean {
+  return a.fg === b.fg && a.bg === b.bg && a.bold === b.bold && a.dim === b.dim &&
+    a.italic === b.italic && a.underline === b.underline && a.underlineStyle === b.underlineStyle &&
+    a.underlineColor === b.underlineColor && a.blink === b.blink && a.inverse === b.inverse &&
+    a.invisible === b.invisible && a.strikethrough === b.strikethrough && a.overline === b.overline;
+}
+
+function isDefaultStyle(style: CellStyle): boolean {
+  return Object.keys(style).length === 0;
+}
+
+/**
+ * Extract styled runs for buffer lines [start, end). Continuation cells of
+ * wide characters are skipped so each glyph appears once; trailing
+ * unstyled whitespace is dropped.
+ */
+export function getStyledRows(terminal: Terminal, start: number, end: number): StyledRow[] {
+  const buffer = terminal.buffer.active;
+  const rows: StyledRow[] = [];
+
+  for (let y = start; y < end; y++) {
+    const line = buffer.getLine(y);
+    const runs: StyledRun[] = [];
+    if (line) {
+      let current: StyledRun | null = null;
+      for (let x = 0; x < line.length; x++) {
+        const cell = line.getCell(x);
+        if (!cell) continue;
+        const width = cell.getWidth();
+        if (width === 0) continue; // second half of a wide character
+
+        const style = getCellStyle(cell);
+        const char = cell.getChars() || " ";
+        if (current && sameStyle(current, style)) {
+          current.text += char;
+          current.width += width;
+        } else {
+          current = { col: x, width, text: char, ...style };
+          runs.push(current);
+        }
+      }
+
+      // Drop trailing default-styled whitespace
+      while (runs.length > 0) {
+        const last = runs[runs.length - 1];
+        const { col: _col, width: _width, text, ...style } = last;
+        if (!isDefaultStyle(style)) break;
+        const trimmed = text.replace(/ +$/, "");
+        if (trimmed.length > 0) {
+          last.width -= text.length - trimmed.length;
+          last.text = trimmed;
+          break;
+        }
+        runs.pop();
+      }
+    }
+    rows.push({ row: y - start, runs });
+  }
+
+  return rows;
+}
+
+/**
+ * Dump the visible viewport as structured rows of styled runs.
+ */
+export function dumpScreen(terminal: Terminal): ScreenDump {
+  const buffer = terminal.buffer.active;
+  return {
+    dimensions: { cols: terminal.cols, rows: terminal.rows },
+    cursor: { x: buffer.cursorX, y: buffer.cursorY },
+    rows: getStyledRows(terminal, buffer.baseY, buffer.baseY + terminal.rows),
+  };
+}
@@ -0,0 +1,113 @@
+/**
+ * Tables drawn by TUIs and CLIs, rewritten as Markdown tables, which
+ * models read far more reliably than ASCII art. Two kinds are recognized:
+ *
+ * - ruled tables: cells between vertical bars (│ ┃ ║, or | with a border
+ *   line such as psql's `----+----`), with or without an outer frame
+ * - aligned tables: an upper-case header whose columns are separated by two
+ *   or more spaces, with rows starting under each header (docker ps,
+ *   kubectl get)
+ *
+ * Everything else passes through unchanged.
+ */
+
+const BOX_VERTICALS = "│┃║";
+const VERTICALS = BOX_VERTICALS + "|";
+// Lines made only of these (and at least one horizontal) are borders
+const BORDER_CHARS = /^[\s─━═┄┈╌┌┐└┘├┤┬┴┼╭╮╰╯╔╗╚╝╠╣╦╩╬╒╕╘╛╞╡╤╧╪╓╖╙╜╟╢╥╨╫┏┓┗┛┣┫┳┻╋+\-=|│┃║:]+$/;
+const HORIZONTALS = /[─━═┄┈╌\-=]/;
+const ALIGNED_HEADER = /^[A-Z][A-Z0-9_%#/().-]*( [A-Z0-9_%#/().-]+)*$/;
+
+export function convertTables(text: string): string {
+  const lines = text.split("\n");
+  const out: string[] = [];
+  for (let i = 0; i < lines.length; ) {
+    const table = ruledTable(lines, i) ?? alignedTable(lines, i);
+    if (table) {
+      out.push(...toMarkdown(table.rows));
+      i = table.end;
+    } else {
+      out.push(lines[i++]);
+    }
+  }
+  return out.join("\n");
+}
+
+interface Table {
+  rows: string[][];
+  /** Index of the first line after the table */
+  end: number;
+}
+
+function isBorder(line: string): boolean {
+  return BORDER_CHARS.test(line) && HORIZONTALS.test(line);
+}
+
+/**
+ * Cells of a row between vertical bars, or null if the line has none.
+ * Empty cells left by an outer frame are dropped.
+ */
+function splitRow(line: string): string[] | null {
+  const cells = line.split(new RegExp(`[${VERTICALS}]`)).map((cell) => cell.trim());
+  if (cells.length < 2) return null;
+  if (cells[0] === "") cells.shift();
+  if (cells[cells.length - 1] === "") cells.pop();
+  return cells.length >= 2 ? cells : null;
+}
+
+function ruledTable(lines: string[], start: number): Table | null {
+  const rows: string[][] = [];
+  let borders = 0;
+  let boxDrawn = false;
+  let end = start;
+  for (; end < lines.length; end++) {
+    const line = lines[end];
+    if (isBorder(line)) {
+      borders++;
+      continue;
+    }
+    const cells = splitRow(line);
+    // Every row has as many cells as the first
+    if (!cells || (rows.length > 0 && cells.length !== rows[0].length)) break;
+    rows.push(cells);
+    if ([...BOX_VERTICALS].some((bar) => line.includes(bar))) boxDrawn = true;
+  }
+  // A shell line with a pipe in it is not a table: plain bars need a border
+  if (rows.length < 2 || (!boxDrawn && borders === 0)) return null;
+  return { rows, end };
+}
+
+/**
+ * Start column of each field of an aligned table's header, or null
+ */
+function headerColumns(line: string): number[] | null {
+  const fields = [...line.matchAll(/\S+(?: \S+)*/g)];
+  if (fields.length < 2 || !fields.every((field) => ALIGNED_HEADER.test(field[0]))) return null;
+  return fields.map((field) => field.index!);
+}
+
+function alignedTable(lines: string[], start: number): Table | null {
+  const columns = headerColumns(lines[start]);
+  if (!columns) return null;
+  const slice = (line: string) =>
+    columns.map((col, i) => line.slice(col, columns[i + 1]).trim());
+  const rows = [slice(lines[start])];
+  let end = start + 1;
+  for (; end < lines.length; end++) {
+    const line = lines[end];
+    if (line.trim() === "") break;
+    // Each cell starts at its header, so the column before it is blank
+    const aligned = columns.every((col) => col === 0 || col >= line.length || line[col - 1] === " ")
+      && line.slice(0, columns[0]).trim() === "";
+    const cells = slice(line);
+    // Prose that happens to line up rarely fills two columns
+    if (!aligned || cells.filter((cell) => cell !== "").length < 2) break;
+    rows.push(cells);
+  }
+  return rows.length >= 2 ? { rows, end } : null;
+}
+
+function toMarkdown(rows: string[][]): string[] {
+  const format = (cells: string[]) => `| ${cells.map((cell) => cell.replace(/\|/g, "\\|")).join(" | ")} |`;
+  return [format(rows[0]), format(rows[0].map(() => "---")), ...rows.slice(1).map(format)];
+}
@@ -0,0 +1,74 @@
+import { McpError } from "@modelcontextprotocol/sdk/types.js";
+
+/**
+ * Limits on how long tool calls may run (--tool-timeout) and how long a
+ * call waits for a session another call is driving (--busy-timeout), so a
+ * stuck call can't hold up every call after it.
+ */
+
+// JSON-RPC error code for a session that stayed busy past --busy-timeout
+// (from the range the spec leaves to servers)
+export const TERMINAL_BUSY = -32001;
+
+export const DEFAULT_BUSY_TIMEOUT_MS = 10_000;
+
+// Calls that write to a session or restart it; only one runs per session
+// at a time, so their input can't interleave. Reads (getContent,
+// takeScreenshot, search, ...) are deliberately left out: they read the
+// emulator's buffer as it stands and never wait for a session held by
+// input, only for --read-guard.
+export const EXCLUSIVE_TOOLS = new Set(["type", "secureType", "sendKey", "sendMouse", "restartShell"]);
+
+export class TerminalBusyError extends McpError {
+  constructor(sessionId: string, waitedMs: number, heldBy: string) {
+    super(
+      TERMINAL_BUSY,
+      `Terminal busy: session '${sessionId}' is still running ${heldBy} after ${waitedMs} ms. ` +
+        `Retry later, or read the screen to see what it is waiting for`,
+      { sessionId, waitedMs, heldBy }
+    );
+  }
+}
+
+/**
+ * Parse --tool-timeout values: `<ms>` for every tool or `<tool>=<ms>` for
+ * one. Later values win. Throws on a malformed one.
+ */
+export function parseToolTimeouts(values: string[]): Record<string, number> {
+  const timeouts: Record<string, number> = {};
+  for (const value of values) {
+    const eq = value.indexOf("=");
+    const tool = eq === -1 ? "*" : value.slice(0, eq).trim();
+    const ms = Number(eq === -1 ? value : value.slice(eq + 1));
+    if (!tool || !Number.isInteger(ms) || ms < 0) {
+      throw new Error(`Invalid --tool-timeout '${value}': expected <ms> or <tool>=<ms>`);
+    }
+    timeouts[tool] = ms;
+  }
+  return timeouts;
+}
+
+/**
+ * Run a tool call, failing it if it takes longer than timeoutMs (0 or
+ * undefined: no limit). The call itself can't be cancelled, so work it
+ * started may finish later.
+ */
+export async function withToolTimeout<T>(
+  tool: string,
+  timeoutMs: number | undefined,
+  call: () => Promise<T>
+): Promise<T> {
+  if (!timeoutMs) return call();
+  let timer: NodeJS.Timeout | undefined;
+  const timeout = new Promise<never>((_, reject) => {
+    timer = setTimeout(
+      () => reject(new Error(`${tool} timed out after ${timeoutMs} ms (--tool-timeout); it may still finish in the background`)),
+      timeoutMs
+    );
+  });
+  try {
+    return await Promise.race([call(), timeout]);
+  } finally {
+    clearTimeout(timer);
+  }
+}